@@ -7,14 +7,24 @@ use std::fmt;
 #[cfg(not(target_arch = "wasm32"))]
 use std::path::Path;
 use std::path::PathBuf;
+use strum::IntoEnumIterator;
 use winit::event::{ElementState, ModifiersState, VirtualKeyCode, WindowEvent};
 use winit::event_loop::{ControlFlow, EventLoop, EventLoopProxy};
 
 use crate::commands::{Command, PuzzleCommand, PuzzleMouseCommand};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::logfile::replay::Replay;
 use crate::logfile::LogFileFormat;
-use crate::preferences::{Key, Keybind, PieceFilter, Preferences, Preset};
+use crate::practice::{CaseTrainer, Drill, CASE_PUZZLE};
+use crate::preferences::{
+    InspectionTwistAction, Key, KeyCombo, Keybind, KeybindSet, NewAction, PieceFilter, Preferences,
+    Preset,
+};
+use crate::puzzle::orientation_scramble::OrientationScrambleJob;
+use crate::puzzle::solve_job::SolveJob;
 use crate::puzzle::*;
 use crate::render::{GraphicsState, PuzzleRenderCache};
+use crate::stats::{GoalStreak, PbKind, Penalty, Session, Solve, TimeAttack};
 
 #[cfg(target_arch = "wasm32")]
 macro_rules! unsupported_on_web {
@@ -31,33 +41,156 @@ macro_rules! unsupported_on_web {
 
 pub struct App {
     pub(crate) timer_start_end: (Option<instant::Instant>, Option<instant::Instant>),
+    /// Time when the timer was paused because the window lost focus.
+    pub(crate) timer_paused_at: Option<instant::Instant>,
+    /// Time when inspection before the next timed solve started, while it's
+    /// in progress. This is not saved.
+    pub(crate) inspection_start: Option<instant::Instant>,
+    /// Penalty from the inspection before the timed solve in progress.
+    inspection_penalty: Penalty,
+    /// Time when the countdown before the timer starts began, while it's in
+    /// progress. This is not saved.
+    pub(crate) countdown_start: Option<instant::Instant>,
+    /// Length of the undo history when the timer started, for measuring the
+    /// reaction time. This is not saved.
+    timer_start_undo_len: usize,
+    /// Time from the start of the timer to the first twist of the timed solve
+    /// in progress. This is not saved.
+    reaction_time: Option<instant::Duration>,
+    /// Timed solves since the application was opened.
+    pub(crate) session: Session,
+    /// Personal bests set by the most recent solve.
+    pub(crate) new_pbs: Vec<PbKind>,
+    /// Penalty most recently assigned to a solve and when, for flashing the
+    /// edges of the puzzle view. This is not saved.
+    pub(crate) penalty_flash: Option<(Penalty, instant::Instant)>,
+    /// Streak of solves toward the streak goal.
+    pub(crate) goal_streak: GoalStreak,
+    /// When the streak goal was most recently reached, for celebrating it in
+    /// the puzzle view. This is not saved.
+    pub(crate) streak_celebration: Option<instant::Instant>,
 
     pub(crate) prefs: Preferences,
 
     events: EventLoopProxy<AppEvent>,
 
     pub(crate) puzzle: PuzzleController,
+    /// Puzzle type being built on another thread, which is loaded once it's
+    /// ready. This is not saved.
+    pub(crate) building_puzzle: Option<PuzzleTypeEnum>,
+    /// Number of puzzles requested from [`App::build_puzzle()`], which tags
+    /// each request so that puzzles from stale requests are ignored. This is
+    /// not saved.
+    puzzle_build_generation: u64,
+    /// Previously active puzzle and its log file, which
+    /// [`Command::SwapPuzzle`] switches back to.
+    background_puzzle: Option<(PuzzleController, Option<PathBuf>)>,
     pub(crate) render_cache: PuzzleRenderCache,
     pub(crate) puzzle_texture_size: (u32, u32),
     force_redraw: bool,
 
+    pub(crate) last_layer_render_cache: PuzzleRenderCache,
+    pub(crate) last_layer_texture_size: (u32, u32),
+    pub(crate) last_layer_texture_id: Option<egui::TextureId>,
+
+    pub(crate) mirror_view_render_cache: PuzzleRenderCache,
+    pub(crate) mirror_view_texture_size: (u32, u32),
+    pub(crate) mirror_view_texture_id: Option<egui::TextureId>,
+
+    /// Scrambled puzzle shown in the scramble preview window, which replaces
+    /// the current puzzle when started. This is not saved.
+    pub(crate) scramble_preview: Option<PuzzleController>,
+    pub(crate) scramble_preview_render_cache: PuzzleRenderCache,
+    pub(crate) scramble_preview_texture_size: (u32, u32),
+    pub(crate) scramble_preview_texture_id: Option<egui::TextureId>,
+
     /// Mouse cursor position relative to the puzzle texture. Each axis ranges
     /// from -1.0 to +1.0.
     pub(crate) cursor_pos: Option<Point2<f32>>,
 
+    /// Time of the most recent keyboard or mouse input, for the idle
+    /// animation.
+    last_input: instant::Instant,
+    /// Time of the last mouse input, for hiding the cursor.
+    last_mouse_input: instant::Instant,
+    /// Time of the most recent click on the background of the puzzle view, for
+    /// detecting double-clicks.
+    last_background_click: Option<instant::Instant>,
+
     /// Set of pressed keys.
     pressed_keys: HashSet<Key>,
+    /// Time of the most recent press of each key, for debouncing.
+    last_key_presses: HashMap<Key, instant::Instant>,
     /// Set of keys toggled on using buttons in the UI.
     toggled_keys: HashSet<Key>,
     /// Set of pressed modifier keys.
     pressed_modifiers: ModifiersState,
     /// Set of modifiers toggled on using buttons in the UI.
     toggled_modifiers: ModifiersState,
+    /// Key press that may be the first key of a chord keybind, which waits
+    /// until another key is pressed or the chord window passes.
+    pending_chord_key: Option<PendingKeyPress>,
+    /// Keys that were pressed as part of a chord, whose key repeat is ignored
+    /// until they are released.
+    chord_keys: HashSet<Key>,
+    /// Connected gamepads, for gamepad binds and rumble.
+    #[cfg(not(target_arch = "wasm32"))]
+    gamepads: crate::gamepad::Gamepads,
 
     /// Grips that are tied to a held key.
     transient_grips: HashMap<Key, Grip>,
     /// Grip that is more permanent.
     pub(crate) toggle_grip: Grip,
+    /// Key being held to peek at the last layer.
+    peek_key: Option<Key>,
+
+    /// Whether the dialog for choosing a new puzzle should be opened on the
+    /// next frame, since commands don't have access to the UI.
+    pub(crate) choose_puzzle_requested: bool,
+    /// Whether the window layout should be saved to the preferences on the
+    /// next frame, since the app doesn't have access to the UI.
+    pub(crate) save_layout_requested: bool,
+    /// Whether everything has been saved since the display was dimmed for
+    /// being idle.
+    saved_while_dimmed: bool,
+
+    /// Whether twist animations are slowed down. This is not saved.
+    pub(crate) slow_motion: bool,
+    /// Whether zen mode is on, which hides the timer and stats and doesn't
+    /// detect or record solves. This is not saved.
+    pub(crate) zen_mode: bool,
+    /// Pattern being applied one twist at a time. This is not saved.
+    pub(crate) demonstration: Option<Demonstration>,
+    /// Hints for the next move of a solution, if enabled. This is not saved.
+    pub(crate) training_wheels: Option<TrainingWheels>,
+    /// Orientation scramble being found in the background, along with the
+    /// puzzle state when it was requested. This is not saved.
+    orientation_scramble: Option<(OrientationScrambleJob, Puzzle)>,
+    /// Whether training wheels have been on during the timed solve in
+    /// progress, in which case it isn't recorded.
+    used_training_wheels: bool,
+    /// Time since the start of the timer when each entry in the undo history
+    /// was added, for exporting replays. This is not saved.
+    #[cfg(not(target_arch = "wasm32"))]
+    twist_times: Vec<instant::Duration>,
+    /// Replay being played back. This is not saved.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) replay: Option<ReplayPlayback>,
+    /// Keybinds loaded from a file that haven't been applied yet. This is not
+    /// saved.
+    pub(crate) keybinds_import: Option<KeybindsImport>,
+    /// Case set being practiced and times for each case.
+    pub(crate) case_trainer: CaseTrainer,
+    /// Case being drilled and times for each rep.
+    pub(crate) drill: Drill,
+    /// Time attack in progress or the most recent one, and its solves.
+    pub(crate) time_attack: TimeAttack,
+    /// Version that was last run before this one, if this is the first run
+    /// since an update. This is not saved.
+    pub(crate) previous_version: Option<String>,
+    /// Fixed timestep for animations and the timer, which only advance one
+    /// step per press of F10, for debugging. This is not saved.
+    pub(crate) frame_step: Option<FrameStep>,
 
     status_msg: String,
 }
@@ -65,24 +198,81 @@ impl App {
     pub(crate) fn new(event_loop: &EventLoop<AppEvent>, initial_file: Option<PathBuf>) -> Self {
         let mut this = Self {
             timer_start_end: (None, None),
+            timer_paused_at: None,
+            inspection_start: None,
+            inspection_penalty: Penalty::None,
+            countdown_start: None,
+            timer_start_undo_len: 0,
+            reaction_time: None,
+            session: Session::default(),
+            new_pbs: vec![],
+            penalty_flash: None,
+            goal_streak: GoalStreak::default(),
+            streak_celebration: None,
             prefs: Preferences::load(None),
 
             events: event_loop.create_proxy(),
 
             puzzle: PuzzleController::default(),
+            building_puzzle: None,
+            puzzle_build_generation: 0,
+            background_puzzle: None,
             render_cache: PuzzleRenderCache::default(),
             puzzle_texture_size: (0, 0),
             force_redraw: true,
 
+            last_layer_render_cache: PuzzleRenderCache::default(),
+            last_layer_texture_size: (0, 0),
+            last_layer_texture_id: None,
+
+            mirror_view_render_cache: PuzzleRenderCache::default(),
+            mirror_view_texture_size: (0, 0),
+            mirror_view_texture_id: None,
+
+            scramble_preview: None,
+            scramble_preview_render_cache: PuzzleRenderCache::default(),
+            scramble_preview_texture_size: (0, 0),
+            scramble_preview_texture_id: None,
+
             cursor_pos: None,
+            last_input: instant::Instant::now(),
+            last_mouse_input: instant::Instant::now(),
+            last_background_click: None,
 
             pressed_keys: HashSet::default(),
+            last_key_presses: HashMap::default(),
             toggled_keys: HashSet::default(),
             pressed_modifiers: ModifiersState::default(),
             toggled_modifiers: ModifiersState::default(),
+            pending_chord_key: None,
+            chord_keys: HashSet::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            gamepads: crate::gamepad::Gamepads::default(),
 
             transient_grips: HashMap::default(),
             toggle_grip: Grip::default(),
+            peek_key: None,
+
+            choose_puzzle_requested: false,
+            save_layout_requested: false,
+            saved_while_dimmed: false,
+
+            slow_motion: false,
+            zen_mode: false,
+            demonstration: None,
+            training_wheels: None,
+            orientation_scramble: None,
+            used_training_wheels: false,
+            #[cfg(not(target_arch = "wasm32"))]
+            twist_times: vec![],
+            #[cfg(not(target_arch = "wasm32"))]
+            replay: None,
+            keybinds_import: None,
+            case_trainer: CaseTrainer::default(),
+            drill: Drill::default(),
+            time_attack: TimeAttack::default(),
+            previous_version: None,
+            frame_step: None,
 
             status_msg: String::default(),
         };
@@ -90,6 +280,13 @@ impl App {
         // Always save preferences after opening.
         this.prefs.needs_save = true;
 
+        let has_initial_file = initial_file.is_some();
+        // Replay files are played back after loading the last open file.
+        #[cfg(not(target_arch = "wasm32"))]
+        let (initial_file, initial_replay) = match initial_file {
+            Some(path) if is_replay_file(&path) => (None, Some(path)),
+            other => (other, None),
+        };
         if let Some(path) = initial_file {
             this.prefs.log_file = Some(path);
         }
@@ -101,6 +298,25 @@ impl App {
         if let Some(path) = this.prefs.log_file.take() {
             this.try_load_puzzle(path);
         }
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(path) = initial_replay {
+            this.try_load_replay(&path);
+        }
+
+        // Start with a fresh scramble of the same puzzle, unless a file was
+        // given on the command line.
+        if this.prefs.interaction.scramble_on_startup && !has_initial_file {
+            this.puzzle = PuzzleController::new(this.puzzle.ty());
+            this.prefs.log_file = None;
+            match this.puzzle.scramble_full() {
+                Ok(()) => {
+                    this.animate_scramble();
+                    this.set_status_ok("Scrambled fully");
+                    this.start_inspection();
+                }
+                Err(e) => this.set_status_err(e),
+            }
+        }
 
         this
     }
@@ -113,6 +329,27 @@ impl App {
         self.force_redraw = false;
         ret
     }
+    pub(crate) fn draw_last_layer(
+        &mut self,
+        gfx: &mut GraphicsState,
+        force_redraw: bool,
+    ) -> Option<wgpu::TextureView> {
+        crate::render::draw_last_layer(self, gfx, force_redraw)
+    }
+    pub(crate) fn draw_mirror_view(
+        &mut self,
+        gfx: &mut GraphicsState,
+        force_redraw: bool,
+    ) -> Option<wgpu::TextureView> {
+        crate::render::draw_mirror_view(self, gfx, force_redraw)
+    }
+    pub(crate) fn draw_scramble_preview(
+        &mut self,
+        gfx: &mut GraphicsState,
+        force_redraw: bool,
+    ) -> Option<wgpu::TextureView> {
+        crate::render::draw_scramble_preview(self, gfx, force_redraw)
+    }
 
     pub(crate) fn event(&self, event: impl Into<AppEvent>) {
         self.events
@@ -149,13 +386,20 @@ impl App {
             AppEvent::WebWorkaround(_) => {
                 panic!("web workaround event should not be handled by app")
             }
+            #[cfg(not(target_arch = "wasm32"))]
+            AppEvent::ResetWindowPosition => {
+                self.prefs.window.geometry = None;
+                self.prefs.needs_save = true;
+                response.reset_window_position = true;
+                self.set_status_ok("Reset window position");
+            }
 
             AppEvent::Command(c) => match c {
                 Command::Open => {
                     unsupported_on_web! {
                         self;
                         if self.confirm_discard_changes("open another file") {
-                            if let Some(path) = file_dialog().pick_file() {
+                            if let Some(path) = open_file_dialog().pick_file() {
                                 self.try_load_puzzle(path);
                             }
                         }
@@ -183,6 +427,18 @@ impl App {
 
                 Command::CopyHscLog => self.try_copy_puzzle(LogFileFormat::Hsc, &mut response),
                 Command::CopyMc4dLog => self.try_copy_puzzle(LogFileFormat::Mc4d, &mut response),
+                Command::CopySolution => {
+                    let normalize_rotations = self.prefs.info.normalize_rotations;
+                    let ty = self.puzzle.ty();
+                    let convention = &self.prefs.interaction.twist_conventions[ty];
+                    let style = self.prefs.interaction.notation_style(ty);
+                    response.copy_string = Some(self.puzzle.solution_string(
+                        normalize_rotations,
+                        convention,
+                        style,
+                    ));
+                    self.set_status_ok("Copied solution");
+                }
                 Command::PasteLog => response.request_paste = true,
 
                 Command::Undo => {
@@ -191,6 +447,9 @@ impl App {
                 Command::Redo => {
                     self.puzzle.redo()?;
                 }
+                Command::DiscardLastTwist => {
+                    self.puzzle.discard_last_twist()?;
+                }
                 Command::Reset => {
                     if self.confirm_discard_changes("reset puzzle") {
                         self.puzzle.reset();
@@ -200,6 +459,7 @@ impl App {
                 Command::ScrambleN(n) => {
                     if self.confirm_discard_changes("scramble") {
                         self.puzzle.scramble_n(n)?;
+                        self.animate_scramble();
                         self.set_status_ok(format!(
                             "Scrambled with {} random {}",
                             n,
@@ -210,14 +470,94 @@ impl App {
                 Command::ScrambleFull => {
                     if self.confirm_discard_changes("scramble") {
                         self.puzzle.scramble_full()?;
+                        self.animate_scramble();
                         self.set_status_ok("Scrambled fully");
+                        self.start_inspection();
+                    }
+                }
+                Command::ScrambleOrientation => {
+                    if self.confirm_discard_changes("scramble") {
+                        if let Some((job, _)) = self.orientation_scramble.take() {
+                            job.cancel();
+                        }
+                        let job = OrientationScrambleJob::start(self.puzzle.ty())?;
+                        self.orientation_scramble = Some((job, self.puzzle.latest().clone()));
+                        self.set_status_ok("Scrambling orientation...");
+                    }
+                }
+                Command::CopyScramble => {
+                    let ty = self.puzzle.ty();
+                    let style = self.prefs.interaction.notation_style(ty);
+                    response.copy_string =
+                        Some(PuzzleController::random_scramble_string(ty, style)?);
+                    self.set_status_ok("Copied new scramble");
+                }
+                Command::NextCase => {
+                    if self.puzzle.ty() != CASE_PUZZLE {
+                        return Err(format!("Case sets are for {}", CASE_PUZZLE.name()));
+                    }
+                    let (case, setup) = self.case_trainer.random_case()?;
+                    if self.confirm_discard_changes("set up case") {
+                        self.puzzle.apply_pattern(setup)?;
+                        self.puzzle.add_scramble_marker(ScrambleState::Partial);
+                        self.case_trainer.start(case, &self.puzzle);
+                        self.set_status_ok(format!(
+                            "Set up {} case",
+                            self.case_trainer.case_set().name,
+                        ));
+                    }
+                }
+                Command::RetryScramble => {
+                    if self.confirm_discard_changes("retry scramble") {
+                        self.puzzle.retry_scramble()?;
+                        self.animate_scramble();
+                        self.set_status_ok("Reapplied scramble");
+                        self.reset_timer();
+                        self.start_inspection();
+                        if self.prefs.interaction.retry_starts_timer
+                            && !self.zen_mode
+                            && self.inspection_start.is_none()
+                        {
+                            self.start_countdown();
+                        }
                     }
                 }
+                Command::SwitchScrambleSide => {
+                    self.puzzle.switch_side()?;
+                    self.set_status_ok(match self.puzzle.side() {
+                        ScrambleSide::Normal => "Solving normal scramble",
+                        ScrambleSide::Inverse => "Solving inverse scramble",
+                    });
+                }
 
+                Command::New | Command::NewOther => {
+                    let mut action = self.prefs.interaction.new_action;
+                    if c == Command::NewOther {
+                        action = action.other();
+                    }
+                    self.event(match action {
+                        NewAction::NewScramble => Command::ScrambleFull,
+                        NewAction::ChoosePuzzle => Command::ChoosePuzzle,
+                    });
+                }
+                Command::ChoosePuzzle => self.choose_puzzle_requested = true,
                 Command::NewPuzzle(puzzle_type) => {
-                    if self.confirm_discard_changes("reset puzzle") {
-                        self.puzzle = PuzzleController::new(puzzle_type);
-                        self.set_status_ok(format!("Loaded {}", puzzle_type));
+                    // The current puzzle is kept in the background, so only
+                    // the old background puzzle is discarded.
+                    if self.confirm_discard_solve("load a new puzzle")
+                        && self.confirm_discard_background_puzzle("load a new puzzle")
+                    {
+                        self.build_puzzle(puzzle_type);
+                    }
+                }
+                Command::SwapPuzzle => {
+                    if self.confirm_discard_solve("switch puzzles") {
+                        let (puzzle, log_file) = self
+                            .background_puzzle
+                            .take()
+                            .ok_or("No other puzzle to switch to")?;
+                        self.swap_in_puzzle(puzzle, log_file);
+                        self.set_status_ok(format!("Switched to {}", self.puzzle.ty()));
                     }
                 }
 
@@ -229,15 +569,222 @@ impl App {
                     self.prefs.needs_save = true;
                     self.request_redraw_puzzle();
                 }
+                Command::ToggleLetters => {
+                    self.prefs.letters.visible ^= true;
+                    self.prefs.needs_save = true;
+                }
+                Command::ToggleSlowMotion => {
+                    self.slow_motion ^= true;
+                    self.set_status_ok(match self.slow_motion {
+                        true => "Slow motion on",
+                        false => "Slow motion off",
+                    });
+                }
+                Command::ToggleZenMode => {
+                    self.zen_mode ^= true;
+                    if self.zen_mode {
+                        self.reset_timer();
+                    }
+                    self.set_status_ok(match self.zen_mode {
+                        true => "Zen mode on",
+                        false => "Zen mode off",
+                    });
+                }
+                Command::ToggleShowTime => {
+                    self.prefs.info.show_time ^= true;
+                    self.prefs.needs_save = true;
+                    self.set_status_ok(match self.prefs.info.show_time {
+                        true => "Showing time",
+                        false => "Hiding time",
+                    });
+                }
+                Command::ToggleShowTwistCount => {
+                    self.prefs.info.show_twist_count ^= true;
+                    self.prefs.needs_save = true;
+                    self.set_status_ok(match self.prefs.info.show_twist_count {
+                        true => "Showing twist count",
+                        false => "Hiding twist count",
+                    });
+                }
+                Command::ToggleKeybindsReference => {
+                    self.prefs.info.keybinds_reference.visible ^= true;
+                    self.prefs.needs_save = true;
+                }
+                Command::RotateColorScheme => {
+                    let ty = self.puzzle.ty();
+                    let face_count = ty.faces().len();
+                    let current = self.prefs.colors.down_color(ty).or(ty.down_face());
+                    let start = current.map_or(0, |f| f.0 as usize);
+                    let center_color = self.prefs.colors.center_color(ty);
+                    // Cycle to the next color that can be rotated onto the
+                    // down face. This doesn't change the puzzle state, so it
+                    // isn't recorded as a twist.
+                    let next = (1..=face_count)
+                        .map(|i| Face(((start + i) % face_count) as _))
+                        .find(|&f| {
+                            ty.color_scheme_rotation_with_center(Some(f), center_color)
+                                .is_some()
+                        })
+                        .ok_or("Color scheme cannot be rotated for this puzzle")?;
+                    self.prefs.colors.down_color[ty] = Some(ty.info(next).symbol.to_owned());
+                    self.prefs.colors.front_color[ty] = None;
+                    self.prefs.needs_save = true;
+                    self.request_redraw_puzzle();
+                    self.set_status_ok(format!(
+                        "Rotated color scheme; {} color is now down",
+                        ty.info(next).name,
+                    ));
+                }
+                Command::MatchColorSchemeToView => {
+                    let ty = self.puzzle.ty();
+                    let twists = self.puzzle.take_view_rotation();
+                    if twists.is_empty() {
+                        return Err("Drag the view to match the physical puzzle first".to_owned());
+                    }
+                    // Recolor the puzzle so that the colors stay where they
+                    // are while the view returns to normal. This doesn't
+                    // change the puzzle state, so it isn't recorded as a
+                    // twist.
+                    let rotation = ty
+                        .rotate_color_scheme(&self.prefs.colors.scheme_rotation(ty), &twists)
+                        .ok_or("Color scheme cannot be rotated for this puzzle")?;
+                    self.prefs.colors.set_scheme_rotation(ty, &rotation)?;
+                    self.prefs.needs_save = true;
+                    self.request_redraw_puzzle();
+                    self.set_status_ok("Matched color scheme to view");
+                }
+
+                Command::CycleColorScheme => {
+                    let ty = self.puzzle.ty();
+                    let next = self
+                        .prefs
+                        .colors
+                        .next_scheme(ty)
+                        .ok_or("No saved color schemes for this puzzle")?
+                        .clone();
+                    self.prefs.colors.load_scheme(ty, &next);
+                    self.prefs.needs_save = true;
+                    self.request_redraw_puzzle();
+                    self.set_status_ok(format!("Color scheme: {}", next.preset_name));
+                }
+
+                Command::ResetView => {
+                    self.puzzle.reset_view_angle_offset();
+                }
+                Command::PeekLastLayer => {
+                    if self.puzzle.is_peeking() {
+                        self.puzzle.end_peek();
+                    } else {
+                        let angle = self.prefs.interaction.peek_angle;
+                        self.puzzle.start_peek(angle, &self.prefs);
+                    }
+                }
+                Command::SetDefaultView => {
+                    let ty = self.puzzle.ty();
+                    self.prefs.view_angles[ty] = Some(self.puzzle.take_view_angle(&self.prefs));
+                    self.prefs.needs_save = true;
+                    self.request_redraw_puzzle();
+                    self.set_status_ok(format!("Saved default view for {}", self.puzzle.name()));
+                }
+                Command::ToggleExplode => {
+                    self.puzzle.set_exploded(!self.puzzle.is_exploded());
+                    self.request_redraw_puzzle();
+                    self.set_status_ok(match self.puzzle.is_exploded() {
+                        true => "Exploded puzzle",
+                        false => "Assembled puzzle",
+                    });
+                }
 
                 Command::None => (),
             },
 
             AppEvent::Twist(twist) => {
+                self.check_countdown_twist(twist)?;
                 self.puzzle.twist(twist)?;
+                self.rumble_twist();
+            }
+
+            AppEvent::PuzzleBuilt(generation, puzzle) => {
+                // Ignore puzzles that were canceled or replaced by another.
+                let puzzle_type = puzzle.ty();
+                if self.building_puzzle.is_some() && generation == self.puzzle_build_generation {
+                    self.building_puzzle = None;
+                    self.swap_in_puzzle(*puzzle, None);
+                    self.set_status_ok(format!("Loaded {}", puzzle_type));
+                }
+            }
+
+            AppEvent::NewScramblePreview => {
+                let mut preview = PuzzleController::new(self.puzzle.ty());
+                preview.scramble_full()?;
+                self.scramble_preview = Some(preview);
+                self.scramble_preview_render_cache = PuzzleRenderCache::default();
+            }
+            AppEvent::StartScramblePreview => {
+                let preview = self
+                    .scramble_preview
+                    .take()
+                    .filter(|preview| preview.ty() == self.puzzle.ty())
+                    .ok_or("No scramble preview for this puzzle")?;
+                if self.confirm_discard_changes("scramble") {
+                    self.puzzle
+                        .scramble_full_with(preview.scramble().to_vec())?;
+                    self.animate_scramble();
+                    self.set_status_ok("Scrambled fully");
+                    self.start_inspection();
+                } else {
+                    self.scramble_preview = Some(preview);
+                }
+            }
+
+            AppEvent::ExportKeybinds => unsupported_on_web! { self; self.try_export_keybinds() },
+            AppEvent::ImportKeybinds => unsupported_on_web! { self; self.try_import_keybinds() },
+            AppEvent::OpenSession => unsupported_on_web! { self; self.try_open_session() },
+            AppEvent::SaveSession => unsupported_on_web! { self; self.try_save_session() },
+            AppEvent::SaveTimeAttack => unsupported_on_web! { self; self.try_save_time_attack() },
+            AppEvent::OpenReplay => unsupported_on_web! { self; self.try_open_replay() },
+            AppEvent::ExportReplay => unsupported_on_web! { self; self.try_export_replay() },
+            AppEvent::ExportRenderLayers => {
+                unsupported_on_web! { self; self.try_export_render_layers() }
+            }
+
+            AppEvent::ApplyPattern(pattern_name) => {
+                let twists = self.pattern_twists(&pattern_name)?;
+                if self.confirm_discard_changes("apply pattern") {
+                    self.puzzle.apply_pattern(twists)?;
+                    self.set_status_ok(format!("Applied pattern {pattern_name:?}"));
+                }
+            }
+            AppEvent::ApplyCommutator(twists) => {
+                if self.confirm_discard_changes("apply commutator") {
+                    self.puzzle.apply_pattern(twists)?;
+                    self.set_status_ok("Applied commutator");
+                }
+            }
+            AppEvent::DemonstratePattern(pattern_name) => {
+                let twists = self.pattern_twists(&pattern_name)?;
+                if self.confirm_discard_changes("demonstrate pattern") {
+                    self.puzzle.reset();
+                    let twists = twists
+                        .into_iter()
+                        .map(|mut twist| {
+                            twist.layers &= self.puzzle.all_layers();
+                            self.puzzle.canonicalize_twist(twist)
+                        })
+                        .collect();
+                    self.demonstration = Some(Demonstration {
+                        ty: self.puzzle.ty(),
+                        pattern_name,
+                        twists,
+                    });
+                }
             }
 
             AppEvent::Click(mouse_button) => {
+                if mouse_button == egui::PointerButton::Primary && self.is_double_click() {
+                    self.event(Command::ResetView);
+                }
+
                 let modifiers_mask = self.modifiers_mask(None, None);
                 let matching_mousebind = self.prefs.mousebinds.iter().find(|bind| {
                     egui::PointerButton::from(bind.button) == mouse_button
@@ -262,12 +809,18 @@ impl App {
             AppEvent::Drag(delta) => {
                 let delta = delta * self.prefs.interaction.drag_sensitivity * 360.0;
                 self.puzzle.freeze_view_angle_offset();
+                let view_prefs = self.puzzle.view_prefs(&self.prefs);
                 self.puzzle
-                    .add_view_angle_offset([delta.x, delta.y], self.prefs.view(self.puzzle.ty()));
+                    .add_view_angle_offset([delta.x, delta.y], &view_prefs);
             }
             AppEvent::DragReleased => {
                 if self.prefs.interaction.realign_on_release {
                     self.puzzle.unfreeze_view_angle_offset();
+                } else if self.prefs.interaction.snap_view_on_release
+                    && !self.pressed_modifiers().shift()
+                {
+                    let view_prefs = self.puzzle.view_prefs(&self.prefs);
+                    self.puzzle.snap_view_angle_offset(&view_prefs);
                 }
             }
 
@@ -295,12 +848,35 @@ impl App {
 
             WindowEvent::Focused(false) => {
                 // Release all keys when the window loses focus.
+                self.pending_chord_key = None;
+                self.chord_keys.clear();
                 for key in std::mem::take(&mut self.pressed_keys) {
                     match key {
                         Key::Sc(sc) => self.handle_key_release(Some(sc), None),
                         Key::Vk(vk) => self.handle_key_release(None, Some(vk)),
                     }
                 }
+
+                let is_solving = matches!(self.timer_start_end, (Some(_), None));
+                if !is_solving {
+                    self.check_goal_streak_break();
+                }
+                if is_solving && self.prefs.interaction.pause_timer_on_focus_loss {
+                    self.timer_paused_at = Some(instant::Instant::now());
+                }
+            }
+            WindowEvent::Focused(true) => {
+                // Resume the timer without counting the time spent paused.
+                if let Some(paused_at) = self.timer_paused_at.take() {
+                    let paused = paused_at.elapsed();
+                    if let (Some(start), None) = &mut self.timer_start_end {
+                        *start += paused;
+                    }
+                    self.set_status_ok(format!(
+                        "Timer was paused for {}",
+                        crate::gui::windows::duration_to_str(paused),
+                    ));
+                }
             }
 
             WindowEvent::ModifiersChanged(mods) => {
@@ -336,15 +912,42 @@ impl App {
                             held |= !self.pressed_keys.insert(Key::Vk(vk));
                         }
 
-                        self.handle_key_press(sc, vk, held);
+                        if let Some(frame_step) = &mut self.frame_step {
+                            if vk == Some(VirtualKeyCode::F10) {
+                                frame_step.pending += 1;
+                                return;
+                            }
+                        }
+
+                        // Any key skips to the end of a scramble animation.
+                        if !held && self.puzzle.is_animating_scramble() {
+                            self.puzzle.skip_twist_animations();
+                            return;
+                        }
+
+                        // Key repeat events are handled by `held`, so only
+                        // debounce new presses.
+                        let keys = sc.map(Key::Sc).into_iter().chain(vk.map(Key::Vk));
+                        if !held && self.is_key_bounce(keys) {
+                            log::trace!("Ignoring key bounce for {:?} {:?}", sc, vk);
+                            return;
+                        }
+
+                        self.handle_chord_key_press(sc, vk, held);
                     }
 
                     ElementState::Released => {
+                        // A key released before its chord window passes
+                        // wasn't part of a chord.
+                        self.flush_pending_chord_key();
+
                         if let Some(sc) = sc {
                             self.pressed_keys.remove(&Key::Sc(sc));
+                            self.chord_keys.remove(&Key::Sc(sc));
                         }
                         if let Some(vk) = vk {
                             self.pressed_keys.remove(&Key::Vk(vk));
+                            self.chord_keys.remove(&Key::Vk(vk));
                         }
 
                         self.handle_key_release(sc, vk);
@@ -364,18 +967,132 @@ impl App {
             if let Some(twists) = self.puzzle.hovered_twists() {
                 if let Some(mut t) = get_twist(twists) {
                     t.layers = self.gripped_layers(t.layers);
+                    self.check_countdown_twist(t)?;
                     self.puzzle.twist(t)?;
+                    self.rumble_twist();
                 }
             }
         }
         Ok(())
     }
 
+    /// Handles a key press from the keyboard. A key that is part of a chord
+    /// keybind waits for the other key of the chord before doing anything, so
+    /// that a chord doesn't also trigger the keybinds of each of its keys.
+    fn handle_chord_key_press(
+        &mut self,
+        sc: Option<KeyMappingCode>,
+        vk: Option<VirtualKeyCode>,
+        held: bool,
+    ) {
+        let press = PendingKeyPress {
+            sc,
+            vk,
+            time: instant::Instant::now(),
+        };
+
+        if held {
+            // Ignore key repeat for keys that are waiting for a chord or
+            // were part of one.
+            let is_pending = self
+                .pending_chord_key
+                .map_or(false, |pending| pending.keys().any(|k| press.has_key(k)));
+            if !is_pending && !press.keys().any(|k| self.chord_keys.contains(&k)) {
+                self.handle_key_press(sc, vk, held);
+            }
+            return;
+        }
+
+        let window = instant::Duration::from_millis(self.prefs.interaction.chord_window_ms as u64);
+        if let Some(pending) = self
+            .pending_chord_key
+            .filter(|pending| press.time - pending.time < window)
+        {
+            let active_puzzle_keybinds =
+                self.prefs.puzzle_keybinds[self.puzzle.ty()].get_active_keybinds();
+            let puzzle_binds = self
+                .resolve_chord(active_puzzle_keybinds, pending, press)
+                .into_iter()
+                .cloned()
+                .collect_vec();
+            let global_binds = self
+                .resolve_chord(&self.prefs.global_keybinds, pending, press)
+                .into_iter()
+                .cloned()
+                .collect_vec();
+            if !puzzle_binds.is_empty() || !global_binds.is_empty() {
+                self.pending_chord_key = None;
+                self.chord_keys.extend(pending.keys().chain(press.keys()));
+                self.run_keybinds(&puzzle_binds, &global_binds, false);
+                return;
+            }
+        }
+        // Otherwise the key that was waiting wasn't part of a chord.
+        self.flush_pending_chord_key();
+
+        if self.is_chord_key(press) {
+            self.pending_chord_key = Some(press);
+        } else {
+            self.handle_key_press(sc, vk, held);
+        }
+    }
+    /// Handles the key press waiting for a chord as a single key press, once
+    /// the chord window has passed.
+    fn update_pending_chord_key(&mut self) {
+        let window = instant::Duration::from_millis(self.prefs.interaction.chord_window_ms as u64);
+        if let Some(pending) = self.pending_chord_key {
+            if pending.time.elapsed() >= window {
+                self.flush_pending_chord_key();
+            }
+        }
+    }
+    /// Handles the key press waiting for a chord, if there is one, as a single
+    /// key press.
+    fn flush_pending_chord_key(&mut self) {
+        if let Some(pending) = self.pending_chord_key.take() {
+            self.handle_key_press(pending.sc, pending.vk, false);
+        }
+    }
+    /// Returns whether a key press is one of the keys of any active chord
+    /// keybind.
+    fn is_chord_key(&self, press: PendingKeyPress) -> bool {
+        let is_in_chord =
+            |key: KeyCombo| key.chord().is_some() && press.keys().any(|k| key.contains_key(k));
+        self.prefs.puzzle_keybinds[self.puzzle.ty()]
+            .get_active_keybinds()
+            .any(|bind| is_in_chord(bind.key))
+            || self
+                .prefs
+                .global_keybinds
+                .iter()
+                .any(|bind| is_in_chord(bind.key))
+    }
+
     fn handle_key_press(
         &mut self,
         sc: Option<KeyMappingCode>,
         vk: Option<VirtualKeyCode>,
         held: bool,
+    ) {
+        let active_puzzle_keybinds =
+            self.prefs.puzzle_keybinds[self.puzzle.ty()].get_active_keybinds();
+        let puzzle_binds = self
+            .resolve_keypress(active_puzzle_keybinds, sc, vk)
+            .into_iter()
+            .cloned()
+            .collect_vec();
+        let global_binds = self
+            .resolve_keypress(&self.prefs.global_keybinds, sc, vk)
+            .into_iter()
+            .cloned()
+            .collect_vec();
+        self.run_keybinds(&puzzle_binds, &global_binds, held);
+    }
+    fn run_keybinds(
+        &mut self,
+        puzzle_binds: &[Keybind<PuzzleCommand>],
+        global_binds: &[Keybind<Command>],
+        held: bool,
     ) {
         // Only allow one twist command per keypress. Don't use
         // multiple keybinds for macros.
@@ -392,9 +1109,7 @@ impl App {
         let mut success = false;
         let mut grip_error = None;
 
-        let active_puzzle_keybinds =
-            self.prefs.puzzle_keybinds[self.puzzle.ty()].get_active_keybinds();
-        for bind in self.resolve_keypress(active_puzzle_keybinds, sc, vk) {
+        for bind in puzzle_binds {
             let key = bind.key.key().unwrap();
             match &bind.command {
                 PuzzleCommand::Grip { axis, layers } => {
@@ -420,6 +1135,7 @@ impl App {
                     axis,
                     direction,
                     layers,
+                    layer_group,
                 } => {
                     if !done_twist_command {
                         if self.prefs.interaction.realign_on_keypress {
@@ -427,7 +1143,16 @@ impl App {
                         } else {
                             self.puzzle.apply_transient_rotation();
                         }
-                        let layers = layers.to_layer_mask(self.puzzle.layer_count());
+                        let layers = match layer_group {
+                            Some(group_name) => match self.layer_group_mask(group_name) {
+                                Ok(layers) => layers,
+                                Err(e) => {
+                                    grip_error = Some(e);
+                                    continue;
+                                }
+                            },
+                            None => layers.to_layer_mask(self.puzzle.layer_count()),
+                        };
                         match self.do_twist(axis.as_deref(), direction, layers) {
                             Ok(()) => {
                                 done_twist_command = true;
@@ -574,10 +1299,20 @@ impl App {
             }
         }
 
-        for bind in self.resolve_keypress(&self.prefs.global_keybinds, sc, vk) {
+        for bind in global_binds {
             match &bind.command {
                 Command::None => return, // Do not try to match other keybinds.
 
+                // Peek only while the key is held.
+                Command::PeekLastLayer => {
+                    if !held {
+                        self.peek_key = bind.key.key();
+                        let angle = self.prefs.interaction.peek_angle;
+                        self.puzzle.start_peek(angle, &self.prefs);
+                    }
+                    success = true;
+                }
+
                 _ => {
                     self.event(bind.command.clone());
 
@@ -594,9 +1329,146 @@ impl App {
             }
         }
     }
+    /// Runs the gamepad binds for buttons pressed since the last frame.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn update_gamepads(&mut self) {
+        for button in self.gamepads.pressed_buttons() {
+            self.reset_idle_timer();
+            let commands = self
+                .prefs
+                .gamepad_binds
+                .iter()
+                .filter(|bind| bind.button == button)
+                .map(|bind| bind.command.clone())
+                .collect_vec();
+            for command in commands {
+                self.event(command);
+            }
+        }
+    }
+    /// Rumbles connected gamepads briefly for a twist, if enabled.
+    fn rumble_twist(&mut self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.prefs.gamepad.rumble_on_twist {
+            let intensity = self.prefs.gamepad.twist_rumble_intensity;
+            self.gamepads
+                .rumble(intensity, crate::gamepad::TWIST_RUMBLE_MS);
+        }
+    }
+    /// Rumbles connected gamepads for a solve, if enabled.
+    fn rumble_solve(&mut self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.prefs.gamepad.rumble_on_solve {
+            let intensity = self.prefs.gamepad.solve_rumble_intensity;
+            self.gamepads
+                .rumble(intensity, crate::gamepad::SOLVE_RUMBLE_MS);
+        }
+    }
+
+    /// Records keyboard or mouse input, which stops the idle animation.
+    pub(crate) fn reset_idle_timer(&mut self) {
+        self.last_input = instant::Instant::now();
+        self.puzzle.stop_idle_rotation();
+        self.saved_while_dimmed = false;
+    }
+    /// Returns whether the puzzle should rotate on its own because there
+    /// hasn't been any input for a while. This never happens during a solve.
+    pub(crate) fn is_idle(&self) -> bool {
+        let prefs = &self.prefs.interaction.idle_rotation;
+        let is_solving = matches!(self.timer_start_end, (Some(_), None));
+        prefs.enabled && !is_solving && self.last_input.elapsed().as_secs_f32() >= prefs.delay
+    }
+    /// Returns whether the display should be dimmed because there hasn't been
+    /// any input for a while. This never happens during a solve.
+    pub(crate) fn is_dimmed(&self) -> bool {
+        let prefs = &self.prefs.interaction.idle_dim;
+        let is_solving = matches!(self.timer_start_end, (Some(_), None));
+        prefs.enabled && !is_solving && self.last_input.elapsed().as_secs_f32() >= prefs.delay
+    }
+    /// Saves the preferences, the window layout, and the puzzle (if it has a
+    /// log file already) once the display is dimmed for being idle.
+    fn update_idle_save(&mut self) {
+        if !self.is_dimmed() || self.saved_while_dimmed {
+            return;
+        }
+        self.saved_while_dimmed = true;
+        self.save_layout_requested = true;
+        self.prefs.needs_save = true;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.puzzle.is_unsaved() {
+            if let Some(path) = self.prefs.log_file.clone() {
+                match crate::logfile::save_file(&path, &mut self.puzzle) {
+                    Ok(()) => {
+                        self.puzzle.mark_saved();
+                        self.set_status_ok(format!("Saved log file to {}", path.display()));
+                    }
+                    Err(e) => self.set_status_err(format!("Unable to save log file: {e}")),
+                }
+            }
+        }
+    }
+    /// Records mouse input, which shows the cursor if it was hidden.
+    pub(crate) fn reset_cursor_timer(&mut self) {
+        self.last_mouse_input = instant::Instant::now();
+    }
+    /// Returns whether the mouse cursor should be hidden because it is over
+    /// the puzzle and there hasn't been any mouse input for a while.
+    pub(crate) fn should_hide_cursor(&self) -> bool {
+        let prefs = &self.prefs.interaction;
+        prefs.hide_cursor
+            && self.cursor_pos.is_some()
+            && self.last_mouse_input.elapsed().as_secs_f32() >= prefs.hide_cursor_delay
+    }
+
+    /// Records a click with the primary mouse button and returns whether it
+    /// completes a double-click on the background of the puzzle view.
+    fn is_double_click(&mut self) -> bool {
+        if self.puzzle.hovered_sticker().is_some() {
+            self.last_background_click = None;
+            return false;
+        }
+
+        let window = instant::Duration::from_millis(self.prefs.interaction.double_click_ms as u64);
+        let now = instant::Instant::now();
+        match self.last_background_click.take() {
+            Some(last_click) if now - last_click < window => true,
+            _ => {
+                self.last_background_click = Some(now);
+                false
+            }
+        }
+    }
+    /// Records a new press of a key and returns whether it came so soon after
+    /// the previous press of the same key that it should be ignored.
+    fn is_key_bounce(&mut self, keys: impl IntoIterator<Item = Key>) -> bool {
+        let debounce =
+            instant::Duration::from_millis(self.prefs.interaction.key_debounce_ms as u64);
+        if debounce.is_zero() {
+            return false;
+        }
+
+        let now = instant::Instant::now();
+        let mut is_bounce = false;
+        for key in keys {
+            if let Some(last_press) = self.last_key_presses.insert(key, now) {
+                is_bounce |= now - last_press < debounce;
+            }
+        }
+        is_bounce
+    }
+
     fn handle_key_release(&mut self, sc: Option<KeyMappingCode>, vk: Option<VirtualKeyCode>) {
         // Remove grips for this held key.
         self.remove_held_grips(|k| Some(k) == sc.map(Key::Sc) || Some(k) == vk.map(Key::Vk));
+
+        // Stop peeking if this is the key that started it.
+        if self.peek_key.is_some()
+            && (self.peek_key == sc.map(Key::Sc) || self.peek_key == vk.map(Key::Vk))
+        {
+            self.peek_key = None;
+            self.puzzle.end_peek();
+        }
     }
 
     pub(crate) fn resolve_keypress<'a, C>(
@@ -618,7 +1490,33 @@ impl App {
                 let key_matches = (sc.is_some() && sc == key) || (vk.is_some() && vk == key);
                 let mods_match =
                     key_combo.mods() & modifiers_mask == self.pressed_modifiers() & modifiers_mask;
-                key_matches && mods_match
+                // Chord keybinds are matched by `resolve_chord()` instead.
+                key_matches && mods_match && key_combo.chord().is_none()
+            })
+            .collect()
+    }
+    /// Returns the chord keybinds for two keys pressed at nearly the same
+    /// time, in either order.
+    fn resolve_chord<'a, C>(
+        &self,
+        keybinds: impl IntoIterator<Item = &'a Keybind<C>>,
+        first: PendingKeyPress,
+        second: PendingKeyPress,
+    ) -> Vec<&'a Keybind<C>> {
+        let modifiers_mask = self.modifiers_mask(None, None);
+
+        keybinds
+            .into_iter()
+            .filter(move |bind| {
+                let key_combo = bind.key;
+                let (Some(a), Some(b)) = (key_combo.key(), key_combo.chord()) else {
+                    return false;
+                };
+                let keys_match =
+                    first.has_key(a) && second.has_key(b) || first.has_key(b) && second.has_key(a);
+                let mods_match =
+                    key_combo.mods() & modifiers_mask == self.pressed_modifiers() & modifiers_mask;
+                keys_match && mods_match
             })
             .collect()
     }
@@ -693,13 +1591,28 @@ impl App {
         direction: &str,
         layers: LayerMask,
     ) -> Result<(), String> {
-        self.event(AppEvent::Twist(Twist {
+        let twist = Twist {
             axis: self.gripped_twist_axis(twist_axis)?,
             direction: self.twist_direction_from_name(direction)?,
             layers: self.gripped_layers(layers),
-        }));
+        };
+        let ty = self.puzzle.ty();
+        let convention = &self.prefs.interaction.twist_conventions[ty];
+        self.event(AppEvent::Twist(convention.apply(ty, twist)));
         Ok(())
     }
+    /// Returns the layers in a layer group of the current puzzle.
+    pub(crate) fn layer_group_mask(&self, group_name: &str) -> Result<LayerMask, String> {
+        let ty = self.puzzle.ty();
+        let group = self.prefs.layer_groups[ty]
+            .iter()
+            .find(|preset| preset.preset_name == group_name)
+            .ok_or_else(|| format!("no layer group named {group_name:?} for {ty}"))?;
+        group
+            .value
+            .layer_mask(ty.layer_count())
+            .map_err(|e| format!("{e}: {group_name:?}"))
+    }
     pub(crate) fn do_recenter(&self, twist_axis: Option<&str>) -> Result<(), String> {
         let axis = self.gripped_twist_axis(twist_axis)?;
         self.event(AppEvent::Twist(self.puzzle.make_recenter_twist(axis)?));
@@ -712,6 +1625,15 @@ impl App {
     pub(crate) fn pressed_modifiers(&self) -> ModifiersState {
         self.pressed_modifiers | self.toggled_modifiers
     }
+    pub(crate) fn toggled_keys(&self) -> &HashSet<Key> {
+        &self.toggled_keys
+    }
+    /// Presses and immediately releases a key, as if it were tapped on a
+    /// keyboard.
+    pub(crate) fn tap_key(&mut self, sc: Option<KeyMappingCode>, vk: Option<VirtualKeyCode>) {
+        self.handle_key_press(sc, vk, false);
+        self.handle_key_release(sc, vk);
+    }
     pub(crate) fn toggle_key(&mut self, sc: Option<KeyMappingCode>, vk: Option<VirtualKeyCode>) {
         let maybe_vk = vk.map(Key::Vk);
         let maybe_sc = sc.map(Key::Sc);
@@ -749,59 +1671,688 @@ impl App {
 
     pub(crate) fn frame(&mut self) {
         self.puzzle.set_grip(self.grip(), &self.prefs.interaction);
+        let solved_definition = self.prefs.interaction.solved_definitions[self.puzzle.ty()];
+        self.puzzle.set_solved_definition(solved_definition);
 
-        if self.puzzle.check_just_solved() {
-            self.set_status_ok("Solved!");
+        if let Some(training_wheels) = &mut self.training_wheels {
+            training_wheels.update(self.puzzle.latest());
         }
-    }
+        #[cfg(not(target_arch = "wasm32"))]
+        self.update_replay();
+        self.update_orientation_scramble();
+        self.update_inspection();
+        self.update_countdown();
+        self.update_time_attack();
+        self.update_pending_chord_key();
+        #[cfg(not(target_arch = "wasm32"))]
+        self.update_gamepads();
+        self.update_reaction_time();
+        self.update_idle_save();
+        let is_solving = matches!(self.timer_start_end, (Some(_), None));
+        self.used_training_wheels =
+            self.training_wheels.is_some() || (is_solving && self.used_training_wheels);
+
+        // Always check, so that a solve in zen mode isn't detected after
+        // leaving zen mode.
+        if self.puzzle.check_just_solved() && !self.zen_mode {
+            let drill_rep = self.drill.record_solve(&self.puzzle);
+            let case_rep = self.case_trainer.record_solve(&self.puzzle);
+            let is_practice_rep = drill_rep.is_some() || case_rep.is_some();
+            match (drill_rep, case_rep) {
+                (Some((variant, time)), _) => self.set_status_ok(format!(
+                    "Solved {variant} in {}",
+                    crate::gui::windows::duration_to_str(time),
+                )),
+                (None, Some((case, time))) => self.set_status_ok(format!(
+                    "Solved {} in {}",
+                    case.name,
+                    crate::gui::windows::duration_to_str(time),
+                )),
+                (None, None) => self.set_status_ok("Solved!"),
+            }
+            self.rumble_solve();
+
+            // Stop the timer and record the solve.
+            if let (Some(start), None) = self.timer_start_end {
+                let end = self.timer_now();
+                self.timer_start_end = (Some(start), Some(end));
+                self.timer_paused_at = None;
+                if self.used_training_wheels {
+                    self.set_status_ok("Solved! Not recorded because training wheels were on");
+                } else if counts_toward_stats(&self.puzzle, is_practice_rep) {
+                    self.record_solve(end - start);
+                } else if !is_practice_rep {
+                    // Practice reps have already reported their time.
+                    self.set_status_ok(
+                        "Solved! Not recorded because the puzzle wasn't fully scrambled",
+                    );
+                }
+            }
 
-    fn confirm_load_puzzle(&self, warnings: &[String]) -> bool {
-        warnings.is_empty()
-            || rfd::MessageDialog::new()
-                .set_title("Errors loading file")
-                .set_description(&format!(
-                    "The following errors were encountered \
-                     while loading this file. Load anyway?\n\n{}",
-                    warnings.iter().join("\n"),
-                ))
-                .set_buttons(rfd::MessageButtons::YesNo)
-                .show()
+            // Set up the next rep only after the solve has been recorded.
+            if drill_rep.is_some() {
+                self.set_up_drill_rep(true);
+            } else if self.time_attack.is_running() {
+                self.next_time_attack_scramble();
+            }
+        }
     }
 
-    fn confirm_discard_changes(&mut self, action: &str) -> bool {
-        let mut needs_save = self.puzzle.is_unsaved();
-
-        if self.prefs.interaction.confirm_discard_only_when_scrambled
-            && !self.puzzle.has_been_fully_scrambled()
-        {
-            needs_save = false;
+    /// Sets up the next variant of the drill case on the puzzle. This asks
+    /// before discarding changes, unless the previous rep has just been
+    /// solved.
+    pub(crate) fn set_up_drill_rep(&mut self, just_solved: bool) {
+        if self.puzzle.ty() != CASE_PUZZLE {
+            self.set_status_err(format!("Drills are for {}", CASE_PUZZLE.name()));
+            return;
         }
-
-        let confirm = !needs_save
-            || rfd::MessageDialog::new()
-                .set_title("Unsaved changes")
-                .set_description(&format!("Discard puzzle state and {}?", action))
-                .set_buttons(rfd::MessageButtons::YesNo)
-                .show();
-        if confirm {
+        if just_solved {
             self.prefs.log_file = None;
             self.prefs.needs_save = true;
+        } else if !self.confirm_discard_changes("set up drill") {
+            return;
         }
-        confirm
-    }
-
-    fn try_paste_puzzle(&mut self, log_file_contents: &str) {
-        match crate::logfile::deserialize(log_file_contents) {
-            Ok((puzzle, warnings)) => {
-                if self.confirm_load_puzzle(&warnings) {
-                    self.puzzle = puzzle;
-
-                    self.set_status_ok("Loaded puzzle log file from clipboard");
 
-                    self.prefs.log_file = None;
-                    self.prefs.needs_save = true;
-                }
-            }
+        let (variant, setup) = match self.drill.next_rep() {
+            Ok(rep) => rep,
+            Err(e) => {
+                self.drill.stop();
+                self.set_status_err(e);
+                return;
+            }
+        };
+        if let Err(e) = self.puzzle.apply_pattern(setup) {
+            self.drill.stop();
+            self.set_status_err(e);
+            return;
+        }
+        self.puzzle.add_scramble_marker(ScrambleState::Partial);
+        self.drill.start(variant, &self.puzzle);
+        if !just_solved {
+            self.set_status_ok(format!("Set up {variant}"));
+        }
+    }
+
+    /// Timestamps new entries in the undo history and applies the twists of
+    /// the replay being played back whose time has come.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn update_replay(&mut self) {
+        let elapsed = match self.timer_start_end {
+            (Some(start), None) => self.timer_now() - start,
+            _ => instant::Duration::ZERO,
+        };
+        self.twist_times
+            .resize(self.puzzle.undo_buffer().len(), elapsed);
+
+        let Some(playback) = &self.replay else {
+            return;
+        };
+        let Some(progress) = playback.progress(&self.puzzle) else {
+            self.stop_replay();
+            self.set_status_ok("Stopped replay");
+            return;
+        };
+        let elapsed = playback.elapsed();
+        let due_twists = playback.replay.twists[progress..]
+            .iter()
+            .take_while(|&&(time, _)| time <= elapsed)
+            .map(|&(_, twist)| twist)
+            .collect_vec();
+        for twist in due_twists {
+            if let Err(e) = self.puzzle.twist_no_collapse(twist) {
+                self.stop_replay();
+                self.set_status_err(format!("Stopped replay: {e}"));
+                return;
+            }
+        }
+    }
+
+    /// Stops playing back a replay and goes back to the saved colors.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn stop_replay(&mut self) {
+        self.replay = None;
+        self.prefs.colors.temporary_faces = None;
+    }
+
+    /// Replays the scramble that was just applied as twist animations, if
+    /// that's enabled, so that it can be followed on a physical puzzle.
+    fn animate_scramble(&mut self) {
+        if self.prefs.interaction.animate_scramble {
+            if let Err(e) = self.puzzle.animate_scramble() {
+                log::warn!("Error animating scramble: {}", e);
+            }
+        }
+    }
+
+    /// Applies the orientation scramble once it has been found, unless the
+    /// puzzle has changed since it was requested.
+    fn update_orientation_scramble(&mut self) {
+        let Some((job, requested_from)) = &self.orientation_scramble else {
+            return;
+        };
+        if self.puzzle.latest() != requested_from {
+            job.cancel();
+            self.orientation_scramble = None;
+            return;
+        }
+        let Some(result) = job.result() else {
+            return;
+        };
+        self.orientation_scramble = None;
+
+        match result.and_then(|scramble| self.puzzle.scramble_orientation(scramble)) {
+            Ok(()) => {
+                self.animate_scramble();
+                self.set_status_ok("Scrambled orientation");
+            }
+            Err(e) => self.set_status_err(e),
+        }
+    }
+
+    /// Starts inspection before a timed solve, if it's enabled and the puzzle
+    /// has just been fully scrambled.
+    fn start_inspection(&mut self) {
+        self.inspection_start = None;
+        if self.prefs.interaction.inspection.enabled
+            && !self.zen_mode
+            && self.puzzle.has_been_fully_scrambled()
+        {
+            self.inspection_start = Some(instant::Instant::now());
+            self.countdown_start = None;
+            self.timer_start_end = (None, None);
+            self.timer_paused_at = None;
+            self.new_pbs.clear();
+        }
+    }
+    /// Ends inspection and starts the timer, with a penalty if inspection
+    /// went over time.
+    pub(crate) fn end_inspection(&mut self) {
+        let Some(remaining) = self.inspection_remaining() else {
+            return;
+        };
+        self.inspection_start = None;
+        self.start_timer();
+        self.inspection_penalty = Penalty::from_inspection_time_left(remaining);
+    }
+    /// Returns the inspection time left in seconds, which is negative once
+    /// inspection goes over time, or `None` if there is no inspection in
+    /// progress.
+    pub(crate) fn inspection_remaining(&self) -> Option<f32> {
+        let start = self.inspection_start?;
+        Some(self.prefs.interaction.inspection.duration - start.elapsed().as_secs_f32())
+    }
+    /// Ends inspection once the puzzle is twisted, other than by rotating the
+    /// whole puzzle.
+    fn update_inspection(&mut self) {
+        if self.inspection_start.is_none() {
+            return;
+        }
+        if !self.puzzle.has_been_fully_scrambled() {
+            self.inspection_start = None;
+            return;
+        }
+        if !self.has_twisted_since(0) {
+            return;
+        }
+
+        self.end_inspection();
+        // The twist that ends inspection also starts the timer, so there's no
+        // time before it.
+        self.reaction_time = Some(instant::Duration::ZERO);
+        match self.prefs.interaction.inspection.twist_action {
+            InspectionTwistAction::StartSolve => (),
+            InspectionTwistAction::Penalize => {
+                if self.inspection_penalty == Penalty::None {
+                    self.inspection_penalty = Penalty::PlusTwo;
+                }
+                self.set_status_err("Twisted the puzzle during inspection (+2)");
+            }
+        }
+    }
+
+    /// Starts the countdown before a timed solve if it's enabled, or else
+    /// starts the timer right away.
+    pub(crate) fn start_countdown(&mut self) {
+        if self.prefs.interaction.countdown.enabled && !self.zen_mode {
+            self.countdown_start = Some(instant::Instant::now());
+            self.timer_start_end = (None, None);
+            self.timer_paused_at = None;
+        } else {
+            self.start_timer();
+        }
+    }
+    /// Returns the countdown time left in seconds, or `None` if there is no
+    /// countdown in progress.
+    pub(crate) fn countdown_remaining(&self) -> Option<f32> {
+        let start = self.countdown_start?;
+        Some(self.prefs.interaction.countdown.seconds - start.elapsed().as_secs_f32())
+    }
+    /// Starts the timer once the countdown reaches zero.
+    fn update_countdown(&mut self) {
+        let Some(remaining) = self.countdown_remaining() else {
+            return;
+        };
+        if remaining <= 0.0 {
+            self.countdown_start = None;
+            self.start_timer();
+        }
+    }
+    /// Returns an error if `twist` does more than rotate the whole puzzle
+    /// while the countdown is in progress.
+    fn check_countdown_twist(&self, twist: Twist) -> Result<(), &'static str> {
+        if self.countdown_start.is_some() && twist.layers != self.puzzle.all_layers() {
+            return Err("Wait for the countdown to finish");
+        }
+        Ok(())
+    }
+
+    /// Starts a time attack, asking before discarding changes.
+    pub(crate) fn start_time_attack(&mut self) {
+        if self.zen_mode {
+            self.set_status_err("Solves aren't recorded in zen mode");
+            return;
+        }
+        if !self.confirm_discard_changes("start time attack") {
+            return;
+        }
+        self.time_attack.start(instant::Instant::now());
+        self.next_time_attack_scramble();
+    }
+    /// Scrambles the puzzle fully and starts the timer right away for the next
+    /// solve of the time attack. There is no inspection, since the time attack
+    /// is already counting down.
+    fn next_time_attack_scramble(&mut self) {
+        self.prefs.log_file = None;
+        self.prefs.needs_save = true;
+        if let Err(e) = self.puzzle.scramble_full() {
+            self.time_attack.stop();
+            self.set_status_err(e);
+            return;
+        }
+        self.reset_timer();
+        self.start_timer();
+    }
+    /// Stops the time attack in progress. The solve in progress isn't
+    /// recorded.
+    pub(crate) fn stop_time_attack(&mut self) {
+        if !self.time_attack.is_running() {
+            return;
+        }
+        self.time_attack.stop();
+        self.reset_timer();
+        let count = self.time_attack.solve_count();
+        match self.time_attack.mean() {
+            Some(mean) => self.set_status_ok(format!(
+                "Time attack over: {count} solves, mean {}",
+                crate::gui::windows::duration_to_str(mean),
+            )),
+            None => self.set_status_ok("Time attack over: no solves"),
+        }
+    }
+    /// Stops the time attack once it runs out of time.
+    fn update_time_attack(&mut self) {
+        if self.time_attack.is_over(instant::Instant::now()) {
+            self.stop_time_attack();
+        }
+    }
+
+    /// Clears the timer, inspection, and countdown, so that the timer is ready
+    /// for a new solve.
+    fn reset_timer(&mut self) {
+        self.timer_start_end = (None, None);
+        self.timer_paused_at = None;
+        self.inspection_start = None;
+        self.inspection_penalty = Penalty::None;
+        self.countdown_start = None;
+        self.new_pbs.clear();
+    }
+    /// Starts the timer for a new timed solve.
+    pub(crate) fn start_timer(&mut self) {
+        self.timer_start_end = (Some(instant::Instant::now()), None);
+        self.timer_paused_at = None;
+        self.inspection_penalty = Penalty::None;
+        self.timer_start_undo_len = self.puzzle.undo_buffer().len();
+        self.reaction_time = None;
+    }
+    /// Measures the reaction time once the puzzle is first twisted after the
+    /// timer starts.
+    fn update_reaction_time(&mut self) {
+        let (Some(start), None) = self.timer_start_end else {
+            return;
+        };
+        if self.reaction_time.is_none() && self.has_twisted_since(self.timer_start_undo_len) {
+            self.reaction_time = Some(self.timer_now() - start);
+        }
+    }
+    /// Returns whether any entry in the undo history from index `start` does
+    /// more than rotate the whole puzzle.
+    fn has_twisted_since(&self, start: usize) -> bool {
+        let all_layers = self.puzzle.all_layers();
+        self.puzzle
+            .undo_buffer()
+            .get(start..)
+            .unwrap_or_default()
+            .iter()
+            .flat_map(|entry| entry.twists())
+            .any(|twist| twist.layers != all_layers)
+    }
+
+    /// Returns the current time according to the timer, which stands still
+    /// while the timer is paused.
+    pub(crate) fn timer_now(&self) -> instant::Instant {
+        self.timer_paused_at.unwrap_or_else(instant::Instant::now)
+    }
+
+    /// Returns the time to advance animations by this frame, given the real
+    /// time since the last frame. In frame step mode, the timer is adjusted to
+    /// advance by the same amount.
+    pub(crate) fn animation_delta(&mut self, real_delta: instant::Duration) -> instant::Duration {
+        let Some(frame_step) = &mut self.frame_step else {
+            return real_delta;
+        };
+        let delta = frame_step.step * std::mem::take(&mut frame_step.pending);
+        if let (Some(start), None) = &mut self.timer_start_end {
+            *start += real_delta;
+            *start -= delta;
+        }
+        delta
+    }
+
+    fn record_solve(&mut self, duration: instant::Duration) {
+        let ty = self.puzzle.ty();
+        let notation = self.puzzle.notation_scheme();
+        let penalty = std::mem::take(&mut self.inspection_penalty);
+        if penalty != Penalty::None && self.prefs.info.penalty_flash.enabled {
+            self.penalty_flash = Some((penalty, instant::Instant::now()));
+        }
+        self.session.solves.push(Solve {
+            puzzle: ty,
+            millis: duration.as_millis() as u64,
+            penalty,
+            reaction_millis: self
+                .reaction_time
+                .take()
+                .map(|reaction_time| reaction_time.as_millis() as u64),
+            down_color: self.prefs.colors.down_color[ty].clone(),
+            twist_count: TwistMetric::iter()
+                .map(|metric| (metric, self.puzzle.twist_count(metric, false)))
+                .collect(),
+            scramble: self
+                .puzzle
+                .scramble()
+                .iter()
+                .map(|&twist| notation.twist_to_string(twist))
+                .join(" "),
+        });
+        self.update_personal_bests(ty);
+        self.update_goal_streak();
+        if let Some(solve) = self.session.solves.last() {
+            self.time_attack.record_solve(solve.clone());
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        self.append_to_solve_log();
+    }
+    /// Appends the most recent solve to the solve log, if it is enabled.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn append_to_solve_log(&mut self) {
+        let prefs = &self.prefs.solve_log;
+        let (true, Some(dir), Some(solve)) =
+            (prefs.enabled, &prefs.directory, self.session.solves.last())
+        else {
+            return;
+        };
+        let notation = self.puzzle.notation_scheme();
+        let solution = self
+            .puzzle
+            .combined_solution()
+            .iter()
+            .map(|&twist| notation.twist_to_string(twist))
+            .join(" ");
+        let result = crate::logfile::solve_log::append(dir, solve, &solution, prefs.rotation());
+        if let Err(e) = result {
+            self.set_status_err(format!("Unable to write to solve log: {e}"));
+        }
+    }
+    /// Extends or resets the streak toward the streak goal with the most
+    /// recent solve, and celebrates if it reaches the goal.
+    fn update_goal_streak(&mut self) {
+        let prefs = self.prefs.info.streak_goal;
+        let Some(solve_index) = self.session.solves.len().checked_sub(1) else {
+            return;
+        };
+        if !prefs.enabled {
+            return;
+        }
+        // Time spent solving doesn't count as a break.
+        if let (Some(max_break), Some(start)) = (prefs.max_break(), self.timer_start_end.0) {
+            self.goal_streak.check_break(max_break, start);
+        }
+        let target_time = prefs.target_duration();
+        if self
+            .goal_streak
+            .record(&self.session, solve_index, target_time, prefs.solve_count)
+        {
+            self.streak_celebration = Some(instant::Instant::now());
+            self.set_status_ok(format!(
+                "Streak goal reached! {} solves in a row under {}",
+                prefs.solve_count,
+                crate::gui::windows::duration_to_str(target_time),
+            ));
+        }
+    }
+    /// Resets the streak toward the streak goal after a long enough break
+    /// between solves.
+    fn check_goal_streak_break(&mut self) {
+        let prefs = self.prefs.info.streak_goal;
+        let (true, Some(max_break)) = (prefs.enabled, prefs.max_break()) else {
+            return;
+        };
+        if self
+            .goal_streak
+            .check_break(max_break, instant::Instant::now())
+        {
+            self.set_status_ok("Streak reset after a break");
+        }
+    }
+    fn update_personal_bests(&mut self, ty: PuzzleTypeEnum) {
+        self.new_pbs = self.prefs.personal_bests[ty].update(&self.session, ty);
+        self.prefs.needs_save = true;
+        if !self.new_pbs.is_empty() {
+            self.set_status_ok(format!("New PB! ({})", self.new_pbs.iter().join(", ")));
+        }
+    }
+
+    /// Changes the penalty of the most recent solve and re-evaluates any
+    /// personal bests it set.
+    pub(crate) fn set_last_solve_penalty(&mut self, penalty: Penalty) {
+        let Some(solve_index) = self.session.solves.len().checked_sub(1) else {
+            return;
+        };
+        let solve = &mut self.session.solves[solve_index];
+        if solve.penalty == penalty {
+            return;
+        }
+        if self.time_attack.session.solves.last() == Some(&*solve) {
+            if let Some(time_attack_solve) = self.time_attack.session.solves.last_mut() {
+                time_attack_solve.penalty = penalty;
+            }
+        }
+        solve.penalty = penalty;
+        let ty = solve.puzzle;
+        if penalty != Penalty::None && self.prefs.info.penalty_flash.enabled {
+            self.penalty_flash = Some((penalty, instant::Instant::now()));
+        }
+        self.prefs.personal_bests[ty].revert_solve(solve_index);
+        self.update_personal_bests(ty);
+        if self.goal_streak.revert_solve(solve_index) {
+            self.update_goal_streak();
+        }
+    }
+
+    /// Clears the session, without affecting all-time personal bests.
+    pub(crate) fn clear_session(&mut self) {
+        self.session = Session::default();
+        self.new_pbs.clear();
+        self.goal_streak = GoalStreak::default();
+        for pbs in self.prefs.personal_bests.values_mut() {
+            pbs.forget_session();
+        }
+    }
+
+    fn confirm_load_puzzle(&self, warnings: &[String]) -> bool {
+        warnings.is_empty()
+            || rfd::MessageDialog::new()
+                .set_title("Errors loading file")
+                .set_description(&format!(
+                    "The following errors were encountered \
+                     while loading this file. Load anyway?\n\n{}",
+                    warnings.iter().join("\n"),
+                ))
+                .set_buttons(rfd::MessageButtons::YesNo)
+                .show()
+    }
+
+    fn confirm_discard_changes(&mut self, action: &str) -> bool {
+        let confirm = if self.needs_confirm_discard_solve() {
+            self.confirm_discard_solve(action)
+        } else {
+            !self.needs_confirm_discard(&self.puzzle)
+                || rfd::MessageDialog::new()
+                    .set_title("Unsaved changes")
+                    .set_description(&format!("Discard puzzle state and {}?", action))
+                    .set_buttons(rfd::MessageButtons::YesNo)
+                    .show()
+        };
+        if confirm {
+            self.prefs.log_file = None;
+            self.prefs.needs_save = true;
+        }
+        confirm
+    }
+    fn confirm_discard_background_puzzle(&self, action: &str) -> bool {
+        let Some((background_puzzle, _)) = &self.background_puzzle else {
+            return true;
+        };
+        !self.needs_confirm_discard(background_puzzle)
+            || rfd::MessageDialog::new()
+                .set_title("Unsaved changes")
+                .set_description(&format!(
+                    "Discard state of background {} and {}?",
+                    background_puzzle.ty(),
+                    action,
+                ))
+                .set_buttons(rfd::MessageButtons::YesNo)
+                .show()
+    }
+    /// Asks for confirmation if there is a solve in progress, which is when
+    /// the timer is running and the puzzle has been twisted since the
+    /// scramble.
+    fn confirm_discard_solve(&self, action: &str) -> bool {
+        !self.needs_confirm_discard_solve()
+            || rfd::MessageDialog::new()
+                .set_title("Solve in progress")
+                .set_description(&format!(
+                    "Discard the solve in progress and {action}?\n\n\
+                     This confirmation can be turned off in the interaction settings.",
+                ))
+                .set_buttons(rfd::MessageButtons::YesNo)
+                .show()
+    }
+    fn needs_confirm_discard_solve(&self) -> bool {
+        self.prefs.interaction.confirm_discard_solve
+            && matches!(self.timer_start_end, (Some(_), None))
+            && !self.puzzle.undo_buffer().is_empty()
+    }
+    fn needs_confirm_discard(&self, puzzle: &PuzzleController) -> bool {
+        puzzle.is_unsaved()
+            && (!self.prefs.interaction.confirm_discard_only_when_scrambled
+                || puzzle.has_been_fully_scrambled())
+    }
+
+    /// Returns the title of the window, which may include the puzzle name and
+    /// the timer depending on preferences.
+    pub(crate) fn window_title(&self) -> String {
+        let mut ret = crate::TITLE.to_owned();
+        if self.prefs.info.title_puzzle {
+            ret += &format!(" - {}", self.puzzle.name());
+        }
+        if self.prefs.info.title_timer && self.prefs.info.show_time {
+            if let (Some(start), end) = self.timer_start_end {
+                let elapsed = end.unwrap_or_else(|| self.timer_now()) - start;
+                ret += &format!(" - {}", crate::gui::windows::duration_to_str(elapsed));
+            }
+        }
+        ret
+    }
+
+    /// Loads a new puzzle of the given type. The puzzle and its geometry are
+    /// built on another thread, because big puzzles can take a while, and
+    /// then [`AppEvent::PuzzleBuilt`] loads the puzzle.
+    fn build_puzzle(&mut self, ty: PuzzleTypeEnum) {
+        self.building_puzzle = Some(ty);
+        self.puzzle_build_generation += 1;
+        let generation = self.puzzle_build_generation;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.set_status_ok(format!("Building {ty}..."));
+            let events = self.events.clone();
+            // Only send the preferences that the geometry depends on.
+            let mut prefs = Preferences {
+                interaction: self.prefs.interaction.clone(),
+                opacity: self.prefs.opacity.clone(),
+                ..Default::default()
+            };
+            *prefs.view_mut(ty) = self.prefs.view(ty).clone();
+            std::thread::spawn(move || {
+                let mut puzzle = PuzzleController::new(ty);
+                // Build the geometry for the first frame, which is cached.
+                puzzle.geometry(&prefs);
+                // The event loop may have exited in the meantime.
+                let _ = events.send_event(AppEvent::PuzzleBuilt(generation, Box::new(puzzle)));
+            });
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        self.event(AppEvent::PuzzleBuilt(
+            generation,
+            Box::new(PuzzleController::new(ty)),
+        ));
+    }
+    /// Stops waiting for a puzzle from [`App::build_puzzle()`].
+    pub(crate) fn cancel_building_puzzle(&mut self) {
+        if let Some(ty) = self.building_puzzle.take() {
+            self.set_status_ok(format!("Canceled loading {ty}"));
+        }
+    }
+
+    /// Returns the type of the puzzle in the background, if there is one.
+    pub(crate) fn background_puzzle_type(&self) -> Option<PuzzleTypeEnum> {
+        self.background_puzzle
+            .as_ref()
+            .map(|(puzzle, _)| puzzle.ty())
+    }
+    /// Makes `puzzle` the active puzzle and moves the previously active puzzle
+    /// to the background, along with its log file.
+    fn swap_in_puzzle(&mut self, puzzle: PuzzleController, log_file: Option<PathBuf>) {
+        let old_puzzle = std::mem::replace(&mut self.puzzle, puzzle);
+        let old_log_file = std::mem::replace(&mut self.prefs.log_file, log_file);
+        self.background_puzzle = Some((old_puzzle, old_log_file));
+        self.prefs.needs_save = true;
+    }
+
+    fn try_paste_puzzle(&mut self, log_file_contents: &str) {
+        match crate::logfile::deserialize(log_file_contents) {
+            Ok((puzzle, warnings)) => {
+                if self.confirm_load_puzzle(&warnings) {
+                    self.puzzle = puzzle;
+
+                    self.set_status_ok("Loaded puzzle log file from clipboard");
+
+                    self.prefs.log_file = None;
+                    self.prefs.needs_save = true;
+                }
+            }
             Err(e) => {
                 self.set_status_err(format!("Unable to load puzzle log: {e}"));
             }
@@ -860,6 +2411,220 @@ impl App {
         }
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
+    fn try_open_session(&mut self) {
+        let Some(path) = session_file_dialog().pick_file() else {
+            return;
+        };
+        match crate::logfile::session::load_file(&path) {
+            Ok((session, warnings)) => {
+                let replace = self.session.solves.is_empty()
+                    || rfd::MessageDialog::new()
+                        .set_title("Replace session")
+                        .set_description(&format!(
+                            "Replace the current session ({} solves) with the opened one?",
+                            self.session.solves.len(),
+                        ))
+                        .set_buttons(rfd::MessageButtons::YesNo)
+                        .show();
+                if replace && self.confirm_load_puzzle(&warnings) {
+                    self.clear_session();
+                    self.session = session;
+                    self.set_status_ok(format!("Loaded session from {}", path.display()));
+                }
+            }
+            Err(e) => show_error_dialog(
+                "Unable to load session",
+                format!("Unable to load session:\n\n{e}"),
+            ),
+        }
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    fn try_save_session(&mut self) {
+        let Some(path) = session_file_dialog().save_file() else {
+            return;
+        };
+        match crate::logfile::session::save_file(&path, &self.session) {
+            Ok(()) => self.set_status_ok(format!("Saved session to {}", path.display())),
+            Err(e) => show_error_dialog(
+                "Unable to save session",
+                format!("Unable to save session:\n\n{e}"),
+            ),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn try_save_time_attack(&mut self) {
+        let Some(path) = session_file_dialog().save_file() else {
+            return;
+        };
+        match crate::logfile::session::save_file(&path, &self.time_attack.session) {
+            Ok(()) => self.set_status_ok(format!("Saved time attack to {}", path.display())),
+            Err(e) => show_error_dialog(
+                "Unable to save session",
+                format!("Unable to save session:\n\n{e}"),
+            ),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn try_open_replay(&mut self) {
+        if let Some(path) = replay_file_dialog().pick_file() {
+            self.try_load_replay(&path);
+        }
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    fn try_load_replay(&mut self, path: &Path) {
+        match crate::logfile::replay::load_file(path) {
+            Ok((replay, warnings)) => {
+                if self.confirm_load_puzzle(&warnings)
+                    && self.confirm_discard_changes("open replay")
+                {
+                    match self.start_replay(replay) {
+                        Ok(()) => {
+                            self.set_status_ok(format!("Playing replay from {}", path.display()))
+                        }
+                        Err(e) => show_error_dialog(
+                            "Unable to play replay",
+                            format!("Unable to play replay:\n\n{e}"),
+                        ),
+                    }
+                }
+            }
+            Err(e) => show_error_dialog(
+                "Unable to load replay",
+                format!("Unable to load replay:\n\n{e}"),
+            ),
+        }
+    }
+    /// Applies the scramble of a replay to a new puzzle, shows the colors it
+    /// was solved with, and starts playing it back.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn start_replay(&mut self, mut replay: Replay) -> Result<(), &'static str> {
+        let mut puzzle = PuzzleController::new(replay.puzzle);
+        for &twist in &replay.scramble {
+            puzzle.twist_no_collapse(twist)?;
+        }
+        puzzle.add_scramble_marker(ScrambleState::Full);
+        puzzle.skip_twist_animations();
+
+        // Canonicalize twists so that they match the undo history.
+        replay.scramble = puzzle.scramble().to_vec();
+        for (_, twist) in &mut replay.twists {
+            twist.layers &= puzzle.all_layers();
+            *twist = puzzle.canonicalize_twist(*twist);
+        }
+
+        // The saved color scheme is left alone, and comes back once the
+        // replay stops.
+        self.prefs.colors.temporary_faces =
+            (!replay.colors.is_empty()).then(|| (replay.puzzle, replay.colors.clone()));
+
+        self.puzzle = puzzle;
+        self.prefs.log_file = None;
+        self.timer_start_end = (None, None);
+        self.replay = Some(ReplayPlayback {
+            replay,
+            start: instant::Instant::now(),
+        });
+        Ok(())
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    fn try_export_replay(&mut self) {
+        let (Some(start), Some(end)) = self.timer_start_end else {
+            self.set_status_err("Finish a timed solve to export a replay");
+            return;
+        };
+        let Some(path) = replay_file_dialog().save_file() else {
+            return;
+        };
+        let ty = self.puzzle.ty();
+        let replay = Replay {
+            puzzle: ty,
+            scramble: self.puzzle.scramble().to_vec(),
+            twists: std::iter::zip(&self.twist_times, self.puzzle.undo_buffer())
+                .flat_map(|(&time, entry)| entry.twists().iter().map(move |&twist| (time, twist)))
+                .collect(),
+            duration: end - start,
+            penalty: self
+                .session
+                .solves
+                .last()
+                .map_or(Penalty::None, |solve| solve.penalty),
+            colors: self.prefs.colors.faces[ty].clone(),
+        };
+        match crate::logfile::replay::save_file(&path, &replay) {
+            Ok(()) => self.set_status_ok(format!("Exported replay to {}", path.display())),
+            Err(e) => show_error_dialog(
+                "Unable to export replay",
+                format!("Unable to export replay:\n\n{e}"),
+            ),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn try_export_render_layers(&mut self) {
+        let (width, height) = self.puzzle_texture_size;
+        if width == 0 || height == 0 {
+            self.set_status_err("Puzzle view is too small to export");
+            return;
+        }
+        let dialog = rfd::FileDialog::new()
+            .add_filter("PNG image", &["png"])
+            .set_file_name("puzzle.png");
+        let Some(path) = dialog.save_file() else {
+            return;
+        };
+
+        let layers = crate::render::render_layers(self, width, height);
+        match layers.save(&path) {
+            Ok(()) => self.set_status_ok(format!("Exported render layers to {}", path.display())),
+            Err(e) => show_error_dialog(
+                "Unable to export render layers",
+                format!("Unable to export render layers:\n\n{e}"),
+            ),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn try_export_keybinds(&mut self) {
+        let ty = self.puzzle.ty();
+        let Some(path) = keybinds_file_dialog().save_file() else {
+            return;
+        };
+        let sets = &self.prefs.puzzle_keybinds[ty].sets;
+        let result = crate::serde_impl::keybinds_file::serialize(ty, sets)
+            .map_err(|e| e.to_string())
+            .and_then(|s| std::fs::write(&path, s).map_err(|e| e.to_string()));
+        match result {
+            Ok(()) => self.set_status_ok(format!("Exported keybinds to {}", path.display())),
+            Err(e) => show_error_dialog(
+                "Unable to export keybinds",
+                format!("Unable to export keybinds:\n\n{e}"),
+            ),
+        }
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    fn try_import_keybinds(&mut self) {
+        let ty = self.puzzle.ty();
+        let Some(path) = keybinds_file_dialog().pick_file() else {
+            return;
+        };
+        let result = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|s| crate::serde_impl::keybinds_file::deserialize(&s, ty));
+        match result {
+            Ok((sets, warnings)) => {
+                self.keybinds_import = Some(KeybindsImport { ty, sets, warnings });
+                self.set_status_ok(format!("Loaded keybinds from {}", path.display()));
+            }
+            Err(e) => show_error_dialog(
+                "Unable to import keybinds",
+                format!("Unable to import keybinds:\n\n{e}"),
+            ),
+        }
+    }
+
     #[cfg(target_arch = "wasm32")]
     const LOCAL_STORAGE_KEY: &str = "hyperspeedcube_puzzle_log";
     #[cfg(target_arch = "wasm32")]
@@ -913,12 +2678,30 @@ impl App {
             .transient_grips
             .values()
             .fold(Grip::default(), |a, b| a | b);
+        if self.prefs.interaction.select_layer_ranges {
+            // Grip every layer between the lowest and highest gripped layers.
+            if let Some(LayerMask(bits)) = ret.layers.filter(|&l| l != LayerMask(0)) {
+                let lo = bits.trailing_zeros() as u8;
+                let hi = 31 - bits.leading_zeros() as u8;
+                ret.layers = Some(LayerMask::from(lo..=hi));
+            }
+        }
         ret.axes.extend(&self.toggle_grip.axes);
         if ret.layers.is_none() {
             ret.layers = self.toggle_grip.layers;
         }
         ret
     }
+    /// Returns the twists for the pattern with the given name.
+    fn pattern_twists(&self, pattern_name: &str) -> Result<Vec<Twist>, String> {
+        let ty = self.puzzle.ty();
+        let pattern = self.prefs.patterns[ty]
+            .iter()
+            .find(|p| p.preset_name == pattern_name)
+            .ok_or_else(|| format!("No pattern named {pattern_name:?}"))?;
+        ty.notation_scheme().parse_alg(&pattern.value.alg)
+    }
+
     fn remove_held_grips(&mut self, mut remove_if: impl FnMut(Key) -> bool) {
         self.transient_grips.retain(|&k, _v| !remove_if(k));
     }
@@ -934,6 +2717,42 @@ pub(crate) enum AppEvent {
     Command(Command),
 
     Twist(Twist),
+    /// Resets the puzzle and applies the pattern with the given name.
+    ApplyPattern(String),
+    /// Resets the puzzle and starts applying the pattern with the given name
+    /// one twist at a time.
+    DemonstratePattern(String),
+    /// Resets the puzzle and applies a commutator from the commutator builder
+    /// window.
+    ApplyCommutator(Vec<Twist>),
+    /// Indicates that a puzzle requested with [`App::build_puzzle()`] is ready
+    /// to load, along with the generation of the request.
+    PuzzleBuilt(u64, Box<PuzzleController>),
+    /// Generates a new scramble for the scramble preview window.
+    NewScramblePreview,
+    /// Replaces the current puzzle with the one in the scramble preview
+    /// window.
+    StartScramblePreview,
+    /// Saves the puzzle keybind sets for the current puzzle to a file chosen
+    /// by the user.
+    ExportKeybinds,
+    /// Loads puzzle keybind sets from a file chosen by the user, to be
+    /// reviewed and then applied.
+    ImportKeybinds,
+    /// Replaces the session with one loaded from a file chosen by the user.
+    OpenSession,
+    /// Saves the session to a file chosen by the user.
+    SaveSession,
+    /// Saves the solves of the most recent time attack as a session, to a
+    /// file chosen by the user.
+    SaveTimeAttack,
+    /// Plays back a replay file chosen by the user.
+    OpenReplay,
+    /// Saves the most recent timed solve to a replay file chosen by the user.
+    ExportReplay,
+    /// Saves the color, depth, and normal layers of the puzzle view to image
+    /// files chosen by the user, for compositing.
+    ExportRenderLayers,
 
     Click(egui::PointerButton),
     /// Drag event with a per-frame delta, sent every frame until the drag ends
@@ -945,6 +2764,10 @@ pub(crate) enum AppEvent {
 
     #[cfg(target_arch = "wasm32")]
     WebWorkaround(crate::web_workarounds::WebEvent),
+    /// Forgets the saved window position and moves the window to the middle
+    /// of the primary monitor.
+    #[cfg(not(target_arch = "wasm32"))]
+    ResetWindowPosition,
 }
 impl From<Command> for AppEvent {
     fn from(c: Command) -> Self {
@@ -957,11 +2780,166 @@ impl From<Twist> for AppEvent {
     }
 }
 
+/// Fixed timestep state for stepping through animations one frame at a time.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct FrameStep {
+    /// Duration of each frame.
+    pub(crate) step: instant::Duration,
+    /// Number of steps to advance on the next frame.
+    pub(crate) pending: u32,
+}
+impl Default for FrameStep {
+    fn default() -> Self {
+        Self {
+            step: instant::Duration::from_secs(1) / 60,
+            pending: 0,
+        }
+    }
+}
+
+/// Pattern being applied one twist at a time from the solved state.
+#[derive(Debug, Clone)]
+pub(crate) struct Demonstration {
+    ty: PuzzleTypeEnum,
+    pub(crate) pattern_name: String,
+    /// Canonicalized twists in the pattern.
+    twists: Vec<Twist>,
+}
+impl Demonstration {
+    /// Returns the twists in the pattern.
+    pub(crate) fn twists(&self) -> &[Twist] {
+        &self.twists
+    }
+    /// Returns the number of twists in the pattern that have been applied, or
+    /// `None` if the puzzle has been changed some other way.
+    pub(crate) fn progress(&self, puzzle: &PuzzleController) -> Option<usize> {
+        let history = puzzle.undo_buffer();
+        let is_in_sync = puzzle.ty() == self.ty
+            && puzzle.scramble().is_empty()
+            && history.len() <= self.twists.len()
+            && std::iter::zip(history, &self.twists).all(|(entry, &twist)| *entry == twist.into());
+        is_in_sync.then_some(history.len())
+    }
+}
+
+/// Replay being played back from its scramble, applying each twist once its
+/// time has come.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) struct ReplayPlayback {
+    pub(crate) replay: Replay,
+    /// Time when playback started.
+    start: instant::Instant,
+}
+#[cfg(not(target_arch = "wasm32"))]
+impl ReplayPlayback {
+    /// Returns the time since playback started.
+    pub(crate) fn elapsed(&self) -> instant::Duration {
+        self.start.elapsed()
+    }
+    /// Returns the number of twists in the replay that have been applied, or
+    /// `None` if the puzzle has been changed some other way.
+    pub(crate) fn progress(&self, puzzle: &PuzzleController) -> Option<usize> {
+        let history = puzzle.undo_buffer();
+        let is_in_sync = puzzle.ty() == self.replay.puzzle
+            && puzzle.scramble() == self.replay.scramble
+            && history.len() <= self.replay.twists.len()
+            && std::iter::zip(history, &self.replay.twists)
+                .all(|(entry, &(_, twist))| *entry == twist.into());
+        is_in_sync.then_some(history.len())
+    }
+}
+
+/// Solution followed by the training wheels hints, which is found again
+/// whenever the puzzle leaves it.
+#[derive(Default)]
+pub(crate) struct TrainingWheels {
+    job: Option<SolveJob>,
+    next_twist: Option<Twist>,
+}
+impl TrainingWheels {
+    /// Returns the next twist of the solution from the current state, or
+    /// `None` if it hasn't been found yet.
+    pub(crate) fn next_twist(&self) -> Option<Twist> {
+        self.next_twist
+    }
+
+    fn update(&mut self, puzzle: &Puzzle) {
+        if !SolveJob::is_supported(puzzle.ty()) || puzzle.is_solved() {
+            *self = Self::default();
+            return;
+        }
+
+        if let Some(job) = &self.job {
+            match job.result() {
+                // Keep waiting for the solution, or keep the error until the
+                // puzzle changes.
+                None | Some(Err(_)) if job.puzzle() == puzzle => return,
+                None | Some(Err(_)) => job.cancel(),
+                Some(Ok(twists)) => {
+                    // Find where the puzzle is along the solution, if it's
+                    // still on it.
+                    let mut state = job.puzzle().clone();
+                    for twist in twists {
+                        if state == *puzzle {
+                            self.next_twist = Some(twist);
+                            return;
+                        }
+                        if state.twist(twist).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        self.next_twist = None;
+        self.job = Some(SolveJob::start(puzzle.clone()));
+    }
+}
+
+/// Puzzle keybind sets loaded from a file, which can be merged with or replace
+/// the existing sets after reviewing conflicts.
+#[derive(Debug, Clone)]
+pub(crate) struct KeybindsImport {
+    pub(crate) ty: PuzzleTypeEnum,
+    pub(crate) sets: Vec<Preset<KeybindSet<PuzzleCommand>>>,
+    /// Keybinds that were skipped when loading the file.
+    pub(crate) warnings: Vec<String>,
+}
+
+/// Key press from the keyboard, which may be waiting to see whether it is the
+/// first key of a chord.
+#[derive(Debug, Copy, Clone)]
+struct PendingKeyPress {
+    sc: Option<KeyMappingCode>,
+    vk: Option<VirtualKeyCode>,
+    time: instant::Instant,
+}
+impl PendingKeyPress {
+    fn keys(self) -> impl Iterator<Item = Key> {
+        self.sc.map(Key::Sc).into_iter().chain(self.vk.map(Key::Vk))
+    }
+    fn has_key(self, key: Key) -> bool {
+        self.keys().any(|k| k == key)
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 #[must_use]
 pub(crate) struct AppEventResponse {
     pub(crate) copy_string: Option<String>,
     pub(crate) request_paste: bool,
+    /// Whether to move the window back to the middle of the primary monitor.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) reset_window_position: bool,
+}
+
+/// Returns whether a timed solve that just finished counts toward the
+/// session, streaks, and personal bests. Only solves from a full scramble
+/// count, so that a few twists can't set a record, and drill and case trainer
+/// reps are kept out of them.
+fn counts_toward_stats(puzzle: &PuzzleController, is_practice_rep: bool) -> bool {
+    puzzle.has_been_fully_scrambled() && !is_practice_rep
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -971,9 +2949,86 @@ fn file_dialog() -> rfd::FileDialog {
         .add_filter("All files", &["*"])
 }
 #[cfg(not(target_arch = "wasm32"))]
+fn open_file_dialog() -> rfd::FileDialog {
+    let importers = crate::logfile::IMPORTERS;
+    let all_extensions = importers
+        .iter()
+        .flat_map(|importer| importer.extensions().iter().copied())
+        .collect_vec();
+    importers
+        .iter()
+        .fold(
+            rfd::FileDialog::new().add_filter("All supported files", &all_extensions),
+            |dialog, importer| dialog.add_filter(importer.name(), importer.extensions()),
+        )
+        .add_filter("All files", &["*"])
+}
+#[cfg(not(target_arch = "wasm32"))]
+fn session_file_dialog() -> rfd::FileDialog {
+    rfd::FileDialog::new()
+        .add_filter(
+            "Hyperspeedcube Sessions",
+            &[crate::logfile::session::EXTENSION],
+        )
+        .add_filter("All files", &["*"])
+}
+#[cfg(not(target_arch = "wasm32"))]
+fn replay_file_dialog() -> rfd::FileDialog {
+    rfd::FileDialog::new()
+        .add_filter(
+            "Hyperspeedcube Replays",
+            &[crate::logfile::replay::EXTENSION],
+        )
+        .add_filter("All files", &["*"])
+}
+#[cfg(not(target_arch = "wasm32"))]
+fn is_replay_file(path: &Path) -> bool {
+    path.extension().map_or(false, |ext| {
+        ext.eq_ignore_ascii_case(crate::logfile::replay::EXTENSION)
+    })
+}
+#[cfg(not(target_arch = "wasm32"))]
+fn keybinds_file_dialog() -> rfd::FileDialog {
+    rfd::FileDialog::new()
+        .add_filter("Keybinds", &["yaml", "yml"])
+        .add_filter("All files", &["*"])
+}
+#[cfg(not(target_arch = "wasm32"))]
 fn show_error_dialog(title: &str, e: impl fmt::Display) {
     rfd::MessageDialog::new()
         .set_title(title)
         .set_description(&e.to_string())
         .show();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::tests::solve;
+    use crate::stats::PersonalBests;
+
+    #[test]
+    fn test_partial_scramble_solve_not_recorded() {
+        let ty = PuzzleTypeEnum::Rubiks3D { layer_count: 3 };
+        let mut puzzle = PuzzleController::new(ty);
+        puzzle.scramble_n(3).unwrap();
+        for twist in invert_alg(ty, puzzle.scramble()) {
+            puzzle.twist(twist).unwrap();
+        }
+        assert!(puzzle.check_just_solved());
+
+        // Record the solve the same way as `App::frame()`.
+        let mut session = Session::default();
+        let mut pbs = PersonalBests::default();
+        if counts_toward_stats(&puzzle, false) {
+            session.solves.push(solve(1_000, Penalty::None));
+            pbs.update(&session, ty);
+        }
+        assert!(session.solves.is_empty());
+        assert_eq!(pbs, PersonalBests::default());
+
+        puzzle.scramble_full().unwrap();
+        assert!(counts_toward_stats(&puzzle, false));
+        assert!(!counts_toward_stats(&puzzle, true));
+    }
+}
@@ -0,0 +1,77 @@
+//! Command-line subcommands that run without opening a window.
+
+use std::path::Path;
+
+use crate::logfile;
+use crate::puzzle::{traits::*, ScrambleState, TwistMetric};
+
+const USAGE: &str = "\
+Usage:
+    hyperspeedcube [FILE]               Open the GUI, optionally loading a log file or playing a replay
+    hyperspeedcube convert INPUT OUTPUT Convert a log file to the format given by OUTPUT's extension
+    hyperspeedcube info FILE            Print information about a log file
+    hyperspeedcube help                 Print this message";
+
+/// Runs the subcommand given by the command-line arguments (excluding the
+/// program name) and returns the process exit code, or returns `None` if
+/// there is no subcommand and the GUI should be launched instead.
+pub(crate) fn run_subcommand(args: &[String]) -> Option<i32> {
+    let result = match args {
+        [cmd, input, output] if cmd == "convert" => convert(Path::new(input), Path::new(output)),
+        [cmd, file] if cmd == "info" => info(Path::new(file)),
+        [cmd, ..] if cmd == "convert" || cmd == "info" => Err(anyhow::anyhow!("{USAGE}")),
+        [cmd] if cmd == "help" || cmd == "--help" || cmd == "-h" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        _ => return None,
+    };
+
+    Some(match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("error: {e}");
+            1
+        }
+    })
+}
+
+fn convert(input: &Path, output: &Path) -> anyhow::Result<()> {
+    let (mut puzzle, warnings) = logfile::load_file(input)?;
+    print_warnings(&warnings);
+    logfile::save_file(output, &mut puzzle)?;
+    Ok(())
+}
+
+fn info(file: &Path) -> anyhow::Result<()> {
+    let (puzzle, warnings) = logfile::load_file(file)?;
+    print_warnings(&warnings);
+
+    let scramble_state = match puzzle.scramble_state() {
+        ScrambleState::None => "none",
+        ScrambleState::Partial => "partial",
+        ScrambleState::Full => "full",
+        ScrambleState::Solved => "full (solved)",
+    };
+    println!("Puzzle:   {}", puzzle.ty().name());
+    println!(
+        "Scramble: {scramble_state}, {} twists",
+        puzzle.scramble().len(),
+    );
+    println!(
+        "Solution: {} STM, {} ETM",
+        puzzle.twist_count(TwistMetric::Stm, false),
+        puzzle.twist_count(TwistMetric::Etm, false),
+    );
+    println!(
+        "Solved:   {}",
+        if puzzle.is_solved() { "yes" } else { "no" },
+    );
+    Ok(())
+}
+
+fn print_warnings(warnings: &[String]) {
+    for warning in warnings {
+        eprintln!("warning: {warning}");
+    }
+}
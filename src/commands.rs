@@ -24,21 +24,45 @@ pub enum Command {
     // File menu (web)
     CopyHscLog,
     CopyMc4dLog,
+    CopySolution,
     PasteLog,
 
     // Edit menu
     Undo,
     Redo,
+    DiscardLastTwist,
     Reset,
 
     // Scramble menu
     ScrambleN(usize),
     ScrambleFull,
+    ScrambleOrientation,
+    SwitchScrambleSide,
+    CopyScramble,
+    RetryScramble,
+    NextCase,
 
     // Puzzle menu
+    New,
+    NewOther,
+    ChoosePuzzle,
     NewPuzzle(PuzzleTypeEnum),
+    SwapPuzzle,
 
     ToggleBlindfold,
+    ToggleLetters,
+    ToggleKeybindsReference,
+    ToggleSlowMotion,
+    ToggleZenMode,
+    ToggleShowTime,
+    ToggleShowTwistCount,
+    RotateColorScheme,
+    MatchColorSchemeToView,
+    CycleColorScheme,
+    ResetView,
+    SetDefaultView,
+    PeekLastLayer,
+    ToggleExplode,
 
     #[default]
     #[serde(other)]
@@ -54,18 +78,42 @@ impl Command {
 
             Self::CopyHscLog => "🗐".to_owned(),
             Self::CopyMc4dLog => "🗐".to_owned(),
+            Self::CopySolution => "Sol🗐".to_owned(),
             Self::PasteLog => "📋".to_owned(),
 
             Self::Undo => "⮪".to_owned(),
             Self::Redo => "⮫".to_owned(),
+            Self::DiscardLastTwist => "⮪✖".to_owned(),
             Self::Reset => "⟲".to_owned(),
 
             Self::ScrambleN(n) => format!("🔀 {n}"),
             Self::ScrambleFull => "🔀".to_owned(),
-
+            Self::ScrambleOrientation => "🔀 Orient".to_owned(),
+            Self::SwitchScrambleSide => "NISS".to_owned(),
+            Self::CopyScramble => "🔀🗐".to_owned(),
+            Self::RetryScramble => "🔁".to_owned(),
+            Self::NextCase => "🔀 Case".to_owned(),
+
+            Self::New => "New".to_owned(),
+            Self::NewOther => "New (other)".to_owned(),
+            Self::ChoosePuzzle => "New…".to_owned(),
             Self::NewPuzzle(ty) => format!("New {}", ty.name()),
+            Self::SwapPuzzle => "⇄".to_owned(),
 
             Self::ToggleBlindfold => "BLD".to_owned(),
+            Self::ToggleLetters => "ABC".to_owned(),
+            Self::ToggleKeybindsReference => "⌨".to_owned(),
+            Self::ToggleSlowMotion => "SLOW".to_owned(),
+            Self::ToggleZenMode => "ZEN".to_owned(),
+            Self::ToggleShowTime => "⏱".to_owned(),
+            Self::ToggleShowTwistCount => "#".to_owned(),
+            Self::RotateColorScheme => "CN".to_owned(),
+            Self::MatchColorSchemeToView => "CN👁".to_owned(),
+            Self::CycleColorScheme => "🎨".to_owned(),
+            Self::ResetView => "👁⟲".to_owned(),
+            Self::SetDefaultView => "👁💾".to_owned(),
+            Self::PeekLastLayer => "👁LL".to_owned(),
+            Self::ToggleExplode => "💥".to_owned(),
 
             Self::None => String::new(),
         }
@@ -101,6 +149,9 @@ pub enum PuzzleCommand {
         direction: String,
         #[serde(default)]
         layers: LayerMaskDesc,
+        /// Name of a layer group to use instead of `layers`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        layer_group: Option<String>,
     },
     Recenter {
         #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -145,12 +196,23 @@ impl PuzzleCommand {
                 axis,
                 direction,
                 layers,
-            } => ty.twist_command_short_description(
-                axis.as_deref()
-                    .and_then(|axis_name| ty.twist_axis_from_name(axis_name)),
-                ty.twist_direction_from_name(direction).unwrap_or_default(),
-                layers.to_layer_mask(ty.layer_count()),
-            ),
+                layer_group,
+            } => {
+                let layers = match layer_group {
+                    Some(_) => LayerMask::default(),
+                    None => layers.to_layer_mask(ty.layer_count()),
+                };
+                let s = ty.twist_command_short_description(
+                    axis.as_deref()
+                        .and_then(|axis_name| ty.twist_axis_from_name(axis_name)),
+                    ty.twist_direction_from_name(direction).unwrap_or_default(),
+                    layers,
+                );
+                match layer_group {
+                    Some(group_name) => format!("{group_name}:{s}"),
+                    None => s,
+                }
+            }
             Self::Recenter { axis } => {
                 match axis
                     .as_deref()
@@ -202,6 +264,12 @@ impl PuzzleCommand {
             _ => None,
         }
     }
+    pub fn layer_group_mut(&mut self) -> Option<&mut Option<String>> {
+        match self {
+            Self::Twist { layer_group, .. } => Some(layer_group),
+            _ => None,
+        }
+    }
     pub fn direction_mut(&mut self) -> Option<&mut String> {
         match self {
             Self::Twist { direction, .. } => Some(direction),
@@ -326,6 +394,15 @@ impl LayerMaskDesc {
         *self == Self::default()
     }
 
+    /// Returns whether every layer referenced exists on a puzzle with the
+    /// given number of layers.
+    pub(crate) fn fits_layer_count(&self, layer_count: u8) -> bool {
+        let fits = |i: i8| i != 0 && i.unsigned_abs() <= layer_count;
+        self.segments
+            .iter()
+            .all(|segment| fits(segment.start) && fits(segment.end))
+    }
+
     pub(crate) fn to_layer_mask(&self, layer_count: u8) -> LayerMask {
         let mut ret = LayerMask(0);
 
@@ -0,0 +1,402 @@
+//! Flat "unfolded net" diagrams of 3D puzzles, for algorithm sheets and
+//! documentation.
+
+#[cfg(not(target_arch = "wasm32"))]
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+use crate::puzzle::{traits::*, Puzzle};
+
+/// Width of a sticker plus the space around it, in pixels.
+const STICKER_PITCH: f32 = 20.0;
+/// Space between stickers, in pixels.
+const STICKER_SPACING: f32 = 2.0;
+/// Extra space between faces, in pixels.
+const FACE_SPACING: f32 = 6.0;
+/// Width of the outline around each sticker in SVG output, in pixels.
+const SVG_OUTLINE_WIDTH: f32 = 1.0;
+
+/// Arrangement of faces in a net diagram.
+#[derive(
+    Serialize, Deserialize, Debug, Display, Default, EnumIter, Copy, Clone, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum NetLayout {
+    /// U above F, with L, F, R, and B in a row and D below F.
+    #[default]
+    Cross,
+    /// L, U, and R in a row, with F, D, and B below U.
+    T,
+    /// U, L, F, R, B, and D in a row.
+    Line,
+}
+impl NetLayout {
+    /// Returns the column and row of each face in the layout.
+    fn face_positions(self) -> [(&'static str, [u8; 2]); 6] {
+        match self {
+            NetLayout::Cross => [
+                ("U", [1, 0]),
+                ("L", [0, 1]),
+                ("F", [1, 1]),
+                ("R", [2, 1]),
+                ("B", [3, 1]),
+                ("D", [1, 2]),
+            ],
+            NetLayout::T => [
+                ("L", [0, 0]),
+                ("U", [1, 0]),
+                ("R", [2, 0]),
+                ("F", [1, 1]),
+                ("D", [1, 2]),
+                ("B", [1, 3]),
+            ],
+            NetLayout::Line => [
+                ("U", [0, 0]),
+                ("L", [1, 0]),
+                ("F", [2, 0]),
+                ("R", [3, 0]),
+                ("B", [4, 0]),
+                ("D", [5, 0]),
+            ],
+        }
+    }
+}
+
+/// Net diagram of the current state of a puzzle.
+#[derive(Debug, Clone)]
+pub struct NetDiagram {
+    /// Size of the whole diagram, in pixels.
+    pub size: egui::Vec2,
+    /// Rectangle and color of each sticker, in pixels.
+    pub stickers: Vec<(egui::Rect, egui::Color32)>,
+}
+impl NetDiagram {
+    /// Constructs a net diagram of a puzzle using the given face colors, or
+    /// returns `None` if the puzzle isn't a cube.
+    pub fn new(puzzle: &Puzzle, face_colors: &[egui::Color32], layout: NetLayout) -> Option<Self> {
+        let ty = puzzle.ty();
+        let n = ty.layer_count() as f32;
+        let face_pitch = n * STICKER_PITCH + FACE_SPACING;
+
+        let mut face_origins = vec![None; ty.faces().len()];
+        let mut size = egui::Vec2::ZERO;
+        for (symbol, [col, row]) in layout.face_positions() {
+            let face = ty.face_from_symbol(symbol)?;
+            let origin = egui::vec2(col as f32, row as f32) * face_pitch;
+            face_origins[face.0 as usize] = Some(origin);
+            size = size.max(origin + egui::Vec2::splat(n * STICKER_PITCH));
+        }
+
+        let mut stickers = vec![];
+        for sticker in (0..ty.stickers().len() as _).map(crate::puzzle::Sticker) {
+            let (face, [row, col]) = puzzle.sticker_grid_location(sticker)?;
+            let origin = face_origins.get(face.0 as usize).copied().flatten()?;
+            let min = origin
+                + egui::vec2(col as f32, row as f32) * STICKER_PITCH
+                + egui::Vec2::splat(STICKER_SPACING / 2.0);
+            let rect = egui::Rect::from_min_size(
+                min.to_pos2(),
+                egui::Vec2::splat(STICKER_PITCH - STICKER_SPACING),
+            );
+            let color = face_colors[ty.info(sticker).color.0 as usize];
+            stickers.push((rect, color));
+        }
+
+        Some(Self { size, stickers })
+    }
+
+    /// Returns the diagram as an SVG image.
+    pub fn to_svg(&self) -> String {
+        let mut ret = String::new();
+        let [w, h] = [self.size.x, self.size.y];
+        let _ = writeln!(
+            ret,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        );
+        for &(rect, color) in &self.stickers {
+            let _ = writeln!(
+                ret,
+                r##"  <rect x="{}" y="{}" width="{}" height="{}" fill="#{:02x}{:02x}{:02x}" stroke="black" stroke-width="{SVG_OUTLINE_WIDTH}"/>"##,
+                rect.min.x,
+                rect.min.y,
+                rect.width(),
+                rect.height(),
+                color.r(),
+                color.g(),
+                color.b(),
+            );
+        }
+        ret.push_str("</svg>\n");
+        ret
+    }
+
+    /// Returns the diagram as a PNG image with a transparent background.
+    pub fn to_png(&self) -> Result<Vec<u8>, png::EncodingError> {
+        let [w, h] = [self.size.x.ceil() as usize, self.size.y.ceil() as usize];
+        let mut pixels = vec![0_u8; w * h * 4];
+        for &(rect, color) in &self.stickers {
+            for y in rect.min.y.round() as usize..(rect.max.y.round() as usize).min(h) {
+                for x in rect.min.x.round() as usize..(rect.max.x.round() as usize).min(w) {
+                    let i = (y * w + x) * 4;
+                    pixels[i..i + 4].copy_from_slice(&[color.r(), color.g(), color.b(), 255]);
+                }
+            }
+        }
+
+        let mut ret = vec![];
+        let mut encoder = png::Encoder::new(&mut ret, w as u32, h as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&pixels)?;
+        Ok(ret)
+    }
+}
+
+/// Printable sheet of algorithm cases, each with a net diagram of the case and
+/// the algorithm that solves it.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub struct AlgSheet {
+    pub title: String,
+    /// Name, algorithm, and net diagram of each case.
+    pub cases: Vec<(String, String, NetDiagram)>,
+}
+#[cfg(not(target_arch = "wasm32"))]
+impl AlgSheet {
+    /// Size of each page (A4), in points.
+    const PAGE_SIZE: [f32; 2] = [595.0, 842.0];
+    /// Space around the edges of each page, in points.
+    const MARGIN: f32 = 36.0;
+    /// Number of cases in each row.
+    const COLUMNS: usize = 3;
+    /// Space around each case, in points.
+    const CELL_PADDING: f32 = 8.0;
+    const TITLE_FONT_SIZE: f32 = 16.0;
+    const NAME_FONT_SIZE: f32 = 11.0;
+    const ALG_FONT_SIZE: f32 = 9.0;
+    /// Height of a line of text, relative to the font size.
+    const LINE_SPACING: f32 = 1.3;
+    /// Approximate width of a character in Helvetica, relative to the font
+    /// size, for wrapping algorithms.
+    const CHAR_WIDTH: f32 = 0.55;
+
+    /// Returns the sheet as a PDF document, with the cases laid out in a grid
+    /// on as many pages as needed.
+    pub fn to_pdf(&self) -> Vec<u8> {
+        let [page_w, page_h] = Self::PAGE_SIZE;
+        let cell_w = (page_w - 2.0 * Self::MARGIN) / Self::COLUMNS as f32;
+        let inner_w = cell_w - 2.0 * Self::CELL_PADDING;
+
+        // Scale every diagram the same amount so that they all fit.
+        let diagram_size = self
+            .cases
+            .iter()
+            .fold(egui::Vec2::ZERO, |size, (_, _, diagram)| {
+                size.max(diagram.size)
+            });
+        let scale = if diagram_size.x > 0.0 {
+            f32::min(1.0, inner_w / diagram_size.x)
+        } else {
+            1.0
+        };
+
+        let max_chars = (inner_w / (Self::ALG_FONT_SIZE * Self::CHAR_WIDTH)) as usize;
+        let wrapped_algs = self
+            .cases
+            .iter()
+            .map(|(_, alg, _)| Self::wrap_text(alg, max_chars))
+            .collect_vec();
+        let alg_lines = wrapped_algs.iter().map(Vec::len).max().unwrap_or(0);
+
+        let title_h = Self::TITLE_FONT_SIZE * Self::LINE_SPACING;
+        let cell_h = 2.0 * Self::CELL_PADDING
+            + diagram_size.y * scale
+            + (Self::NAME_FONT_SIZE + alg_lines as f32 * Self::ALG_FONT_SIZE) * Self::LINE_SPACING;
+        let rows_per_page = (((page_h - 2.0 * Self::MARGIN - title_h) / cell_h) as usize).max(1);
+
+        let cases = self.cases.iter().zip(&wrapped_algs).collect_vec();
+        let mut pages = vec![];
+        for page_cases in cases.chunks(rows_per_page * Self::COLUMNS) {
+            let top = page_h - Self::MARGIN;
+            let mut content = String::new();
+            Self::write_pdf_text(
+                &mut content,
+                "F2",
+                Self::TITLE_FONT_SIZE,
+                [Self::MARGIN, top - Self::TITLE_FONT_SIZE],
+                &self.title,
+            );
+
+            let _ = writeln!(content, "0 G {:.2} w", SVG_OUTLINE_WIDTH * scale);
+            for (i, ((name, _, diagram), alg)) in page_cases.iter().enumerate() {
+                let (row, col) = (i / Self::COLUMNS, i % Self::COLUMNS);
+                let x = Self::MARGIN + col as f32 * cell_w + Self::CELL_PADDING;
+                let mut y = top - title_h - row as f32 * cell_h - Self::CELL_PADDING;
+
+                // PDF coordinates go up from the bottom of the page.
+                for &(rect, color) in &diagram.stickers {
+                    let [r, g, b] = [color.r(), color.g(), color.b()].map(|c| c as f32 / 255.0);
+                    let _ = writeln!(
+                        content,
+                        "{r:.3} {g:.3} {b:.3} rg {:.2} {:.2} {:.2} {:.2} re B",
+                        x + rect.min.x * scale,
+                        y - rect.max.y * scale,
+                        rect.width() * scale,
+                        rect.height() * scale,
+                    );
+                }
+                y -= diagram_size.y * scale;
+
+                y -= Self::NAME_FONT_SIZE * Self::LINE_SPACING;
+                Self::write_pdf_text(&mut content, "F2", Self::NAME_FONT_SIZE, [x, y], name);
+                for line in alg.iter() {
+                    y -= Self::ALG_FONT_SIZE * Self::LINE_SPACING;
+                    Self::write_pdf_text(&mut content, "F1", Self::ALG_FONT_SIZE, [x, y], line);
+                }
+            }
+            pages.push(content);
+        }
+
+        Self::pdf_document(&pages)
+    }
+
+    /// Splits text into lines of at most `max_chars` characters, breaking only
+    /// between words.
+    fn wrap_text(text: &str, max_chars: usize) -> Vec<String> {
+        let mut lines: Vec<String> = vec![];
+        for word in text.split_whitespace() {
+            match lines.last_mut() {
+                Some(line) if line.len() + 1 + word.len() <= max_chars => {
+                    line.push(' ');
+                    line.push_str(word);
+                }
+                _ => lines.push(word.to_string()),
+            }
+        }
+        lines
+    }
+
+    /// Writes a line of black text in one of the fonts from
+    /// [`Self::pdf_document()`]: `F1` for regular or `F2` for bold.
+    fn write_pdf_text(content: &mut String, font: &str, size: f32, [x, y]: [f32; 2], text: &str) {
+        // The built-in fonts only cover ASCII reliably.
+        let escaped: String = text
+            .chars()
+            .map(|c| match c {
+                '(' | ')' | '\\' => format!("\\{c}"),
+                ' '..='~' => c.to_string(),
+                _ => "?".to_string(),
+            })
+            .collect();
+        let _ = writeln!(
+            content,
+            "BT 0 g /{font} {size} Tf {x:.2} {y:.2} Td ({escaped}) Tj ET",
+        );
+    }
+
+    /// Returns a PDF document with one page for each content stream.
+    fn pdf_document(pages: &[String]) -> Vec<u8> {
+        let [page_w, page_h] = Self::PAGE_SIZE;
+
+        // Objects 1 through 4 are the catalog, the page tree, and the two
+        // fonts. Each page is followed by its content stream.
+        let page_refs = (0..pages.len())
+            .map(|i| format!("{} 0 R", 5 + 2 * i))
+            .join(" ");
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!(
+                "<< /Type /Pages /Kids [{page_refs}] /Count {} >>",
+                pages.len(),
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold >>".to_string(),
+        ];
+        for (i, content) in pages.iter().enumerate() {
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {page_w} {page_h}] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                6 + 2 * i,
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{content}endstream",
+                content.len(),
+            ));
+        }
+
+        let mut ret = String::from("%PDF-1.4\n");
+        let mut offsets = vec![];
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(ret.len());
+            let _ = write!(ret, "{} 0 obj\n{object}\nendobj\n", i + 1);
+        }
+        // Each entry in the cross-reference table must be exactly 20 bytes.
+        let xref_offset = ret.len();
+        let _ = writeln!(ret, "xref\n0 {}", objects.len() + 1);
+        ret += "0000000000 65535 f \n";
+        for offset in offsets {
+            let _ = writeln!(ret, "{offset:010} 00000 n ");
+        }
+        let _ = write!(
+            ret,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref_offset}\n%%EOF\n",
+            objects.len() + 1,
+        );
+        ret.into_bytes()
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::puzzle::PuzzleTypeEnum;
+
+    #[test]
+    fn test_alg_sheet_pdf_xref() {
+        let puzzle = Puzzle::new(PuzzleTypeEnum::Rubiks3D { layer_count: 3 });
+        let face_colors = vec![egui::Color32::RED; puzzle.faces().len()];
+        let diagram = NetDiagram::new(&puzzle, &face_colors, NetLayout::Cross).unwrap();
+        let sheet = AlgSheet {
+            title: "Cases (with parens)".to_string(),
+            cases: (0..40)
+                .map(|i| {
+                    (
+                        format!("Case {i}"),
+                        "R U R' U'".to_string(),
+                        diagram.clone(),
+                    )
+                })
+                .collect(),
+        };
+        let pdf = String::from_utf8(sheet.to_pdf()).unwrap();
+        assert!(pdf.matches("/Type /Page /Parent").count() > 1);
+
+        // The trailer points at the cross-reference table.
+        let (_, startxref) = pdf.rsplit_once("startxref\n").unwrap();
+        let xref_offset: usize = startxref.lines().next().unwrap().parse().unwrap();
+        let mut xref = pdf[xref_offset..].lines();
+        assert_eq!(xref.next(), Some("xref"));
+        let size: usize = xref.next().unwrap()[2..].parse().unwrap();
+        assert_eq!(xref.next(), Some("0000000000 65535 f "));
+
+        // Each entry is 20 bytes and points at the start of its object.
+        for i in 1..size {
+            let entry = xref.next().unwrap();
+            assert_eq!(entry.len() + 1, 20);
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(pdf[offset..].starts_with(&format!("{i} 0 obj\n")));
+        }
+        assert_eq!(xref.next(), Some("trailer"));
+    }
+
+    #[test]
+    fn test_wrap_text() {
+        assert_eq!(AlgSheet::wrap_text("R U R' U'", 100), ["R U R' U'"]);
+        assert_eq!(AlgSheet::wrap_text("R U R' U'", 5), ["R U", "R' U'"]);
+        // Words longer than a line are kept whole.
+        assert_eq!(AlgSheet::wrap_text("Rw2 U", 2), ["Rw2", "U"]);
+        assert_eq!(AlgSheet::wrap_text("  R   U  ", 10), ["R U"]);
+        assert!(AlgSheet::wrap_text("", 10).is_empty());
+    }
+}
@@ -0,0 +1,103 @@
+//! Gamepad buttons and rumble feedback.
+//!
+//! If gamepad support isn't available or no gamepad is connected, nothing
+//! happens.
+
+use gilrs::ff::{BaseEffect, BaseEffectType, Effect, EffectBuilder, Replay, Ticks};
+use gilrs::{Button, EventType, Gilrs};
+use itertools::Itertools;
+
+use crate::preferences::GamepadButton;
+
+/// Length of the rumble on each twist, in milliseconds.
+pub(crate) const TWIST_RUMBLE_MS: u32 = 40;
+/// Length of the rumble when the puzzle is solved, in milliseconds.
+pub(crate) const SOLVE_RUMBLE_MS: u32 = 400;
+
+pub(crate) struct Gamepads {
+    /// Gamepad input, or `None` if it isn't supported.
+    gilrs: Option<Gilrs>,
+    /// Rumble that is playing, which stops when it is dropped.
+    effect: Option<Effect>,
+}
+impl Default for Gamepads {
+    fn default() -> Self {
+        let gilrs = Gilrs::new()
+            .map_err(|e| log::warn!("Error initializing gamepad support: {}", e))
+            .ok();
+        Self {
+            gilrs,
+            effect: None,
+        }
+    }
+}
+impl Gamepads {
+    /// Returns the buttons that have been pressed on any gamepad since the
+    /// last call.
+    pub fn pressed_buttons(&mut self) -> Vec<GamepadButton> {
+        let Some(gilrs) = &mut self.gilrs else {
+            return vec![];
+        };
+        std::iter::from_fn(|| gilrs.next_event())
+            .filter_map(|event| match event.event {
+                EventType::ButtonPressed(button, _) => gamepad_button(button),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Rumbles every connected gamepad that supports it, with an intensity
+    /// from 0.0 to 1.0. This replaces any rumble that is still playing.
+    pub fn rumble(&mut self, intensity: f32, duration_ms: u32) {
+        let Some(gilrs) = &mut self.gilrs else {
+            return;
+        };
+        let gamepad_ids = gilrs
+            .gamepads()
+            .filter(|(_, gamepad)| gamepad.is_ff_supported())
+            .map(|(id, _)| id)
+            .collect_vec();
+        if gamepad_ids.is_empty() || intensity <= 0.0 {
+            return;
+        }
+
+        let magnitude = (intensity.clamp(0.0, 1.0) * u16::MAX as f32) as u16;
+        let effect = EffectBuilder::new()
+            .add_effect(BaseEffect {
+                kind: BaseEffectType::Strong { magnitude },
+                scheduling: Replay {
+                    play_for: Ticks::from_ms(duration_ms),
+                    ..Default::default()
+                },
+                envelope: Default::default(),
+            })
+            .gamepads(&gamepad_ids)
+            .finish(gilrs);
+        match effect.and_then(|effect| effect.play().map(|()| effect)) {
+            Ok(effect) => self.effect = Some(effect),
+            Err(e) => log::warn!("Error rumbling gamepad: {}", e),
+        }
+    }
+}
+
+fn gamepad_button(button: Button) -> Option<GamepadButton> {
+    match button {
+        Button::South => Some(GamepadButton::South),
+        Button::East => Some(GamepadButton::East),
+        Button::North => Some(GamepadButton::North),
+        Button::West => Some(GamepadButton::West),
+        Button::LeftTrigger => Some(GamepadButton::LeftBumper),
+        Button::RightTrigger => Some(GamepadButton::RightBumper),
+        Button::LeftTrigger2 => Some(GamepadButton::LeftTrigger),
+        Button::RightTrigger2 => Some(GamepadButton::RightTrigger),
+        Button::Select => Some(GamepadButton::Select),
+        Button::Start => Some(GamepadButton::Start),
+        Button::LeftThumb => Some(GamepadButton::LeftStick),
+        Button::RightThumb => Some(GamepadButton::RightStick),
+        Button::DPadUp => Some(GamepadButton::DPadUp),
+        Button::DPadDown => Some(GamepadButton::DPadDown),
+        Button::DPadLeft => Some(GamepadButton::DPadLeft),
+        Button::DPadRight => Some(GamepadButton::DPadRight),
+        _ => None,
+    }
+}
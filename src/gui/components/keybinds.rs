@@ -187,6 +187,7 @@ where
                                 Some(keybind.key),
                                 self.keybind_set.clone(),
                                 idx,
+                                self.keybind_set.use_vk_by_default(&self.app.prefs),
                             )
                         }
 
@@ -247,16 +248,40 @@ impl egui::Widget for CommandSelectWidget<'_, GlobalKeybindsAccessor> {
 
                     "Copy .hsc" => Cmd::CopyHscLog,
                     "Copy .log" => Cmd::CopyMc4dLog,
+                    "Copy solution" => Cmd::CopySolution,
                     "Paste .log" => Cmd::PasteLog,
 
                     "Undo" => Cmd::Undo,
                     "Redo" => Cmd::Redo,
+                    "Discard last twist" => Cmd::DiscardLastTwist,
                     "Reset" => Cmd::Reset,
 
                     "Scramble partially" => Cmd::ScrambleN(PARTIAL_SCRAMBLE_MOVE_COUNT_MIN),
                     "Scramble fully" => Cmd::ScrambleFull,
+                    "Scramble orientation" => Cmd::ScrambleOrientation,
+                    "Switch scramble side" => Cmd::SwitchScrambleSide,
+                    "Copy scramble" => Cmd::CopyScramble,
+                    "Retry scramble" => Cmd::RetryScramble,
+                    "Next case" => Cmd::NextCase,
                     "Toggle blindfold" => Cmd::ToggleBlindfold,
+                    "Toggle letters" => Cmd::ToggleLetters,
+                    "Toggle keybinds reference" => Cmd::ToggleKeybindsReference,
+                    "Toggle slow motion" => Cmd::ToggleSlowMotion,
+                    "Toggle zen mode" => Cmd::ToggleZenMode,
+                    "Toggle time display" => Cmd::ToggleShowTime,
+                    "Toggle twist count display" => Cmd::ToggleShowTwistCount,
+                    "Rotate color scheme" => Cmd::RotateColorScheme,
+                    "Match color scheme to view" => Cmd::MatchColorSchemeToView,
+                    "Cycle color scheme" => Cmd::CycleColorScheme,
+                    "Reset view" => Cmd::ResetView,
+                    "Set default view" => Cmd::SetDefaultView,
+                    "Peek at last layer" => Cmd::PeekLastLayer,
+                    "Toggle explode" => Cmd::ToggleExplode,
+                    "New" => Cmd::New,
+                    "New (other)" => Cmd::NewOther,
+                    "Choose puzzle" => Cmd::ChoosePuzzle,
                     "New puzzle" => Cmd::NewPuzzle(PuzzleTypeEnum::default()),
+                    "Swap puzzle" => Cmd::SwapPuzzle,
                 }
             );
             changed |= r.changed();
@@ -314,6 +339,7 @@ impl egui::Widget for CommandSelectWidget<'_, PuzzleKeybindsAccessor> {
                             puzzle_type.twist_directions()[0].name.to_owned()
                         }),
                         layers: self.cmd.layers_mut().cloned().unwrap_or_default(),
+                        layer_group: self.cmd.layer_group_mut().cloned().unwrap_or_default(),
                     },
                     "Recenter" => Cmd::Recenter {
                         axis: self.cmd.axis_mut().cloned().unwrap_or_default(),
@@ -342,7 +368,32 @@ impl egui::Widget for CommandSelectWidget<'_, PuzzleKeybindsAccessor> {
             );
             changed |= r.changed();
 
-            if let Some(layers) = self.cmd.layers_mut() {
+            let layer_groups = &self.prefs.layer_groups[puzzle_type];
+            let mut uses_layer_group = false;
+            if let Some(layer_group) = self.cmd.layer_group_mut() {
+                // Only show layer groups if there are any.
+                if layer_group.is_some() || !layer_groups.is_empty() {
+                    let mut options = vec![(None, "Layers".into())];
+                    options.extend(layer_groups.iter().map(|preset| {
+                        let name = &preset.preset_name;
+                        (Some(name.clone()), name.as_str().into())
+                    }));
+                    let r = ui
+                        .add(FancyComboBox {
+                            combo_box: egui::ComboBox::from_id_source(unique_id!(self.idx)),
+                            selected: &mut *layer_group,
+                            options,
+                        })
+                        .on_hover_explanation(
+                            "",
+                            "You can manage layer groups \
+                             in the \"Layer groups\" tool.",
+                        );
+                    changed |= r.changed();
+                }
+                uses_layer_group = layer_group.is_some();
+            }
+            if let Some(layers) = self.cmd.layers_mut().filter(|_| !uses_layer_group) {
                 let r = ui.add(LayerMaskEdit {
                     id: unique_id!(self.idx),
                     layers,
@@ -445,7 +496,9 @@ impl egui::Widget for CommandSelectWidget<'_, PuzzleKeybindsAccessor> {
 pub trait KeybindSetAccessor: 'static + Clone + Hash + Send + Sync {
     type Command: Default + Clone + Eq + Serialize + for<'a> Deserialize<'a>;
 
-    const USE_VK_BY_DEFAULT: bool;
+    /// Returns whether new keybinds should use virtual keycodes instead of
+    /// scancodes by default.
+    fn use_vk_by_default(&self, prefs: &Preferences) -> bool;
 
     fn display_name(&self) -> String;
 
@@ -480,7 +533,10 @@ pub struct PuzzleKeybindsAccessor {
 impl KeybindSetAccessor for PuzzleKeybindsAccessor {
     type Command = PuzzleCommand;
 
-    const USE_VK_BY_DEFAULT: bool = false; // Position is more important for puzzle keybinds
+    fn use_vk_by_default(&self, prefs: &Preferences) -> bool {
+        // Position is usually more important for puzzle keybinds.
+        prefs.interaction.puzzle_keybinds_use_keycodes
+    }
 
     fn display_name(&self) -> String {
         format!(
@@ -527,7 +583,9 @@ pub struct GlobalKeybindsAccessor;
 impl KeybindSetAccessor for GlobalKeybindsAccessor {
     type Command = Command;
 
-    const USE_VK_BY_DEFAULT: bool = true; // Shortcuts like ctrl+Z should move depending on keyboard layout
+    fn use_vk_by_default(&self, _prefs: &Preferences) -> bool {
+        true // Shortcuts like ctrl+Z should move depending on keyboard layout
+    }
 
     fn display_name(&self) -> String {
         "general".to_string()
@@ -1,11 +1,17 @@
 use egui::NumExt;
+use strum::IntoEnumIterator;
 
 use crate::app::App;
-use crate::gui::components::{with_reset_button, PresetsUi, WidgetWithReset};
+use crate::gui::components::{
+    with_reset_button, FancyComboBox, PresetsUi, PresetsUiStrings, WidgetWithReset,
+};
 use crate::gui::ext::*;
 use crate::gui::util::Access;
-use crate::preferences::{OpacityPreferences, DEFAULT_PREFS};
-use crate::puzzle::{traits::*, Face, ProjectionType};
+use crate::preferences::{
+    ColorScheme, InspectionTwistAction, NewAction, OpacityPreferences, PresentMode, ScreenCorner,
+    DEFAULT_PREFS,
+};
+use crate::puzzle::{traits::*, Face, NotationStyle, ProjectionType, SolvedDefinition, TwistAxis};
 use crate::serde_impl::hex_color;
 
 pub struct PrefsUi<'a, T> {
@@ -131,6 +137,48 @@ pub fn build_colors_section(ui: &mut egui::Ui, app: &mut App) {
     let prefs = &mut app.prefs;
 
     let mut changed = false;
+
+    ui.collapsing("Schemes", |ui| {
+        let colors = &mut prefs.colors;
+        let mut presets_ui = PresetsUi {
+            id: unique_id!(),
+            presets: &mut colors.schemes[puzzle_type],
+            changed: &mut changed,
+            strings: PresetsUiStrings {
+                edit: "Edit color schemes",
+                save: "Save color scheme",
+                name: "Color scheme name",
+            },
+            enable_yaml: true,
+        };
+
+        presets_ui.show_header_with_active_preset(
+            ui,
+            || ColorScheme {
+                faces: colors.faces[puzzle_type].clone(),
+            },
+            |new_preset| colors.active_scheme[puzzle_type] = Some(new_preset.preset_name.clone()),
+        );
+        ui.separator();
+        let mut to_load = None;
+        presets_ui.show_list(ui, |ui, _idx, preset| {
+            let r = ui.scope(|ui| {
+                if ui.button("Load").clicked() {
+                    to_load = Some(preset.clone());
+                }
+                if colors.active_scheme[puzzle_type].as_ref() == Some(&preset.preset_name) {
+                    ui.strong(&preset.preset_name);
+                } else {
+                    ui.label(&preset.preset_name);
+                }
+            });
+            r.response
+        });
+        if let Some(preset) = to_load {
+            colors.load_scheme(puzzle_type, &preset);
+            changed = true;
+        }
+    });
     let mut prefs_ui = PrefsUi {
         ui,
         current: &mut prefs.colors,
@@ -142,6 +190,29 @@ pub fn build_colors_section(ui: &mut egui::Ui, app: &mut App) {
     for (i, &face) in puzzle_type.faces().iter().enumerate() {
         prefs_ui.color(face.name, access!([(puzzle_type, Face(i as _))]));
     }
+    if puzzle_type.center_face().is_some() {
+        prefs_ui.ui.horizontal(|ui| {
+            let mut options = vec![(None, "Default".into())];
+            options.extend(
+                puzzle_type
+                    .faces()
+                    .iter()
+                    .map(|face| (Some(face.symbol.to_owned()), face.name.into())),
+            );
+            let r = ui.add(FancyComboBox {
+                combo_box: egui::ComboBox::from_id_source(unique_id!()),
+                selected: &mut prefs_ui.current.center_color[puzzle_type],
+                options,
+            });
+            *prefs_ui.changed |= r.changed();
+            ui.label("Center cell").on_hover_explanation(
+                "",
+                "Rotates the color scheme so that this color \
+                 is on the cell at the center of the projection. \
+                 This only affects colors, not the puzzle state.",
+            );
+        });
+    }
 
     prefs_ui.ui.separator();
 
@@ -149,6 +220,21 @@ pub fn build_colors_section(ui: &mut egui::Ui, app: &mut App) {
     prefs_ui.color("Background", access!(.background));
     prefs_ui.color("Blindfolded stickers", access!(.blind_face));
     prefs_ui.checkbox("Blindfold mode", access!(.blindfold));
+    prefs_ui.color("Supercube markers", access!(.supercube_marker));
+    prefs_ui.checkbox("Show supercube markers", access!(.supercube_markers));
+    prefs_ui.color("4D reference frame", access!(.reference_frame_color));
+    prefs_ui.percent(
+        "4D reference frame opacity",
+        access!(.reference_frame_opacity),
+    );
+    prefs_ui
+        .checkbox("Show 4D reference frame", access!(.reference_frame))
+        .on_hover_explanation(
+            "",
+            "Draws a faint wireframe around 4D puzzles that \
+             stays still as pieces move, to help keep track \
+             of the orientation of the projection.",
+        );
 
     prefs.needs_save |= changed;
     if changed {
@@ -166,12 +252,134 @@ pub fn build_graphics_section(ui: &mut egui::Ui, app: &mut App) {
         changed: &mut changed,
     };
 
-    let speed = prefs_ui.current.fps_limit as f64 / 1000.0; // logarithmic speed
+    prefs_ui.ui.horizontal_wrapped(|ui| {
+        for present_mode in PresentMode::iter() {
+            let r = ui
+                .selectable_value(
+                    &mut prefs_ui.current.present_mode,
+                    present_mode,
+                    present_mode.to_string(),
+                )
+                .on_hover_explanation(
+                    "",
+                    match present_mode {
+                        PresentMode::Fifo => {
+                            "Waits for the display to refresh before \
+                             showing each frame, which saves power."
+                        }
+                        PresentMode::Immediate => {
+                            "Shows each frame as soon as it is ready, \
+                             which may cause tearing. Falls back to \
+                             VSync if unsupported."
+                        }
+                        PresentMode::Mailbox => {
+                            "Waits for the display to refresh, but \
+                             always shows the newest frame. Falls back \
+                             to VSync if unsupported."
+                        }
+                    },
+                );
+            *prefs_ui.changed |= r.changed();
+        }
+    });
+
     prefs_ui
+        .checkbox("Limit FPS", access!(.limit_fps))
+        .on_hover_explanation("", "Limits framerate independently of the present mode");
+    let speed = prefs_ui.current.fps_limit as f64 / 1000.0; // logarithmic speed
+    let limit_fps = prefs_ui.current.limit_fps;
+    prefs_ui.ui.add_enabled_ui(limit_fps, |ui| {
+        PrefsUi {
+            ui,
+            current: &mut *prefs_ui.current,
+            defaults: prefs_ui.defaults,
+            changed: &mut *prefs_ui.changed,
+        }
         .num("FPS limit", access!(.fps_limit), |dv| {
             dv.fixed_decimals(0).clamp_range(30..=1000).speed(speed)
         })
         .on_hover_explanation("Frames Per Second", "Limits framerate to save power");
+    });
+
+    prefs_ui
+        .checkbox("Stickerless", access!(.stickerless))
+        .on_hover_explanation(
+            "",
+            "Draws the outline of each sticker in the \
+             color of the sticker instead of the outline \
+             color, so that pieces look like stickerless \
+             plastic. Outlines of hovered, selected, and \
+             hidden pieces are still highlighted.",
+        );
+    prefs_ui
+        .num("Sticker brightness", access!(.sticker_brightness), |dv| {
+            dv.fixed_decimals(2).clamp_range(0.0..=2.0_f32).speed(0.01)
+        })
+        .on_hover_explanation(
+            "",
+            "Makes every sticker color brighter or darker, \
+             on top of the color scheme.",
+        );
+    prefs_ui
+        .num("Sticker saturation", access!(.sticker_saturation), |dv| {
+            dv.fixed_decimals(2).clamp_range(0.0..=2.0_f32).speed(0.01)
+        })
+        .on_hover_explanation(
+            "",
+            "Makes every sticker color more or less vivid, \
+             on top of the color scheme. 0 is grayscale.",
+        );
+    prefs_ui
+        .checkbox("Tint solved stickers", access!(.tint_solved_stickers))
+        .on_hover_explanation(
+            "",
+            "When enabled, stickers that are on the face where \
+             most stickers of their color are get brighter, to \
+             show progress during a solve. This is turned off \
+             while blindfolded.",
+        );
+    let tint_solved_stickers = prefs_ui.current.tint_solved_stickers;
+    prefs_ui.ui.add_enabled_ui(tint_solved_stickers, |ui| {
+        PrefsUi {
+            ui,
+            current: &mut *prefs_ui.current,
+            defaults: prefs_ui.defaults,
+            changed: &mut *prefs_ui.changed,
+        }
+        .percent("Solved tint intensity", access!(.solved_tint_intensity));
+    });
+    prefs_ui
+        .checkbox("Floor reflection", access!(.floor_reflection.enabled))
+        .on_hover_explanation(
+            "",
+            "When enabled, 3D puzzles are reflected in a \
+             floor below them, which fades out farther from \
+             the puzzle. The reflection is a mirror image of \
+             the puzzle as drawn on screen, so it shows the \
+             same side of the puzzle rather than its \
+             underside. This is only for looks, such as in \
+             screenshots and streams.",
+        );
+    let floor_reflection = prefs_ui.current.floor_reflection.enabled;
+    prefs_ui.ui.add_enabled_ui(floor_reflection, |ui| {
+        let mut prefs_ui = PrefsUi {
+            ui,
+            current: &mut *prefs_ui.current,
+            defaults: prefs_ui.defaults,
+            changed: &mut *prefs_ui.changed,
+        };
+        prefs_ui.percent("Reflectivity", access!(.floor_reflection.reflectivity));
+        prefs_ui
+            .num("Reflection blur", access!(.floor_reflection.blur), |dv| {
+                dv.fixed_decimals(3).clamp_range(0.0..=0.1_f32).speed(0.001)
+            })
+            .on_hover_explanation(
+                "",
+                "Radius of the blur of the reflection, as a \
+                 fraction of the size of the puzzle. 0 is a \
+                 sharp reflection.",
+            );
+    });
 
     let is_msaa_disabled = cfg!(target_arch = "wasm32");
     prefs_ui.ui.add_enabled_ui(!is_msaa_disabled, |ui| {
@@ -216,6 +424,61 @@ pub fn build_interaction_section(ui: &mut egui::Ui, app: &mut App) {
              is only shown when the puzzle has been fully \
              scrambled.",
         );
+    prefs_ui
+        .checkbox("Confirm discarding solve", access!(.confirm_discard_solve))
+        .on_hover_explanation(
+            "",
+            "When enabled, a confirmation dialog is shown \
+             before any action that would discard a solve \
+             in progress, even if the puzzle would not \
+             otherwise need confirmation. A solve is in \
+             progress when the timer is running and the \
+             puzzle has been twisted.",
+        );
+    prefs_ui
+        .checkbox(
+            "Pause timer when unfocused",
+            access!(.pause_timer_on_focus_loss),
+        )
+        .on_hover_explanation(
+            "",
+            "When enabled, the timer pauses while the window \
+             is unfocused during a solve and resumes when it \
+             is focused again. The paused time is not \
+             counted in the solve time.",
+        );
+    prefs_ui
+        .checkbox("Start timer on retry", access!(.retry_starts_timer))
+        .on_hover_explanation(
+            "",
+            "When enabled, retrying a scramble also starts \
+             the timer (after the countdown, if it's \
+             enabled), so that each attempt is timed and \
+             recorded. With inspection, the timer starts \
+             after inspection as usual.",
+        );
+    prefs_ui
+        .checkbox("Scramble on startup", access!(.scramble_on_startup))
+        .on_hover_explanation(
+            "",
+            "When enabled, the last used puzzle is fully \
+             scrambled when the program starts, unless a \
+             log file is opened from the command line.",
+        );
+    prefs_ui.ui.label("\"New\" command:").on_hover_explanation(
+        "",
+        "Whether the \"New\" command scrambles the \
+         current puzzle again or opens a dialog for \
+         choosing a new puzzle. The \"New (other)\" \
+         command does the other one.",
+    );
+    prefs_ui.ui.horizontal_wrapped(|ui| {
+        for action in NewAction::iter() {
+            let r =
+                ui.selectable_value(&mut prefs_ui.current.new_action, action, action.to_string());
+            *prefs_ui.changed |= r.changed();
+        }
+    });
 
     prefs_ui.ui.separator();
 
@@ -229,6 +492,24 @@ pub fn build_interaction_section(ui: &mut egui::Ui, app: &mut App) {
             "When enabled, the puzzle snaps back immediately when \
              the mouse is released after dragging to rotate it.",
         );
+    let realign_on_release = prefs_ui.current.realign_on_release;
+    prefs_ui.ui.add_enabled_ui(!realign_on_release, |ui| {
+        PrefsUi {
+            ui,
+            current: &mut *prefs_ui.current,
+            defaults: prefs_ui.defaults,
+            changed: &mut *prefs_ui.changed,
+        }
+        .checkbox("Snap view on release", access!(.snap_view_on_release))
+        .on_hover_explanation(
+            "",
+            "When enabled, the view eases to the nearest angle \
+             where a face, edge, or corner of the puzzle points \
+             straight at the camera when the mouse is released \
+             after dragging to rotate it. Hold shift while \
+             releasing to leave the view where it is.",
+        );
+    });
     prefs_ui
         .checkbox("Realign puzzle on keypress", access!(.realign_on_keypress))
         .on_hover_explanation(
@@ -244,6 +525,86 @@ pub fn build_interaction_section(ui: &mut egui::Ui, app: &mut App) {
              similar orientation, not the original. This \
              adds a full-puzzle rotation to the undo history.",
         );
+    prefs_ui
+        .num("Key debounce (ms)", access!(.key_debounce_ms), |dv| {
+            dv.clamp_range(0..=100_u32).speed(0.1)
+        })
+        .on_hover_explanation(
+            "",
+            "Presses of the same key that come sooner than \
+             this after the previous press are ignored, for \
+             keyboards that sometimes register one press \
+             twice. Different keys are never ignored. Set \
+             to 0 to disable.",
+        );
+    prefs_ui
+        .num("Chord window (ms)", access!(.chord_window_ms), |dv| {
+            dv.clamp_range(10..=500_u32).speed(0.5)
+        })
+        .on_hover_explanation(
+            "",
+            "Maximum time between pressing the two keys of \
+             a chord keybind. Keys that are part of a chord \
+             wait this long before doing anything on their \
+             own, so that a chord doesn't also trigger the \
+             keybinds of each key.",
+        );
+    prefs_ui
+        .checkbox("Select layer ranges", access!(.select_layer_ranges))
+        .on_hover_explanation(
+            "",
+            "When enabled, holding several keys that grip \
+             layers grips every layer between them. For \
+             example, holding 2 and 4 grips layers 2, 3, \
+             and 4.",
+        );
+    prefs_ui
+        .checkbox(
+            "Puzzle keybinds use keycodes",
+            access!(.puzzle_keybinds_use_keycodes),
+        )
+        .on_hover_explanation(
+            "",
+            "By default, new puzzle keybinds use scancodes, \
+             which are based on physical key position, so \
+             the keys stay in the same place on any \
+             keyboard layout. When enabled, new puzzle \
+             keybinds use virtual keycodes, which depend on \
+             the keyboard layout. This can be overridden \
+             when binding each key.",
+        );
+    prefs_ui
+        .num("Double-click (ms)", access!(.double_click_ms), |dv| {
+            dv.clamp_range(0..=1000_u32).speed(1.0)
+        })
+        .on_hover_explanation(
+            "",
+            "Clicking the background of the puzzle twice \
+             within this time resets the view to the \
+             default orientation. Set this to 0 to disable \
+             double-clicking.",
+        );
+    prefs_ui
+        .checkbox("Hide idle cursor", access!(.hide_cursor))
+        .on_hover_explanation(
+            "",
+            "When enabled, the mouse cursor is hidden while \
+             it is over the puzzle and the mouse hasn't been \
+             used for a while. Moving the mouse shows it \
+             again.",
+        );
+    let hide_cursor = prefs_ui.current.hide_cursor;
+    prefs_ui.ui.add_enabled_ui(hide_cursor, |ui| {
+        PrefsUi {
+            ui,
+            current: &mut *prefs_ui.current,
+            defaults: prefs_ui.defaults,
+            changed: &mut *prefs_ui.changed,
+        }
+        .num("Cursor delay (s)", access!(.hide_cursor_delay), |dv| {
+            dv.fixed_decimals(1).clamp_range(0.0..=60.0_f32).speed(0.05)
+        });
+    });
 
     prefs_ui.ui.separator();
 
@@ -256,6 +617,17 @@ pub fn build_interaction_section(ui: &mut egui::Ui, app: &mut App) {
                  many moves are queued up. When all queued \
                  moves are complete, the twist speed resets.",
             );
+        prefs_ui
+            .checkbox(
+                "Proportional twist duration",
+                access!(.proportional_twist_duration),
+            )
+            .on_hover_explanation(
+                "",
+                "When enabled, twist duration is proportional \
+                 to the angle of the twist, so a 180° twist \
+                 takes twice as long as a 90° twist.",
+            );
 
         let speed = prefs_ui.current.twist_duration.at_least(0.1) / 100.0; // logarithmic speed
         prefs_ui.num("Twist duration", access!(.twist_duration), |dv| {
@@ -272,6 +644,31 @@ pub fn build_interaction_section(ui: &mut egui::Ui, app: &mut App) {
                 "Number of seconds for other animations, \
                  such as hiding a piece.",
             );
+
+        prefs_ui
+            .checkbox("Animate scrambles", access!(.animate_scramble))
+            .on_hover_explanation(
+                "",
+                "When enabled, scrambles are animated twist by \
+                 twist instead of applied instantly, so that \
+                 they can be followed on a physical puzzle. \
+                 Press any key to skip to the end.",
+            );
+        let animate_scramble = prefs_ui.current.animate_scramble;
+        prefs_ui.ui.add_enabled_ui(animate_scramble, |ui| {
+            let speed = prefs_ui.current.scramble_twist_duration.at_least(0.1) / 100.0; // logarithmic speed
+            PrefsUi {
+                ui,
+                current: &mut *prefs_ui.current,
+                defaults: prefs_ui.defaults,
+                changed: &mut *prefs_ui.changed,
+            }
+            .num(
+                "Scramble twist duration",
+                access!(.scramble_twist_duration),
+                |dv| dv.fixed_decimals(2).clamp_range(0.0..=1.0_f32).speed(speed),
+            );
+        });
     });
 
     prefs.needs_save |= changed;
@@ -331,15 +728,634 @@ pub fn build_opacity_section(ui: &mut egui::Ui, app: &mut App) {
     prefs_ui.percent("Selected", access!(.selected));
     build_unhide_grip_checkbox(&mut prefs_ui);
 
+    prefs_ui.ui.separator();
+    prefs_ui
+        .checkbox("See-through mode", access!(.see_through))
+        .on_hover_explanation(
+            "",
+            "When enabled, every sticker is drawn \
+             semi-transparent, including the far side of \
+             each sticker, to show the whole structure of \
+             the puzzle at once.",
+        );
+    prefs_ui
+        .ui
+        .add_enabled_ui(prefs_ui.current.see_through, |ui| {
+            PrefsUi { ui, ..prefs_ui }.percent("See-through", access!(.see_through_opacity));
+        });
+
     prefs.needs_save |= changed;
     if changed {
         app.request_redraw_puzzle();
     }
 }
+pub fn build_window_title_section(ui: &mut egui::Ui, app: &mut App) {
+    let prefs = &mut app.prefs;
+
+    let mut changed = false;
+    let mut prefs_ui = PrefsUi {
+        ui,
+        current: &mut prefs.info,
+        defaults: &DEFAULT_PREFS.info,
+        changed: &mut changed,
+    };
+
+    prefs_ui.checkbox("Show puzzle", access!(.title_puzzle));
+    prefs_ui.checkbox("Show timer", access!(.title_timer));
+
+    prefs.needs_save |= changed;
+}
+pub fn build_timer_section(ui: &mut egui::Ui, app: &mut App) {
+    let prefs = &mut app.prefs;
+
+    let mut changed = false;
+    let mut prefs_ui = PrefsUi {
+        ui,
+        current: &mut prefs.info,
+        defaults: &DEFAULT_PREFS.info,
+        changed: &mut changed,
+    };
+
+    prefs_ui
+        .checkbox("Show solved progress", access!(.solved_progress))
+        .on_hover_explanation(
+            "",
+            "Shows a bar in the timer window with the \
+             fraction of pieces that are in place. This \
+             is only a rough estimate for motivation.",
+        );
+    prefs_ui
+        .checkbox("Show time", access!(.show_time))
+        .on_hover_explanation(
+            "",
+            "Shows the time of the solve in progress in the \
+             timer window and the window title. When \
+             disabled, solves are still timed and recorded.",
+        );
+    prefs_ui
+        .checkbox("Show twist count", access!(.show_twist_count))
+        .on_hover_explanation(
+            "",
+            "Shows the twist count in the status bar. When \
+             disabled, twists are still counted and \
+             recorded.",
+        );
+
+    prefs.needs_save |= changed;
+}
+
+pub fn build_move_log_section(ui: &mut egui::Ui, app: &mut App) {
+    let prefs = &mut app.prefs;
+
+    let mut changed = false;
+    let mut prefs_ui = PrefsUi {
+        ui,
+        current: &mut prefs.info.move_log,
+        defaults: &DEFAULT_PREFS.info.move_log,
+        changed: &mut changed,
+    };
+
+    prefs_ui
+        .checkbox("Show recent moves", access!(.visible))
+        .on_hover_explanation(
+            "",
+            "Shows the most recent moves on top of the \
+             puzzle, with older moves faded out.",
+        );
+    prefs_ui.num("Moves shown", access!(.count), |dv| {
+        dv.clamp_range(1..=50_usize).speed(0.1)
+    });
+    prefs_ui.ui.horizontal_wrapped(|ui| {
+        for corner in ScreenCorner::iter() {
+            let r = ui.selectable_value(&mut prefs_ui.current.corner, corner, corner.to_string());
+            *prefs_ui.changed |= r.changed();
+        }
+    });
+
+    prefs.needs_save |= changed;
+}
+
+pub fn build_penalty_flash_section(ui: &mut egui::Ui, app: &mut App) {
+    let prefs = &mut app.prefs;
+
+    let mut changed = false;
+    let mut prefs_ui = PrefsUi {
+        ui,
+        current: &mut prefs.info.penalty_flash,
+        defaults: &DEFAULT_PREFS.info.penalty_flash,
+        changed: &mut changed,
+    };
+
+    prefs_ui
+        .checkbox("Flash on penalty", access!(.enabled))
+        .on_hover_explanation(
+            "",
+            "Briefly flashes the edges of the puzzle \
+             view when a penalty is assigned to a \
+             solve: red for DNF and yellow for +2. \
+             Turn this off if flashing lights are a \
+             problem for you.",
+        );
+    let enabled = prefs_ui.current.enabled;
+    prefs_ui.ui.add_enabled_ui(enabled, |ui| {
+        PrefsUi {
+            ui,
+            current: &mut *prefs_ui.current,
+            defaults: prefs_ui.defaults,
+            changed: &mut *prefs_ui.changed,
+        }
+        .percent("Intensity", access!(.intensity));
+    });
+
+    prefs.needs_save |= changed;
+}
+
+pub fn build_streak_goal_section(ui: &mut egui::Ui, app: &mut App) {
+    let prefs = &mut app.prefs;
+
+    let mut changed = false;
+    let mut prefs_ui = PrefsUi {
+        ui,
+        current: &mut prefs.info.streak_goal,
+        defaults: &DEFAULT_PREFS.info.streak_goal,
+        changed: &mut changed,
+    };
+
+    prefs_ui
+        .checkbox("Track streak goal", access!(.enabled))
+        .on_hover_explanation(
+            "",
+            "Counts solves in a row under a target \
+             time, shown in the timer window, and \
+             celebrates when the count reaches the \
+             goal. A slower solve or DNF resets the \
+             streak.",
+        );
+    let enabled = prefs_ui.current.enabled;
+    prefs_ui.ui.add_enabled_ui(enabled, |ui| {
+        let mut prefs_ui = PrefsUi {
+            ui,
+            current: &mut *prefs_ui.current,
+            defaults: prefs_ui.defaults,
+            changed: &mut *prefs_ui.changed,
+        };
+        prefs_ui.num("Solves in a row", access!(.solve_count), |dv| {
+            dv.clamp_range(1..=1000_usize).speed(0.1)
+        });
+        prefs_ui
+            .num("Target time (s)", access!(.target_time), |dv| {
+                dv.fixed_decimals(1)
+                    .clamp_range(0.1..=3600.0_f32)
+                    .speed(0.1)
+            })
+            .on_hover_explanation(
+                "",
+                "Each solve must be faster than this, including penalties.",
+            );
+        prefs_ui
+            .checkbox("Reset after a break", access!(.reset_on_break))
+            .on_hover_explanation(
+                "",
+                "Resets the streak when the time between \
+                 the end of one solve and the start of \
+                 the next is longer than the break time.",
+            );
+        let reset_on_break = prefs_ui.current.reset_on_break;
+        prefs_ui.ui.add_enabled_ui(reset_on_break, |ui| {
+            PrefsUi {
+                ui,
+                current: &mut *prefs_ui.current,
+                defaults: prefs_ui.defaults,
+                changed: &mut *prefs_ui.changed,
+            }
+            .num("Break time (min)", access!(.break_minutes), |dv| {
+                dv.fixed_decimals(1).clamp_range(0.5..=600.0_f32).speed(0.1)
+            });
+        });
+    });
+
+    prefs.needs_save |= changed;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn build_solve_log_section(ui: &mut egui::Ui, app: &mut App) {
+    let prefs = &mut app.prefs;
+
+    let mut changed = false;
+    let mut prefs_ui = PrefsUi {
+        ui,
+        current: &mut prefs.solve_log,
+        defaults: &DEFAULT_PREFS.solve_log,
+        changed: &mut changed,
+    };
+
+    prefs_ui
+        .checkbox("Log every solve", access!(.enabled))
+        .on_hover_explanation(
+            "",
+            "When enabled, every completed solve is added \
+             to a solve log file in the chosen folder, \
+             with its date, time, scramble, and solution, \
+             even if the session is never saved.",
+        );
+    prefs_ui.ui.horizontal(|ui| {
+        let folder = match &prefs_ui.current.directory {
+            Some(dir) => dir.display().to_string(),
+            None => "No folder chosen".to_owned(),
+        };
+        ui.label(folder);
+        if ui.button("Choose folder…").clicked() {
+            if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                prefs_ui.current.directory = Some(dir);
+                *prefs_ui.changed = true;
+            }
+        }
+    });
+    prefs_ui
+        .num("Max size (KB)", access!(.max_size_kb), |dv| {
+            dv.clamp_range(0..=u32::MAX).speed(8.0)
+        })
+        .on_hover_explanation(
+            "",
+            "Once the solve log would grow past this \
+             size, it is renamed with a number and a new \
+             one is started. 0 means there is no maximum.",
+        );
+    prefs_ui
+        .num("Old logs to keep", access!(.old_file_count), |dv| {
+            dv.clamp_range(0..=100)
+        })
+        .on_hover_explanation(
+            "",
+            "Number of old solve logs to keep. Older ones \
+             are deleted.",
+        );
+
+    prefs.needs_save |= changed;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn build_gamepad_section(ui: &mut egui::Ui, app: &mut App) {
+    let prefs = &mut app.prefs;
+
+    let mut changed = false;
+    let mut prefs_ui = PrefsUi {
+        ui,
+        current: &mut prefs.gamepad,
+        defaults: &DEFAULT_PREFS.gamepad,
+        changed: &mut changed,
+    };
+
+    prefs_ui
+        .checkbox("Rumble on twist", access!(.rumble_on_twist))
+        .on_hover_explanation(
+            "",
+            "When enabled, connected gamepads rumble \
+             briefly on each twist. Gamepads that don't \
+             support rumble are ignored.",
+        );
+    let rumble_on_twist = prefs_ui.current.rumble_on_twist;
+    prefs_ui.ui.add_enabled_ui(rumble_on_twist, |ui| {
+        PrefsUi {
+            ui,
+            current: &mut *prefs_ui.current,
+            defaults: prefs_ui.defaults,
+            changed: &mut *prefs_ui.changed,
+        }
+        .percent("Twist intensity", access!(.twist_rumble_intensity));
+    });
+    prefs_ui
+        .checkbox("Rumble on solve", access!(.rumble_on_solve))
+        .on_hover_explanation(
+            "",
+            "When enabled, connected gamepads rumble \
+             longer when the puzzle is solved.",
+        );
+    let rumble_on_solve = prefs_ui.current.rumble_on_solve;
+    prefs_ui.ui.add_enabled_ui(rumble_on_solve, |ui| {
+        PrefsUi {
+            ui,
+            current: &mut *prefs_ui.current,
+            defaults: prefs_ui.defaults,
+            changed: &mut *prefs_ui.changed,
+        }
+        .percent("Solve intensity", access!(.solve_rumble_intensity));
+    });
+
+    prefs.needs_save |= changed;
+}
+
+pub fn build_twist_convention_section(ui: &mut egui::Ui, app: &mut App) {
+    let ty = app.puzzle.ty();
+    let convention = &mut app.prefs.interaction.twist_conventions[ty];
+
+    let mut changed = false;
+
+    ui.label(format!("For {} puzzles", ty.family_display_name()));
+    changed |= ui
+        .checkbox(&mut convention.reverse_all, "Reverse all")
+        .on_hover_explanation(
+            "",
+            "Reverses which direction is clockwise for \
+             every twist axis. Axes checked below are \
+             reversed back.",
+        )
+        .changed();
+    ui.horizontal_wrapped(|ui| {
+        for axis in (0..ty.twist_axes().len() as u8).map(TwistAxis) {
+            let name = ty.info(axis).name;
+            let mut is_reversed = convention.reversed_axes.contains(name);
+            if ui.checkbox(&mut is_reversed, name).changed() {
+                match is_reversed {
+                    true => convention.reversed_axes.insert(name.to_owned()),
+                    false => convention.reversed_axes.remove(name),
+                };
+                changed = true;
+            }
+        }
+    });
+    ui.label(
+        "Applies to puzzle keybinds and notation shown, \
+         copied, or pasted. Log files always use the \
+         standard directions.",
+    );
+
+    app.prefs.needs_save |= changed;
+}
+
+pub fn build_solved_definition_section(ui: &mut egui::Ui, app: &mut App) {
+    let ty = app.puzzle.ty();
+    let definition = &mut app.prefs.interaction.solved_definitions[ty];
+
+    let mut changed = false;
+
+    ui.label(format!("For {}", ty.name()));
+    ui.horizontal_wrapped(|ui| {
+        for d in SolvedDefinition::iter() {
+            let explanation = match d {
+                SolvedDefinition::Standard => {
+                    "Every face is one color. Centers may be \
+                     rotated."
+                }
+                SolvedDefinition::Supercube => {
+                    "Every face is one color, and every piece is \
+                     also rotated the same way, including \
+                     centers."
+                }
+                SolvedDefinition::IgnoreCenters => {
+                    "Every face is one color, not counting \
+                     pieces with only one sticker."
+                }
+            };
+            changed |= ui
+                .selectable_value(definition, d, d.to_string())
+                .on_hover_explanation("", explanation)
+                .changed();
+        }
+    });
+    ui.label("Decides when the timer stops.");
+
+    app.prefs.needs_save |= changed;
+}
+
+pub fn build_notation_style_section(ui: &mut egui::Ui, app: &mut App) {
+    let ty = app.puzzle.ty();
+    let mut style = app.prefs.interaction.notation_style(ty);
+
+    let mut changed = false;
+
+    if ty.notation_styles().len() < 2 {
+        ui.label(format!("{} has only one notation style", ty.name()));
+        return;
+    }
+    ui.label(format!("For {}", ty.name()));
+    ui.horizontal_wrapped(|ui| {
+        for &s in ty.notation_styles() {
+            let explanation = match s {
+                NotationStyle::Standard if ty.projection_type() == ProjectionType::_4D => {
+                    "Twist directions are named by the faces \
+                     that pieces move toward, such as IUF."
+                }
+                NotationStyle::Standard => {
+                    "WCA notation, with wide twists such as Rw \
+                     and 3Rw."
+                }
+                NotationStyle::Sign => {
+                    "SiGN notation, with wide twists such as r \
+                     and 3r, and inner slices such as 2R."
+                }
+                NotationStyle::Mc4d => {
+                    "Twist directions are named by the axes of \
+                     the cell that is twisted, such as Ixy."
+                }
+            };
+            changed |= ui
+                .selectable_value(&mut style, s, s.to_string())
+                .on_hover_explanation("", explanation)
+                .changed();
+        }
+    });
+    ui.label(
+        "Applies to notation shown, copied, or pasted. \
+         Log files and saved patterns always use the \
+         standard notation.",
+    );
+
+    if changed {
+        app.prefs.interaction.notation_styles[ty] = style;
+        app.prefs.needs_save = true;
+    }
+}
+
+pub fn build_inspection_section(ui: &mut egui::Ui, app: &mut App) {
+    let prefs = &mut app.prefs;
+
+    let mut changed = false;
+    let mut prefs_ui = PrefsUi {
+        ui,
+        current: &mut prefs.interaction.inspection,
+        defaults: &DEFAULT_PREFS.interaction.inspection,
+        changed: &mut changed,
+    };
+
+    prefs_ui
+        .checkbox("Inspect before solves", access!(.enabled))
+        .on_hover_explanation(
+            "",
+            "When enabled, an inspection time counts down \
+             after each full scramble, like in competitions. \
+             The timer starts when the puzzle is first \
+             twisted or the timer is clicked. Rotating the \
+             whole puzzle doesn't count as a twist. Going \
+             up to 2 seconds over time is a +2 penalty, and \
+             going any further over is a DNF.",
+        );
+    prefs_ui.num("Inspection time (s)", access!(.duration), |dv| {
+        dv.fixed_decimals(0).clamp_range(1.0..=60.0_f32).speed(0.1)
+    });
+    prefs_ui
+        .ui
+        .label("Twisting during inspection:")
+        .on_hover_explanation(
+            "",
+            "Whether twisting the puzzle during inspection \
+             starts the solve, or also gives a +2 penalty \
+             with a warning, like applying moves during \
+             inspection in competitions.",
+        );
+    prefs_ui.ui.horizontal_wrapped(|ui| {
+        for action in InspectionTwistAction::iter() {
+            let r = ui.selectable_value(
+                &mut prefs_ui.current.twist_action,
+                action,
+                action.to_string(),
+            );
+            *prefs_ui.changed |= r.changed();
+        }
+    });
+
+    prefs.needs_save |= changed;
+}
+
+pub fn build_countdown_section(ui: &mut egui::Ui, app: &mut App) {
+    let prefs = &mut app.prefs;
+
+    let mut changed = false;
+    let mut prefs_ui = PrefsUi {
+        ui,
+        current: &mut prefs.interaction.countdown,
+        defaults: &DEFAULT_PREFS.interaction.countdown,
+        changed: &mut changed,
+    };
+
+    prefs_ui
+        .checkbox("Count down before solves", access!(.enabled))
+        .on_hover_explanation(
+            "",
+            "When enabled, starting the timer counts down \
+             first, and the timer starts when the countdown \
+             reaches zero. Unlike inspection, the puzzle \
+             can't be twisted until then, but it can still \
+             be rotated.",
+        );
+    let enabled = prefs_ui.current.enabled;
+    prefs_ui.ui.add_enabled_ui(enabled, |ui| {
+        PrefsUi {
+            ui,
+            current: &mut *prefs_ui.current,
+            defaults: prefs_ui.defaults,
+            changed: &mut *prefs_ui.changed,
+        }
+        .num("Countdown time (s)", access!(.seconds), |dv| {
+            dv.fixed_decimals(0).clamp_range(1.0..=10.0_f32).speed(0.1)
+        });
+    });
+
+    prefs.needs_save |= changed;
+}
+
+pub fn build_peek_section(ui: &mut egui::Ui, app: &mut App) {
+    let prefs = &mut app.prefs;
+
+    let mut changed = false;
+    let mut prefs_ui = PrefsUi {
+        ui,
+        current: &mut prefs.interaction.peek_angle,
+        defaults: &DEFAULT_PREFS.interaction.peek_angle,
+        changed: &mut changed,
+    };
+
+    prefs_ui.ui.label(
+        "Angle to rotate the view to while the \"Peek at \
+         last layer\" key is held.",
+    );
+    prefs_ui.angle("Pitch", access!(.pitch), |dv| dv.clamp_range(-90.0..=90.0));
+    prefs_ui.angle("Yaw", access!(.yaw), |dv| dv.clamp_range(-180.0..=180.0));
+    prefs_ui.angle("Roll", access!(.roll), |dv| dv.clamp_range(-180.0..=180.0));
+
+    prefs.needs_save |= changed;
+}
+
+pub fn build_idle_rotation_section(ui: &mut egui::Ui, app: &mut App) {
+    let prefs = &mut app.prefs;
+
+    let mut changed = false;
+    let mut prefs_ui = PrefsUi {
+        ui,
+        current: &mut prefs.interaction.idle_rotation,
+        defaults: &DEFAULT_PREFS.interaction.idle_rotation,
+        changed: &mut changed,
+    };
+
+    prefs_ui
+        .checkbox("Rotate when idle", access!(.enabled))
+        .on_hover_explanation(
+            "",
+            "When enabled, the puzzle slowly rotates when \
+             there hasn't been any input for a while, \
+             except during a solve. Any input stops it. \
+             This doesn't twist the puzzle.",
+        );
+    prefs_ui.num("Delay (s)", access!(.delay), |dv| {
+        dv.fixed_decimals(0).clamp_range(1.0..=600.0_f32).speed(0.5)
+    });
+    prefs_ui.angle("Yaw speed", access!(.yaw_speed), |dv| {
+        dv.suffix("°/s").clamp_range(-180.0..=180.0).speed(0.5)
+    });
+    prefs_ui.angle("Pitch speed", access!(.pitch_speed), |dv| {
+        dv.suffix("°/s").clamp_range(-180.0..=180.0).speed(0.5)
+    });
+
+    prefs.needs_save |= changed;
+}
+
+pub fn build_idle_dim_section(ui: &mut egui::Ui, app: &mut App) {
+    let prefs = &mut app.prefs;
+
+    let mut changed = false;
+    let mut prefs_ui = PrefsUi {
+        ui,
+        current: &mut prefs.interaction.idle_dim,
+        defaults: &DEFAULT_PREFS.interaction.idle_dim,
+        changed: &mut changed,
+    };
+
+    prefs_ui
+        .checkbox("Dim when idle", access!(.enabled))
+        .on_hover_explanation(
+            "",
+            "When enabled, the display is dimmed when there \
+             hasn't been any input for a while, except during \
+             a solve. The preferences, the window layout, and \
+             the puzzle (if it has been saved to a file \
+             before) are saved at the same time. Any input \
+             restores full brightness immediately.",
+        );
+    let enabled = prefs_ui.current.enabled;
+    prefs_ui.ui.add_enabled_ui(enabled, |ui| {
+        let mut prefs_ui = PrefsUi {
+            ui,
+            current: &mut *prefs_ui.current,
+            defaults: prefs_ui.defaults,
+            changed: &mut *prefs_ui.changed,
+        };
+        prefs_ui.num("Delay (s)", access!(.delay), |dv| {
+            dv.fixed_decimals(0)
+                .clamp_range(10.0..=3600.0_f32)
+                .speed(1.0)
+        });
+        prefs_ui.percent("Dimmed brightness", access!(.brightness));
+    });
+
+    prefs.needs_save |= changed;
+}
+
 pub fn build_view_section(ui: &mut egui::Ui, app: &mut App) {
     let puzzle_type = app.puzzle.ty();
     let proj_ty = puzzle_type.projection_type();
     let prefs = &mut app.prefs;
+    let has_puzzle_view_angle = prefs.view_angles[puzzle_type].is_some();
+    let mut clear_puzzle_view_angle = false;
     let presets = prefs.view_presets(&app.puzzle);
 
     let mut changed = false;
@@ -402,6 +1418,18 @@ pub fn build_view_section(ui: &mut egui::Ui, app: &mut App) {
     });
 
     prefs_ui.collapsing("View angle", |mut prefs_ui| {
+        if has_puzzle_view_angle {
+            let r = prefs_ui
+                .ui
+                .button("Clear default view for this puzzle")
+                .on_hover_explanation(
+                    "",
+                    "This puzzle has its own view angle, saved \
+                     with \"Set view as default\", which is used \
+                     instead of the one below.",
+                );
+            clear_puzzle_view_angle = r.clicked();
+        }
         prefs_ui.angle("Pitch", access!(.pitch), |dv| dv.clamp_range(-90.0..=90.0));
         prefs_ui.angle("Yaw", access!(.yaw), |dv| dv.clamp_range(-180.0..=180.0));
         prefs_ui.angle("Roll", access!(.roll), |dv| dv.clamp_range(-180.0..=180.0));
@@ -426,9 +1454,16 @@ pub fn build_view_section(ui: &mut egui::Ui, app: &mut App) {
         } else {
             "3D FOV"
         };
-        prefs_ui.angle(label, access!(.fov_3d), |dv| {
-            dv.clamp_range(-120.0..=120.0).speed(0.5)
-        });
+        prefs_ui
+            .angle(label, access!(.fov_3d), |dv| {
+                dv.clamp_range(-120.0..=120.0).speed(0.5)
+            })
+            .on_hover_explanation(
+                "",
+                "Lower values flatten the view and 0° is orthographic. \
+                 Higher values exaggerate perspective, and negative values \
+                 invert it so that farther parts of the puzzle look bigger.",
+            );
     });
 
     prefs_ui.collapsing("Geometry", |mut prefs_ui| {
@@ -447,6 +1482,18 @@ pub fn build_view_section(ui: &mut egui::Ui, app: &mut App) {
         prefs_ui.num("Sticker spacing", access!(.sticker_spacing), |dv| {
             dv.fixed_decimals(2).clamp_range(0.0..=0.9_f32).speed(0.005)
         });
+
+        prefs_ui
+            .num("Explode distance", access!(.explode), |dv| {
+                dv.fixed_decimals(2).clamp_range(0.0..=5.0_f32).speed(0.01)
+            })
+            .on_hover_explanation(
+                "",
+                "How far pieces move outward from the center \
+                 when the puzzle is exploded with the \
+                 \"Explode\" command, relative to their \
+                 distance from the center.",
+            );
     });
 
     prefs_ui.collapsing("Lighting", |mut prefs_ui| {
@@ -460,6 +1507,11 @@ pub fn build_view_section(ui: &mut egui::Ui, app: &mut App) {
         prefs_ui.percent("Ambient", access!(.light_ambient));
     });
 
+    if clear_puzzle_view_angle {
+        prefs.view_angles[puzzle_type] = None;
+        changed = true;
+    }
+
     prefs.needs_save |= changed;
     if changed {
         app.request_redraw_puzzle();
@@ -37,5 +37,11 @@ pub fn puzzle_type_menu(ui: &mut egui::Ui) -> Option<PuzzleTypeEnum> {
         ret = Some(default);
     }
 
+    let ty = PuzzleTypeEnum::Ivy;
+    if ui.button(ty.family_display_name()).clicked() {
+        ui.close_menu();
+        ret = Some(ty);
+    }
+
     ret
 }
@@ -22,6 +22,13 @@ pub(super) struct State {
     mods: ModifiersState,
     last_vk_pressed: Option<VirtualKeyCode>,
     last_sc_pressed: Option<KeyMappingCode>,
+    /// Whether the last key pressed is still held, in which case pressing
+    /// another key makes a chord.
+    is_last_key_held: bool,
+    /// Second key of a chord, which was pressed while the first was held.
+    chord_vk_pressed: Option<VirtualKeyCode>,
+    chord_sc_pressed: Option<KeyMappingCode>,
+    is_chord_key_held: bool,
 
     use_vk: bool,
     use_vk_id: Option<egui::Id>,
@@ -32,13 +39,58 @@ impl State {
         let vk = self.last_vk_pressed.map(Key::Vk);
         let key = if self.use_vk { vk.or(sc) } else { sc.or(vk) };
 
-        self.key = Some(KeyCombo::new(key, self.mods));
+        let chord_sc = self.chord_sc_pressed.map(Key::Sc);
+        let chord_vk = self.chord_vk_pressed.map(Key::Vk);
+        let chord = if self.use_vk {
+            chord_vk.or(chord_sc)
+        } else {
+            chord_sc.or(chord_vk)
+        };
+
+        self.key = Some(KeyCombo::new(key, self.mods).with_chord(chord));
     }
     fn set_key(&mut self, sc: KeyMappingCode, vk: VirtualKeyCode) {
         self.last_sc_pressed = Some(sc);
         self.last_vk_pressed = Some(vk);
+        self.is_last_key_held = false;
+        self.chord_sc_pressed = None;
+        self.chord_vk_pressed = None;
+        self.is_chord_key_held = false;
+        self.update_keybind();
+    }
+    fn press_key(&mut self, sc: Option<KeyMappingCode>, vk: Option<VirtualKeyCode>) {
+        let is_repeat = self.is_last_key_held
+            && (sc, vk) == (self.last_sc_pressed, self.last_vk_pressed)
+            || self.is_chord_key_held && (sc, vk) == (self.chord_sc_pressed, self.chord_vk_pressed);
+        let is_modifier = sc
+            .map(Key::Sc)
+            .or(vk.map(Key::Vk))
+            .map_or(false, Key::is_modifier);
+
+        if is_repeat {
+            return;
+        } else if self.is_last_key_held && !is_modifier {
+            self.chord_sc_pressed = sc;
+            self.chord_vk_pressed = vk;
+            self.is_chord_key_held = true;
+        } else {
+            self.last_sc_pressed = sc;
+            self.last_vk_pressed = vk;
+            self.is_last_key_held = !is_modifier;
+            self.chord_sc_pressed = None;
+            self.chord_vk_pressed = None;
+            self.is_chord_key_held = false;
+        }
         self.update_keybind();
     }
+    fn release_key(&mut self, sc: Option<KeyMappingCode>, vk: Option<VirtualKeyCode>) {
+        if (sc, vk) == (self.last_sc_pressed, self.last_vk_pressed) {
+            self.is_last_key_held = false;
+        }
+        if (sc, vk) == (self.chord_sc_pressed, self.chord_vk_pressed) {
+            self.is_chord_key_held = false;
+        }
+    }
     fn confirm(&mut self, app: &mut App) {
         if let Some(callback) = self.callback.take() {
             callback(app, self.key.unwrap_or_default());
@@ -64,14 +116,18 @@ pub(super) fn open<S: KeybindSetAccessor>(
     key: Option<KeyCombo>,
     keybind_set: S,
     idx: usize,
+    use_vk_by_default: bool,
 ) {
     let mut data = ctx.data();
 
     // Global keybinds should use virtual keycodes by default, while puzzle
-    // keybinds should use scancodes by default. If the user manually overrides
-    // one, remember that decision for as long as the application is running.
-    let use_vk_id = unique_id!().with(S::USE_VK_BY_DEFAULT);
-    let use_vk = data.get_temp(use_vk_id).unwrap_or(S::USE_VK_BY_DEFAULT);
+    // keybinds should use scancodes by default unless the user prefers
+    // otherwise. If the user manually overrides one, remember that decision for
+    // as long as the application is running.
+    let use_vk_id = unique_id!()
+        .with(std::any::type_name::<S>())
+        .with(use_vk_by_default);
+    let use_vk = data.get_temp(use_vk_id).unwrap_or(use_vk_by_default);
 
     *popup_state_mut(&mut data) = State {
         callback: Some(Arc::new(move |app, new_key_combo| {
@@ -84,6 +140,10 @@ pub(super) fn open<S: KeybindSetAccessor>(
         mods: ModifiersState::empty(),
         last_vk_pressed: None,
         last_sc_pressed: None,
+        is_last_key_held: false,
+        chord_vk_pressed: None,
+        chord_sc_pressed: None,
+        is_chord_key_held: false,
 
         use_vk,
         use_vk_id: Some(use_vk_id),
@@ -117,6 +177,7 @@ pub(super) fn build(ctx: &egui::Context, app: &mut App) -> Option<egui::Response
                                 ui.spacing_mut().item_spacing.y = 20.0;
 
                                 ui.heading("Press a key combination");
+                                ui.label("Hold a key and press another to bind a chord");
 
                                 let key_combo = popup_state(ctx).key.unwrap_or_default();
                                 if key_combo.key().is_some() {
@@ -226,13 +287,16 @@ pub(crate) fn key_combo_popup_handle_event(
                 match input.virtual_keycode {
                     Some(VirtualKeyCode::Return) if popup.mods.is_empty() => popup.confirm(app),
                     Some(VirtualKeyCode::Escape) if popup.mods.is_empty() => popup.cancel(),
-                    _ => {
-                        popup.last_sc_pressed = key_names::sc_to_key(input.scancode as u16);
-                        popup.last_vk_pressed = input.virtual_keycode;
-                        popup.update_keybind();
-                    }
+                    _ => popup.press_key(
+                        key_names::sc_to_key(input.scancode as u16),
+                        input.virtual_keycode,
+                    ),
                 }
             }
+            winit::event::WindowEvent::KeyboardInput { input, .. } => popup.release_key(
+                key_names::sc_to_key(input.scancode as u16),
+                input.virtual_keycode,
+            ),
 
             winit::event::WindowEvent::ModifiersChanged(mods) => popup.mods = *mods,
 
@@ -3,6 +3,8 @@ use super::ext::ResponseExt;
 use super::windows;
 use crate::app::App;
 use crate::commands::Command;
+use crate::preferences::NewAction;
+use crate::puzzle::{orientation_scramble, ScrambleSide};
 
 pub fn build(ui: &mut egui::Ui, app: &mut App) {
     egui::menu::bar(ui, |ui| {
@@ -16,6 +18,47 @@ pub fn build(ui: &mut egui::Ui, app: &mut App) {
                 command_button(ui, app, "Save", Command::Save);
                 command_button(ui, app, "Save as...", Command::SaveAs);
                 ui.separator();
+                if ui.button("Open session...").clicked() {
+                    ui.close_menu();
+                    app.event(crate::app::AppEvent::OpenSession);
+                }
+                if ui
+                    .button("Save session...")
+                    .on_hover_text("Saves every solve in this session, to be reopened later")
+                    .clicked()
+                {
+                    ui.close_menu();
+                    app.event(crate::app::AppEvent::SaveSession);
+                }
+                if ui.button("Open replay...").clicked() {
+                    ui.close_menu();
+                    app.event(crate::app::AppEvent::OpenReplay);
+                }
+                if ui
+                    .button("Export replay...")
+                    .on_hover_text(
+                        "Saves the most recent timed solve with its \
+                         timing and colors, to be watched later",
+                    )
+                    .clicked()
+                {
+                    ui.close_menu();
+                    app.event(crate::app::AppEvent::ExportReplay);
+                }
+                if app.prefs.show_advanced_tools
+                    && ui
+                        .button("Export render layers...")
+                        .on_hover_text(
+                            "Saves the puzzle view as separate color, \
+                             depth, and normal images, for compositing \
+                             the puzzle into other images",
+                        )
+                        .clicked()
+                {
+                    ui.close_menu();
+                    app.event(crate::app::AppEvent::ExportRenderLayers);
+                }
+                ui.separator();
             }
             command_button_with_explanation(
                 ui,
@@ -33,6 +76,17 @@ pub fn build(ui: &mut egui::Ui, app: &mut App) {
                 "MC4D-compatible log file",
                 "Backwards-compatible with Magic Cube 4D",
             );
+            command_button_with_explanation(
+                ui,
+                app,
+                "Copy solution",
+                Command::CopySolution,
+                "Notation for the solution",
+                "Includes twists on both sides of the scramble. \
+                 Whole-puzzle rotations are removed if \
+                 \"Normalize rotations\" is enabled in the \
+                 twist count menu.",
+            );
 
             #[cfg(not(target_arch = "wasm32"))]
             {
@@ -48,6 +102,18 @@ pub fn build(ui: &mut egui::Ui, app: &mut App) {
             ui.add_enabled_ui(app.puzzle.has_redo(), |ui| {
                 command_button(ui, app, "Redo twist", Command::Redo);
             });
+            ui.add_enabled_ui(app.puzzle.has_undo(), |ui| {
+                command_button_with_explanation(
+                    ui,
+                    app,
+                    "Discard last twist",
+                    Command::DiscardLastTwist,
+                    "",
+                    "Undoes the last twist and removes it from \
+                     the history so that it can't be redone, for \
+                     fixing a misturn.",
+                );
+            });
             ui.separator();
             command_button(ui, app, "Reset puzzle", Command::Reset);
         });
@@ -58,12 +124,196 @@ pub fn build(ui: &mut egui::Ui, app: &mut App) {
             }
             ui.separator();
             command_button(ui, app, "Full", Command::ScrambleFull);
+            let ty = app.puzzle.ty();
+            ui.add_enabled_ui(orientation_scramble::is_supported(ty), |ui| {
+                command_button_with_explanation(
+                    ui,
+                    app,
+                    "Orientation only",
+                    Command::ScrambleOrientation,
+                    "",
+                    "Scrambles the puzzle so that every piece is \
+                     in its original location but may be twisted \
+                     or flipped, for practicing orientation (such \
+                     as OLL). This is only available for the \
+                     2x2x2 and 3x3x3.",
+                );
+            });
+            command_button_with_explanation(
+                ui,
+                app,
+                "Copy new scramble",
+                Command::CopyScramble,
+                "",
+                "Copies the notation for a new full scramble \
+                 without changing the current puzzle.",
+            );
+            windows::SCRAMBLE_PREVIEW.menu_button_toggle(ui);
+            ui.add_enabled_ui(!app.puzzle.scramble().is_empty(), |ui| {
+                command_button_with_explanation(
+                    ui,
+                    app,
+                    "Retry scramble",
+                    Command::RetryScramble,
+                    "",
+                    "Resets the puzzle and reapplies the current \
+                     scramble, for another attempt at the same \
+                     starting position.",
+                );
+            });
+            ui.separator();
+            command_button_with_explanation(
+                ui,
+                app,
+                match app.puzzle.side() {
+                    ScrambleSide::Normal => "Switch to inverse",
+                    ScrambleSide::Inverse => "Switch to normal",
+                },
+                Command::SwitchScrambleSide,
+                "",
+                "Switches between solving the scramble and \
+                 solving its inverse. Twists done on one side \
+                 are applied as premoves on the other side, \
+                 and the twist count includes both.",
+            );
         });
 
         ui.menu_button("Puzzle", |ui| {
+            // Both actions are always shown, with whichever one is bound to
+            // "New" showing its keybind.
+            let new_action = app.prefs.interaction.new_action;
+            let new_command = |action| {
+                if action == new_action {
+                    Command::New
+                } else {
+                    Command::NewOther
+                }
+            };
+            command_button_with_explanation(
+                ui,
+                app,
+                "New scramble",
+                new_command(NewAction::NewScramble),
+                "",
+                "Scrambles the current puzzle fully.",
+            );
+            command_button_with_explanation(
+                ui,
+                app,
+                "New puzzle...",
+                new_command(NewAction::ChoosePuzzle),
+                "",
+                "Opens a dialog for choosing a new puzzle.",
+            );
+            ui.separator();
             if let Some(ty) = puzzle_type_menu(ui) {
                 app.event(Command::NewPuzzle(ty));
             }
+            let background_puzzle_type = app.background_puzzle_type();
+            ui.add_enabled_ui(background_puzzle_type.is_some(), |ui| {
+                command_button_with_explanation(
+                    ui,
+                    app,
+                    &match background_puzzle_type {
+                        Some(ty) => format!("Switch to {ty}"),
+                        None => "Switch to previous puzzle".to_owned(),
+                    },
+                    Command::SwapPuzzle,
+                    "",
+                    "Switches between the current puzzle and the \
+                     previous one, keeping the state of both.",
+                );
+            });
+            ui.separator();
+            command_button_with_explanation(
+                ui,
+                app,
+                "Rotate color scheme",
+                Command::RotateColorScheme,
+                "",
+                "Cycles which color is on the down face, \
+                 for color-neutral practice. This does not \
+                 twist the puzzle.",
+            );
+            command_button_with_explanation(
+                ui,
+                app,
+                "Match color scheme to view",
+                Command::MatchColorSchemeToView,
+                "",
+                "After dragging the view to match how a \
+                 physical puzzle is held, rotates the color \
+                 scheme so that the colors stay where they are \
+                 and resets the view. This does not twist the \
+                 puzzle.",
+            );
+            command_button_with_explanation(
+                ui,
+                app,
+                "Cycle color scheme",
+                Command::CycleColorScheme,
+                "",
+                "Loads the next color scheme saved in the \
+                 \"Colors\" settings for this puzzle.",
+            );
+            command_button_with_explanation(
+                ui,
+                app,
+                "Reset view",
+                Command::ResetView,
+                "",
+                "Rotates the view back to the default \
+                 orientation after dragging. Double-clicking \
+                 the background of the puzzle does this too.",
+            );
+            command_button_with_explanation(
+                ui,
+                app,
+                "Set view as default",
+                Command::SetDefaultView,
+                "",
+                "Saves the current orientation as the view \
+                 angle for this puzzle, which \"Reset view\" \
+                 returns to.",
+            );
+            command_button_with_explanation(
+                ui,
+                app,
+                "Peek at last layer",
+                Command::PeekLastLayer,
+                "",
+                "Rotates the view to the peek angle from the \
+                 interaction settings, or back to where it was. \
+                 When bound to a key, the view returns once the \
+                 key is released.",
+            );
+            command_button_with_explanation(
+                ui,
+                app,
+                match app.puzzle.is_exploded() {
+                    true => "Assemble",
+                    false => "Explode",
+                },
+                Command::ToggleExplode,
+                "",
+                "Moves every piece outward from the center of \
+                 the puzzle to show the inside, or back again. \
+                 The distance can be changed in the \"View\" \
+                 settings.",
+            );
+            command_button_with_explanation(
+                ui,
+                app,
+                match app.zen_mode {
+                    true => "Exit zen mode",
+                    false => "Zen mode",
+                },
+                Command::ToggleZenMode,
+                "",
+                "Hides the timer and stats and stops detecting \
+                 and recording solves, for turning the puzzle \
+                 without any pressure.",
+            );
         });
 
         ui.menu_button("Settings", |ui| {
@@ -74,6 +324,44 @@ pub fn build(ui: &mut egui::Ui, app: &mut App) {
             windows::GLOBAL_KEYBINDS.menu_button_toggle(ui);
             windows::PUZZLE_KEYBINDS.menu_button_toggle(ui);
             windows::MOUSEBINDS.menu_button_toggle(ui);
+            #[cfg(not(target_arch = "wasm32"))]
+            windows::GAMEPAD.menu_button_toggle(ui);
+            ui.separator();
+            let r = ui.button("Reset layout").on_hover_explanation(
+                "",
+                "Closes all windows and resets their positions \
+                 and sizes. Positions are otherwise saved \
+                 between sessions.",
+            );
+            if r.clicked() {
+                ui.close_menu();
+                super::reset_layout(ui.ctx(), &mut app.prefs);
+            }
+
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                let r = ui
+                    .checkbox(
+                        &mut app.prefs.window.remember_geometry,
+                        "Remember window position",
+                    )
+                    .on_hover_explanation(
+                        "",
+                        "Restores the position and size of the window, \
+                         and which monitor it was on, on startup.",
+                    );
+                app.prefs.needs_save |= r.changed();
+                let r = ui.button("Reset window position").on_hover_explanation(
+                    "",
+                    "Moves the window back to the middle of the \
+                     primary monitor, such as if it ended up off \
+                     screen.",
+                );
+                if r.clicked() {
+                    ui.close_menu();
+                    app.event(crate::app::AppEvent::ResetWindowPosition);
+                }
+            }
 
             #[cfg(target_arch = "wasm32")]
             {
@@ -91,14 +379,38 @@ pub fn build(ui: &mut egui::Ui, app: &mut App) {
             windows::PIECE_FILTERS.menu_button_toggle(ui);
             windows::PUZZLE_CONTROLS.menu_button_toggle(ui);
             windows::KEYBIND_SETS.menu_button_toggle(ui);
+            windows::LAYER_GROUPS.menu_button_toggle(ui);
             windows::MODIFIER_KEYS.menu_button_toggle(ui);
+            windows::TOUCH_CONTROLS.menu_button_toggle(ui);
             windows::TIMER.menu_button_toggle(ui);
+            windows::STATS.menu_button_toggle(ui);
+            windows::LAST_LAYER_VIEW.menu_button_toggle(ui);
+            windows::MIRROR_VIEW.menu_button_toggle(ui);
+            windows::LETTER_SCHEME.menu_button_toggle(ui);
+            windows::BLIND_TRACE.menu_button_toggle(ui);
+            windows::NET_DIAGRAM.menu_button_toggle(ui);
+            #[cfg(not(target_arch = "wasm32"))]
+            windows::ALG_SHEET.menu_button_toggle(ui);
+            windows::PATTERNS.menu_button_toggle(ui);
+            windows::CASE_TRAINER.menu_button_toggle(ui);
+            windows::DRILL.menu_button_toggle(ui);
+            windows::TIME_ATTACK.menu_button_toggle(ui);
+            windows::SOLUTION_COMPARISON.menu_button_toggle(ui);
+            windows::COMMUTATOR_BUILDER.menu_button_toggle(ui);
+            ui.separator();
+            let r = ui.checkbox(&mut app.prefs.show_advanced_tools, "Show advanced tools");
+            app.prefs.needs_save |= r.changed();
+            if app.prefs.show_advanced_tools {
+                windows::STATE_CHECKER.menu_button_toggle(ui);
+                windows::SOLVER.menu_button_toggle(ui);
+            }
         });
 
         ui.menu_button("Help", |ui| {
             windows::KEYBINDS_REFERENCE.menu_button_toggle(ui);
             ui.separator();
             windows::WELCOME.menu_button_toggle(ui);
+            windows::WHATS_NEW.menu_button_toggle(ui);
             windows::ABOUT.menu_button_toggle(ui);
             #[cfg(debug_assertions)]
             windows::DEBUG.menu_button_toggle(ui);
@@ -15,10 +15,39 @@ mod side_bar;
 mod status_bar;
 pub(super) mod windows;
 
+use std::collections::BTreeMap;
+
 use crate::app::App;
+use crate::preferences::{Preferences, WindowLayout};
 pub(super) use key_combo_popup::{key_combo_popup_captures_event, key_combo_popup_handle_event};
 
 pub fn build(ctx: &egui::Context, app: &mut App, puzzle_texture_id: egui::TextureId) {
+    // The keybinds reference can be toggled by a keybind, which only has
+    // access to preferences.
+    let keybinds_reference_visible = app.prefs.info.keybinds_reference.visible;
+    if windows::KEYBINDS_REFERENCE.is_open(ctx) != keybinds_reference_visible {
+        windows::KEYBINDS_REFERENCE.set_open(ctx, keybinds_reference_visible);
+    }
+
+    // Show the touch controls the first time the screen is touched, since
+    // there probably isn't a keyboard.
+    let touch_controls = &mut app.prefs.info.touch_controls;
+    if touch_controls.show_on_touch && ctx.input().any_touches() {
+        touch_controls.show_on_touch = false;
+        touch_controls.visible = true;
+        app.prefs.needs_save = true;
+    }
+    if windows::TOUCH_CONTROLS.is_open(ctx) != touch_controls.visible {
+        windows::TOUCH_CONTROLS.set_open(ctx, touch_controls.visible);
+    }
+
+    if std::mem::take(&mut app.choose_puzzle_requested) {
+        windows::NEW_PUZZLE.set_open(ctx, true);
+    }
+    if std::mem::take(&mut app.save_layout_requested) {
+        save_layout(ctx, &mut app.prefs);
+    }
+
     egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| menu_bar::build(ui, app));
 
     egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| status_bar::build(ui, app));
@@ -41,4 +70,67 @@ pub fn build(ctx: &egui::Context, app: &mut App, puzzle_texture_id: egui::Textur
         });
 
     key_combo_popup::build(ctx, app);
+
+    if app.is_dimmed() {
+        draw_idle_dim(ctx, app);
+    }
+}
+
+/// Darkens the whole window, including the puzzle and all other windows.
+fn draw_idle_dim(ctx: &egui::Context, app: &App) {
+    let brightness = app.prefs.interaction.idle_dim.brightness.clamp(0.0, 1.0);
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Tooltip,
+        egui::Id::new("idle_dim"),
+    ));
+    painter.rect_filled(
+        ctx.input().screen_rect(),
+        0.0,
+        egui::Rgba::from_black_alpha(1.0 - brightness),
+    );
+}
+
+/// Restores the window layout saved in the preferences.
+pub fn load_layout(ctx: &egui::Context, prefs: &Preferences) {
+    for window in windows::ALL {
+        if let Some(layout) = prefs.layout.get(window.name) {
+            window.set_open(ctx, layout.is_open);
+            window.set_pos(ctx, layout.pos.map(egui::Pos2::from));
+        }
+    }
+}
+
+/// Saves the position and open state of each window to the preferences if
+/// they have changed.
+pub fn save_layout(ctx: &egui::Context, prefs: &mut Preferences) {
+    let layout: BTreeMap<String, WindowLayout> = windows::ALL
+        .iter()
+        .map(|window| {
+            let window_layout = WindowLayout {
+                is_open: window.is_open(ctx),
+                pos: window.pos(ctx).map(|pos| [pos.x, pos.y]),
+            };
+            (window.name.to_owned(), window_layout)
+        })
+        .filter(|(_, window_layout)| *window_layout != WindowLayout::default())
+        .collect();
+    if prefs.layout != layout {
+        prefs.layout = layout;
+        prefs.needs_save = true;
+    }
+}
+
+/// Closes all windows and resets their positions and sizes.
+fn reset_layout(ctx: &egui::Context, prefs: &mut Preferences) {
+    replace_memory(ctx, egui::Memory::default());
+    prefs.layout.clear();
+    prefs.needs_save = true;
+}
+
+/// Replaces egui's memory, keeping the current style.
+fn replace_memory(ctx: &egui::Context, memory: egui::Memory) {
+    let mut old_memory = ctx.memory();
+    let options = old_memory.options.clone();
+    *old_memory = memory;
+    old_memory.options = options;
 }
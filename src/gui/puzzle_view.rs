@@ -1,6 +1,8 @@
 use winit::event::ModifiersState;
 
 use crate::app::{App, AppEvent};
+use crate::puzzle::traits::*;
+use crate::stats::Penalty;
 
 // experimental
 const ENABLE_CONTEXT_MENU: bool = false;
@@ -32,6 +34,44 @@ pub fn build(ui: &mut egui::Ui, app: &mut App, puzzle_texture_id: egui::TextureI
         egui::Image::new(puzzle_texture_id, egui_rect.size()).sense(egui::Sense::click_and_drag()),
     );
 
+    if app.prefs.letters.visible {
+        draw_letters(ui, app, egui_rect);
+    }
+    if app.prefs.colors.supercube_markers {
+        draw_supercube_markers(ui, app, egui_rect);
+    }
+    if app.prefs.colors.reference_frame {
+        draw_reference_frame(ui, app, egui_rect);
+    }
+    if app.prefs.info.move_log.visible {
+        draw_move_log(ui, app, egui_rect);
+    }
+    if app.demonstration.is_some() {
+        draw_demonstration_hint(ui, app, egui_rect);
+    }
+    if app.training_wheels.is_some() {
+        draw_training_wheels_hint(ui, app, egui_rect);
+    }
+    if app.inspection_start.is_some() {
+        draw_inspection_countdown(ui, app, egui_rect);
+    }
+    if app.countdown_start.is_some() {
+        draw_start_countdown(ui, app, egui_rect);
+    }
+    if app.time_attack.is_running() {
+        draw_time_attack_clock(ui, app, egui_rect);
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if app.replay.is_some() {
+        draw_replay_hint(ui, app, egui_rect);
+    }
+    if app.penalty_flash.is_some() {
+        draw_penalty_flash(ui, app, egui_rect);
+    }
+    if app.streak_celebration.is_some() {
+        draw_streak_celebration(ui, app, egui_rect);
+    }
+
     // Update app cursor position.
     app.cursor_pos = r.hover_pos().map(|pos| {
         let p = (pos - egui_rect.min) / egui_rect.size();
@@ -81,8 +121,6 @@ pub fn build(ui: &mut egui::Ui, app: &mut App, puzzle_texture_id: egui::TextureI
     // Show debug info for each sticker.
     #[cfg(debug_assertions)]
     if let Some(sticker) = app.puzzle.hovered_sticker() {
-        use crate::puzzle::traits::*;
-
         let mut s = String::new();
         app.puzzle.displayed().sticker_debug_info(&mut s, sticker);
         if !s.is_empty() {
@@ -95,6 +133,410 @@ pub fn build(ui: &mut egui::Ui, app: &mut App, puzzle_texture_id: egui::TextureI
     }
 }
 
+/// Returns a function that transforms a point from projected puzzle
+/// coordinates to egui coordinates within `rect`.
+fn puzzle_to_screen(app: &mut App, rect: egui::Rect) -> impl Fn(cgmath::Point3<f32>) -> egui::Pos2 {
+    let view_prefs = app.puzzle.view_prefs(&app.prefs);
+    let (align_h, align_v) = (view_prefs.align_h, view_prefs.align_v);
+
+    // This must match the transformation in `render::draw_puzzle()`.
+    let min_dimen = f32::min(rect.width(), rect.height());
+    let [mirror_x, mirror_y] = app.prefs.info.mirror_view.main_view_scale();
+    let scale = egui::vec2(
+        min_dimen * view_prefs.scale / rect.width() * mirror_x,
+        min_dimen * view_prefs.scale / rect.height() * mirror_y,
+    );
+    move |p| {
+        let x = p.x * scale.x + align_h;
+        let y = p.y * scale.y + align_v;
+        // Transform from wgpu to egui coordinates.
+        rect.min + egui::vec2((x + 1.0) / 2.0, (1.0 - y) / 2.0) * rect.size()
+    }
+}
+
+/// Draws the letter scheme on top of the puzzle, at the center of each visible
+/// sticker.
+fn draw_letters(ui: &mut egui::Ui, app: &mut App, rect: egui::Rect) {
+    let ty = app.puzzle.ty();
+    let to_screen = puzzle_to_screen(app, rect);
+    let scheme = app.prefs.letters.scheme(ty);
+    let geometry = app.puzzle.geometry(&app.prefs);
+
+    let painter = ui.painter_at(rect);
+    for geom in geometry.iter() {
+        let Some(polygon) = geom.front_polygons.first() else {
+            continue; // facing away from the camera
+        };
+        let Some(letter) = scheme.letter(ty, geom.sticker) else {
+            continue;
+        };
+        let min = to_screen(polygon.min_bound);
+        let max = to_screen(polygon.max_bound);
+        let size = f32::min((max.x - min.x).abs(), (max.y - min.y).abs()) * 0.6;
+        if size < 1.0 {
+            continue;
+        }
+        let center = polygon
+            .verts
+            .iter()
+            .map(|&v| to_screen(v).to_vec2())
+            .fold(egui::Vec2::ZERO, |sum, v| sum + v)
+            / polygon.verts.len() as f32;
+        painter.text(
+            center.to_pos2(),
+            egui::Align2::CENTER_CENTER,
+            letter,
+            egui::FontId::proportional(size),
+            app.prefs.letters.color,
+        );
+    }
+}
+
+/// Draws a line from the center of each visible sticker to its orientation
+/// marker, so that center orientation can be seen on supercubes.
+fn draw_supercube_markers(ui: &mut egui::Ui, app: &mut App, rect: egui::Rect) {
+    let to_screen = puzzle_to_screen(app, rect);
+    let geometry = app.puzzle.geometry(&app.prefs);
+
+    let painter = ui.painter_at(rect);
+    for geom in geometry.iter() {
+        let Some(polygon) = geom.front_polygons.first() else {
+            continue; // facing away from the camera
+        };
+        let Some(marker) = geom.orientation_marker else {
+            continue;
+        };
+        let center = polygon
+            .verts
+            .iter()
+            .map(|&v| to_screen(v).to_vec2())
+            .fold(egui::Vec2::ZERO, |sum, v| sum + v)
+            / polygon.verts.len() as f32;
+        let tip = to_screen(marker);
+        let width = (tip - center.to_pos2()).length() * 0.15;
+        if width < 0.5 {
+            continue;
+        }
+        painter.line_segment(
+            [center.to_pos2(), tip],
+            egui::Stroke::new(width, app.prefs.colors.supercube_marker),
+        );
+        painter.circle_filled(tip, width, app.prefs.colors.supercube_marker);
+    }
+}
+
+/// Draws a wireframe tesseract around a 4D puzzle.
+fn draw_reference_frame(ui: &mut egui::Ui, app: &mut App, rect: egui::Rect) {
+    const WIDTH: f32 = 1.0;
+
+    let to_screen = puzzle_to_screen(app, rect);
+    let edges = app.puzzle.reference_frame_edges(&app.prefs);
+    let colors = &app.prefs.colors;
+    let opacity = colors.reference_frame_opacity.clamp(0.0, 1.0);
+    let stroke = egui::Stroke::new(WIDTH, colors.reference_frame_color.linear_multiply(opacity));
+
+    let painter = ui.painter_at(rect);
+    for [a, b] in edges {
+        painter.line_segment([to_screen(a), to_screen(b)], stroke);
+    }
+}
+
+/// Draws the most recent moves in a corner of the puzzle view, with older
+/// moves faded out.
+fn draw_move_log(ui: &mut egui::Ui, app: &mut App, rect: egui::Rect) {
+    const MARGIN: f32 = 8.0;
+    const MIN_OPACITY: f32 = 0.2;
+
+    let prefs = app.prefs.info.move_log;
+    let ty = app.puzzle.ty();
+    let convention = &app.prefs.interaction.twist_conventions[ty];
+    let style = app.prefs.interaction.notation_style(ty);
+    let history = app.puzzle.undo_buffer();
+    let recent = &history[history.len().saturating_sub(prefs.count)..];
+    if recent.is_empty() {
+        return;
+    }
+
+    let painter = ui.painter_at(rect);
+    let font_id = egui::TextStyle::Heading.resolve(ui.style());
+    let spacing = font_id.size / 2.0;
+    let color = ui.visuals().strong_text_color();
+    let galleys = recent
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let age = (recent.len() - 1 - i) as f32 / prefs.count as f32;
+            let opacity = 1.0 - age * (1.0 - MIN_OPACITY);
+            painter.layout_no_wrap(
+                entry.to_string(ty, convention, style),
+                font_id.clone(),
+                color.linear_multiply(opacity),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let width = galleys.iter().map(|g| g.size().x + spacing).sum::<f32>() - spacing;
+    let height = galleys.iter().map(|g| g.size().y).fold(0.0, f32::max);
+    let log_rect = prefs
+        .corner
+        .align()
+        .align_size_within_rect(egui::vec2(width, height), rect.shrink(MARGIN));
+    let mut x = log_rect.left();
+    for galley in galleys {
+        let galley_width = galley.size().x;
+        painter.galley(egui::pos2(x, log_rect.top()), galley);
+        x += galley_width + spacing;
+    }
+}
+
+/// Outlines the stickers moved by the next twist of the training wheels
+/// solution and shows the twist at the top of the puzzle view.
+fn draw_training_wheels_hint(ui: &mut egui::Ui, app: &mut App, rect: egui::Rect) {
+    const MARGIN: f32 = 8.0;
+    const OUTLINE_WIDTH: f32 = 3.0;
+
+    let Some(twist) = app.training_wheels.as_ref().and_then(|t| t.next_twist()) else {
+        return;
+    };
+
+    let to_screen = puzzle_to_screen(app, rect);
+    let geometry = app.puzzle.geometry(&app.prefs);
+    let painter = ui.painter_at(rect);
+    let stroke = egui::Stroke::new(OUTLINE_WIDTH, ui.visuals().selection.bg_fill);
+    for geom in geometry.iter() {
+        let piece = app.puzzle.info(geom.sticker).piece;
+        if !app
+            .puzzle
+            .displayed()
+            .is_piece_affected_by_twist(twist, piece)
+        {
+            continue;
+        }
+        for polygon in geom.front_polygons.iter() {
+            let points = polygon.verts.iter().map(|&v| to_screen(v)).collect();
+            painter.add(egui::Shape::closed_line(points, stroke));
+        }
+    }
+
+    painter.text(
+        rect.center_top() + egui::vec2(0.0, MARGIN),
+        egui::Align2::CENTER_TOP,
+        format!(
+            "Next: {}",
+            app.puzzle
+                .notation_scheme_for_style(app.prefs.interaction.notation_style(app.puzzle.ty()))
+                .twist_to_string(twist)
+        ),
+        egui::TextStyle::Heading.resolve(ui.style()),
+        ui.visuals().strong_text_color(),
+    );
+}
+
+/// Briefly flashes the edges of the puzzle view in a color for the most
+/// recently assigned penalty: red for DNF and yellow for +2.
+fn draw_penalty_flash(ui: &mut egui::Ui, app: &mut App, rect: egui::Rect) {
+    const DURATION: f32 = 0.5;
+    const WIDTH: f32 = 12.0;
+
+    let Some((penalty, start)) = app.penalty_flash else {
+        return;
+    };
+    let t = start.elapsed().as_secs_f32() / DURATION;
+    if t >= 1.0 {
+        app.penalty_flash = None;
+        return;
+    }
+    let color = match penalty {
+        Penalty::None => return,
+        Penalty::PlusTwo => egui::Color32::YELLOW,
+        Penalty::Dnf => egui::Color32::RED,
+    };
+
+    let opacity = (1.0 - t) * app.prefs.info.penalty_flash.intensity.clamp(0.0, 1.0);
+    ui.painter_at(rect).rect_stroke(
+        rect.shrink(WIDTH / 2.0),
+        0.0,
+        egui::Stroke::new(WIDTH, color.linear_multiply(opacity)),
+    );
+    ui.ctx().request_repaint();
+}
+
+/// Shows a message in the middle of the puzzle view that fades out after the
+/// streak goal is reached.
+fn draw_streak_celebration(ui: &mut egui::Ui, app: &mut App, rect: egui::Rect) {
+    const DURATION: f32 = 3.0;
+    const FONT_SIZE: f32 = 32.0;
+
+    let Some(start) = app.streak_celebration else {
+        return;
+    };
+    let t = start.elapsed().as_secs_f32() / DURATION;
+    if t >= 1.0 {
+        app.streak_celebration = None;
+        return;
+    }
+
+    let goal = app.prefs.info.streak_goal;
+    ui.painter_at(rect).text(
+        rect.center(),
+        egui::Align2::CENTER_CENTER,
+        format!(
+            "🎉 {} in a row under {}!",
+            goal.solve_count,
+            crate::gui::windows::duration_to_str(goal.target_duration()),
+        ),
+        egui::FontId::proportional(FONT_SIZE),
+        egui::Color32::GOLD.linear_multiply(1.0 - t),
+    );
+    ui.ctx().request_repaint();
+}
+
+/// Draws the next twist of the pattern being demonstrated and its finger-trick
+/// hint at the top of the puzzle view.
+fn draw_demonstration_hint(ui: &mut egui::Ui, app: &mut App, rect: egui::Rect) {
+    const MARGIN: f32 = 8.0;
+
+    let Some(demo) = &app.demonstration else {
+        return;
+    };
+    let Some(progress) = demo.progress(&app.puzzle) else {
+        return;
+    };
+    let Some(&twist) = demo.twists().get(progress) else {
+        return;
+    };
+
+    let style = app.prefs.interaction.notation_style(app.puzzle.ty());
+    let mut text = app
+        .puzzle
+        .notation_scheme_for_style(style)
+        .twist_to_string(twist);
+    let hint = app.prefs.patterns[app.puzzle.ty()]
+        .iter()
+        .find(|p| p.preset_name == demo.pattern_name)
+        .and_then(|p| p.value.hints.get(progress))
+        .filter(|hint| !hint.is_empty());
+    if let Some(hint) = hint {
+        text += ": ";
+        text += hint;
+    }
+
+    ui.painter_at(rect).text(
+        rect.center_top() + egui::vec2(0.0, MARGIN),
+        egui::Align2::CENTER_TOP,
+        text,
+        egui::TextStyle::Heading.resolve(ui.style()),
+        ui.visuals().strong_text_color(),
+    );
+}
+
+/// Draws the inspection time left at the top of the puzzle view, so that it's
+/// visible even when the timer window is closed.
+fn draw_inspection_countdown(ui: &mut egui::Ui, app: &mut App, rect: egui::Rect) {
+    const MARGIN: f32 = 8.0;
+
+    let Some(remaining) = app.inspection_remaining() else {
+        return;
+    };
+    let color = match Penalty::from_inspection_time_left(remaining) {
+        Penalty::None => ui.visuals().strong_text_color(),
+        Penalty::PlusTwo => egui::Color32::YELLOW,
+        Penalty::Dnf => egui::Color32::RED,
+    };
+
+    ui.painter_at(rect).text(
+        rect.center_top() + egui::vec2(0.0, MARGIN),
+        egui::Align2::CENTER_TOP,
+        crate::gui::windows::inspection_str(remaining),
+        egui::TextStyle::Heading.resolve(ui.style()),
+        color,
+    );
+}
+
+/// Draws the countdown before the timer starts in large numbers in the middle
+/// of the puzzle view.
+fn draw_start_countdown(ui: &mut egui::Ui, app: &mut App, rect: egui::Rect) {
+    const FONT_SIZE: f32 = 96.0;
+
+    let Some(remaining) = app.countdown_remaining() else {
+        return;
+    };
+    ui.ctx().request_repaint();
+
+    ui.painter_at(rect).text(
+        rect.center(),
+        egui::Align2::CENTER_CENTER,
+        remaining.ceil().max(1.0).to_string(),
+        egui::FontId::proportional(FONT_SIZE),
+        ui.visuals().strong_text_color(),
+    );
+}
+
+/// Draws the time left in the time attack and the number of solves so far at
+/// the top right of the puzzle view.
+fn draw_time_attack_clock(ui: &mut egui::Ui, app: &mut App, rect: egui::Rect) {
+    const MARGIN: f32 = 8.0;
+
+    let Some(remaining) = app.time_attack.remaining(instant::Instant::now()) else {
+        return;
+    };
+    ui.ctx().request_repaint();
+
+    ui.painter_at(rect).text(
+        rect.right_top() + egui::vec2(-MARGIN, MARGIN),
+        egui::Align2::RIGHT_TOP,
+        format!(
+            "{} · {} solved",
+            crate::gui::windows::clock_str(remaining),
+            app.time_attack.solve_count(),
+        ),
+        egui::TextStyle::Heading.resolve(ui.style()),
+        ui.visuals().strong_text_color(),
+    );
+}
+
+/// Draws the progress of the replay being played back at the top of the
+/// puzzle view.
+#[cfg(not(target_arch = "wasm32"))]
+fn draw_replay_hint(ui: &mut egui::Ui, app: &mut App, rect: egui::Rect) {
+    const MARGIN: f32 = 8.0;
+
+    let Some(playback) = &app.replay else {
+        return;
+    };
+    let Some(progress) = playback.progress(&app.puzzle) else {
+        return;
+    };
+    let replay = &playback.replay;
+
+    let mut text = if progress < replay.twists.len() {
+        ui.ctx().request_repaint();
+        format!(
+            "Replay: {} ({progress}/{} twists)",
+            crate::gui::windows::duration_to_str(playback.elapsed()),
+            replay.twists.len(),
+        )
+    } else {
+        format!(
+            "Replay finished: {}",
+            crate::gui::windows::duration_to_str(replay.duration)
+        )
+    };
+    match replay.penalty {
+        Penalty::None => (),
+        Penalty::PlusTwo => text += " (+2)",
+        Penalty::Dnf => text += " (DNF)",
+    }
+
+    ui.painter_at(rect).text(
+        rect.center_top() + egui::vec2(0.0, MARGIN),
+        egui::Align2::CENTER_TOP,
+        text,
+        egui::TextStyle::Heading.resolve(ui.style()),
+        ui.visuals().strong_text_color(),
+    );
+}
+
 fn build_puzzle_context_menu(_ui: &mut egui::Ui, _app: &mut App) {
     // let ty = app.puzzle.ty();
 
@@ -1,3 +1,4 @@
+use itertools::Itertools;
 use key_names::KeyMappingCode;
 use strum::EnumMessage;
 use winit::event::VirtualKeyCode;
@@ -6,7 +7,7 @@ use super::ext::*;
 use crate::app::App;
 use crate::commands::Command;
 use crate::preferences::Key;
-use crate::puzzle::TwistMetric;
+use crate::puzzle::{traits::*, ScrambleSide, TwistMetric};
 
 pub fn build(ui: &mut egui::Ui, app: &mut App) {
     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
@@ -14,8 +15,32 @@ pub fn build(ui: &mut egui::Ui, app: &mut App) {
         bld_toggle(ui, app);
         ui.separator();
 
-        twist_count(ui, app);
-        ui.separator();
+        if app.prefs.info.show_twist_count {
+            twist_count(ui, app);
+            ui.separator();
+        }
+
+        if !app.puzzle.scramble().is_empty() {
+            scramble_info(ui, app);
+            ui.separator();
+        }
+
+        if app.slow_motion {
+            slow_motion_toggle(ui, app);
+            ui.separator();
+        }
+
+        if app.zen_mode {
+            zen_mode_toggle(ui, app);
+            ui.separator();
+        }
+
+        if app.puzzle.side() == ScrambleSide::Inverse
+            || !app.puzzle.twists_on_side(ScrambleSide::Inverse).is_empty()
+        {
+            scramble_side_toggle(ui, app);
+            ui.separator();
+        }
 
         // Left-aligned segments
         ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
@@ -24,6 +49,12 @@ pub fn build(ui: &mut egui::Ui, app: &mut App) {
                 ui.separator();
             }
 
+            if app.building_puzzle.is_some() {
+                ui.spinner();
+                if ui.small_button("✖").on_hover_text("Cancel").clicked() {
+                    app.cancel_building_puzzle();
+                }
+            }
             ui.label(app.status_msg());
         });
     });
@@ -79,13 +110,90 @@ fn bld_toggle(ui: &mut egui::Ui, app: &mut App) {
     }
 }
 
+fn slow_motion_toggle(ui: &mut egui::Ui, app: &mut App) {
+    let r = ui
+        .selectable_label(app.slow_motion, "SLOW-MO")
+        .on_hover_explanation("Slow motion", "Twists are animated at reduced speed");
+    if r.clicked() {
+        app.event(Command::ToggleSlowMotion);
+    }
+}
+
+fn zen_mode_toggle(ui: &mut egui::Ui, app: &mut App) {
+    let r = ui
+        .selectable_label(app.zen_mode, "ZEN")
+        .on_hover_explanation(
+            "Zen mode",
+            "The timer and stats are hidden and solves are not recorded",
+        );
+    if r.clicked() {
+        app.event(Command::ToggleZenMode);
+    }
+}
+
+fn scramble_info(ui: &mut egui::Ui, app: &mut App) {
+    const PREVIEW_TWISTS: usize = 3;
+
+    let style = app.prefs.interaction.notation_style(app.puzzle.ty());
+    let notation = app.puzzle.notation_scheme_for_style(style);
+    let twists = app.puzzle.scramble();
+    let mut preview = twists
+        .iter()
+        .take(PREVIEW_TWISTS)
+        .map(|&twist| notation.twist_to_string(twist))
+        .join(" ");
+    if twists.len() > PREVIEW_TWISTS {
+        preview.push_str(" …");
+    }
+
+    let r = ui
+        .add(egui::Label::new(format!("Scramble: {preview}")).sense(egui::Sense::click()))
+        .on_hover_explanation(
+            &format!("Scramble ({} twists)", twists.len()),
+            &format!(
+                "{}\n\nClick to retry this scramble.",
+                app.puzzle.scramble_string_in_style(style),
+            ),
+        );
+    if r.clicked() {
+        app.event(Command::RetryScramble);
+    }
+}
+
+fn scramble_side_toggle(ui: &mut egui::Ui, app: &mut App) {
+    let inverse = app.puzzle.side() == ScrambleSide::Inverse;
+    let r = ui.selectable_label(inverse, "INV").on_hover_explanation(
+        "Inverse scramble",
+        &format!(
+            "Currently solving the {} scramble. \
+             {} twists on the normal side, \
+             {} twists on the inverse side.",
+            if inverse { "inverse" } else { "normal" },
+            app.puzzle.twists_on_side(ScrambleSide::Normal).len(),
+            app.puzzle.twists_on_side(ScrambleSide::Inverse).len(),
+        ),
+    );
+    if r.clicked() {
+        app.event(Command::SwitchScrambleSide);
+    }
+}
+
 fn twist_count(ui: &mut egui::Ui, app: &mut App) {
     let mut changed = false;
 
+    let normalize_rotations = app.prefs.info.normalize_rotations;
+    let ty = app.puzzle.ty();
     let metric = &mut app.prefs.info.metric;
-    let twist_count = app.puzzle.twist_count(*metric);
-    let r = ui
-        .add(egui::Label::new(format!("{}: {}", metric, twist_count)).sense(egui::Sense::click()));
+    let twist_count = app.puzzle.twist_count(*metric, normalize_rotations);
+    let twist_target = app.prefs.info.twist_targets[ty];
+    let mut text = egui::RichText::new(match twist_target {
+        Some(target) => format!("{}: {}/{}", metric, twist_count, target),
+        None => format!("{}: {}", metric, twist_count),
+    });
+    if twist_target.map_or(false, |target| twist_count > target) {
+        text = text.color(egui::Color32::RED);
+    }
+    let r = ui.add(egui::Label::new(text).sense(egui::Sense::click()));
     {
         let mut data = ui.data();
         let last_frame_metric = data.get_temp_mut_or_default(unique_id!());
@@ -132,7 +240,10 @@ fn twist_count(ui: &mut egui::Ui, app: &mut App) {
                                             .selectable_value(
                                                 metric,
                                                 m,
-                                                format!("{m}: {}", app.puzzle.twist_count(m)),
+                                                format!(
+                                                    "{m}: {}",
+                                                    app.puzzle.twist_count(m, normalize_rotations)
+                                                ),
                                             )
                                             .changed();
                                     };
@@ -153,6 +264,37 @@ fn twist_count(ui: &mut egui::Ui, app: &mut App) {
                                         .add(egui::Checkbox::new(&mut app.prefs.info.qtm, "QTM"))
                                         .changed();
                                     metric.set_qtm(app.prefs.info.qtm);
+                                    changed |= ui
+                                        .add(egui::Checkbox::new(
+                                            &mut app.prefs.info.normalize_rotations,
+                                            "Normalize rotations",
+                                        ))
+                                        .on_hover_text(
+                                            "Remove whole-puzzle rotations from the \
+                                             solution and remap the twists after \
+                                             them, for a clean move count",
+                                        )
+                                        .changed();
+                                    ui.separator();
+                                    let twist_target = &mut app.prefs.info.twist_targets[ty];
+                                    let mut has_target = twist_target.is_some();
+                                    changed |= ui
+                                        .checkbox(&mut has_target, "Target")
+                                        .on_hover_text(
+                                            "Highlight the twist count when it \
+                                             goes over a target for this puzzle",
+                                        )
+                                        .changed();
+                                    match (has_target, &mut *twist_target) {
+                                        (true, None) => *twist_target = Some(twist_count.max(1)),
+                                        (false, Some(_)) => *twist_target = None,
+                                        _ => (),
+                                    }
+                                    if let Some(target) = twist_target {
+                                        changed |= ui
+                                            .add(egui::DragValue::new(target).clamp_range(1..=9999))
+                                            .changed();
+                                    }
                                 },
                             );
                         });
@@ -0,0 +1,142 @@
+use itertools::Itertools;
+use std::sync::Arc;
+
+use super::Window;
+use crate::app::App;
+use crate::diagram::{AlgSheet, NetDiagram, NetLayout};
+use crate::practice::{setup_from_alg, CASE_PUZZLE, CASE_SETS};
+use crate::puzzle::{traits::*, Puzzle};
+
+pub(crate) const ALG_SHEET: Window = Window {
+    name: "Algorithm sheet",
+    vscroll: true,
+    build,
+    ..Window::DEFAULT
+};
+
+fn build(ui: &mut egui::Ui, app: &mut App) {
+    ui.label(
+        "Saves a printable PDF with a net diagram of each case and the \
+         algorithm that solves it. Enter one case per line as \
+         \"name: algorithm\".",
+    );
+    ui.separator();
+
+    let title_id = unique_id!();
+    let mut title: String = ui
+        .data()
+        .get_temp(title_id)
+        .unwrap_or_else(|| "Algorithms".to_string());
+    let text_id = unique_id!();
+    let mut text: String = ui.data().get_temp(text_id).unwrap_or_default();
+
+    ui.horizontal_wrapped(|ui| {
+        ui.label("Start from:");
+        for case_set in CASE_SETS {
+            if ui.button(case_set.name).clicked() {
+                title = case_set.name.to_string();
+                text = case_set
+                    .cases
+                    .iter()
+                    .map(|case| format!("{}: {}", case.name, case.alg))
+                    .join("\n");
+            }
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.label("Title:");
+        ui.text_edit_singleline(&mut title);
+    });
+    ui.add(egui::TextEdit::multiline(&mut text).code_editor());
+
+    ui.data().insert_temp(title_id, title.clone());
+    ui.data().insert_temp(text_id, text.clone());
+
+    // Parsing a case builds a puzzle and draws its diagram, so only do it
+    // when something changes.
+    let face_colors = app.prefs.colors.face_colors_list(CASE_PUZZLE);
+    let layout = app.prefs.info.net_layout;
+    let parsed_id = unique_id!();
+    let parsed = ui
+        .data()
+        .get_temp::<Arc<ParsedCases>>(parsed_id)
+        .filter(|p| p.text == text && p.face_colors == face_colors && p.layout == layout);
+    let parsed = parsed.unwrap_or_else(|| {
+        let cases = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| parse_case(line, &face_colors, layout))
+            .collect();
+        let parsed = Arc::new(ParsedCases {
+            text,
+            face_colors,
+            layout,
+            cases,
+        });
+        ui.data().insert_temp(parsed_id, Arc::clone(&parsed));
+        parsed
+    });
+    let cases = match &parsed.cases {
+        Ok(cases) => cases,
+        Err(e) => {
+            ui.colored_label(egui::Color32::RED, format!("✖ {e}"));
+            return;
+        }
+    };
+
+    ui.separator();
+
+    ui.add_enabled_ui(!cases.is_empty(), |ui| {
+        if ui.button("Save PDF…").clicked() {
+            let cases = cases.clone();
+            save_pdf(&AlgSheet { title, cases });
+        }
+    });
+}
+
+/// Cases parsed from the text of the window, along with what they were parsed
+/// from.
+#[derive(Debug)]
+struct ParsedCases {
+    text: String,
+    face_colors: Vec<egui::Color32>,
+    layout: NetLayout,
+    cases: Result<Vec<(String, String, NetDiagram)>, String>,
+}
+
+/// Parses a line of the form `name: algorithm` and draws the case that the
+/// algorithm solves, using the net layout from the net diagram window.
+fn parse_case(
+    line: &str,
+    face_colors: &[egui::Color32],
+    layout: NetLayout,
+) -> Result<(String, String, NetDiagram), String> {
+    let (name, alg) = line
+        .split_once(':')
+        .ok_or_else(|| format!("Missing ':' between name and algorithm in {line:?}"))?;
+    let (name, alg) = (name.trim(), alg.trim());
+
+    let mut puzzle = Puzzle::new(CASE_PUZZLE);
+    for twist in setup_from_alg(alg).map_err(|e| format!("{e} in {name:?}"))? {
+        puzzle.twist(twist)?;
+    }
+    let diagram =
+        NetDiagram::new(&puzzle, face_colors, layout).ok_or("Unable to draw net diagram")?;
+
+    Ok((name.to_string(), alg.to_string(), diagram))
+}
+
+fn save_pdf(sheet: &AlgSheet) {
+    let dialog = rfd::FileDialog::new()
+        .add_filter("PDF document", &["pdf"])
+        .set_file_name("algorithms.pdf");
+    let Some(path) = dialog.save_file() else {
+        return;
+    };
+    if let Err(e) = std::fs::write(&path, sheet.to_pdf()) {
+        rfd::MessageDialog::new()
+            .set_title("Unable to save algorithm sheet")
+            .set_description(&format!("Unable to save algorithm sheet:\n\n{e}"))
+            .show();
+    }
+}
@@ -0,0 +1,92 @@
+use super::Window;
+use crate::app::App;
+use crate::puzzle::blind_trace::{blind_trace, is_supported, PieceTrace};
+use crate::puzzle::{traits::*, Puzzle};
+
+pub(crate) const BLIND_TRACE: Window = Window {
+    name: "Blind trace",
+    build,
+    ..Window::DEFAULT
+};
+
+fn build(ui: &mut egui::Ui, app: &mut App) {
+    let ty = app.puzzle.ty();
+    if !is_supported(ty) {
+        ui.label("Only available for 2x2x2 and 3x3x3");
+        return;
+    }
+
+    ui.label(
+        "Traces the scramble with the current letter scheme, so that memo \
+         can be checked after memorizing it.",
+    );
+    ui.separator();
+
+    let letter_prefs = &mut app.prefs.letters;
+    egui::Grid::new(unique_id!()).num_columns(2).show(ui, |ui| {
+        ui.label("Corner buffer:");
+        let r =
+            ui.add(egui::TextEdit::singleline(&mut letter_prefs.corner_buffer).desired_width(60.0));
+        app.prefs.needs_save |= r.changed();
+        ui.end_row();
+
+        if ty.layer_count() == 3 {
+            ui.label("Edge buffer:");
+            let r = ui
+                .add(egui::TextEdit::singleline(&mut letter_prefs.edge_buffer).desired_width(60.0));
+            app.prefs.needs_save |= r.changed();
+            ui.end_row();
+        }
+    });
+
+    // Hide the trace by default so that it doesn't give away the memo.
+    let show_id = unique_id!();
+    let mut show = ui.data().get_temp::<bool>(show_id).unwrap_or(false);
+    if ui.checkbox(&mut show, "Show trace").changed() {
+        ui.data().insert_temp(show_id, show);
+    }
+    if !show {
+        return;
+    }
+    ui.separator();
+
+    let mut puzzle = Puzzle::new(ty);
+    for &twist in app.puzzle.scramble() {
+        if let Err(e) = puzzle.twist(twist) {
+            ui.colored_label(egui::Color32::RED, e);
+            return;
+        }
+    }
+    let letter_prefs = &app.prefs.letters;
+    let trace = blind_trace(
+        &puzzle,
+        &letter_prefs.scheme(ty),
+        &letter_prefs.corner_buffer,
+        &letter_prefs.edge_buffer,
+    );
+    match trace {
+        Ok(trace) => {
+            show_piece_trace(ui, "Corners", &trace.corners);
+            if let Some(edges) = &trace.edges {
+                show_piece_trace(ui, "Edges", edges);
+            }
+            if trace.has_parity() {
+                ui.strong("Parity");
+            }
+        }
+        Err(e) => {
+            ui.colored_label(egui::Color32::RED, e);
+        }
+    }
+}
+
+fn show_piece_trace(ui: &mut egui::Ui, label: &str, trace: &PieceTrace) {
+    if trace.letters.is_empty() {
+        ui.label(format!("{label}: none"));
+    } else {
+        ui.label(format!("{label}: {}", trace.letter_pairs()));
+    }
+    if !trace.twisted.is_empty() {
+        ui.label(format!("Twisted in place: {}", trace.twisted.join(", ")));
+    }
+}
@@ -0,0 +1,81 @@
+use instant::Duration;
+
+use super::{duration_to_str, Window};
+use crate::app::App;
+use crate::commands::Command;
+use crate::gui::components::FancyComboBox;
+use crate::practice::{CASE_PUZZLE, CASE_SETS};
+use crate::puzzle::traits::*;
+
+pub(crate) const CASE_TRAINER: Window = Window {
+    name: "Case trainer",
+    vscroll: true,
+    build,
+    ..Window::DEFAULT
+};
+
+fn build(ui: &mut egui::Ui, app: &mut App) {
+    ui.horizontal(|ui| {
+        ui.add(FancyComboBox {
+            combo_box: egui::ComboBox::from_id_source(unique_id!()),
+            selected: &mut app.case_trainer.set_index,
+            options: CASE_SETS
+                .iter()
+                .enumerate()
+                .map(|(i, case_set)| (i, case_set.name.into()))
+                .collect(),
+        });
+        ui.add_enabled_ui(app.puzzle.ty() == CASE_PUZZLE, |ui| {
+            if ui.button("Next case").clicked() {
+                app.event(Command::NextCase);
+            }
+        });
+    });
+
+    if app.puzzle.ty() != CASE_PUZZLE {
+        ui.label(format!("Case sets are for {}", CASE_PUZZLE.name()));
+        return;
+    }
+
+    ui.separator();
+    let trainer = &mut app.case_trainer;
+    if trainer.is_attempting(&app.puzzle) {
+        ui.label("Solve the case to see which one it was.");
+    } else if let Some((case, time)) = trainer.last_solve() {
+        ui.strong(format!("{}: {}", case.name, duration_to_str(time)));
+        ui.label(case.alg);
+    } else {
+        ui.label("Sets up a random case from the selected set to solve.");
+    }
+
+    ui.separator();
+    egui::Grid::new(unique_id!()).striped(true).show(ui, |ui| {
+        ui.strong("Case");
+        ui.strong("Solves");
+        ui.strong("Best");
+        ui.strong("Mean");
+        ui.end_row();
+
+        for &case in trainer.case_set().cases {
+            let times = trainer.times(case);
+            ui.label(case.name).on_hover_text(case.alg);
+            ui.label(times.len().to_string());
+            ui.label(
+                times
+                    .iter()
+                    .min()
+                    .map_or("-".to_owned(), |&t| duration_to_str(t)),
+            );
+            ui.label(mean(times).map_or("-".to_owned(), duration_to_str));
+            ui.end_row();
+        }
+    });
+    if ui.button("Clear times").clicked() {
+        trainer.clear_times();
+    }
+}
+
+fn mean(times: &[Duration]) -> Option<Duration> {
+    let count = u32::try_from(times.len()).ok().filter(|&n| n > 0)?;
+    Some(times.iter().sum::<Duration>() / count)
+}
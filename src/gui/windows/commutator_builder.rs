@@ -0,0 +1,97 @@
+use itertools::Itertools;
+
+use super::Window;
+use crate::app::{App, AppEvent};
+use crate::commands::Command;
+use crate::puzzle::commutator::{commutator, piece_cycles, piece_name};
+
+pub(crate) const COMMUTATOR_BUILDER: Window = Window {
+    name: "Commutator builder",
+    vscroll: true,
+    build,
+    ..Window::DEFAULT
+};
+
+fn build(ui: &mut egui::Ui, app: &mut App) {
+    ui.label(
+        "Builds the commutator [A, B] = A B A' B' of two algorithms and \
+         shows which pieces it cycles when applied to a solved puzzle.",
+    );
+    ui.separator();
+
+    let a_id = unique_id!();
+    let b_id = unique_id!();
+    let mut a_text: String = ui.data().get_temp(a_id).unwrap_or_default();
+    let mut b_text: String = ui.data().get_temp(b_id).unwrap_or_default();
+    egui::Grid::new(unique_id!()).num_columns(2).show(ui, |ui| {
+        ui.label("A:");
+        ui.text_edit_singleline(&mut a_text);
+        ui.end_row();
+        ui.label("B:");
+        ui.text_edit_singleline(&mut b_text);
+        ui.end_row();
+    });
+    ui.data().insert_temp(a_id, a_text.clone());
+    ui.data().insert_temp(b_id, b_text.clone());
+
+    if a_text.trim().is_empty() || b_text.trim().is_empty() {
+        return;
+    }
+
+    let ty = app.puzzle.ty();
+    let convention = &app.prefs.interaction.twist_conventions[ty];
+    let style = app.prefs.interaction.notation_style(ty);
+    let result = convention
+        .parse_alg(ty, style, &a_text)
+        .and_then(|a| Ok((a, convention.parse_alg(ty, style, &b_text)?)))
+        .and_then(|(a, b)| {
+            let twists = commutator(ty, &a, &b);
+            let cycles = piece_cycles(ty, &twists)?;
+            Ok((twists, cycles))
+        });
+    let (twists, cycles) = match result {
+        Ok(ok) => ok,
+        Err(e) => {
+            ui.colored_label(egui::Color32::RED, format!("✖ {e}"));
+            return;
+        }
+    };
+
+    ui.separator();
+    let alg = twists
+        .iter()
+        .map(|&twist| convention.twist_to_string(ty, style, twist))
+        .join(" ");
+    ui.label(egui::RichText::new(alg).monospace());
+    ui.label(format!(
+        "{} {}",
+        twists.len(),
+        if twists.len() == 1 { "move" } else { "moves" },
+    ));
+
+    if cycles.cycles.is_empty() && cycles.twisted.is_empty() {
+        ui.label("No pieces are affected");
+    }
+    for cycle in &cycles.cycles {
+        let names = cycle.iter().map(|&piece| piece_name(ty, piece)).join(" → ");
+        ui.label(format!("{}-cycle: {names}", cycle.len()));
+    }
+    if !cycles.twisted.is_empty() {
+        let names = cycles
+            .twisted
+            .iter()
+            .map(|&piece| piece_name(ty, piece))
+            .join(", ");
+        ui.label(format!("Twisted in place: {names}"));
+    }
+
+    ui.separator();
+    ui.horizontal(|ui| {
+        if ui.button("Apply").clicked() {
+            app.event(AppEvent::ApplyCommutator(twists));
+        }
+        if ui.button("Reset").clicked() {
+            app.event(Command::Reset);
+        }
+    });
+}
@@ -0,0 +1,113 @@
+use super::{duration_to_str, Window};
+use crate::app::App;
+use crate::practice::{CASE_PUZZLE, CASE_SETS};
+use crate::puzzle::traits::*;
+
+/// Maximum number of recent reps to list.
+const MAX_LISTED_REPS: usize = 10;
+
+pub(crate) const DRILL: Window = Window {
+    name: "Drill",
+    vscroll: true,
+    build,
+    ..Window::DEFAULT
+};
+
+fn build(ui: &mut egui::Ui, app: &mut App) {
+    if app.puzzle.ty() != CASE_PUZZLE {
+        ui.label(format!("Drills are for {}", CASE_PUZZLE.name()));
+        return;
+    }
+
+    ui.label(
+        "Sets up a case, and then sets up the next variant of it each \
+         time it is solved.",
+    );
+    ui.separator();
+
+    let drill = &mut app.drill;
+    ui.horizontal(|ui| {
+        ui.label("Algorithm:");
+        ui.menu_button("Load case", |ui| {
+            for case_set in CASE_SETS {
+                ui.menu_button(case_set.name, |ui| {
+                    for case in case_set.cases {
+                        if ui.button(case.name).on_hover_text(case.alg).clicked() {
+                            drill.alg = case.alg.to_string();
+                            drill.stop();
+                            ui.close_menu();
+                        }
+                    }
+                });
+            }
+        });
+    });
+    if ui
+        .add(egui::TextEdit::singleline(&mut drill.alg).code_editor())
+        .changed()
+    {
+        drill.stop();
+    }
+
+    ui.horizontal_wrapped(|ui| {
+        let mut changed = false;
+        changed |= ui.checkbox(&mut drill.mirror, "Mirror").changed();
+        changed |= ui.checkbox(&mut drill.inverse, "Inverse").changed();
+        changed |= ui
+            .checkbox(&mut drill.rotate, "Rotate")
+            .on_hover_text("Sets up the case from every side, using y rotations")
+            .changed();
+        if changed {
+            drill.stop();
+        }
+    });
+
+    ui.horizontal(|ui| {
+        let button_text = if app.drill.is_running() {
+            "Skip"
+        } else {
+            "Start"
+        };
+        if ui.button(button_text).clicked() {
+            app.set_up_drill_rep(false);
+        }
+        if ui
+            .add_enabled(app.drill.is_running(), egui::Button::new("Stop"))
+            .clicked()
+        {
+            app.drill.stop();
+        }
+    });
+
+    let drill = &mut app.drill;
+    match drill.current_variant(&app.puzzle) {
+        Some(variant) => ui.strong(format!("Current: {variant}")),
+        None => ui.label(format!("Next: {}", drill.next_variant())),
+    };
+
+    ui.separator();
+    egui::Grid::new(unique_id!()).striped(true).show(ui, |ui| {
+        ui.strong("Variant");
+        ui.strong("Reps");
+        ui.strong("Mean");
+        ui.end_row();
+
+        for (variant, count, mean) in drill.variant_means() {
+            ui.label(variant.to_string());
+            ui.label(count.to_string());
+            ui.label(duration_to_str(mean));
+            ui.end_row();
+        }
+    });
+
+    if !drill.reps().is_empty() {
+        ui.collapsing("Recent reps", |ui| {
+            for (variant, time) in drill.reps().iter().rev().take(MAX_LISTED_REPS) {
+                ui.label(format!("{variant}: {}", duration_to_str(*time)));
+            }
+        });
+        if ui.button("Clear times").clicked() {
+            drill.clear_reps();
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use strum::IntoEnumIterator;
+
+use super::Window;
+use crate::app::App;
+use crate::commands::Command;
+use crate::gui::components::{
+    big_icon_button, FancyComboBox, PlaintextYamlEditor, ReorderableList,
+};
+use crate::preferences::{GamepadBind, GamepadButton};
+
+pub(crate) const GAMEPAD: Window = Window {
+    name: "Gamepad",
+    build: |ui, app| {
+        ui.collapsing("Rumble", |ui| {
+            crate::gui::components::prefs::build_gamepad_section(ui, app);
+        });
+        ui.separator();
+        let r = ui.add(GamepadBindsTable { app });
+        app.prefs.needs_save |= r.changed();
+    },
+    ..Window::DEFAULT
+};
+
+struct GamepadBindsTable<'a> {
+    app: &'a mut App,
+}
+
+impl egui::Widget for GamepadBindsTable<'_> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let mut changed = false;
+
+        let gamepad_binds = &mut self.app.prefs.gamepad_binds;
+
+        let yaml_editor = PlaintextYamlEditor { id: unique_id!() };
+
+        let mut r = yaml_editor.show(ui, gamepad_binds).unwrap_or_else(|| {
+            ui.scope(|ui| {
+                ui.horizontal(|ui| {
+                    if big_icon_button(ui, "✏", "Edit as plaintext").clicked() {
+                        yaml_editor.set_active(ui, gamepad_binds);
+                    }
+
+                    if big_icon_button(ui, "➕", "Add a new gamepad bind").clicked() {
+                        gamepad_binds.push(GamepadBind::default());
+                        changed = true;
+                    };
+
+                    ui.strong("Button");
+                    ui.strong("Command");
+                });
+
+                ui.separator();
+
+                egui::ScrollArea::new([false, true]).show(ui, |ui| {
+                    let id = unique_id!();
+                    let r = ReorderableList::new(id, gamepad_binds).show(ui, |ui, idx, bind| {
+                        let mut r = ui.add(FancyComboBox {
+                            combo_box: egui::ComboBox::from_id_source(unique_id!(idx)),
+                            selected: &mut bind.button,
+                            options: GamepadButton::iter()
+                                .map(|button| (button, button.to_string().into()))
+                                .collect(),
+                        });
+
+                        r |= ui.add(FancyComboBox {
+                            combo_box: egui::ComboBox::from_id_source(unique_id!(idx)),
+                            selected: &mut bind.command,
+                            options: vec![
+                                (Command::None, "None".into()),
+                                (Command::Undo, "Undo".into()),
+                                (Command::Redo, "Redo".into()),
+                                (Command::Reset, "Reset".into()),
+                                (Command::ScrambleFull, "Scramble fully".into()),
+                                (Command::RetryScramble, "Retry scramble".into()),
+                                (Command::NextCase, "Next case".into()),
+                                (Command::ResetView, "Reset view".into()),
+                                (Command::ToggleBlindfold, "Toggle blindfold".into()),
+                                (Command::ToggleSlowMotion, "Toggle slow motion".into()),
+                                (Command::ToggleZenMode, "Toggle zen mode".into()),
+                            ],
+                        });
+
+                        r
+                    });
+                    changed |= r.changed();
+
+                    ui.allocate_space(egui::vec2(1.0, 200.0));
+                });
+            })
+            .response
+        });
+
+        if changed {
+            r.mark_changed();
+        }
+        r
+    }
+}
@@ -1,5 +1,6 @@
 use egui::NumExt;
 use key_names::KeyMappingCode;
+use winit::event::VirtualKeyCode;
 
 use super::Window;
 use crate::app::App;
@@ -15,10 +16,25 @@ const MIN_KEY_PADDING: f32 = 4.0;
 pub(crate) const KEYBINDS_REFERENCE: Window = Window {
     name: "Keybinds reference",
     build,
+    cleanup,
     ..Window::DEFAULT
 };
 
+fn cleanup(_ctx: &egui::Context, app: &mut App) {
+    let visible = &mut app.prefs.info.keybinds_reference.visible;
+    if *visible {
+        *visible = false;
+        app.prefs.needs_save = true;
+    }
+}
+
 fn build(ui: &mut egui::Ui, app: &mut App) {
+    let visible = &mut app.prefs.info.keybinds_reference.visible;
+    if !*visible {
+        *visible = true;
+        app.prefs.needs_save = true;
+    }
+
     ui.scope(|ui| {
         let prefs = app.prefs.info.keybinds_reference;
 
@@ -101,53 +117,12 @@ fn build(ui: &mut egui::Ui, app: &mut App) {
 
 fn draw_key(ui: &mut egui::Ui, app: &mut App, key: KeyMappingCode, rect: egui::Rect) {
     let puzzle_type = app.puzzle.ty();
+    let new_action = app.prefs.interaction.new_action;
 
     let vk = key_names::key_to_winit_vkey(key);
-    let matching_puzzle_keybinds: Vec<&Keybind<PuzzleCommand>> = app
-        .resolve_keypress(
-            app.prefs.puzzle_keybinds[puzzle_type].get_active_keybinds(),
-            Some(key),
-            vk,
-        )
-        .into_iter()
-        .take_while(|bind| bind.command != PuzzleCommand::None)
-        .collect();
-    let matching_global_keybinds: Vec<&Keybind<Command>> = app
-        .resolve_keypress(&app.prefs.global_keybinds, Some(key), vk)
-        .into_iter()
-        .take_while(|bind| bind.command != Command::None)
-        .collect();
-
-    let s = matching_puzzle_keybinds
-        .iter()
-        .find_map(|bind| {
-            let mut c = bind.command.clone();
-            match &mut c {
-                // Don't show keybinds that depend on a grip when we don't have an
-                // axis gripped.
-                PuzzleCommand::Twist { axis, .. } | PuzzleCommand::Recenter { axis } => {
-                    match app.gripped_twist_axis(axis.as_deref()) {
-                        Ok(gripped_axis) => {
-                            *axis = Some(puzzle_type.info(gripped_axis).name.to_string())
-                        }
-                        Err(_) => return None,
-                    }
-                }
-                _ => (),
-            }
-            Some(c.short_description(puzzle_type))
-        })
-        .or_else(|| {
-            matching_puzzle_keybinds
-                .first()
-                .map(|bind| bind.command.short_description(puzzle_type))
-        })
-        .or_else(|| {
-            matching_global_keybinds
-                .first()
-                .map(|bind| bind.command.short_description())
-        })
-        .unwrap_or_default();
+    let (matching_puzzle_keybinds, matching_global_keybinds) =
+        matching_keybinds(app, Some(key), vk);
+    let s = keybinds_label(app, &matching_puzzle_keybinds, &matching_global_keybinds);
 
     let text = autosize_button_text(
         ui,
@@ -185,9 +160,18 @@ fn draw_key(ui: &mut egui::Ui, app: &mut App, key: KeyMappingCode, rect: egui::R
                     axis,
                     direction,
                     layers,
+                    layer_group,
                 } => {
                     let layers = layers.to_layer_mask(puzzle_type.layer_count());
-                    if layers == puzzle_type.all_layers() {
+                    if let Some(group_name) = layer_group {
+                        ui.label("Twist");
+                        ui.strong(group_name);
+                        ui.label("layers of");
+                        ui.strong(axis.as_deref().unwrap_or("gripped"));
+                        ui.label("in");
+                        ui.strong(direction);
+                        ui.label("direction");
+                    } else if layers == puzzle_type.all_layers() {
                         ui.label("Rotate");
                         ui.strong("whole puzzle");
                         ui.label("in");
@@ -246,10 +230,12 @@ fn draw_key(ui: &mut egui::Ui, app: &mut App, key: KeyMappingCode, rect: egui::R
 
                 Command::CopyHscLog => ui.label("Copy puzzle log (.hsc)"),
                 Command::CopyMc4dLog => ui.label("Copy puzzle log (.log)"),
+                Command::CopySolution => ui.label("Copy solution"),
                 Command::PasteLog => ui.label("Paste puzzle log"),
 
                 Command::Undo => ui.label("Undo"),
                 Command::Redo => ui.label("Redo"),
+                Command::DiscardLastTwist => ui.label("Discard last twist"),
                 Command::Reset => ui.label("Reset"),
 
                 Command::ScrambleN(n) => {
@@ -257,14 +243,37 @@ fn draw_key(ui: &mut egui::Ui, app: &mut App, key: KeyMappingCode, rect: egui::R
                     ui.strong(n.to_string())
                 }
                 Command::ScrambleFull => ui.label("Scramble fully"),
+                Command::ScrambleOrientation => ui.label("Scramble orientation"),
 
+                Command::New => ui.label(new_action.to_string()),
+                Command::NewOther => ui.label(new_action.other().to_string()),
+                Command::ChoosePuzzle => ui.label("Choose puzzle"),
                 Command::NewPuzzle(ty) => {
                     ui.label("Load new");
                     ui.strong(ty.name());
                     ui.label("puzzle")
                 }
+                Command::SwapPuzzle => ui.label("Switch to previous puzzle"),
+
+                Command::SwitchScrambleSide => ui.label("Switch scramble side"),
+                Command::CopyScramble => ui.label("Copy new scramble"),
+                Command::RetryScramble => ui.label("Retry scramble"),
+                Command::NextCase => ui.label("Next case"),
 
                 Command::ToggleBlindfold => ui.label("Toggle blindfold"),
+                Command::ToggleLetters => ui.label("Toggle letters"),
+                Command::ToggleKeybindsReference => ui.label("Toggle keybinds reference"),
+                Command::ToggleSlowMotion => ui.label("Toggle slow motion"),
+                Command::ToggleZenMode => ui.label("Toggle zen mode"),
+                Command::ToggleShowTime => ui.label("Toggle time display"),
+                Command::ToggleShowTwistCount => ui.label("Toggle twist count display"),
+                Command::RotateColorScheme => ui.label("Rotate color scheme"),
+                Command::MatchColorSchemeToView => ui.label("Match color scheme to view"),
+                Command::CycleColorScheme => ui.label("Cycle color scheme"),
+                Command::ResetView => ui.label("Reset view"),
+                Command::SetDefaultView => ui.label("Set default view"),
+                Command::PeekLastLayer => ui.label("Peek at last layer"),
+                Command::ToggleExplode => ui.label("Toggle explode"),
 
                 Command::None => unreachable!(),
             });
@@ -272,6 +281,70 @@ fn draw_key(ui: &mut egui::Ui, app: &mut App, key: KeyMappingCode, rect: egui::R
     });
 }
 
+/// Returns the puzzle keybinds and global keybinds that a key would trigger
+/// with the modifiers that are currently held.
+pub(crate) fn matching_keybinds(
+    app: &App,
+    sc: Option<KeyMappingCode>,
+    vk: Option<VirtualKeyCode>,
+) -> (Vec<&Keybind<PuzzleCommand>>, Vec<&Keybind<Command>>) {
+    let matching_puzzle_keybinds = app
+        .resolve_keypress(
+            app.prefs.puzzle_keybinds[app.puzzle.ty()].get_active_keybinds(),
+            sc,
+            vk,
+        )
+        .into_iter()
+        .take_while(|bind| bind.command != PuzzleCommand::None)
+        .collect();
+    let matching_global_keybinds = app
+        .resolve_keypress(&app.prefs.global_keybinds, sc, vk)
+        .into_iter()
+        .take_while(|bind| bind.command != Command::None)
+        .collect();
+    (matching_puzzle_keybinds, matching_global_keybinds)
+}
+
+/// Returns a short description of what a key does, given the keybinds that it
+/// would trigger.
+pub(crate) fn keybinds_label(
+    app: &App,
+    matching_puzzle_keybinds: &[&Keybind<PuzzleCommand>],
+    matching_global_keybinds: &[&Keybind<Command>],
+) -> String {
+    let puzzle_type = app.puzzle.ty();
+    matching_puzzle_keybinds
+        .iter()
+        .find_map(|bind| {
+            let mut c = bind.command.clone();
+            match &mut c {
+                // Don't show keybinds that depend on a grip when we don't have an
+                // axis gripped.
+                PuzzleCommand::Twist { axis, .. } | PuzzleCommand::Recenter { axis } => {
+                    match app.gripped_twist_axis(axis.as_deref()) {
+                        Ok(gripped_axis) => {
+                            *axis = Some(puzzle_type.info(gripped_axis).name.to_string())
+                        }
+                        Err(_) => return None,
+                    }
+                }
+                _ => (),
+            }
+            Some(c.short_description(puzzle_type))
+        })
+        .or_else(|| {
+            matching_puzzle_keybinds
+                .first()
+                .map(|bind| bind.command.short_description(puzzle_type))
+        })
+        .or_else(|| {
+            matching_global_keybinds
+                .first()
+                .map(|bind| bind.command.short_description())
+        })
+        .unwrap_or_default()
+}
+
 fn autosize_button_text(
     ui: &mut egui::Ui,
     button_text: String,
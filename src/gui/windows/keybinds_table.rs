@@ -1,8 +1,11 @@
 use super::{Location, Window};
+use crate::app::{App, AppEvent};
 use crate::gui::components::{
     GlobalKeybindsAccessor, KeybindIncludesList, KeybindSetsList, KeybindsTable,
     PuzzleKeybindsAccessor,
 };
+use crate::gui::ext::ResponseExt;
+use crate::puzzle::traits::*;
 
 pub(crate) const GLOBAL_KEYBINDS: Window = Window {
     name: "Global keybinds",
@@ -23,6 +26,17 @@ pub(crate) const PUZZLE_KEYBINDS: Window = Window {
     build: |ui, app| {
         let puzzle_type = app.puzzle.ty();
 
+        ui.horizontal(|ui| {
+            if ui.button("Export keybinds...").clicked() {
+                app.event(AppEvent::ExportKeybinds);
+            }
+            if ui.button("Import keybinds...").clicked() {
+                app.event(AppEvent::ImportKeybinds);
+            }
+        });
+        build_keybinds_import(ui, app);
+        ui.separator();
+
         egui::CollapsingHeader::new("Keybind sets")
             .default_open(true)
             .show(ui, |ui| ui.add(KeybindSetsList { app }));
@@ -49,3 +63,72 @@ pub(crate) const PUZZLE_KEYBINDS: Window = Window {
     },
     ..Window::DEFAULT
 };
+
+/// Shows keybinds loaded from a file, with options to apply them.
+fn build_keybinds_import(ui: &mut egui::Ui, app: &mut App) {
+    let puzzle_type = app.puzzle.ty();
+    let Some(import) = &app.keybinds_import else {
+        return;
+    };
+    if import.ty.family_internal_name() != puzzle_type.family_internal_name() {
+        return;
+    }
+
+    let conflicts = app.prefs.puzzle_keybinds[puzzle_type].conflicts(&import.sets);
+
+    let mut merge = false;
+    let mut replace = false;
+    let mut cancel = false;
+    ui.group(|ui| {
+        ui.strong(format!("Imported {} keybind sets", import.sets.len()));
+        for warning in &import.warnings {
+            ui.colored_label(egui::Color32::YELLOW, warning);
+        }
+        if conflicts.is_empty() {
+            ui.label("No conflicts with existing keybinds");
+        } else {
+            ui.label(format!(
+                "{} conflicts with existing keybinds:",
+                conflicts.len()
+            ));
+            for (set_name, bind, old_command) in &conflicts {
+                ui.label(format!(
+                    "{set_name}: {} is {:?} instead of {:?}",
+                    bind.key,
+                    bind.command.short_description(puzzle_type),
+                    old_command.short_description(puzzle_type),
+                ));
+            }
+        }
+
+        ui.horizontal(|ui| {
+            merge = ui
+                .button("Merge")
+                .on_hover_explanation(
+                    "",
+                    "Adds the imported keybind sets to the existing \
+                     ones. Imported keybinds replace existing keybinds \
+                     for the same key in sets with the same name.",
+                )
+                .clicked();
+            replace = ui
+                .button("Replace")
+                .on_hover_explanation("", "Replaces all existing keybind sets for this puzzle.")
+                .clicked();
+            cancel = ui.button("Cancel").clicked();
+        });
+    });
+
+    if merge || replace || cancel {
+        let Some(import) = app.keybinds_import.take() else {
+            return;
+        };
+        let keybind_sets = &mut app.prefs.puzzle_keybinds[puzzle_type];
+        if merge {
+            keybind_sets.merge(import.sets);
+        } else if replace {
+            keybind_sets.replace(import.sets);
+        }
+        app.prefs.needs_save |= merge || replace;
+    }
+}
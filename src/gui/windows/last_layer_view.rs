@@ -0,0 +1,55 @@
+use super::Window;
+use crate::app::App;
+use crate::puzzle::{traits::*, ProjectionType};
+
+pub(crate) const LAST_LAYER_VIEW: Window = Window {
+    name: "Last layer",
+    build,
+    cleanup,
+    ..Window::DEFAULT
+};
+
+const MIN_SIZE: f32 = 160.0;
+
+fn cleanup(_ctx: &egui::Context, app: &mut App) {
+    let visible = &mut app.prefs.info.last_layer_view.visible;
+    if *visible {
+        *visible = false;
+        app.prefs.needs_save = true;
+    }
+}
+
+fn build(ui: &mut egui::Ui, app: &mut App) {
+    let ll_prefs = &mut app.prefs.info.last_layer_view;
+    if !ll_prefs.visible {
+        ll_prefs.visible = true;
+        app.prefs.needs_save = true;
+    }
+
+    let ty = app.puzzle.ty();
+    if ty.projection_type() != ProjectionType::_3D {
+        ui.label("Only available for 3D puzzles");
+        return;
+    }
+
+    let ll_prefs = &mut app.prefs.info.last_layer_view;
+    ui.horizontal(|ui| {
+        ui.label("Up:");
+        for face in ty.faces() {
+            let r = ui
+                .selectable_value(&mut ll_prefs.up_face, face.symbol.to_owned(), face.symbol)
+                .on_hover_text(face.name);
+            app.prefs.needs_save |= r.changed();
+        }
+    });
+
+    let Some(texture_id) = app.last_layer_texture_id else {
+        return;
+    };
+
+    let dpi = ui.ctx().pixels_per_point();
+    let size = f32::max(ui.available_width(), MIN_SIZE);
+    let pixels = (size * dpi).round() as u32;
+    app.last_layer_texture_size = (pixels, pixels);
+    ui.image(texture_id, egui::vec2(size, size));
+}
@@ -0,0 +1,56 @@
+use super::Window;
+use crate::app::App;
+use crate::gui::components::{LayerMaskEdit, PresetsUi, PresetsUiStrings};
+use crate::preferences::LayerGroup;
+use crate::puzzle::traits::*;
+
+pub(crate) const LAYER_GROUPS: Window = Window {
+    name: "Layer groups",
+    vscroll: true,
+    build,
+    ..Window::DEFAULT
+};
+
+fn build(ui: &mut egui::Ui, app: &mut App) {
+    let ty = app.puzzle.ty();
+
+    ui.label(format!(
+        "Twist keybinds can use these layer groups on {ty}."
+    ));
+
+    let mut layer_groups = std::mem::take(&mut app.prefs.layer_groups[ty]);
+
+    let mut changed = false;
+    let mut presets_ui = PresetsUi {
+        id: unique_id!(),
+        presets: &mut layer_groups,
+        changed: &mut changed,
+        strings: PresetsUiStrings {
+            edit: "Edit layer groups",
+            save: "Add layer group",
+            name: "Layer group name",
+        },
+        enable_yaml: true,
+    };
+
+    presets_ui.show_header(ui, || LayerGroup {
+        layers: "1..2".parse().unwrap_or_default(),
+    });
+    ui.separator();
+    presets_ui.show_list(ui, |ui, idx, preset| {
+        ui.label(&preset.preset_name);
+        let r = ui.add(LayerMaskEdit {
+            id: unique_id!(idx),
+            layers: &mut preset.value.layers,
+        });
+        match preset.value.layer_mask(ty.layer_count()) {
+            Ok(layers) => ui.label(layers.long_description()),
+            Err(e) => ui.colored_label(egui::Color32::RED, e),
+        };
+        r
+    });
+
+    app.prefs.layer_groups[ty] = layer_groups;
+
+    app.prefs.needs_save |= changed;
+}
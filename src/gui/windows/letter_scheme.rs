@@ -0,0 +1,69 @@
+use super::Window;
+use crate::app::App;
+use crate::puzzle::{traits::*, Face, Sticker};
+
+pub(crate) const LETTER_SCHEME: Window = Window {
+    name: "Letter scheme",
+    vscroll: true,
+    build,
+    ..Window::DEFAULT
+};
+
+const LETTER_WIDTH: f32 = 20.0;
+
+fn build(ui: &mut egui::Ui, app: &mut App) {
+    let ty = app.puzzle.ty();
+    let letter_prefs = &mut app.prefs.letters;
+
+    ui.horizontal(|ui| {
+        let r = ui.checkbox(&mut letter_prefs.visible, "Show letters");
+        app.prefs.needs_save |= r.changed();
+        let r = ui.color_edit_button_srgba(&mut letter_prefs.color);
+        app.prefs.needs_save |= r.changed();
+    });
+
+    if ty.sticker_grid_position(Sticker(0)).is_none() {
+        ui.label("Only available for 3D puzzles");
+        return;
+    }
+
+    ui.separator();
+
+    // Edit a copy so that the default scheme isn't saved as a custom one
+    // unless it's actually changed.
+    let mut scheme = letter_prefs.scheme(ty).into_owned();
+    let n = ty.layer_count();
+    let mut changed = false;
+    ui.horizontal_wrapped(|ui| {
+        for (i, face) in ty.faces().iter().enumerate() {
+            ui.vertical(|ui| {
+                ui.strong(face.name);
+                egui::Grid::new(unique_id!(i))
+                    .spacing([2.0, 2.0])
+                    .show(ui, |ui| {
+                        for row in 0..n {
+                            for col in 0..n {
+                                let letter = scheme.letter_mut(ty, Face(i as _), [row, col]);
+                                let r = ui.add(
+                                    egui::TextEdit::singleline(letter).desired_width(LETTER_WIDTH),
+                                );
+                                changed |= r.changed();
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+        }
+    });
+
+    if changed {
+        *letter_prefs.scheme_mut(ty) = scheme;
+        app.prefs.needs_save = true;
+    }
+
+    ui.separator();
+
+    if ui.button("Reset to Speffz").clicked() {
+        app.prefs.needs_save |= letter_prefs.reset_scheme(ty);
+    }
+}
@@ -0,0 +1,70 @@
+use strum::IntoEnumIterator;
+
+use super::Window;
+use crate::app::App;
+use crate::preferences::MirrorPlane;
+
+pub(crate) const MIRROR_VIEW: Window = Window {
+    name: "Mirrored view",
+    build,
+    cleanup,
+    ..Window::DEFAULT
+};
+
+const MIN_SIZE: f32 = 160.0;
+
+fn cleanup(_ctx: &egui::Context, app: &mut App) {
+    let mirror_prefs = &mut app.prefs.info.mirror_view;
+    if mirror_prefs.visible {
+        mirror_prefs.visible = false;
+        app.prefs.needs_save = true;
+        app.request_redraw_puzzle();
+    }
+}
+
+fn build(ui: &mut egui::Ui, app: &mut App) {
+    let mirror_prefs = &mut app.prefs.info.mirror_view;
+    let mut changed = false;
+    if !mirror_prefs.visible {
+        mirror_prefs.visible = true;
+        changed = true;
+    }
+
+    ui.horizontal(|ui| {
+        ui.label("Mirror:");
+        for plane in MirrorPlane::iter() {
+            let r = ui.selectable_value(&mut mirror_prefs.plane, plane, plane.to_string());
+            changed |= r.changed();
+        }
+    });
+    let r = ui
+        .checkbox(
+            &mut mirror_prefs.mirror_main_view,
+            "Mirror main view instead",
+        )
+        .on_hover_text(
+            "Mirrors the main puzzle view while this window \
+             is open, instead of showing a mirrored copy here. \
+             Input is not mirrored.",
+        );
+    changed |= r.changed();
+    let mirror_main_view = mirror_prefs.mirror_main_view;
+
+    if changed {
+        app.prefs.needs_save = true;
+        app.request_redraw_puzzle();
+    }
+
+    if mirror_main_view {
+        return;
+    }
+    let Some(texture_id) = app.mirror_view_texture_id else {
+        return;
+    };
+
+    let dpi = ui.ctx().pixels_per_point();
+    let size = f32::max(ui.available_width(), MIN_SIZE);
+    let pixels = (size * dpi).round() as u32;
+    app.mirror_view_texture_size = (pixels, pixels);
+    ui.image(texture_id, egui::vec2(size, size));
+}
@@ -1,27 +1,73 @@
 mod about;
+#[cfg(not(target_arch = "wasm32"))]
+mod alg_sheet;
+mod blind_trace;
+mod case_trainer;
+mod commutator_builder;
+mod drill;
+#[cfg(not(target_arch = "wasm32"))]
+mod gamepad;
 mod keybind_sets;
 mod keybinds_reference;
 mod keybinds_table;
+mod last_layer_view;
+mod layer_groups;
+mod letter_scheme;
+mod mirror_view;
 mod modifier_keys;
 mod mousebinds_table;
+mod net_diagram;
+mod new_puzzle;
+mod patterns;
 mod piece_filters;
 mod puzzle_controls;
+mod scramble_preview;
 mod settings;
+mod solution_comparison;
+mod solver;
+mod state_checker;
+mod stats;
+mod time_attack;
 mod timer;
+mod touch_controls;
 mod welcome;
+mod whats_new;
 
 use crate::app::App;
 pub(crate) use about::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use alg_sheet::*;
+pub(crate) use blind_trace::*;
+pub(crate) use case_trainer::*;
+pub(crate) use commutator_builder::*;
+pub(crate) use drill::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use gamepad::*;
 pub(crate) use keybind_sets::*;
 pub(crate) use keybinds_reference::*;
 pub(crate) use keybinds_table::*;
+pub(crate) use last_layer_view::*;
+pub(crate) use layer_groups::*;
+pub(crate) use letter_scheme::*;
+pub(crate) use mirror_view::*;
 pub(crate) use modifier_keys::*;
 pub(crate) use mousebinds_table::*;
+pub(crate) use net_diagram::*;
+pub(crate) use new_puzzle::*;
+pub(crate) use patterns::*;
 pub(crate) use piece_filters::*;
 pub(crate) use puzzle_controls::*;
+pub(crate) use scramble_preview::*;
 pub(crate) use settings::*;
+pub(crate) use solution_comparison::*;
+pub(crate) use solver::*;
+pub(crate) use state_checker::*;
+pub(crate) use stats::*;
+pub(crate) use time_attack::*;
 pub(crate) use timer::*;
+pub(crate) use touch_controls::*;
 pub(crate) use welcome::*;
+pub(crate) use whats_new::*;
 
 pub const FLOATING_WINDOW_OPACITY: f32 = 0.98;
 pub const PREFS_WINDOW_WIDTH: f32 = 240.0;
@@ -31,15 +77,36 @@ pub const WELCOME_WINDOW_WIDTH: f32 = 540.0;
 pub const ALL: &[Window] = &[
     // Misc.
     WELCOME,
+    WHATS_NEW,
     ABOUT,
+    NEW_PUZZLE,
     #[cfg(debug_assertions)]
     DEBUG,
     // Tools
     KEYBINDS_REFERENCE,
     PUZZLE_CONTROLS,
     PIECE_FILTERS,
+    LAYER_GROUPS,
     MODIFIER_KEYS,
+    TOUCH_CONTROLS,
     TIMER,
+    STATS,
+    LAST_LAYER_VIEW,
+    MIRROR_VIEW,
+    LETTER_SCHEME,
+    BLIND_TRACE,
+    NET_DIAGRAM,
+    #[cfg(not(target_arch = "wasm32"))]
+    ALG_SHEET,
+    PATTERNS,
+    CASE_TRAINER,
+    DRILL,
+    TIME_ATTACK,
+    SOLUTION_COMPARISON,
+    COMMUTATOR_BUILDER,
+    SCRAMBLE_PREVIEW,
+    STATE_CHECKER,
+    SOLVER,
     // Settings
     APPEARANCE_SETTINGS,
     INTERACTION_SETTINGS,
@@ -49,6 +116,8 @@ pub const ALL: &[Window] = &[
     GLOBAL_KEYBINDS,
     PUZZLE_KEYBINDS,
     MOUSEBINDS,
+    #[cfg(not(target_arch = "wasm32"))]
+    GAMEPAD,
 ];
 
 #[cfg(debug_assertions)]
@@ -57,7 +126,36 @@ pub const DEBUG: Window = Window {
     location: Location::Floating,
     fixed_width: None,
     vscroll: true,
-    build: |ui, _app| {
+    build: |ui, app| {
+        let mut frame_step = app.frame_step.is_some();
+        ui.checkbox(&mut frame_step, "Frame step mode")
+            .on_hover_text(
+                "Animations and the timer only advance by a \
+                 fixed timestep each time F10 is pressed",
+            );
+        match (frame_step, app.frame_step.is_some()) {
+            (true, false) => app.frame_step = Some(crate::app::FrameStep::default()),
+            (false, true) => app.frame_step = None,
+            _ => (),
+        }
+        if let Some(frame_step) = &mut app.frame_step {
+            ui.horizontal(|ui| {
+                let mut millis = frame_step.step.as_millis() as u64;
+                let r = ui.add(
+                    egui::DragValue::new(&mut millis)
+                        .clamp_range(1..=1000)
+                        .suffix(" ms"),
+                );
+                if r.changed() {
+                    frame_step.step = instant::Duration::from_millis(millis);
+                }
+                if ui.button("Step").clicked() {
+                    frame_step.pending += 1;
+                }
+            });
+        }
+        ui.separator();
+
         let mut debug_info = std::mem::take(&mut *crate::debug::FRAME_DEBUG_INFO.lock().unwrap());
         ui.add(egui::TextEdit::multiline(&mut debug_info).code_editor());
     },
@@ -102,8 +200,25 @@ impl Window {
 
         ctx.data().insert_persisted(self.id(), is_open);
     }
+    /// Returns the position of the top left corner of a floating window the
+    /// last time it was shown, or its saved position if it hasn't been shown
+    /// yet.
+    pub fn pos(self, ctx: &egui::Context) -> Option<egui::Pos2> {
+        ctx.data().get_temp(self.id().with("pos"))
+    }
+    pub fn set_pos(self, ctx: &egui::Context, pos: Option<egui::Pos2>) {
+        let id = self.id().with("pos");
+        match pos {
+            Some(pos) => ctx.data().insert_temp(id, pos),
+            None => ctx.data().remove::<egui::Pos2>(id),
+        }
+    }
 
     pub fn show(self, ctx: &egui::Context, app: &mut App) {
+        if app.zen_mode && [TIMER.id(), STATS.id()].contains(&self.id()) {
+            return;
+        }
+
         let opacity = if self.id() == KEYBINDS_REFERENCE.id() {
             app.prefs.info.keybinds_reference.opacity
         } else {
@@ -121,19 +236,27 @@ impl Window {
                         .collapsible(false)
                         .resizable(false);
                 } else {
+                    if let Some(pos) = self.pos(ctx) {
+                        w = w.default_pos(pos);
+                    }
                     w = w
                         .collapsible(true)
                         .scroll2([false, self.vscroll])
                         .resizable(self.fixed_width.is_none() || self.vscroll)
                         .frame(egui::Frame::popup(&ctx.style()).multiply_with_opacity(opacity));
                 }
-                w.show(ctx, |ui| {
+                let r = w.show(ctx, |ui| {
                     if let Some(w) = self.fixed_width {
                         ui.set_min_width(w);
                         ui.set_max_width(w);
                     }
                     (self.build)(ui, app);
                 });
+                if let Some(r) = r {
+                    if self.location == Location::Floating {
+                        self.set_pos(ctx, Some(r.response.rect.min));
+                    }
+                }
             }
             Location::LeftSide => {
                 super::side_bar::build(ctx, self.name, &mut is_open, |ui| (self.build)(ui, app));
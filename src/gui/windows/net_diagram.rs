@@ -0,0 +1,80 @@
+use strum::IntoEnumIterator;
+
+use super::Window;
+use crate::app::App;
+use crate::diagram::{NetDiagram, NetLayout};
+
+pub(crate) const NET_DIAGRAM: Window = Window {
+    name: "Net diagram",
+    build,
+    ..Window::DEFAULT
+};
+
+fn build(ui: &mut egui::Ui, app: &mut App) {
+    ui.horizontal(|ui| {
+        ui.label("Layout:");
+        for layout in NetLayout::iter() {
+            let r = ui.selectable_value(&mut app.prefs.info.net_layout, layout, layout.to_string());
+            app.prefs.needs_save |= r.changed();
+        }
+    });
+
+    let ty = app.puzzle.ty();
+    let face_colors = app.prefs.colors.face_colors_list(ty);
+    let layout = app.prefs.info.net_layout;
+    let Some(diagram) = NetDiagram::new(app.puzzle.latest(), &face_colors, layout) else {
+        ui.label("Only available for 3D cubes");
+        return;
+    };
+
+    ui.separator();
+
+    // Preview, scaled down to fit the window.
+    let scale = f32::min(1.0, ui.available_width() / diagram.size.x);
+    let (rect, _) = ui.allocate_exact_size(diagram.size * scale, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    for &(sticker_rect, color) in &diagram.stickers {
+        let sticker_rect = egui::Rect::from_min_max(
+            rect.min + sticker_rect.min.to_vec2() * scale,
+            rect.min + sticker_rect.max.to_vec2() * scale,
+        );
+        painter.rect_filled(sticker_rect, 0.0, color);
+    }
+
+    ui.separator();
+
+    ui.horizontal(|ui| {
+        if ui.button("Copy SVG").clicked() {
+            ui.output().copied_text = diagram.to_svg();
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if ui.button("Save SVG…").clicked() {
+                save_file("SVG image", "svg", || Ok(diagram.to_svg().into_bytes()));
+            }
+            if ui.button("Save PNG…").clicked() {
+                save_file("PNG image", "png", || diagram.to_png().map_err(Into::into));
+            }
+        }
+    });
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_file(
+    filter_name: &str,
+    extension: &str,
+    contents: impl FnOnce() -> anyhow::Result<Vec<u8>>,
+) {
+    let dialog = rfd::FileDialog::new()
+        .add_filter(filter_name, &[extension])
+        .set_file_name(&format!("net.{extension}"));
+    let Some(path) = dialog.save_file() else {
+        return;
+    };
+    if let Err(e) = contents().and_then(|bytes| Ok(std::fs::write(&path, bytes)?)) {
+        rfd::MessageDialog::new()
+            .set_title("Unable to save diagram")
+            .set_description(&format!("Unable to save diagram:\n\n{e}"))
+            .show();
+    }
+}
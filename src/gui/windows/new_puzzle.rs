@@ -0,0 +1,32 @@
+use super::{Location, Window};
+use crate::app::App;
+use crate::commands::Command;
+use crate::gui::components::puzzle_type_menu;
+
+pub(crate) const NEW_PUZZLE: Window = Window {
+    name: "New puzzle",
+    location: Location::Centered,
+    build,
+    ..Window::DEFAULT
+};
+
+fn build(ui: &mut egui::Ui, app: &mut App) {
+    let mut chosen = false;
+
+    if ui
+        .button(format!("New scramble of {}", app.puzzle.ty()))
+        .clicked()
+    {
+        app.event(Command::ScrambleFull);
+        chosen = true;
+    }
+    ui.separator();
+    if let Some(ty) = puzzle_type_menu(ui) {
+        app.event(Command::NewPuzzle(ty));
+        chosen = true;
+    }
+
+    if chosen {
+        NEW_PUZZLE.set_open(ui.ctx(), false);
+    }
+}
@@ -0,0 +1,98 @@
+use super::Window;
+use crate::app::{App, AppEvent};
+use crate::puzzle::traits::*;
+
+pub(crate) const PATTERNS: Window = Window {
+    name: "Patterns",
+    vscroll: true,
+    build,
+    ..Window::DEFAULT
+};
+
+fn build(ui: &mut egui::Ui, app: &mut App) {
+    let ty = app.puzzle.ty();
+    let patterns = &app.prefs.patterns[ty];
+
+    if patterns.is_empty() {
+        ui.label(format!("No patterns for {}", ty.name()));
+        return;
+    }
+
+    ui.label(
+        "Resets the puzzle and applies a pattern, which can be undone all at once. \
+         Demonstrating a pattern applies it one twist at a time instead.",
+    );
+    ui.separator();
+
+    for pattern in patterns {
+        ui.horizontal(|ui| {
+            let r = ui
+                .button(&pattern.preset_name)
+                .on_hover_text(&pattern.value.alg);
+            if r.clicked() {
+                app.event(AppEvent::ApplyPattern(pattern.preset_name.clone()));
+            }
+            if ui.button("Demonstrate").clicked() {
+                app.event(AppEvent::DemonstratePattern(pattern.preset_name.clone()));
+            }
+        });
+    }
+
+    build_demonstration(ui, app);
+}
+
+fn build_demonstration(ui: &mut egui::Ui, app: &mut App) {
+    let Some(demo) = &app.demonstration else {
+        return;
+    };
+    let Some(progress) = demo.progress(&app.puzzle) else {
+        app.demonstration = None;
+        return;
+    };
+    let demo = demo.clone();
+    let twists = demo.twists();
+
+    ui.separator();
+    ui.strong(format!(
+        "Demonstrating {:?} ({progress}/{})",
+        demo.pattern_name,
+        twists.len(),
+    ));
+    ui.horizontal(|ui| {
+        ui.add_enabled_ui(progress < twists.len(), |ui| {
+            if ui.button("Next twist").clicked() {
+                app.event(AppEvent::Twist(twists[progress]));
+            }
+        });
+        if ui.button("Stop").clicked() {
+            app.demonstration = None;
+        }
+    });
+
+    let ty = app.puzzle.ty();
+    let notation = ty.notation_scheme_for_style(app.prefs.interaction.notation_style(ty));
+    let Some(pattern) = app.prefs.patterns[ty]
+        .iter_mut()
+        .find(|p| p.preset_name == demo.pattern_name)
+    else {
+        return;
+    };
+    let hints = &mut pattern.value.hints;
+    hints.resize(hints.len().max(twists.len()), String::new());
+
+    ui.collapsing("Hints", |ui| {
+        ui.label("Finger-trick hints shown on the puzzle before each twist");
+        egui::Grid::new(unique_id!()).striped(true).show(ui, |ui| {
+            for (i, (&twist, hint)) in twists.iter().zip(hints.iter_mut()).enumerate() {
+                let text = egui::RichText::new(notation.twist_to_string(twist)).monospace();
+                ui.label(if i == progress { text.strong() } else { text });
+                app.prefs.needs_save |= ui.text_edit_singleline(hint).changed();
+                ui.end_row();
+            }
+        });
+    });
+    // Don't save trailing empty hints.
+    while hints.last().map_or(false, |hint| hint.is_empty()) {
+        hints.pop();
+    }
+}
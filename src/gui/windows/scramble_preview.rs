@@ -0,0 +1,57 @@
+use super::Window;
+use crate::app::{App, AppEvent};
+use crate::puzzle::traits::*;
+
+pub(crate) const SCRAMBLE_PREVIEW: Window = Window {
+    name: "Scramble preview",
+    build,
+    cleanup,
+    ..Window::DEFAULT
+};
+
+const MIN_SIZE: f32 = 160.0;
+
+fn cleanup(_ctx: &egui::Context, app: &mut App) {
+    app.scramble_preview = None;
+}
+
+fn build(ui: &mut egui::Ui, app: &mut App) {
+    let ty = app.puzzle.ty();
+    let preview = app
+        .scramble_preview
+        .as_ref()
+        .filter(|preview| preview.ty() == ty);
+
+    ui.horizontal(|ui| {
+        if ui.button("New scramble").clicked() {
+            app.event(AppEvent::NewScramblePreview);
+        }
+        ui.add_enabled_ui(preview.is_some(), |ui| {
+            if ui.button("Start").clicked() {
+                app.event(AppEvent::StartScramblePreview);
+            }
+        });
+    });
+
+    let Some(preview) = preview else {
+        ui.label(format!(
+            "Generates a full scramble for {} without changing the current \
+             puzzle, so that it can be inspected before starting.",
+            ty.name(),
+        ));
+        return;
+    };
+    let scramble = preview.scramble_string();
+
+    let Some(texture_id) = app.scramble_preview_texture_id else {
+        return;
+    };
+
+    let dpi = ui.ctx().pixels_per_point();
+    let size = f32::max(ui.available_width(), MIN_SIZE);
+    let pixels = (size * dpi).round() as u32;
+    app.scramble_preview_texture_size = (pixels, pixels);
+    ui.image(texture_id, egui::vec2(size, size));
+
+    ui.collapsing("Scramble", |ui| ui.label(scramble));
+}
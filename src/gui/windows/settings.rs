@@ -18,6 +18,18 @@ pub(crate) const APPEARANCE_SETTINGS: Window = Window {
         ui.collapsing("Performance", |ui| {
             prefs::build_graphics_section(ui, app);
         });
+        ui.collapsing("Window title", |ui| {
+            prefs::build_window_title_section(ui, app);
+        });
+        ui.collapsing("Timer", |ui| {
+            prefs::build_timer_section(ui, app);
+        });
+        ui.collapsing("Move log", |ui| {
+            prefs::build_move_log_section(ui, app);
+        });
+        ui.collapsing("Penalty flash", |ui| {
+            prefs::build_penalty_flash_section(ui, app);
+        });
     },
     ..Window::DEFAULT
 };
@@ -25,7 +37,33 @@ pub(crate) const APPEARANCE_SETTINGS: Window = Window {
 pub(crate) const INTERACTION_SETTINGS: Window = Window {
     name: "Interaction",
     fixed_width: Some(PREFS_WINDOW_WIDTH),
-    build: prefs::build_interaction_section,
+    build: |ui, app| {
+        prefs::build_interaction_section(ui, app);
+        ui.collapsing("Twist directions", |ui| {
+            prefs::build_twist_convention_section(ui, app);
+        });
+        ui.collapsing("Notation style", |ui| {
+            prefs::build_notation_style_section(ui, app);
+        });
+        ui.collapsing("Solved definition", |ui| {
+            prefs::build_solved_definition_section(ui, app);
+        });
+        ui.collapsing("Inspection", |ui| {
+            prefs::build_inspection_section(ui, app);
+        });
+        ui.collapsing("Countdown", |ui| {
+            prefs::build_countdown_section(ui, app);
+        });
+        ui.collapsing("Peek", |ui| {
+            prefs::build_peek_section(ui, app);
+        });
+        ui.collapsing("Idle rotation", |ui| {
+            prefs::build_idle_rotation_section(ui, app);
+        });
+        ui.collapsing("Idle dimming", |ui| {
+            prefs::build_idle_dim_section(ui, app);
+        });
+    },
     ..Window::DEFAULT
 };
 
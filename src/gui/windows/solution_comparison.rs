@@ -0,0 +1,83 @@
+use super::Window;
+use crate::app::App;
+use crate::puzzle::comparison::{compare_solutions, TwistDiff};
+
+pub(crate) const SOLUTION_COMPARISON: Window = Window {
+    name: "Compare solution",
+    vscroll: true,
+    build,
+    ..Window::DEFAULT
+};
+
+fn build(ui: &mut egui::Ui, app: &mut App) {
+    ui.label(
+        "Compares the current solution move by move against a reference \
+         solution to the same scramble, after removing rotations and \
+         simplifying both.",
+    );
+    ui.separator();
+
+    let text_id = unique_id!();
+    let mut text: String = ui.data().get_temp(text_id).unwrap_or_default();
+    ui.label("Reference solution:");
+    ui.add(egui::TextEdit::multiline(&mut text).code_editor());
+    ui.data().insert_temp(text_id, text.clone());
+
+    if app.puzzle.scramble().is_empty() {
+        ui.label("Scramble the puzzle first");
+        return;
+    }
+    if text.trim().is_empty() {
+        return;
+    }
+
+    let ty = app.puzzle.ty();
+    let convention = &app.prefs.interaction.twist_conventions[ty];
+    let style = app.prefs.interaction.notation_style(ty);
+    let result = convention
+        .parse_alg(ty, style, &text)
+        .and_then(|reference| {
+            compare_solutions(
+                ty,
+                app.puzzle.scramble(),
+                &app.puzzle.combined_solution(),
+                &reference,
+            )
+        });
+    let diff = match result {
+        Ok(diff) => diff,
+        Err(e) => {
+            ui.colored_label(egui::Color32::RED, format!("✖ {e}"));
+            return;
+        }
+    };
+
+    ui.separator();
+    let solution_len = diff
+        .iter()
+        .filter(|d| !matches!(d, TwistDiff::Deleted(_)))
+        .count();
+    let reference_len = diff
+        .iter()
+        .filter(|d| !matches!(d, TwistDiff::Inserted(_)))
+        .count();
+    ui.label(format!(
+        "Your solution: {solution_len} moves, reference: {reference_len} moves"
+    ));
+    let notation = |twist| convention.twist_to_string(ty, style, twist);
+    ui.horizontal_wrapped(|ui| {
+        for d in diff {
+            let text = match d {
+                TwistDiff::Same(twist) => egui::RichText::new(notation(twist)),
+                TwistDiff::Inserted(twist) => {
+                    egui::RichText::new(format!("+{}", notation(twist))).color(egui::Color32::GREEN)
+                }
+                TwistDiff::Deleted(twist) => egui::RichText::new(format!("-{}", notation(twist)))
+                    .color(egui::Color32::RED)
+                    .strikethrough(),
+            };
+            ui.label(text.monospace());
+        }
+    });
+    ui.label("+ only in your solution, - only in the reference solution");
+}
@@ -0,0 +1,117 @@
+use super::Window;
+use crate::app::{App, AppEvent, TrainingWheels};
+use crate::gui::ext::ResponseExt;
+use crate::puzzle::solve_job::{SolveJob, TWO_PHASE_TARGET_LENGTH};
+use crate::puzzle::{traits::*, PuzzleTypeEnum};
+
+pub(crate) const SOLVER: Window = Window {
+    name: "Solver",
+    vscroll: true,
+    build,
+    ..Window::DEFAULT
+};
+
+fn build(ui: &mut egui::Ui, app: &mut App) {
+    match app.puzzle.ty() {
+        PuzzleTypeEnum::Rubiks3D { layer_count: 2 } => ui.label(
+            "Finds the shortest solution from the current state, in the \
+             half-turn metric. The first solve may take a few seconds.",
+        ),
+        PuzzleTypeEnum::Rubiks3D { layer_count: 3 } => ui.label(format!(
+            "Finds a short solution from the current state using Kociemba's \
+             two-phase algorithm. The search continues until it finds a \
+             solution of at most {TWO_PHASE_TARGET_LENGTH} moves or is \
+             stopped.",
+        )),
+        _ => {
+            ui.label("Only available for 2x2x2 and 3x3x3");
+            return;
+        }
+    };
+    ui.separator();
+
+    let mut training_wheels = app.training_wheels.is_some();
+    if ui
+        .checkbox(&mut training_wheels, "Training wheels")
+        .on_hover_explanation(
+            "",
+            "Highlights the next move of a solution on the \
+             puzzle, finding a new solution whenever a \
+             different move is made. Solves timed with this \
+             on are not recorded in the stats.",
+        )
+        .changed()
+    {
+        app.training_wheels = training_wheels.then(TrainingWheels::default);
+    }
+    ui.separator();
+
+    // The solve is abandoned as soon as the puzzle leaves the state it was
+    // started from.
+    let job_id = unique_id!();
+    let mut job: Option<SolveJob> = ui.data().get_temp(job_id);
+    if let Some(j) = &job {
+        if j.puzzle() != app.puzzle.latest() {
+            j.cancel();
+            job = None;
+        }
+    }
+
+    let is_running = job.as_ref().map_or(false, |j| j.result().is_none());
+    ui.add_enabled_ui(!is_running, |ui| {
+        if ui.button("Solve").clicked() {
+            job = Some(SolveJob::start(app.puzzle.latest().clone()));
+        }
+    });
+
+    if let Some(j) = &job {
+        match j.result() {
+            None => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    match j.best() {
+                        Some(best) => ui.label(format!("Best so far: {} moves", best.len())),
+                        None => ui.label("Searching…"),
+                    };
+                });
+                if ui.button("Stop").clicked() {
+                    j.cancel();
+                }
+                // Keep showing progress even if nothing else is happening.
+                ui.ctx().request_repaint();
+            }
+            Some(Err(e)) => {
+                ui.colored_label(egui::Color32::RED, format!("✖ {e}"));
+            }
+            Some(Ok(twists)) if twists.is_empty() => {
+                ui.label("Puzzle is already solved");
+            }
+            Some(Ok(twists)) => {
+                let style = app.prefs.interaction.notation_style(app.puzzle.ty());
+                let notation = app.puzzle.notation_scheme_for_style(style);
+                let s = twists
+                    .iter()
+                    .map(|&twist| notation.twist_to_string(twist))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                ui.label(format!("{} moves:", twists.len()));
+                ui.add(egui::Label::new(egui::RichText::new(&s).monospace()).wrap(true));
+                ui.horizontal(|ui| {
+                    if ui.button("Copy").clicked() {
+                        ui.output().copied_text = s;
+                    }
+                    if ui.button("Apply solution").clicked() {
+                        for &twist in &twists {
+                            app.event(AppEvent::Twist(twist));
+                        }
+                    }
+                });
+            }
+        }
+    }
+
+    match job {
+        Some(job) => ui.data().insert_temp(job_id, job),
+        None => ui.data().remove::<SolveJob>(job_id),
+    }
+}
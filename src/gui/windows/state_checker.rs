@@ -0,0 +1,70 @@
+use super::Window;
+use crate::app::App;
+use crate::puzzle::{traits::*, Puzzle, PuzzleSnapshot};
+
+pub(crate) const STATE_CHECKER: Window = Window {
+    name: "State checker",
+    vscroll: true,
+    build,
+    ..Window::DEFAULT
+};
+
+fn build(ui: &mut egui::Ui, app: &mut App) {
+    ui.label(
+        "Checks that the puzzle is in a state that can be reached by \
+         twisting it, such as after importing a log file.",
+    );
+    ui.separator();
+
+    show_check_result(ui, app.puzzle.latest());
+
+    if let Some(snapshot) = app.puzzle.latest().state() {
+        ui.separator();
+        if ui.button("Copy state").clicked() {
+            match serde_yaml::to_string(&snapshot) {
+                Ok(s) => ui.output().copied_text = s,
+                Err(e) => log::error!("error serializing puzzle state: {e}"),
+            }
+        }
+    }
+
+    ui.separator();
+    ui.collapsing("Check another state", |ui| {
+        ui.label("Paste the position and orientation of every piece, as copied above.");
+        let text_id = unique_id!();
+        let mut text: String = ui.data().get_temp(text_id).unwrap_or_default();
+        ui.add(egui::TextEdit::multiline(&mut text).code_editor());
+        if !text.trim().is_empty() {
+            match serde_yaml::from_str::<PuzzleSnapshot>(&text)
+                .map_err(|e| e.to_string())
+                .and_then(|snapshot| Puzzle::from_state(&snapshot))
+            {
+                Ok(puzzle) => {
+                    ui.label(format!("Valid arrangement of pieces for {}", puzzle.name()));
+                    show_check_result(ui, &puzzle);
+                }
+                Err(e) => {
+                    ui.colored_label(egui::Color32::RED, format!("✖ {e}"));
+                }
+            }
+        }
+        ui.data().insert_temp(text_id, text);
+    });
+}
+
+fn show_check_result(ui: &mut egui::Ui, puzzle: &Puzzle) {
+    match puzzle.check_state() {
+        None => {
+            ui.label("Not available for this puzzle");
+        }
+        Some(errors) if errors.is_empty() => {
+            ui.colored_label(egui::Color32::GREEN, "✔ Puzzle state is valid");
+        }
+        Some(errors) => {
+            ui.colored_label(egui::Color32::RED, "✖ Puzzle state is invalid");
+            for error in errors {
+                ui.label(format!("• {error}"));
+            }
+        }
+    }
+}
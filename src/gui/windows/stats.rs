@@ -0,0 +1,228 @@
+use egui::plot::{Bar, BarChart, Plot};
+use instant::Duration;
+use itertools::Itertools;
+use strum::IntoEnumIterator;
+
+use super::{duration_to_str, Window};
+use crate::app::App;
+use crate::puzzle::traits::*;
+use crate::stats::{PbKind, Penalty, SolveResult};
+
+pub(crate) const STATS: Window = Window {
+    name: "Statistics",
+    vscroll: true,
+    build,
+    ..Window::DEFAULT
+};
+
+fn result_to_str(result: Option<SolveResult>) -> String {
+    match result {
+        Some(SolveResult::Time(d)) => duration_to_str(d),
+        Some(SolveResult::Dnf) => "DNF".to_owned(),
+        None => "-".to_owned(),
+    }
+}
+fn millis_to_str(millis: Option<u64>) -> String {
+    result_to_str(millis.map(|ms| SolveResult::Time(Duration::from_millis(ms))))
+}
+
+fn build(ui: &mut egui::Ui, app: &mut App) {
+    let ty = app.puzzle.ty();
+
+    ui.strong(ty.name());
+
+    if !app.new_pbs.is_empty() {
+        ui.colored_label(
+            egui::Color32::GOLD,
+            format!("New PB! ({})", app.new_pbs.iter().join(", ")),
+        );
+    }
+
+    let pbs = &app.prefs.personal_bests[ty];
+    egui::Grid::new(unique_id!()).striped(true).show(ui, |ui| {
+        ui.label("");
+        ui.strong("Current");
+        ui.strong("Session");
+        ui.strong("All-time");
+        ui.end_row();
+
+        for kind in PbKind::iter() {
+            ui.label(kind.to_string());
+            ui.label(result_to_str(app.session.latest(ty, kind)));
+            ui.label(result_to_str(app.session.best(ty, kind)));
+            ui.label(millis_to_str(pbs.get(kind)));
+            ui.end_row();
+        }
+
+        ui.label("streak");
+        ui.label(app.session.current_streak(ty).to_string());
+        ui.label("");
+        ui.label(pbs.longest_streak.to_string());
+        ui.end_row();
+
+        ui.label("reaction")
+            .on_hover_text("Time from the start of the timer to the first twist");
+        ui.label(millis_to_str(
+            app.session
+                .solves_of(ty)
+                .last()
+                .and_then(|(_, solve)| solve.reaction_millis),
+        ));
+        ui.label(result_to_str(
+            app.session.mean_reaction_time(ty).map(SolveResult::Time),
+        ));
+        ui.label("");
+        ui.end_row();
+    });
+
+    ui.separator();
+
+    let last_solve = app.session.solves.last().cloned();
+    ui.horizontal(|ui| {
+        ui.label("Last solve:");
+        match &last_solve {
+            Some(solve) => {
+                ui.strong(result_to_str(Some(solve.result())));
+                for penalty in Penalty::iter() {
+                    if ui
+                        .selectable_label(solve.penalty == penalty, penalty.to_string())
+                        .clicked()
+                    {
+                        app.set_last_solve_penalty(penalty);
+                    }
+                }
+            }
+            None => {
+                ui.label("-");
+            }
+        }
+    });
+
+    ui.collapsing("Session solves", |ui| {
+        let metric = app.prefs.info.metric;
+        for (i, solve) in app.session.solves_of(ty).rev() {
+            let mut text = format!("{}. {}", i + 1, result_to_str(Some(solve.result())));
+            if let Some(twist_count) = solve.twist_count.get(&metric) {
+                text += &format!(" ({metric}: {twist_count})");
+            }
+            let mut hover_text = vec![];
+            if let Some(reaction_millis) = solve.reaction_millis {
+                hover_text.push(format!(
+                    "Reaction: {}",
+                    millis_to_str(Some(reaction_millis))
+                ));
+            }
+            if !solve.scramble.is_empty() {
+                hover_text.push(format!("Scramble: {}", solve.scramble));
+            }
+            let r = ui.label(text);
+            if !hover_text.is_empty() {
+                r.on_hover_text(hover_text.join("\n"));
+            }
+        }
+    });
+
+    ui.collapsing("Distribution", |ui| build_histogram(ui, app));
+
+    let pbs = &app.prefs.personal_bests[ty];
+    ui.collapsing("Recent PBs", |ui| {
+        if pbs.history.is_empty() {
+            ui.label("No personal bests yet");
+        }
+        for record in pbs.history.iter().rev() {
+            ui.label(format!(
+                "{} {} (was {})",
+                record.kind,
+                millis_to_str(Some(record.millis)),
+                millis_to_str(record.previous),
+            ));
+        }
+    });
+
+    ui.collapsing("Streak goal", |ui| {
+        crate::gui::components::prefs::build_streak_goal_section(ui, app);
+        ui.add_enabled_ui(app.goal_streak.count > 0, |ui| {
+            if ui.button("Reset streak").clicked() {
+                app.goal_streak.reset();
+            }
+        });
+    });
+    #[cfg(not(target_arch = "wasm32"))]
+    ui.collapsing("Solve log", |ui| {
+        crate::gui::components::prefs::build_solve_log_section(ui, app);
+    });
+
+    ui.separator();
+
+    if ui
+        .button("Clear session")
+        .on_hover_text("All-time personal bests are kept")
+        .clicked()
+    {
+        app.clear_session();
+    }
+}
+
+fn build_histogram(ui: &mut egui::Ui, app: &mut App) {
+    let bin_width = &mut app.prefs.info.histogram_bin_width;
+    ui.horizontal(|ui| {
+        ui.label("Bin width:");
+        let r = ui.add(
+            egui::DragValue::new(bin_width)
+                .suffix("s")
+                .fixed_decimals(1)
+                .clamp_range(0.1..=60.0_f32)
+                .speed(0.1),
+        );
+        app.prefs.needs_save |= r.changed();
+    });
+
+    let bin_width = Duration::from_secs_f32(app.prefs.info.histogram_bin_width.max(0.1));
+    let histogram = app.session.histogram(app.puzzle.ty(), bin_width);
+    if histogram.bins.is_empty() && histogram.dnf_count == 0 {
+        ui.label("No solves yet");
+        return;
+    }
+
+    let width = histogram.bin_width.as_secs_f64();
+    let bars = histogram
+        .bins
+        .iter()
+        .enumerate()
+        .map(|(i, &count)| {
+            let range = histogram.bin_range(i);
+            let name = format!(
+                "{} - {}",
+                duration_to_str(range.start),
+                duration_to_str(range.end),
+            );
+            Bar::new(range.start.as_secs_f64() + width / 2.0, count as f64)
+                .width(width)
+                .name(name)
+        })
+        .collect();
+    // Show DNFs as a separate bar after the last bin.
+    let dnf_bar = Bar::new(
+        histogram.bin_range(histogram.bins.len()).end.as_secs_f64() + width / 2.0,
+        histogram.dnf_count as f64,
+    )
+    .width(width)
+    .name("DNF");
+
+    let formatter = |bar: &Bar, _: &BarChart| format!("{}\n{} solves", bar.name, bar.value);
+    Plot::new(unique_id!())
+        .height(120.0)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .include_y(0.0)
+        .show(ui, |plot_ui| {
+            plot_ui.bar_chart(BarChart::new(bars).element_formatter(Box::new(formatter)));
+            if histogram.dnf_count > 0 {
+                plot_ui.bar_chart(
+                    BarChart::new(vec![dnf_bar])
+                        .color(egui::Color32::DARK_RED)
+                        .element_formatter(Box::new(formatter)),
+                );
+            }
+        });
+}
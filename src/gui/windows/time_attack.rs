@@ -0,0 +1,71 @@
+use instant::{Duration, Instant};
+
+use super::{duration_to_str, Window};
+use crate::app::App;
+
+pub(crate) const TIME_ATTACK: Window = Window {
+    name: "Time attack",
+    build,
+    ..Window::DEFAULT
+};
+
+fn build(ui: &mut egui::Ui, app: &mut App) {
+    ui.label(
+        "Solve as many scrambles as possible before time runs out. Each \
+         solve is followed by a new scramble and the timer starts right away.",
+    );
+    ui.separator();
+
+    let is_running = app.time_attack.is_running();
+    ui.add_enabled_ui(!is_running, |ui| {
+        ui.horizontal(|ui| {
+            ui.label("Length:");
+            ui.add(
+                egui::DragValue::new(&mut app.time_attack.minutes)
+                    .fixed_decimals(1)
+                    .clamp_range(0.5..=60.0_f32)
+                    .speed(0.1)
+                    .suffix(" min"),
+            );
+        });
+    });
+
+    ui.horizontal(|ui| {
+        if is_running {
+            if ui.button("Stop").clicked() {
+                app.stop_time_attack();
+            }
+        } else if ui.button("Start").clicked() {
+            app.start_time_attack();
+        }
+    });
+
+    ui.separator();
+    if let Some(remaining) = app.time_attack.remaining(Instant::now()) {
+        ui.heading(clock_str(remaining));
+    } else if !app.time_attack.session.solves.is_empty() {
+        ui.strong("Time's up!");
+    }
+    ui.label(format!("Solves: {}", app.time_attack.solve_count()));
+    if let Some(mean) = app.time_attack.mean() {
+        ui.label(format!("Mean: {}", duration_to_str(mean)));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    if !is_running
+        && !app.time_attack.session.solves.is_empty()
+        && ui
+            .button("Save as session...")
+            .on_hover_text("Saves every solve in this time attack, to be reopened later")
+            .clicked()
+    {
+        app.event(crate::app::AppEvent::SaveTimeAttack);
+    }
+}
+
+/// Returns the time left in a time attack as minutes and seconds, rounded up
+/// so that it reads 0:00 only once time is up.
+pub(crate) fn clock_str(remaining: Duration) -> String {
+    let seconds = remaining.as_secs_f32().ceil() as u64;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
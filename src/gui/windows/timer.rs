@@ -1,6 +1,13 @@
 use instant::{Duration, Instant};
+use itertools::Itertools;
 
 use super::Window;
+use crate::app::App;
+use crate::commands::Command;
+use crate::stats::Penalty;
+
+/// Maximum number of pips to show for queued twists.
+const MAX_QUEUE_PIPS: usize = 10;
 
 // TODO: start/stop timer with keyboard input (Command::ToggleTimer maybe)
 // TODO: start timer on mouse-release instead of mouse-down
@@ -12,8 +19,22 @@ pub(crate) const TIMER: Window = Window {
         if ui
             .add(egui::Button::new(
                 egui::RichText::new(match app.timer_start_end {
-                    (None, None) => "Start".into(),
+                    (None, None) => {
+                        if let Some(remaining) = app.inspection_remaining() {
+                            inspection_str(remaining)
+                        } else if let Some(remaining) = app.countdown_remaining() {
+                            countdown_str(remaining)
+                        } else {
+                            "Start".into()
+                        }
+                    }
                     (None, Some(_)) => panic!("invalid timer state"),
+                    // Keep timing without showing the time.
+                    (Some(_), None) if !app.prefs.info.show_time => "⏱ Timing".into(),
+                    (Some(_), Some(_)) if !app.prefs.info.show_time => "⏱ Done".into(),
+                    (Some(start), None) if app.timer_paused_at.is_some() => {
+                        format!("⏸ {}", duration_to_str(app.timer_now() - start))
+                    }
                     (Some(start), None) => duration_to_str(start.elapsed()),
                     (Some(start), Some(end)) => duration_to_str(end - start),
                 })
@@ -21,18 +42,157 @@ pub(crate) const TIMER: Window = Window {
             ))
             .clicked()
         {
-            app.timer_start_end = match app.timer_start_end {
-                (None, None) => (Some(Instant::now()), None),
-                (None, Some(_)) => panic!("invalid timer state"),
-                (Some(start), None) => (Some(start), Some(Instant::now())),
-                (Some(_), Some(_)) => (Some(Instant::now()), None),
-            };
+            if app.inspection_start.is_some() {
+                app.end_inspection();
+            } else if app.countdown_start.is_some() {
+                // Cancel the countdown.
+                app.countdown_start = None;
+            } else {
+                match app.timer_start_end {
+                    (None, Some(_)) => panic!("invalid timer state"),
+                    (Some(start), None) => {
+                        app.timer_start_end = (Some(start), Some(Instant::now()))
+                    }
+                    (None, None) | (Some(_), Some(_)) => app.start_countdown(),
+                }
+            }
+            if app.timer_start_end.1.is_none() {
+                app.new_pbs.clear();
+            }
+        }
+
+        build_twist_queue(ui, app);
+        if app.prefs.info.solved_progress {
+            build_solved_progress(ui, app);
+        }
+
+        if !app.new_pbs.is_empty() {
+            ui.colored_label(
+                egui::Color32::GOLD,
+                format!("New PB! ({})", app.new_pbs.iter().join(", ")),
+            );
+        }
+        if app.prefs.info.streak_goal.enabled {
+            build_goal_streak(ui, app);
         }
+        build_scramble_attempts(ui, app);
     },
     ..Window::DEFAULT
 };
 
-fn duration_to_str(duration: Duration) -> String {
+/// Returns the text for the timer during inspection, which counts down the
+/// inspection time left and then shows the penalty for going over time.
+pub(crate) fn inspection_str(remaining: f32) -> String {
+    if remaining > 0.0 {
+        format!("Inspection: {}", remaining.ceil())
+    } else {
+        format!(
+            "Inspection: {}",
+            Penalty::from_inspection_time_left(remaining)
+        )
+    }
+}
+
+/// Returns the text for the timer during the countdown before it starts.
+fn countdown_str(remaining: f32) -> String {
+    format!("Starting in {}", remaining.ceil().max(1.0))
+}
+
+/// Shows the number of solves in a row under the target time of the streak
+/// goal, highlighted once the goal is reached.
+fn build_goal_streak(ui: &mut egui::Ui, app: &App) {
+    let goal = app.prefs.info.streak_goal;
+    let count = match app.goal_streak.puzzle {
+        Some(ty) if ty == app.puzzle.ty() => app.goal_streak.count,
+        _ => 0,
+    };
+    let mut text = egui::RichText::new(format!("🔥 Streak: {count}/{}", goal.solve_count))
+        .size(16.0)
+        .strong();
+    if goal.solve_count > 0 && count >= goal.solve_count {
+        text = text.color(egui::Color32::GOLD);
+    }
+    ui.label(text).on_hover_text(format!(
+        "Solves in a row under {}",
+        duration_to_str(goal.target_duration()),
+    ));
+}
+
+/// Shows the results of every attempt on the current scramble in this
+/// session, once it has been retried.
+fn build_scramble_attempts(ui: &mut egui::Ui, app: &mut App) {
+    let scramble = app.puzzle.scramble_string();
+    if scramble.is_empty() {
+        return;
+    }
+    let attempts = app.session.attempts_on(app.puzzle.ty(), &scramble);
+    if attempts.is_empty() {
+        return;
+    }
+
+    let times = attempts.iter().filter_map(|r| r.millis()).collect_vec();
+    ui.collapsing(
+        format!("Attempts on this scramble: {}", attempts.len()),
+        |ui| {
+            if let (Some(best), Some(worst)) = (times.iter().min(), times.iter().max()) {
+                let mean = times.iter().sum::<u64>() / times.len() as u64;
+                egui::Grid::new(unique_id!()).show(ui, |ui| {
+                    for (label, millis) in [("Best", best), ("Mean", &mean), ("Worst", worst)] {
+                        ui.label(label);
+                        ui.label(duration_to_str(Duration::from_millis(*millis)));
+                        ui.end_row();
+                    }
+                });
+            }
+            let dnf_count = attempts.len() - times.len();
+            if dnf_count > 0 {
+                ui.label(format!("DNF: {dnf_count}"));
+            }
+            ui.label(
+                attempts
+                    .iter()
+                    .map(|result| match result.millis() {
+                        Some(millis) => duration_to_str(Duration::from_millis(millis)),
+                        None => "DNF".to_owned(),
+                    })
+                    .join(", "),
+            );
+            if ui.button("🔁 Retry scramble").clicked() {
+                app.event(Command::RetryScramble);
+            }
+        },
+    );
+}
+
+/// Shows a bar with the fraction of pieces that are in place, which is only a
+/// rough estimate of how solved the puzzle is.
+fn build_solved_progress(ui: &mut egui::Ui, app: &App) {
+    ui.add(egui::ProgressBar::new(app.puzzle.solved_fraction()).show_percentage())
+        .on_hover_text(
+            "Fraction of pieces that are in place. This is only \
+             a rough estimate for motivation, not a measure of \
+             how many moves are left.",
+        );
+}
+
+/// Shows a pip for each twist waiting for the current twist animation to
+/// finish, so that it's clear when input is outrunning the animation.
+fn build_twist_queue(ui: &mut egui::Ui, app: &App) {
+    let queued = app.puzzle.queued_twist_count();
+    if queued == 0 {
+        return;
+    }
+    let mut pips = "●".repeat(queued.min(MAX_QUEUE_PIPS));
+    if queued > MAX_QUEUE_PIPS {
+        pips += &format!(" +{}", queued - MAX_QUEUE_PIPS);
+    }
+    ui.weak(pips).on_hover_text(match queued {
+        1 => "1 twist queued".to_owned(),
+        n => format!("{n} twists queued"),
+    });
+}
+
+pub(crate) fn duration_to_str(duration: Duration) -> String {
     let milliseconds = duration.as_millis();
     let seconds = milliseconds / 1000;
     let minutes = seconds / 60;
@@ -0,0 +1,119 @@
+use itertools::Itertools;
+
+use super::keybinds_reference::{keybinds_label, matching_keybinds};
+use super::Window;
+use crate::app::App;
+use crate::commands::{Command, PuzzleCommand};
+use crate::gui::components::PrefsUi;
+use crate::gui::ext::ResponseExt;
+use crate::preferences::{Key, DEFAULT_PREFS};
+
+pub(crate) const TOUCH_CONTROLS: Window = Window {
+    name: "Touch controls",
+    build,
+    cleanup,
+    ..Window::DEFAULT
+};
+
+fn cleanup(_ctx: &egui::Context, app: &mut App) {
+    let visible = &mut app.prefs.info.touch_controls.visible;
+    if *visible {
+        *visible = false;
+        app.prefs.needs_save = true;
+    }
+}
+
+fn build(ui: &mut egui::Ui, app: &mut App) {
+    let visible = &mut app.prefs.info.touch_controls.visible;
+    if !*visible {
+        *visible = true;
+        app.prefs.needs_save = true;
+    }
+
+    let size = egui::Vec2::splat(app.prefs.info.touch_controls.button_size);
+
+    ui.horizontal_wrapped(|ui| {
+        ui.spacing_mut().interact_size = size;
+        crate::gui::status_bar::modifier_toggles(ui, app, true);
+        for command in [Command::Undo, Command::Redo] {
+            let button = egui::Button::new(command.short_description());
+            if ui.add_sized(size, button).clicked() {
+                app.event(command);
+            }
+        }
+    });
+
+    ui.separator();
+
+    // Show a button for each key bound in the active keybind sets, so the
+    // buttons are laid out differently for each puzzle.
+    let keys = app.prefs.puzzle_keybinds[app.puzzle.ty()]
+        .get_active_keybinds()
+        .filter(|bind| bind.command != PuzzleCommand::None)
+        .filter_map(|bind| bind.key.key())
+        .filter(|key| key.modifier_bit().is_empty())
+        .unique()
+        .collect_vec();
+    ui.horizontal_wrapped(|ui| {
+        for key in keys {
+            key_button(ui, app, key, size);
+        }
+    });
+
+    ui.collapsing("Settings", |ui| {
+        let mut changed = false;
+        let mut prefs_ui = PrefsUi {
+            ui,
+            current: &mut app.prefs.info.touch_controls,
+            defaults: &DEFAULT_PREFS.info.touch_controls,
+            changed: &mut changed,
+        };
+
+        prefs_ui.num("Button size", access!(.button_size), |dv| {
+            dv.fixed_decimals(0).clamp_range(24.0..=128.0).speed(0.5)
+        });
+        prefs_ui
+            .checkbox("Show on first touch", access!(.show_on_touch))
+            .on_hover_explanation(
+                "",
+                "Opens the touch controls the first time the screen \
+                 is touched. This turns itself off once it happens.",
+            );
+
+        app.prefs.needs_save |= changed;
+    });
+}
+
+/// Shows a button that does the same thing as pressing a key, labeled with
+/// what the key does given the current grip and modifiers.
+fn key_button(ui: &mut egui::Ui, app: &mut App, key: Key, size: egui::Vec2) {
+    let (sc, vk) = match key {
+        Key::Sc(sc) => (Some(sc), key_names::key_to_winit_vkey(sc)),
+        Key::Vk(vk) => (None, Some(vk)),
+    };
+
+    let (matching_puzzle_keybinds, matching_global_keybinds) = matching_keybinds(app, sc, vk);
+    let label = keybinds_label(app, &matching_puzzle_keybinds, &matching_global_keybinds);
+    // Grips and peeking only last as long as their key is held, so their
+    // buttons stay held until they are clicked again.
+    let is_grip = matching_puzzle_keybinds
+        .iter()
+        .any(|bind| matches!(bind.command, PuzzleCommand::Grip { .. }))
+        || matching_global_keybinds
+            .iter()
+            .any(|bind| bind.command == Command::PeekLastLayer);
+    let is_held = app.toggled_keys().contains(&key);
+
+    let r = ui
+        .add_enabled_ui(is_held || !label.is_empty(), |ui| {
+            ui.add_sized(size, egui::SelectableLabel::new(is_held, label))
+        })
+        .inner;
+    if r.clicked() {
+        if is_grip || is_held {
+            app.toggle_key(sc, vk);
+        } else {
+            app.tap_key(sc, vk);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use regex::Regex;
+use std::borrow::Cow;
+
+use super::{Window, WELCOME_WINDOW_WIDTH};
+use crate::app::App;
+use crate::gui::util::{set_widget_spacing_to_space_width, subtract_space};
+
+const CHANGELOG: &str = include_str!("../../../CHANGELOG.md");
+
+pub(crate) const WHATS_NEW: Window = Window {
+    name: "What's new",
+    fixed_width: Some(WELCOME_WINDOW_WIDTH),
+    vscroll: true,
+    build,
+    ..Window::DEFAULT
+};
+
+fn build(ui: &mut egui::Ui, app: &mut App) {
+    let current_version = env!("CARGO_PKG_VERSION");
+    match &app.previous_version {
+        Some(v) => ui.label(format!(
+            "{} has been updated from v{v} to v{current_version}.",
+            crate::TITLE,
+        )),
+        None => ui.label(format!("{} v{current_version}", crate::TITLE)),
+    };
+
+    // List every release since the previous version, through the current
+    // version. Unreleased changes are listed first in development builds.
+    for (i, section) in sections().enumerate() {
+        if app.previous_version.as_deref() == Some(section.version) {
+            break;
+        }
+
+        ui.separator();
+        ui.strong(section.title);
+        for (heading, items) in &section.groups {
+            egui::CollapsingHeader::new(*heading)
+                .id_source((section.title, heading))
+                .default_open(i == 0)
+                .show(ui, |ui| {
+                    for item in items {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.label("•");
+                            ui.label(clean_markdown(item));
+                        });
+                    }
+                });
+        }
+
+        if section.version == current_version {
+            break;
+        }
+    }
+
+    ui.separator();
+    ui.horizontal_wrapped(|ui| {
+        set_widget_spacing_to_space_width(ui);
+        ui.label("You can reopen this window from");
+        ui.strong("Help ➡ What's new");
+        subtract_space(ui);
+        ui.label(".");
+    });
+}
+
+/// Section of the changelog for one release.
+struct ChangelogSection<'a> {
+    /// Heading of the section, such as `[1.0.9] - 2024-11-20`.
+    title: &'a str,
+    /// Version number, such as `1.0.9` or `Unreleased`.
+    version: &'a str,
+    /// Kinds of changes, such as `Added`, each with a list of changes.
+    groups: Vec<(&'a str, Vec<&'a str>)>,
+}
+
+/// Returns the sections of the changelog, newest first.
+fn sections() -> impl Iterator<Item = ChangelogSection<'static>> {
+    CHANGELOG.split("\n## ").skip(1).map(|section| {
+        let (title, body) = section.split_once('\n').unwrap_or((section, ""));
+        let version = title
+            .trim_start_matches('[')
+            .split(']')
+            .next()
+            .unwrap_or(title);
+
+        let mut groups: Vec<(&str, Vec<&str>)> = vec![];
+        for line in body.lines() {
+            if let Some(heading) = line.strip_prefix("### ") {
+                groups.push((heading.trim(), vec![]));
+            } else if let Some(item) = line.strip_prefix("- ") {
+                match groups.last_mut() {
+                    Some((_, items)) => items.push(item),
+                    None => groups.push(("Changes", vec![item])),
+                }
+            }
+        }
+
+        ChangelogSection {
+            title,
+            version,
+            groups,
+        }
+    })
+}
+
+/// Strips links and HTML tags from a line of Markdown.
+fn clean_markdown(s: &str) -> Cow<'_, str> {
+    lazy_static! {
+        static ref LINK_REGEX: Regex = Regex::new(r"\[([^\]]*)\](\([^)]*\))?").unwrap();
+        static ref TAG_REGEX: Regex = Regex::new(r"</?\w+>").unwrap();
+    }
+
+    match LINK_REGEX.replace_all(s, "$1") {
+        Cow::Borrowed(s) => TAG_REGEX.replace_all(s, ""),
+        Cow::Owned(s) => Cow::Owned(TAG_REGEX.replace_all(&s, "").into_owned()),
+    }
+}
@@ -1,12 +1,13 @@
 #![allow(missing_docs)]
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use cgmath::{Matrix4, SquareMatrix};
 use itertools::Itertools;
 use std::error::Error;
 use std::fmt;
 use std::str::FromStr;
 
+use super::SolveImporter;
 use crate::puzzle::*;
 
 const MAGIC_STRING: &str = "MagicCube4D";
@@ -18,6 +19,27 @@ pub fn is_mc4d_log_file(s: &str) -> bool {
     s.starts_with(MAGIC_STRING)
 }
 
+/// MagicCube4D log file format.
+pub struct Mc4dImporter;
+impl SolveImporter for Mc4dImporter {
+    fn name(&self) -> &'static str {
+        "MagicCube4D Log Files"
+    }
+    fn extensions(&self) -> &'static [&'static str] {
+        &["log"]
+    }
+    fn detect(&self, contents: &str) -> bool {
+        is_mc4d_log_file(contents)
+    }
+    fn import(&self, contents: &str) -> Result<(PuzzleController, Vec<String>)> {
+        let puzzle = Mc4dLogFile::from_str(contents)?
+            .to_puzzle()
+            .map_err(|e| anyhow!(e))?;
+        let warnings = vec![];
+        Ok((puzzle, warnings))
+    }
+}
+
 #[derive(Debug)]
 pub struct Mc4dLogFile {
     edge_length: u8,
@@ -158,11 +180,9 @@ impl Mc4dLogFile {
                 scramble_state: puzzle.scramble_state(),
                 view_matrix: Matrix4::identity(),
                 scramble_twists: puzzle.scramble().to_vec(),
-                solve_twists: puzzle
-                    .undo_buffer()
-                    .iter()
-                    .filter_map(|entry| entry.twist())
-                    .collect(),
+                // MC4D has no notion of an inverse scramble, so save the
+                // combined solution.
+                solve_twists: puzzle.combined_solution(),
             }),
             _ => Err(LogFileError::UnsupportedPuzzle(puzzle.name().to_string())),
         }
@@ -7,25 +7,69 @@ use std::error::Error;
 use std::fmt;
 #[cfg(not(target_arch = "wasm32"))]
 use std::path::Path;
-use std::str::FromStr;
 use strum::IntoEnumIterator;
 
 mod mc4d_compat;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod replay;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod session;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod solve_log;
+mod text;
 
 use crate::puzzle::*;
 
+/// File format that puzzle states and solves can be loaded from.
+///
+/// To support another format, implement this trait and add it to
+/// [`IMPORTERS`].
+pub(crate) trait SolveImporter: Sync {
+    /// Name of the format, for the file dialog.
+    fn name(&self) -> &'static str;
+    /// File extensions used for the format, without the leading `.`.
+    fn extensions(&self) -> &'static [&'static str];
+    /// Returns whether a file looks like it is in this format, for files with
+    /// an extension that no format claims. This only checks the start of the
+    /// file and does not parse it.
+    fn detect(&self, contents: &str) -> bool;
+    /// Loads the puzzle state, along with any warnings.
+    fn import(&self, contents: &str) -> Result<(PuzzleController, Vec<String>)>;
+}
+
+/// Formats that can be loaded, in the order they are tried when detecting the
+/// format of a file.
+pub(crate) const IMPORTERS: &[&dyn SolveImporter] = &[
+    &mc4d_compat::Mc4dImporter,
+    &HscImporter,
+    &text::TextImporter,
+];
+
 /// Loads a log file string and returns the puzzle state, along with any
 /// warnings.
 pub fn deserialize(log_file_contents: &str) -> anyhow::Result<(PuzzleController, Vec<String>)> {
-    if mc4d_compat::is_mc4d_log_file(log_file_contents) {
-        let puzzle = mc4d_compat::Mc4dLogFile::from_str(log_file_contents)?
-            .to_puzzle()
-            .map_err(|e| anyhow!(e))?;
-        let warnings = vec![];
-        Ok((puzzle, warnings))
-    } else {
-        serde_yaml::from_str::<LogFile>(log_file_contents)?.to_puzzle()
-    }
+    import(log_file_contents, None)
+}
+
+/// Loads a file in any supported format. The format is chosen by the file
+/// extension if a format claims it, and otherwise detected from the contents.
+fn import(contents: &str, extension: Option<&str>) -> Result<(PuzzleController, Vec<String>)> {
+    let claims_extension = |importer: &&&dyn SolveImporter| {
+        extension.map_or(false, |ext| {
+            importer
+                .extensions()
+                .iter()
+                .any(|e| ext.eq_ignore_ascii_case(e))
+        })
+    };
+    let importer = IMPORTERS
+        .iter()
+        .find(claims_extension)
+        .or_else(|| IMPORTERS.iter().find(|importer| importer.detect(contents)))
+        .copied()
+        // Report errors as if it were our own format.
+        .unwrap_or(&HscImporter);
+    importer.import(contents)
 }
 
 /// Saves the puzzle state to a log file string.
@@ -42,7 +86,8 @@ pub(crate) fn serialize(
 /// Loads a log file and returns the puzzle state, along with any warnings.
 #[cfg(not(target_arch = "wasm32"))]
 pub fn load_file(path: &Path) -> anyhow::Result<(PuzzleController, Vec<String>)> {
-    deserialize(&std::fs::read_to_string(path)?)
+    let extension = path.extension().and_then(|ext| ext.to_str());
+    import(&std::fs::read_to_string(path)?, extension)
 }
 
 /// Saves the puzzle state to a log file.
@@ -77,6 +122,28 @@ impl LogFileFormat {
     }
 }
 
+/// Hyperspeedcube's own log file format.
+struct HscImporter;
+impl SolveImporter for HscImporter {
+    fn name(&self) -> &'static str {
+        "Hyperspeedcube Log Files"
+    }
+    fn extensions(&self) -> &'static [&'static str] {
+        &["hsc"]
+    }
+    fn detect(&self, contents: &str) -> bool {
+        // The version always comes first, after any comments.
+        contents
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+            .map_or(false, |line| line.starts_with("version:"))
+    }
+    fn import(&self, contents: &str) -> Result<(PuzzleController, Vec<String>)> {
+        serde_yaml::from_str::<LogFile>(contents)?.to_puzzle()
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 struct LogFile {
     version: usize,
@@ -102,6 +169,8 @@ struct LogFile {
     scramble: String,
     #[serde(default, skip_serializing)] // manually serialized
     twists: String,
+    #[serde(default, skip_serializing)] // manually serialized
+    inverse_twists: String,
 }
 impl fmt::Display for LogFile {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -123,6 +192,12 @@ impl fmt::Display for LogFile {
                 writeln!(f, "  {line}")?;
             }
         }
+        if !self.inverse_twists.is_empty() {
+            writeln!(f, "inverse_twists: >")?;
+            for line in self.inverse_twists.lines() {
+                writeln!(f, "  {line}")?;
+            }
+        }
         Ok(())
     }
 }
@@ -142,16 +217,22 @@ impl LogFile {
                 .then(|| puzzle.visible_pieces().to_bitvec()),
             scramble_length: puzzle.scramble().len(),
             twist_count: TwistMetric::iter()
-                .map(|metric| (metric, puzzle.twist_count(metric)))
+                .map(|metric| (metric, puzzle.twist_count(metric, false)))
                 .collect(),
             scramble: crate::util::wrap_words(
                 puzzle.scramble().iter().map(|twist| twist.to_string()),
             ),
             twists: crate::util::wrap_words(
                 puzzle
-                    .undo_buffer()
-                    .iter()
-                    .map(|&entry| entry.to_string(notation)),
+                    .twists_on_side(ScrambleSide::Normal)
+                    .into_iter()
+                    .map(|twist| notation.twist_to_string(twist)),
+            ),
+            inverse_twists: crate::util::wrap_words(
+                puzzle
+                    .twists_on_side(ScrambleSide::Inverse)
+                    .into_iter()
+                    .map(|twist| notation.twist_to_string(twist)),
             ),
         }
     }
@@ -178,10 +259,13 @@ impl LogFile {
         (ret_twists, ret_errors)
     }
 
-    fn twists(&self, puzzle_type: &dyn PuzzleType) -> (Vec<Twist>, Vec<TwistParseError<'_>>) {
+    fn twists<'a>(
+        twists: &'a str,
+        puzzle_type: &dyn PuzzleType,
+    ) -> (Vec<Twist>, Vec<TwistParseError<'a>>) {
         let mut ret_twists = vec![];
         let mut ret_errors = vec![];
-        for twist_str in self.twists.split_whitespace() {
+        for twist_str in twists.split_whitespace() {
             match puzzle_type.notation_scheme().parse_twist(twist_str) {
                 Ok(twist) => ret_twists.push(twist),
                 Err(error_msg) => ret_errors.push(TwistParseError {
@@ -226,13 +310,21 @@ impl LogFile {
         }
         ret.add_scramble_marker(scramble_state);
 
-        let (twists, parse_errors) = self.twists(&puzzle_type);
+        let (twists, parse_errors) = Self::twists(&self.twists, &puzzle_type);
         warnings.extend(parse_errors.iter().map(|e| e.to_string()));
         for twist in twists {
             if let Err(e) = ret.twist_no_collapse(twist) {
                 warnings.push(e.to_string());
             }
         }
+
+        let (inverse_twists, parse_errors) = Self::twists(&self.inverse_twists, &puzzle_type);
+        warnings.extend(parse_errors.iter().map(|e| e.to_string()));
+        if !inverse_twists.is_empty() {
+            if let Err(e) = ret.set_inverse_twists(inverse_twists) {
+                warnings.push(e.to_string());
+            }
+        }
         ret.skip_twist_animations();
         ret.mark_saved();
 
@@ -0,0 +1,164 @@
+//! Replay files, which store a single timed solve along with the colors it was
+//! done with so that it can be watched later.
+
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::preferences::FaceColor;
+use crate::puzzle::{PuzzleTypeEnum, Twist};
+use crate::stats::Penalty;
+
+/// File extension used for replay files, without the leading `.`.
+pub const EXTENSION: &str = "hscreplay";
+
+/// Timed solve that can be played back.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    pub puzzle: PuzzleTypeEnum,
+    pub scramble: Vec<Twist>,
+    /// Twists of the solution, each with the time since the start of the
+    /// solve.
+    pub twists: Vec<(Duration, Twist)>,
+    /// Final time of the solve, not including the penalty.
+    pub duration: Duration,
+    pub penalty: Penalty,
+    /// Face colors used during the solve.
+    pub colors: BTreeMap<String, FaceColor>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct ReplayFile {
+    version: usize,
+    puzzle: PuzzleTypeEnum,
+    #[serde(default)]
+    millis: u64,
+    #[serde(default)]
+    penalty: Penalty,
+    #[serde(default)]
+    colors: BTreeMap<String, FaceColor>,
+    #[serde(default)]
+    scramble: String,
+    #[serde(default)]
+    twists: Vec<ReplayTwist>,
+}
+impl ReplayFile {
+    const COMMENT_STRING: &'static str = "# Hyperspeedcube replay";
+    const VERSION: usize = 1;
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct ReplayTwist {
+    millis: u64,
+    twist: String,
+}
+
+fn serialize(replay: &Replay) -> Result<String> {
+    let file = ReplayFile {
+        version: ReplayFile::VERSION,
+        puzzle: replay.puzzle,
+        millis: replay.duration.as_millis() as u64,
+        penalty: replay.penalty,
+        colors: replay.colors.clone(),
+        scramble: replay.scramble.iter().join(" "),
+        twists: replay
+            .twists
+            .iter()
+            .map(|(time, twist)| ReplayTwist {
+                millis: time.as_millis() as u64,
+                twist: twist.to_string(),
+            })
+            .collect(),
+    };
+    Ok(format!(
+        "{}\n{}",
+        ReplayFile::COMMENT_STRING,
+        serde_yaml::to_string(&file)?,
+    ))
+}
+
+fn deserialize(contents: &str) -> Result<(Replay, Vec<String>)> {
+    let file: ReplayFile = serde_yaml::from_str(contents)?;
+    file.puzzle.validate().map_err(|e| anyhow!(e))?;
+
+    let mut warnings = vec![];
+    if file.version != ReplayFile::VERSION {
+        warnings.push(format!(
+            "This replay was saved using a \
+             different version of Hyperspeedcube \
+             (replay file format v{:?}; expected v{:?})",
+            file.version,
+            ReplayFile::VERSION,
+        ));
+    }
+
+    let parse_twist = |s: &str| s.parse().map_err(|()| anyhow!("invalid twist {s:?}"));
+    let scramble = file
+        .scramble
+        .split_whitespace()
+        .map(parse_twist)
+        .collect::<Result<_>>()?;
+    let twists = file
+        .twists
+        .iter()
+        .map(|t| Ok((Duration::from_millis(t.millis), parse_twist(&t.twist)?)))
+        .collect::<Result<_>>()?;
+
+    Ok((
+        Replay {
+            puzzle: file.puzzle,
+            scramble,
+            twists,
+            duration: Duration::from_millis(file.millis),
+            penalty: file.penalty,
+            colors: file.colors,
+        },
+        warnings,
+    ))
+}
+
+/// Loads a replay file and returns the replay, along with any warnings.
+pub fn load_file(path: &Path) -> Result<(Replay, Vec<String>)> {
+    deserialize(&std::fs::read_to_string(path)?)
+}
+
+/// Saves a replay to a file.
+pub fn save_file(path: &Path, replay: &Replay) -> Result<()> {
+    std::fs::write(path, serialize(replay)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay_file_roundtrip() {
+        let replay = Replay {
+            puzzle: PuzzleTypeEnum::Rubiks3D { layer_count: 3 },
+            scramble: vec!["0,1,1".parse().unwrap(), "2,0,3".parse().unwrap()],
+            twists: vec![
+                (Duration::from_millis(0), "2,1,3".parse().unwrap()),
+                (Duration::from_millis(1_234), "0,0,1".parse().unwrap()),
+            ],
+            duration: Duration::from_millis(1_500),
+            penalty: Penalty::PlusTwo,
+            colors: [("R".to_string(), FaceColor(egui::Color32::RED))]
+                .into_iter()
+                .collect(),
+        };
+
+        let (loaded, warnings) = deserialize(&serialize(&replay).unwrap()).unwrap();
+        assert_eq!(loaded, replay);
+        assert!(warnings.is_empty());
+
+        let contents = serialize(&replay)
+            .unwrap()
+            .replace("version: 1", "version: 2");
+        let (_, warnings) = deserialize(&contents).unwrap();
+        assert_eq!(warnings.len(), 1);
+    }
+}
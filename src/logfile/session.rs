@@ -0,0 +1,104 @@
+//! Session files, which store every solve in a practice session so that it can
+//! be reopened later.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::stats::{Session, Solve};
+
+/// File extension used for session files, without the leading `.`.
+pub const EXTENSION: &str = "hscsession";
+
+#[derive(Serialize, Deserialize, Debug)]
+struct SessionFile {
+    version: usize,
+    #[serde(default)]
+    solves: Vec<Solve>,
+}
+impl SessionFile {
+    const COMMENT_STRING: &'static str = "# Hyperspeedcube session";
+    const VERSION: usize = 1;
+}
+
+fn serialize(session: &Session) -> Result<String> {
+    let file = SessionFile {
+        version: SessionFile::VERSION,
+        solves: session.solves.clone(),
+    };
+    Ok(format!(
+        "{}\n{}",
+        SessionFile::COMMENT_STRING,
+        serde_yaml::to_string(&file)?,
+    ))
+}
+
+fn deserialize(contents: &str) -> Result<(Session, Vec<String>)> {
+    let file: SessionFile = serde_yaml::from_str(contents)?;
+
+    let mut warnings = vec![];
+    if file.version != SessionFile::VERSION {
+        warnings.push(format!(
+            "This session file was saved using a \
+             different version of Hyperspeedcube \
+             (session file format v{:?}; expected v{:?})",
+            file.version,
+            SessionFile::VERSION,
+        ));
+    }
+    for solve in &file.solves {
+        solve.puzzle.validate().map_err(|e| anyhow!(e))?;
+    }
+
+    Ok((
+        Session {
+            solves: file.solves,
+        },
+        warnings,
+    ))
+}
+
+/// Loads a session file and returns the session, along with any warnings.
+pub fn load_file(path: &Path) -> Result<(Session, Vec<String>)> {
+    deserialize(&std::fs::read_to_string(path)?)
+}
+
+/// Saves a session to a file.
+pub fn save_file(path: &Path, session: &Session) -> Result<()> {
+    std::fs::write(path, serialize(session)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzle::{PuzzleTypeEnum, TwistMetric};
+    use crate::stats::tests::solve;
+    use crate::stats::Penalty;
+
+    #[test]
+    fn test_session_file_roundtrip() {
+        let session = Session {
+            solves: vec![
+                Solve {
+                    reaction_millis: Some(450),
+                    down_color: Some("U".to_string()),
+                    twist_count: [(TwistMetric::Stm, 2)].into_iter().collect(),
+                    scramble: "R U".to_string(),
+                    ..solve(12_345, Penalty::PlusTwo)
+                },
+                Solve {
+                    puzzle: PuzzleTypeEnum::Rubiks4D { layer_count: 3 },
+                    ..solve(600_000, Penalty::None)
+                },
+            ],
+        };
+
+        let (loaded, warnings) = deserialize(&serialize(&session).unwrap()).unwrap();
+        assert_eq!(loaded.solves, session.solves);
+        assert!(warnings.is_empty());
+
+        let (_, warnings) = deserialize("version: 2\n").unwrap();
+        assert_eq!(warnings.len(), 1);
+    }
+}
@@ -0,0 +1,157 @@
+//! Solve log, which has every completed solve appended to it automatically so
+//! that no solves are lost even if the session is never saved.
+//!
+//! The log is a YAML list with one entry per solve. Once it would grow past
+//! its maximum size, it is renamed with a number and a new log is started.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::stats::Solve;
+
+/// File name of the current solve log, without the extension.
+const FILE_NAME: &str = "solve_log";
+/// File extension used for solve logs, without the leading `.`.
+pub const EXTENSION: &str = "yaml";
+const COMMENT_STRING: &str = "# Hyperspeedcube solve log";
+
+/// When to start a new solve log.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Rotation {
+    /// Maximum size of a solve log, in bytes. 0 means there is no maximum.
+    pub max_bytes: u64,
+    /// Number of old solve logs to keep. The oldest one is deleted when there
+    /// are more than this.
+    pub old_file_count: usize,
+}
+
+#[derive(Serialize, Debug)]
+struct SolveLogEntry<'a> {
+    /// Local date and time when the solve was completed.
+    date: String,
+    #[serde(flatten)]
+    solve: &'a Solve,
+    /// Solution in the puzzle's standard notation.
+    #[serde(skip_serializing_if = "str::is_empty")]
+    solution: &'a str,
+}
+
+/// Returns the path of the current solve log in `dir`, or of an old one if
+/// `index` is nonzero.
+fn file_path(dir: &Path, index: usize) -> PathBuf {
+    match index {
+        0 => dir.join(format!("{FILE_NAME}.{EXTENSION}")),
+        i => dir.join(format!("{FILE_NAME}.{i}.{EXTENSION}")),
+    }
+}
+
+fn serialize_entry(solve: &Solve, solution: &str) -> Result<String> {
+    let now = time::OffsetDateTime::now_local().unwrap_or_else(|_| time::OffsetDateTime::now_utc());
+    let entry = SolveLogEntry {
+        date: format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            now.year(),
+            now.month() as u8,
+            now.day(),
+            now.hour(),
+            now.minute(),
+            now.second(),
+        ),
+        solve,
+        solution,
+    };
+    let s = serde_yaml::to_string(&[entry])?;
+    Ok(format!(
+        "{}\n",
+        s.strip_prefix("---\n").unwrap_or(&s).trim_end()
+    ))
+}
+
+/// Renames each solve log in `dir` to the next number, deleting the oldest
+/// one if there are too many.
+fn rotate(dir: &Path, old_file_count: usize) -> Result<()> {
+    let oldest = file_path(dir, old_file_count);
+    if oldest.exists() {
+        std::fs::remove_file(oldest)?;
+    }
+    for i in (0..old_file_count).rev() {
+        let path = file_path(dir, i);
+        if path.exists() {
+            std::fs::rename(path, file_path(dir, i + 1))?;
+        }
+    }
+    Ok(())
+}
+
+/// Appends a completed solve and its solution to the solve log in `dir`,
+/// starting a new log first if it would grow too large.
+pub fn append(dir: &Path, solve: &Solve, solution: &str, rotation: Rotation) -> Result<()> {
+    let entry = serialize_entry(solve, solution)?;
+
+    std::fs::create_dir_all(dir)?;
+    let path = file_path(dir, 0);
+    let len = std::fs::metadata(&path).map_or(0, |m| m.len());
+    if len > 0 && rotation.max_bytes > 0 && len + entry.len() as u64 > rotation.max_bytes {
+        rotate(dir, rotation.old_file_count)?;
+    }
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?;
+    if file.metadata()?.len() == 0 {
+        writeln!(file, "{COMMENT_STRING}")?;
+    }
+    file.write_all(entry.as_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::tests::solve;
+    use crate::stats::Penalty;
+
+    #[test]
+    fn test_solve_log_append_and_rotate() {
+        let dir = std::env::temp_dir().join(format!("hsc_solve_log_test_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let solve = Solve {
+            scramble: "R U".to_string(),
+            ..solve(12_345, Penalty::PlusTwo)
+        };
+        let rotation = Rotation {
+            max_bytes: 0,
+            old_file_count: 1,
+        };
+        append(&dir, &solve, "U' R'", rotation).unwrap();
+        append(&dir, &solve, "U' R'", rotation).unwrap();
+
+        let contents = std::fs::read_to_string(file_path(&dir, 0)).unwrap();
+        assert!(contents.starts_with(COMMENT_STRING));
+        let entries: Vec<serde_yaml::Value> = serde_yaml::from_str(&contents).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["millis"].as_u64(), Some(12_345));
+        assert_eq!(entries[1]["solution"].as_str(), Some("U' R'"));
+
+        // Once the log is full, it is moved out of the way, replacing the
+        // previous old log.
+        let rotation = Rotation {
+            max_bytes: contents.len() as u64,
+            ..rotation
+        };
+        for _ in 0..3 {
+            append(&dir, &solve, "U' R'", rotation).unwrap();
+        }
+        assert!(file_path(&dir, 1).exists());
+        assert!(!file_path(&dir, 2).exists());
+        let contents = std::fs::read_to_string(file_path(&dir, 0)).unwrap();
+        let entries: Vec<serde_yaml::Value> = serde_yaml::from_str(&contents).unwrap();
+        assert_eq!(entries.len(), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,151 @@
+//! Plain text reconstructions in standard notation, such as those shared from
+//! timers and reconstruction sites.
+//!
+//! ```text
+//! Puzzle: 3x3x3
+//! Scramble: R U R' U'
+//! Solution:
+//! U R U' R' // comment
+//! ```
+//!
+//! Keys are case-insensitive and lines without a key continue the previous
+//! one. The puzzle defaults to 3x3x3 if it is not given.
+
+use anyhow::{anyhow, Result};
+
+use super::{LogFile, SolveImporter};
+use crate::puzzle::*;
+
+const COMMENT_PREFIX: &str = "//";
+
+/// Plain text reconstruction format.
+pub struct TextImporter;
+impl SolveImporter for TextImporter {
+    fn name(&self) -> &'static str {
+        "Text Reconstructions"
+    }
+    fn extensions(&self) -> &'static [&'static str] {
+        &["txt"]
+    }
+    fn detect(&self, contents: &str) -> bool {
+        lines(contents).any(|(key, _)| matches!(key.as_deref(), Some("scramble" | "solution")))
+    }
+    fn import(&self, contents: &str) -> Result<(PuzzleController, Vec<String>)> {
+        let mut warnings = vec![];
+
+        let mut puzzle_type = PuzzleTypeEnum::Rubiks3D {
+            layer_count: rubiks_3d::DEFAULT_LAYER_COUNT,
+        };
+        let mut scramble = String::new();
+        let mut solution = String::new();
+        let mut current_key = None;
+        for (key, value) in lines(contents) {
+            if key.is_some() {
+                current_key = key;
+            }
+            match current_key.as_deref() {
+                Some("puzzle") => {
+                    puzzle_type = puzzle_type_from_name(value)
+                        .ok_or_else(|| anyhow!("unknown puzzle {value:?}"))?;
+                }
+                Some("scramble") => {
+                    scramble += " ";
+                    scramble += value;
+                }
+                Some("solution") => {
+                    solution += " ";
+                    solution += value;
+                }
+                Some(other) => warnings.push(format!("Ignoring unknown key {other:?}")),
+                None => warnings.push(format!("Ignoring line {value:?}")),
+            }
+        }
+
+        let mut ret = PuzzleController::new(puzzle_type);
+
+        let (twists, parse_errors) = LogFile::twists(&scramble, &puzzle_type);
+        warnings.extend(parse_errors.iter().map(|e| e.to_string()));
+        let is_scrambled = !twists.is_empty();
+        for twist in twists {
+            if let Err(e) = ret.twist_no_collapse(twist) {
+                warnings.push(e.to_string());
+            }
+        }
+        if is_scrambled {
+            ret.add_scramble_marker(ScrambleState::Full);
+        }
+
+        let (twists, parse_errors) = LogFile::twists(&solution, &puzzle_type);
+        warnings.extend(parse_errors.iter().map(|e| e.to_string()));
+        for twist in twists {
+            if let Err(e) = ret.twist_no_collapse(twist) {
+                warnings.push(e.to_string());
+            }
+        }
+        ret.skip_twist_animations();
+        ret.mark_saved();
+
+        Ok((ret, warnings))
+    }
+}
+
+/// Returns the non-empty lines of a file with comments removed, split into an
+/// optional lowercase key and a value.
+fn lines(contents: &str) -> impl Iterator<Item = (Option<String>, &str)> {
+    contents
+        .lines()
+        .map(|line| match line.split_once(COMMENT_PREFIX) {
+            Some((before_comment, _)) => before_comment,
+            None => line,
+        })
+        .map(|line| match line.split_once(':') {
+            Some((key, value)) => (Some(key.trim().to_lowercase()), value.trim()),
+            None => (None, line.trim()),
+        })
+        .filter(|(key, value)| key.is_some() || !value.is_empty())
+}
+
+fn puzzle_type_from_name(name: &str) -> Option<PuzzleTypeEnum> {
+    let rubiks_3d =
+        rubiks_3d::LAYER_COUNT_RANGE.map(|layer_count| PuzzleTypeEnum::Rubiks3D { layer_count });
+    let rubiks_4d =
+        rubiks_4d::LAYER_COUNT_RANGE.map(|layer_count| PuzzleTypeEnum::Rubiks4D { layer_count });
+    rubiks_3d
+        .chain(rubiks_4d)
+        .chain([PuzzleTypeEnum::Ivy])
+        .find(|ty| ty.name().eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_import() {
+        let contents = "Puzzle: 2x2x2\n\
+                        Scramble: R U // setup\n\
+                        \n\
+                        Solution:\n\
+                        U' // undo U\n\
+                        R'\n";
+        let (puzzle, warnings) = super::super::deserialize(contents).unwrap();
+        assert!(warnings.is_empty(), "{warnings:?}");
+        assert_eq!(PuzzleTypeEnum::Rubiks3D { layer_count: 2 }, puzzle.ty());
+        assert_eq!(2, puzzle.scramble().len());
+        assert!(puzzle.is_solved());
+
+        assert!(TextImporter.import("Puzzle: 0x0x0\nScramble: R").is_err());
+    }
+
+    #[test]
+    fn test_import_by_extension() {
+        let contents = "Puzzle: 0x0x0\nScramble: R";
+        let e = super::super::import(contents, Some("TXT")).unwrap_err();
+        assert!(e.to_string().contains("unknown puzzle"), "{e}");
+
+        // Files with unknown extensions are detected from their contents.
+        let contents = "Scramble: R U\nSolution: U' R'";
+        let (puzzle, _) = super::super::import(contents, Some("md")).unwrap();
+        assert!(puzzle.is_solved());
+    }
+}
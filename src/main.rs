@@ -31,18 +31,27 @@ use winit::platform::web::WindowBuilderExtWebSys;
 #[macro_use]
 mod debug;
 mod app;
+#[cfg(not(target_arch = "wasm32"))]
+mod cli;
 mod commands;
+mod diagram;
+#[cfg(not(target_arch = "wasm32"))]
+mod gamepad;
 mod gui;
 #[cfg(not(target_arch = "wasm32"))]
 mod icon;
 mod logfile;
+mod practice;
 mod preferences;
 pub mod puzzle;
 mod render;
 mod serde_impl;
+mod stats;
 mod util;
 #[cfg(target_arch = "wasm32")]
 mod web_workarounds;
+#[cfg(not(target_arch = "wasm32"))]
+mod window_geometry;
 
 use app::App;
 
@@ -108,13 +117,47 @@ fn main() {
     wasm_bindgen_futures::spawn_local(run());
 }
 
+fn show_graphics_error_dialog(e: anyhow::Error) {
+    let requirement = if cfg!(target_arch = "wasm32") {
+        "a browser that supports WebGL 2 or WebGPU"
+    } else {
+        "a graphics card that supports Vulkan, Metal, DirectX 12, DirectX 11, or OpenGL 3.3. \
+         Updating your graphics drivers may help"
+    };
+    rfd::MessageDialog::new()
+        .set_title(&format!("{TITLE} could not start"))
+        .set_description(&format!(
+            "Unable to initialize graphics.\n\n\
+             {TITLE} requires {requirement}.\n\n\
+             Details: {e}"
+        ))
+        .set_level(rfd::MessageLevel::Error)
+        .show();
+}
+
 async fn run() {
-    // Initialize window.
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    // Run subcommands headlessly, without opening a window.
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(exit_code) = cli::run_subcommand(&args) {
+        std::process::exit(exit_code);
+    }
+
     let event_loop = EventLoopBuilder::with_user_event().build();
+
+    // Initialize app state first, so that the window can be restored to
+    // where it was.
+    let initial_file = args.first().map(std::path::PathBuf::from);
+    let mut app = App::new(&event_loop, initial_file);
+
+    // Initialize window.
     #[cfg(not(target_arch = "wasm32"))]
     let window_builder = winit::window::WindowBuilder::new()
         .with_title(crate::TITLE)
         .with_window_icon(icon::load_application_icon());
+    #[cfg(not(target_arch = "wasm32"))]
+    let window_builder = window_geometry::restore(window_builder, &event_loop, &app.prefs.window);
     #[cfg(target_arch = "wasm32")]
     let window_builder =
         winit::window::WindowBuilder::new().with_canvas(Some(find_canvas_element()));
@@ -125,7 +168,14 @@ async fn run() {
     let mut clipboard = clipboard(&event_loop);
 
     // Initialize graphics state.
-    let mut gfx = render::GraphicsState::new(&window).await;
+    let mut gfx = match render::GraphicsState::new(&window).await {
+        Ok(gfx) => gfx,
+        Err(e) => {
+            log::error!("Error initializing graphics: {e}");
+            show_graphics_error_dialog(e);
+            return;
+        }
+    };
     let mut last_fps = 0;
     let mut frames_this_second = 0;
     let mut last_second = Instant::now();
@@ -144,14 +194,40 @@ async fn run() {
         &gfx.dummy_texture_view(),
         wgpu::FilterMode::Linear,
     );
+    let last_layer_texture_id = egui_renderer.register_native_texture(
+        &gfx.device,
+        &gfx.dummy_texture_view(),
+        wgpu::FilterMode::Linear,
+    );
+    let mirror_view_texture_id = egui_renderer.register_native_texture(
+        &gfx.device,
+        &gfx.dummy_texture_view(),
+        wgpu::FilterMode::Linear,
+    );
+    let scramble_preview_texture_id = egui_renderer.register_native_texture(
+        &gfx.device,
+        &gfx.dummy_texture_view(),
+        wgpu::FilterMode::Linear,
+    );
 
-    let initial_file = std::env::args().nth(1).map(std::path::PathBuf::from);
-
-    // Initialize app state.
-    let mut app = App::new(&event_loop, initial_file);
+    app.last_layer_texture_id = Some(last_layer_texture_id);
+    app.mirror_view_texture_id = Some(mirror_view_texture_id);
+    app.scramble_preview_texture_id = Some(scramble_preview_texture_id);
 
-    if app.prefs.show_welcome_at_startup {
-        gui::windows::WELCOME.set_open(&egui_ctx, true);
+    gui::load_layout(&egui_ctx, &app.prefs);
+    gui::windows::WELCOME.set_open(&egui_ctx, app.prefs.show_welcome_at_startup);
+    if let Some(previous_version) = app
+        .prefs
+        .update_last_seen_version(env!("CARGO_PKG_VERSION"))
+    {
+        app.previous_version = Some(previous_version).filter(|v| !v.is_empty());
+        gui::windows::WHATS_NEW.set_open(&egui_ctx, true);
+    }
+    if app.prefs.info.last_layer_view.visible {
+        gui::windows::LAST_LAYER_VIEW.set_open(&egui_ctx, true);
+    }
+    if app.prefs.info.mirror_view.visible {
+        gui::windows::MIRROR_VIEW.set_open(&egui_ctx, true);
     }
 
     #[cfg(target_arch = "wasm32")]
@@ -160,6 +236,8 @@ async fn run() {
     #[cfg(not(target_arch = "wasm32"))]
     let mut request_paste = false;
 
+    let mut window_title = TITLE.to_owned();
+
     // Begin main loop.
     let mut next_frame_time = Instant::now();
     event_loop.run(move |ev, _ev_loop, control_flow| {
@@ -224,6 +302,25 @@ async fn run() {
         match ev {
             // Handle window events.
             Event::WindowEvent { window_id, event } if window_id == window.id() => {
+                if matches!(
+                    event,
+                    WindowEvent::KeyboardInput { .. }
+                        | WindowEvent::MouseInput { .. }
+                        | WindowEvent::MouseWheel { .. }
+                        | WindowEvent::CursorMoved { .. }
+                        | WindowEvent::Touch(_)
+                ) {
+                    app.reset_idle_timer();
+                }
+                if matches!(
+                    event,
+                    WindowEvent::MouseInput { .. }
+                        | WindowEvent::MouseWheel { .. }
+                        | WindowEvent::CursorMoved { .. }
+                ) {
+                    app.reset_cursor_timer();
+                }
+
                 // If the key combo popup didn't capture the event, then let
                 // egui handle it before anything else.
                 if !event_has_been_captured {
@@ -299,6 +396,17 @@ async fn run() {
                     #[cfg(not(target_arch = "wasm32"))]
                     clipboard.set(copy_string);
                 }
+                #[cfg(not(target_arch = "wasm32"))]
+                if r.reset_window_position {
+                    window_geometry::reset(&window);
+                }
+            }
+
+            Event::LoopDestroyed => {
+                gui::save_layout(&egui_ctx, &mut app.prefs);
+                #[cfg(not(target_arch = "wasm32"))]
+                window_geometry::save(&window, &mut app.prefs);
+                app.prefs.save();
             }
 
             Event::MainEventsCleared => {
@@ -337,7 +445,7 @@ async fn run() {
                         }
                     }
 
-                    let egui_output = egui_ctx.run(egui_input, |ctx| {
+                    let mut egui_output = egui_ctx.run(egui_input, |ctx| {
                         // Build all the UI.
                         gui::build(ctx, &mut app, puzzle_texture_id);
                     });
@@ -351,6 +459,11 @@ async fn run() {
                         );
                     }
 
+                    // egui-winit hides the cursor via winit when there's no
+                    // cursor icon, and shows it again once there is one.
+                    if app.should_hide_cursor() {
+                        egui_output.platform_output.cursor_icon = egui::CursorIcon::None;
+                    }
                     egui_winit_state.handle_platform_output(
                         &window,
                         &egui_ctx,
@@ -367,7 +480,9 @@ async fn run() {
                     }
 
                     // Draw puzzle if necessary.
-                    if let Some(puzzle_texture) = app.draw_puzzle(&mut gfx) {
+                    let puzzle_texture = app.draw_puzzle(&mut gfx);
+                    let puzzle_repainted = puzzle_texture.is_some();
+                    if let Some(puzzle_texture) = puzzle_texture {
                         log::trace!("Repainting puzzle");
 
                         // Update texture for egui.
@@ -381,6 +496,41 @@ async fn run() {
                         // Request a repaint.
                         egui_ctx.request_repaint();
                     }
+                    if let Some(last_layer_texture) =
+                        app.draw_last_layer(&mut gfx, puzzle_repainted)
+                    {
+                        egui_renderer.update_egui_texture_from_wgpu_texture(
+                            &gfx.device,
+                            &last_layer_texture,
+                            wgpu::FilterMode::Linear,
+                            last_layer_texture_id,
+                        );
+                        egui_ctx.request_repaint();
+                    }
+                    if let Some(mirror_view_texture) =
+                        app.draw_mirror_view(&mut gfx, puzzle_repainted)
+                    {
+                        egui_renderer.update_egui_texture_from_wgpu_texture(
+                            &gfx.device,
+                            &mirror_view_texture,
+                            wgpu::FilterMode::Linear,
+                            mirror_view_texture_id,
+                        );
+                        egui_ctx.request_repaint();
+                    }
+                    if let Some(scramble_preview_texture) =
+                        app.draw_scramble_preview(&mut gfx, puzzle_repainted)
+                    {
+                        egui_renderer.update_egui_texture_from_wgpu_texture(
+                            &gfx.device,
+                            &scramble_preview_texture,
+                            wgpu::FilterMode::Linear,
+                            scramble_preview_texture_id,
+                        );
+                        egui_ctx.request_repaint();
+                    }
+
+                    gfx.set_present_mode(app.prefs.gfx.present_mode());
 
                     let frame_duration = app.prefs.gfx.frame_duration();
                     next_frame_time += frame_duration;
@@ -391,6 +541,12 @@ async fn run() {
                     // Update app state.
                     app.frame();
 
+                    let new_window_title = app.window_title();
+                    if new_window_title != window_title {
+                        set_window_title(&window, &new_window_title);
+                        window_title = new_window_title;
+                    }
+
                     let output_frame = match gfx.surface.get_current_texture() {
                         Ok(tex) => tex,
                         // Log other errors to the console.
@@ -531,6 +687,18 @@ fn find_canvas_element() -> web_sys::HtmlCanvasElement {
         .expect("failed to find canvas for Hyperspeedcube")
 }
 
+fn set_window_title(window: &winit::window::Window, title: &str) {
+    #[cfg(not(target_arch = "wasm32"))]
+    window.set_title(title);
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = window;
+        if let Some(document) = web_sys::window().and_then(|w| w.document()) {
+            document.set_title(title);
+        }
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 fn clipboard<T>(
     event_loop: &winit::event_loop::EventLoopWindowTarget<T>,
@@ -0,0 +1,478 @@
+//! Sets of algorithm cases for practicing the last layer of a 3x3x3, and
+//! drills that repeat a single case in several variants.
+
+use instant::{Duration, Instant};
+use itertools::Itertools;
+use rand::seq::SliceRandom;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::puzzle::{invert_alg, traits::*, PuzzleController, PuzzleTypeEnum, Twist};
+
+/// Puzzle that the case sets are for.
+pub(crate) const CASE_PUZZLE: PuzzleTypeEnum = PuzzleTypeEnum::Rubiks3D { layer_count: 3 };
+
+/// Adjustments of the top layer, one of which is applied after setting up a
+/// case so that it isn't always seen from the same side.
+const AUFS: &[&str] = &["", "U", "U2", "U'"];
+
+/// Named set of algorithm cases.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct CaseSet {
+    pub(crate) name: &'static str,
+    pub(crate) cases: &'static [Case],
+}
+
+/// Algorithm case, which is set up by applying the inverse of its algorithm
+/// to a solved puzzle.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct Case {
+    /// Name of the case, which is unique across all case sets.
+    pub(crate) name: &'static str,
+    /// Algorithm that solves the case, in the notation for [`CASE_PUZZLE`].
+    pub(crate) alg: &'static str,
+}
+impl Case {
+    /// Returns the twists that set up the case from the solved state,
+    /// followed by `auf`.
+    fn setup(&self, auf: &str) -> Result<Vec<Twist>, String> {
+        let mut twists = setup_from_alg(self.alg)?;
+        twists.extend(CASE_PUZZLE.notation_scheme().parse_alg(auf)?);
+        Ok(twists)
+    }
+}
+/// Returns the twists that set up a case from the solved state, given an
+/// algorithm that solves it in the notation for [`CASE_PUZZLE`].
+pub(crate) fn setup_from_alg(alg: &str) -> Result<Vec<Twist>, String> {
+    let twists = CASE_PUZZLE.notation_scheme().parse_alg(alg)?;
+    Ok(invert_alg(CASE_PUZZLE, &twists))
+}
+const fn case(name: &'static str, alg: &'static str) -> Case {
+    Case { name, alg }
+}
+
+const PLL_AA: Case = case("Aa", "R' F R' B2 R F' R' B2 R2");
+const PLL_AB: Case = case("Ab", "R2 B2 R F R' B2 R F' R");
+const PLL_E: Case = case("E", "x' R U' R' D R U R' D' R U R' D R U' R' D' x");
+const PLL_F: Case = case("F", "R' U' F' R U R' U' R' F R2 U' R' U' R U R' U R");
+const PLL_GA: Case = case("Ga", "R2 U R' U R' U' R U' R2 U' D R' U R D'");
+const PLL_GB: Case = case("Gb", "R' U' R U D' R2 U R' U R U' R U' R2 D");
+const PLL_GC: Case = case("Gc", "R2 U' R U' R U R' U R2 U D' R U' R' D");
+const PLL_GD: Case = case("Gd", "R U R' U' D R2 U' R U' R' U R' U R2 D'");
+const PLL_H: Case = case("H", "M2 U M2 U2 M2 U M2");
+const PLL_JA: Case = case("Ja", "R' U L' U2 R U' R' U2 R L");
+const PLL_JB: Case = case("Jb", "R U R' F' R U R' U' R' F R2 U' R'");
+const PLL_NA: Case = case(
+    "Na",
+    "R U R' U R U R' F' R U R' U' R' F R2 U' R' U2 R U' R'",
+);
+const PLL_NB: Case = case("Nb", "R' U R U' R' F' U' F R U R' F R' F' R U' R");
+const PLL_RA: Case = case("Ra", "R U' R' U' R U R D R' U' R D' R' U2 R'");
+const PLL_RB: Case = case("Rb", "R2 F R U R U' R' F' R U2 R' U2 R");
+const PLL_T: Case = case("T", "R U R' U' R' F R2 U' R' U' R U R' F'");
+const PLL_UA: Case = case("Ua", "M2 U M U2 M' U M2");
+const PLL_UB: Case = case("Ub", "M2 U' M U2 M' U' M2");
+const PLL_V: Case = case("V", "R' U R' U' R D' R' D R' U D' R2 U' R2 D R2");
+const PLL_Y: Case = case("Y", "F R U' R' U' R U R' F' R U R' U' R' F R F'");
+const PLL_Z: Case = case("Z", "M' U M2 U M2 U M' U2 M2");
+
+/// Built-in case sets.
+pub(crate) const CASE_SETS: &[CaseSet] = &[
+    CaseSet {
+        name: "PLL",
+        cases: &[
+            PLL_AA, PLL_AB, PLL_E, PLL_F, PLL_GA, PLL_GB, PLL_GC, PLL_GD, PLL_H, PLL_JA, PLL_JB,
+            PLL_NA, PLL_NB, PLL_RA, PLL_RB, PLL_T, PLL_UA, PLL_UB, PLL_V, PLL_Y, PLL_Z,
+        ],
+    },
+    CaseSet {
+        name: "EPLL",
+        cases: &[PLL_UA, PLL_UB, PLL_H, PLL_Z],
+    },
+    CaseSet {
+        name: "Corner PLL",
+        cases: &[PLL_AA, PLL_AB, PLL_E],
+    },
+    CaseSet {
+        name: "G-perms",
+        cases: &[PLL_GA, PLL_GB, PLL_GC, PLL_GD],
+    },
+    CaseSet {
+        name: "OCLL",
+        cases: &[
+            case("Sune (OLL 27)", "R U R' U R U2 R'"),
+            case("Antisune (OLL 26)", "R U2 R' U' R U' R'"),
+            case("H (OLL 21)", "R U R' U R U' R' U R U2 R'"),
+            case("Pi (OLL 22)", "R U2 R2 U' R2 U' R2 U2 R"),
+            case("Headlights (OLL 23)", "R2 D R' U2 R D' R' U2 R'"),
+            case("T (OLL 24)", "Rw U R' U' Rw' F R F'"),
+            case("Bowtie (OLL 25)", "F R' F' Rw U R U' Rw'"),
+        ],
+    },
+];
+
+/// Case that has been set up on the puzzle.
+#[derive(Debug, Clone)]
+struct CaseAttempt {
+    case: Case,
+    /// Scramble of the puzzle after setting up the case, to detect whether the
+    /// puzzle has been changed some other way since then.
+    scramble: Vec<Twist>,
+    start: Instant,
+}
+
+/// State for practicing a case set. This is not saved.
+#[derive(Debug, Default)]
+pub(crate) struct CaseTrainer {
+    /// Index of the selected case set in [`CASE_SETS`].
+    pub(crate) set_index: usize,
+    attempt: Option<CaseAttempt>,
+    /// Most recently solved case and how long it took.
+    last_solve: Option<(Case, Duration)>,
+    /// Times for each case, by name.
+    times: HashMap<&'static str, Vec<Duration>>,
+}
+impl CaseTrainer {
+    /// Returns the selected case set.
+    pub(crate) fn case_set(&self) -> &'static CaseSet {
+        CASE_SETS.get(self.set_index).unwrap_or(&CASE_SETS[0])
+    }
+
+    /// Returns a random case from the selected set, along with the twists
+    /// that set it up from the solved state.
+    pub(crate) fn random_case(&self) -> Result<(Case, Vec<Twist>), String> {
+        let mut rng = rand::thread_rng();
+        let case = *self
+            .case_set()
+            .cases
+            .choose(&mut rng)
+            .ok_or("Case set is empty")?;
+
+        let setup = case.setup(AUFS.choose(&mut rng).unwrap())?;
+        Ok((case, setup))
+    }
+    /// Begins timing a case that has just been set up on the puzzle.
+    pub(crate) fn start(&mut self, case: Case, puzzle: &PuzzleController) {
+        self.attempt = Some(CaseAttempt {
+            case,
+            scramble: puzzle.scramble().to_vec(),
+            start: Instant::now(),
+        });
+    }
+    /// Returns whether a case is set up on the puzzle and hasn't been solved
+    /// yet.
+    pub(crate) fn is_attempting(&self, puzzle: &PuzzleController) -> bool {
+        self.attempt
+            .as_ref()
+            .map_or(false, |attempt| attempt.is_set_up_on(puzzle))
+    }
+    /// Records the time for the case that was set up on the puzzle, if
+    /// there is one, and returns it. This should be called when the puzzle is
+    /// solved.
+    pub(crate) fn record_solve(&mut self, puzzle: &PuzzleController) -> Option<(Case, Duration)> {
+        let attempt = self.attempt.take()?;
+        if !attempt.is_set_up_on(puzzle) {
+            return None;
+        }
+        let time = attempt.start.elapsed();
+        self.times.entry(attempt.case.name).or_default().push(time);
+        self.last_solve = Some((attempt.case, time));
+        self.last_solve
+    }
+
+    /// Returns the most recently solved case and how long it took.
+    pub(crate) fn last_solve(&self) -> Option<(Case, Duration)> {
+        self.last_solve
+    }
+    /// Returns the times for a case, from oldest to newest.
+    pub(crate) fn times(&self, case: Case) -> &[Duration] {
+        self.times
+            .get(case.name)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+    /// Forgets the times for every case.
+    pub(crate) fn clear_times(&mut self) {
+        self.times.clear();
+        self.last_solve = None;
+    }
+}
+
+impl CaseAttempt {
+    fn is_set_up_on(&self, puzzle: &PuzzleController) -> bool {
+        puzzle.ty() == CASE_PUZZLE && puzzle.scramble() == self.scramble
+    }
+}
+
+/// Rotations that a drill case is set up from, so that it isn't always seen
+/// from the same side.
+const ROTATIONS: &[&str] = &["", "y", "y2", "y'"];
+
+/// Variant of a case for one rep of a drill.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct DrillVariant {
+    /// Whether the case is mirrored left-to-right.
+    pub(crate) mirror: bool,
+    /// Whether the case is the inverse of the original case, which is solved
+    /// by the inverse of the algorithm.
+    pub(crate) inverse: bool,
+    /// Index into [`ROTATIONS`] of the rotation the case is set up from.
+    pub(crate) rotation: usize,
+}
+impl fmt::Display for DrillVariant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.mirror, self.inverse) {
+            (false, false) => write!(f, "Original")?,
+            (true, false) => write!(f, "Mirror")?,
+            (false, true) => write!(f, "Inverse")?,
+            (true, true) => write!(f, "Mirror inverse")?,
+        }
+        match ROTATIONS.get(self.rotation) {
+            Some(&"") | None => Ok(()),
+            Some(rotation) => write!(f, " ({rotation})"),
+        }
+    }
+}
+impl DrillVariant {
+    /// Returns the twists that set up this variant of a case from the solved
+    /// state, given an algorithm that solves the original case in the
+    /// notation for [`CASE_PUZZLE`].
+    pub(crate) fn setup(self, alg: &str) -> Result<Vec<Twist>, String> {
+        let notation = CASE_PUZZLE.notation_scheme();
+        let mut twists = notation.parse_alg(alg)?;
+        if self.mirror {
+            twists = twists.into_iter().map(mirror_twist).collect();
+        }
+        // The original case is set up by the inverse of the algorithm, so the
+        // inverse case is set up by the algorithm itself.
+        if !self.inverse {
+            twists = invert_alg(CASE_PUZZLE, &twists);
+        }
+        let rotation = notation.parse_alg(ROTATIONS.get(self.rotation).unwrap_or(&""))?;
+        Ok([rotation.clone(), twists, invert_alg(CASE_PUZZLE, &rotation)].concat())
+    }
+}
+/// Mirrors a twist of [`CASE_PUZZLE`] left-to-right, so that R becomes L' and
+/// U becomes U'.
+fn mirror_twist(twist: Twist) -> Twist {
+    let ty = CASE_PUZZLE;
+    let axis = match ty.info(twist.axis).name {
+        "R" | "L" => ty.opposite_twist_axis(twist.axis).unwrap_or(twist.axis),
+        _ => twist.axis,
+    };
+    Twist {
+        axis,
+        direction: ty.reverse_twist_direction(twist.direction),
+        layers: twist.layers,
+    }
+}
+
+/// Variant of a drill case that has been set up on the puzzle.
+#[derive(Debug, Clone)]
+struct DrillAttempt {
+    variant: DrillVariant,
+    /// Scramble of the puzzle after setting up the variant, to detect whether
+    /// the puzzle has been changed some other way since then.
+    scramble: Vec<Twist>,
+    start: Instant,
+}
+impl DrillAttempt {
+    fn is_set_up_on(&self, puzzle: &PuzzleController) -> bool {
+        puzzle.ty() == CASE_PUZZLE && puzzle.scramble() == self.scramble
+    }
+}
+
+/// State for drilling a single case, which sets up the next variant of the
+/// case each time it is solved. This is not saved.
+#[derive(Debug, Default)]
+pub(crate) struct Drill {
+    /// Algorithm that solves the original case, in the notation for
+    /// [`CASE_PUZZLE`].
+    pub(crate) alg: String,
+    /// Whether to also drill the mirror of the case.
+    pub(crate) mirror: bool,
+    /// Whether to also drill the inverse of the case.
+    pub(crate) inverse: bool,
+    /// Whether to also drill the case from every side.
+    pub(crate) rotate: bool,
+
+    /// Index of the next variant to set up in `variants()`.
+    next_variant: usize,
+    attempt: Option<DrillAttempt>,
+    /// Variant and time of each rep, from oldest to newest.
+    reps: Vec<(DrillVariant, Duration)>,
+}
+impl Drill {
+    /// Returns the variants of the case to cycle through, in order.
+    pub(crate) fn variants(&self) -> Vec<DrillVariant> {
+        let rotations = if self.rotate {
+            0..ROTATIONS.len()
+        } else {
+            0..1
+        };
+        [false, true]
+            .into_iter()
+            .filter(|&m| !m || self.mirror)
+            .cartesian_product([false, true].into_iter().filter(|&i| !i || self.inverse))
+            .cartesian_product(rotations)
+            .map(|((mirror, inverse), rotation)| DrillVariant {
+                mirror,
+                inverse,
+                rotation,
+            })
+            .collect()
+    }
+    /// Returns the variant that will be set up by the next rep.
+    pub(crate) fn next_variant(&self) -> DrillVariant {
+        let variants = self.variants();
+        variants[self.next_variant % variants.len()]
+    }
+    /// Returns the next variant along with the twists that set it up from the
+    /// solved state, and moves on to the variant after it.
+    pub(crate) fn next_rep(&mut self) -> Result<(DrillVariant, Vec<Twist>), String> {
+        if self.alg.trim().is_empty() {
+            return Err("No algorithm to drill".to_string());
+        }
+        let variant = self.next_variant();
+        let setup = variant.setup(&self.alg)?;
+        self.next_variant = (self.next_variant + 1) % self.variants().len();
+        Ok((variant, setup))
+    }
+    /// Begins timing a variant that has just been set up on the puzzle.
+    pub(crate) fn start(&mut self, variant: DrillVariant, puzzle: &PuzzleController) {
+        self.attempt = Some(DrillAttempt {
+            variant,
+            scramble: puzzle.scramble().to_vec(),
+            start: Instant::now(),
+        });
+    }
+    /// Stops setting up new reps, and starts over from the first variant.
+    pub(crate) fn stop(&mut self) {
+        self.attempt = None;
+        self.next_variant = 0;
+    }
+    /// Returns the variant that is set up on the puzzle, if it hasn't been
+    /// solved yet.
+    pub(crate) fn current_variant(&self, puzzle: &PuzzleController) -> Option<DrillVariant> {
+        self.attempt
+            .as_ref()
+            .filter(|attempt| attempt.is_set_up_on(puzzle))
+            .map(|attempt| attempt.variant)
+    }
+    /// Returns whether the drill is running, in which case the next rep
+    /// should be set up once the puzzle is solved.
+    pub(crate) fn is_running(&self) -> bool {
+        self.attempt.is_some()
+    }
+    /// Records the time for the variant that was set up on the puzzle, if
+    /// there is one, and returns it. This should be called when the puzzle is
+    /// solved. If the puzzle was changed some other way, the drill stops.
+    pub(crate) fn record_solve(
+        &mut self,
+        puzzle: &PuzzleController,
+    ) -> Option<(DrillVariant, Duration)> {
+        let attempt = self.attempt.take()?;
+        if !attempt.is_set_up_on(puzzle) {
+            self.stop();
+            return None;
+        }
+        let rep = (attempt.variant, attempt.start.elapsed());
+        self.reps.push(rep);
+        Some(rep)
+    }
+
+    /// Returns the variant and time of each rep, from oldest to newest.
+    pub(crate) fn reps(&self) -> &[(DrillVariant, Duration)] {
+        &self.reps
+    }
+    /// Returns the mean time of the reps of each variant that has any, in
+    /// the order of `variants()`.
+    pub(crate) fn variant_means(&self) -> Vec<(DrillVariant, usize, Duration)> {
+        self.variants()
+            .into_iter()
+            .filter_map(|variant| {
+                let times = self
+                    .reps
+                    .iter()
+                    .filter(|(v, _)| *v == variant)
+                    .map(|(_, t)| *t)
+                    .collect_vec();
+                let count = u32::try_from(times.len()).ok().filter(|&n| n > 0)?;
+                Some((variant, times.len(), times.iter().sum::<Duration>() / count))
+            })
+            .collect()
+    }
+    /// Forgets the time of every rep.
+    pub(crate) fn clear_reps(&mut self) {
+        self.reps.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case_sets() {
+        let mut algs_by_name = HashMap::new();
+        for case in CASE_SETS.iter().flat_map(|set| set.cases) {
+            let alg = *algs_by_name.entry(case.name).or_insert(case.alg);
+            assert_eq!(alg, case.alg, "two cases are named {:?}", case.name);
+
+            let mut puzzle = PuzzleController::new(CASE_PUZZLE);
+            puzzle.apply_pattern(case.setup("").unwrap()).unwrap();
+            assert!(!puzzle.is_solved(), "{:?} is already solved", case.name);
+        }
+    }
+
+    #[test]
+    fn test_drill_variants() {
+        let notation = CASE_PUZZLE.notation_scheme();
+        let sune = "R U R' U R U2 R'";
+        // Algorithms that solve each variant of Sune, before the rotation.
+        let solutions = [
+            (false, false, sune),
+            (true, false, "L' U' L U' L' U2 L"),
+            (false, true, "R U2 R' U' R U' R'"),
+            (true, true, "L' U2 L U L' U L"),
+        ];
+
+        let mut drill = Drill {
+            alg: sune.to_string(),
+            mirror: true,
+            inverse: true,
+            rotate: true,
+            ..Default::default()
+        };
+        assert_eq!(drill.variants().len(), 16);
+        assert!(drill.variants().iter().all_unique());
+
+        for _ in 0..drill.variants().len() {
+            let (variant, setup) = drill.next_rep().unwrap();
+            let (_, _, solution) = solutions
+                .iter()
+                .find(|(m, i, _)| (*m, *i) == (variant.mirror, variant.inverse))
+                .unwrap();
+            let rotation = notation.parse_alg(ROTATIONS[variant.rotation]).unwrap();
+            let solution = notation.parse_alg(solution).unwrap();
+
+            let mut puzzle = crate::puzzle::Puzzle::new(CASE_PUZZLE);
+            for twist in setup {
+                puzzle.twist(twist).unwrap();
+            }
+            assert!(!puzzle.is_solved(), "{variant} is already solved");
+            for twist in [
+                rotation.clone(),
+                solution,
+                invert_alg(CASE_PUZZLE, &rotation),
+            ]
+            .concat()
+            {
+                puzzle.twist(twist).unwrap();
+            }
+            assert!(puzzle.is_solved(), "{variant} is not solved");
+        }
+        // The variants repeat once they have all been drilled.
+        assert_eq!(drill.next_variant(), DrillVariant::default());
+    }
+}
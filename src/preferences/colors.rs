@@ -1,8 +1,9 @@
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::ops::{Index, IndexMut};
 
-use super::PerPuzzleFamily;
+use super::{PerPuzzleFamily, Preset};
 use crate::puzzle::{traits::*, Face, PuzzleTypeEnum};
 use crate::serde_impl::hex_color;
 
@@ -15,14 +16,45 @@ pub struct ColorPreferences {
     pub blind_face: egui::Color32,
     pub blindfold: bool,
 
+    /// Whether to draw orientation markers on center stickers, for solving
+    /// supercubes.
+    pub supercube_markers: bool,
+    #[serde(with = "hex_color")]
+    pub supercube_marker: egui::Color32,
+
+    /// Whether to draw a wireframe tesseract around 4D puzzles, as a spatial
+    /// reference that doesn't move with the pieces.
+    pub reference_frame: bool,
+    #[serde(with = "hex_color")]
+    pub reference_frame_color: egui::Color32,
+    pub reference_frame_opacity: f32,
+
     pub faces: PerPuzzleFamily<BTreeMap<String, FaceColor>>,
+    /// Face colors shown instead of the saved ones for one puzzle type, such
+    /// as the colors that a replay being played back was solved with. This is
+    /// not saved.
+    #[serde(skip)]
+    pub temporary_faces: Option<(PuzzleTypeEnum, BTreeMap<String, FaceColor>)>,
+    /// Saved color schemes, which can be cycled through.
+    pub schemes: PerPuzzleFamily<Vec<Preset<ColorScheme>>>,
+    /// Name of the saved color scheme that was most recently loaded.
+    pub active_scheme: PerPuzzleFamily<Option<String>>,
+
+    /// Symbol of the face whose color is rotated onto the down face, for
+    /// color-neutral practice.
+    pub down_color: PerPuzzleFamily<Option<String>>,
+    /// Symbol of the face whose color is rotated onto the front face, to
+    /// match how a physical puzzle is held.
+    pub front_color: PerPuzzleFamily<Option<String>>,
+    /// Symbol of the face whose color is rotated onto the center of the 4D
+    /// projection.
+    pub center_color: PerPuzzleFamily<Option<String>>,
 }
 impl Index<(PuzzleTypeEnum, Face)> for ColorPreferences {
     type Output = egui::Color32;
 
     fn index(&self, (puzzle_type, face): (PuzzleTypeEnum, Face)) -> &Self::Output {
-        self.faces
-            .get(puzzle_type)
+        self.displayed_faces(puzzle_type)
             .and_then(|face_colors| face_colors.get(puzzle_type.info(face).symbol))
             .map(|color| &color.0)
             .unwrap_or(&self.blind_face)
@@ -41,19 +73,116 @@ impl IndexMut<(PuzzleTypeEnum, Face)> for ColorPreferences {
 }
 
 // TODO: rename this type and use it for all colors. also impl display
-#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
 #[serde(transparent)]
 pub struct FaceColor(#[serde(with = "hex_color")] pub egui::Color32);
 
+/// Named set of face colors.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct ColorScheme {
+    pub faces: BTreeMap<String, FaceColor>,
+}
+
 impl ColorPreferences {
+    /// Replaces the face colors with a saved color scheme.
+    pub fn load_scheme(&mut self, ty: PuzzleTypeEnum, scheme: &Preset<ColorScheme>) {
+        self.faces[ty] = scheme.value.faces.clone();
+        self.active_scheme[ty] = Some(scheme.preset_name.clone());
+    }
+    /// Returns the saved color scheme after the one that was most recently
+    /// loaded, wrapping around at the end.
+    pub fn next_scheme(&self, ty: PuzzleTypeEnum) -> Option<&Preset<ColorScheme>> {
+        let schemes = &self.schemes[ty];
+        let active = self.active_scheme[ty].as_ref();
+        match schemes.iter().position(|p| Some(&p.preset_name) == active) {
+            Some(i) => schemes.get(i + 1).or_else(|| schemes.first()),
+            None => schemes.first(),
+        }
+    }
+
+    /// Returns the color of each face, taking into account the color-neutral
+    /// rotation of the color scheme.
     pub fn face_colors_list(&self, ty: PuzzleTypeEnum) -> Vec<egui::Color32> {
-        let faces = &self.faces[ty];
-        ty.faces()
+        let faces = self.displayed_faces(ty);
+        let colors = ty
+            .faces()
+            .iter()
+            .map(
+                |face| match faces.and_then(|faces| faces.get(face.symbol)) {
+                    Some(c) => c.0,
+                    None => self.blind_face,
+                },
+            )
+            .collect_vec();
+        self.scheme_rotation(ty)
             .iter()
-            .map(|face| match faces.get(face.symbol) {
-                Some(c) => c.0,
-                None => self.blind_face,
-            })
+            .map(|f| colors[f.0 as usize])
             .collect()
     }
+    /// Returns the face colors to display for a puzzle type, which are the
+    /// temporary ones if there are any.
+    fn displayed_faces(&self, ty: PuzzleTypeEnum) -> Option<&BTreeMap<String, FaceColor>> {
+        match &self.temporary_faces {
+            Some((temporary_ty, faces)) if *temporary_ty == ty => Some(faces),
+            _ => self.faces.get(ty),
+        }
+    }
+    /// Returns the face whose color is displayed on stickers of each color,
+    /// in the same format as `PuzzleTypeEnum::color_scheme_rotation()`.
+    pub fn scheme_rotation(&self, ty: PuzzleTypeEnum) -> Vec<Face> {
+        let (down_color, front_color, center_color) = (
+            self.down_color(ty),
+            self.front_color(ty),
+            self.center_color(ty),
+        );
+        let rotation = match (down_color, front_color, center_color) {
+            (None, None, None) => None,
+            _ => ty.color_scheme_orientation(down_color, front_color, center_color),
+        };
+        rotation.unwrap_or_else(|| (0..ty.faces().len() as _).map(Face).collect())
+    }
+    /// Rotates the color scheme so that stickers of each color display the
+    /// color given by `rotation`, in the same format as
+    /// `PuzzleTypeEnum::color_scheme_rotation()`.
+    pub fn set_scheme_rotation(
+        &mut self,
+        ty: PuzzleTypeEnum,
+        rotation: &[Face],
+    ) -> Result<(), &'static str> {
+        let color_on = |face: Face| rotation[face.0 as usize];
+        let (Some(down), Some(front)) = (ty.down_face(), ty.front_face()) else {
+            return Err("Color scheme cannot be rotated for this puzzle");
+        };
+        let (down_color, front_color) = (color_on(down), color_on(front));
+        let center_color = ty.center_face().map(color_on);
+        if ty.color_scheme_orientation(Some(down_color), Some(front_color), center_color)
+            != Some(rotation.to_vec())
+        {
+            return Err("Color scheme cannot be rotated this way");
+        }
+
+        let symbol = |face: Face| Some(ty.info(face).symbol.to_owned());
+        self.down_color[ty] = symbol(down_color);
+        self.front_color[ty] = symbol(front_color);
+        self.center_color[ty] = center_color.and_then(symbol);
+        Ok(())
+    }
+
+    /// Returns the face whose color is displayed on the down face, or `None`
+    /// if the color scheme is not rotated.
+    pub fn down_color(&self, ty: PuzzleTypeEnum) -> Option<Face> {
+        ty.face_from_symbol(self.down_color[ty].as_deref()?)
+    }
+    /// Returns the face whose color is displayed on the front face, or `None`
+    /// if it is not rotated.
+    pub fn front_color(&self, ty: PuzzleTypeEnum) -> Option<Face> {
+        ty.face_from_symbol(self.front_color[ty].as_deref()?)
+    }
+    /// Returns the face whose color is displayed on the center face of the 4D
+    /// projection, or `None` if it is not rotated.
+    pub fn center_color(&self, ty: PuzzleTypeEnum) -> Option<Face> {
+        ty.center_face()?;
+        ty.face_from_symbol(self.center_color[ty].as_deref()?)
+    }
 }
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct GamepadPreferences {
+    /// Whether to rumble connected gamepads briefly on each twist.
+    pub rumble_on_twist: bool,
+    /// Strength of the rumble on each twist, from 0.0 to 1.0.
+    pub twist_rumble_intensity: f32,
+    /// Whether to rumble connected gamepads when the puzzle is solved.
+    pub rumble_on_solve: bool,
+    /// Strength of the rumble when the puzzle is solved, from 0.0 to 1.0.
+    pub solve_rumble_intensity: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct GamepadBind<C> {
+    pub button: GamepadButton,
+    pub command: C,
+}
+
+/// Gamepad button, named by its position on the gamepad.
+#[derive(
+    Serialize, Deserialize, Debug, Display, Default, EnumIter, Copy, Clone, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum GamepadButton {
+    /// Bottom face button (A on Xbox controllers).
+    #[default]
+    #[strum(serialize = "South")]
+    South,
+    /// Right face button (B on Xbox controllers).
+    #[strum(serialize = "East")]
+    East,
+    /// Top face button (Y on Xbox controllers).
+    #[strum(serialize = "North")]
+    North,
+    /// Left face button (X on Xbox controllers).
+    #[strum(serialize = "West")]
+    West,
+    #[strum(serialize = "Left bumper")]
+    LeftBumper,
+    #[strum(serialize = "Right bumper")]
+    RightBumper,
+    #[strum(serialize = "Left trigger")]
+    LeftTrigger,
+    #[strum(serialize = "Right trigger")]
+    RightTrigger,
+    #[strum(serialize = "Select")]
+    Select,
+    #[strum(serialize = "Start")]
+    Start,
+    #[strum(serialize = "Left stick")]
+    LeftStick,
+    #[strum(serialize = "Right stick")]
+    RightStick,
+    #[strum(serialize = "D-pad up")]
+    DPadUp,
+    #[strum(serialize = "D-pad down")]
+    DPadDown,
+    #[strum(serialize = "D-pad left")]
+    DPadLeft,
+    #[strum(serialize = "D-pad right")]
+    DPadRight,
+}
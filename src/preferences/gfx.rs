@@ -4,21 +4,78 @@ use serde::{Deserialize, Serialize};
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(default)]
 pub struct GfxPreferences {
+    /// Whether to limit the framerate to `fps_limit`, independently of
+    /// `present_mode`.
+    pub limit_fps: bool,
     pub fps_limit: usize,
+    pub present_mode: PresentMode,
     pub msaa: bool,
+    /// Whether to draw outlines in the color of each sticker, so that pieces
+    /// look like stickerless plastic.
+    pub stickerless: bool,
+    /// Multiplier for the brightness of every sticker color.
+    pub sticker_brightness: f32,
+    /// Multiplier for the saturation of every sticker color. 0.0 is
+    /// grayscale.
+    pub sticker_saturation: f32,
+    /// Whether to brighten stickers that are in place during a solve.
+    pub tint_solved_stickers: bool,
+    /// How much to brighten stickers that are in place, from 0.0 to 1.0.
+    pub solved_tint_intensity: f32,
+    pub floor_reflection: FloorReflectionPreferences,
 }
 impl Default for GfxPreferences {
     fn default() -> Self {
         Self {
+            limit_fps: true,
             fps_limit: 60,
+            present_mode: PresentMode::default(),
             msaa: true,
+            stickerless: false,
+            sticker_brightness: 1.0,
+            sticker_saturation: 1.0,
+            tint_solved_stickers: false,
+            solved_tint_intensity: 0.25,
+            floor_reflection: FloorReflectionPreferences::default(),
         }
     }
 }
 impl GfxPreferences {
-    /// Returns the duration of one frame based on the configured FPS value.
+    /// Returns the duration of one frame based on the configured FPS value,
+    /// or zero if the framerate is not limited.
     pub fn frame_duration(&self) -> Duration {
-        Duration::from_secs_f64(1.0 / self.fps_limit as f64)
+        if self.limit_fps {
+            Duration::from_secs_f64(1.0 / self.fps_limit as f64)
+        } else {
+            Duration::ZERO
+        }
+    }
+    /// Returns the present mode to request when configuring the surface.
+    pub fn present_mode(&self) -> wgpu::PresentMode {
+        match self.present_mode {
+            PresentMode::Fifo => wgpu::PresentMode::Fifo,
+            PresentMode::Immediate => wgpu::PresentMode::Immediate,
+            PresentMode::Mailbox => wgpu::PresentMode::Mailbox,
+        }
+    }
+
+    /// Applies the brightness and saturation adjustments to a sticker color.
+    pub fn adjust_sticker_color(&self, color: egui::Rgba) -> egui::Rgba {
+        let [r, g, b, a] = color.to_array();
+        let luminance = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        let adjust = |c: f32| {
+            let saturated = luminance + (c - luminance) * self.sticker_saturation;
+            (saturated * self.sticker_brightness).clamp(0.0, a)
+        };
+        egui::Rgba::from_rgba_premultiplied(adjust(r), adjust(g), adjust(b), a)
+    }
+    /// Brightens the color of a sticker that is in place, by mixing it with
+    /// white.
+    pub fn tint_solved_sticker(&self, color: egui::Rgba) -> egui::Rgba {
+        let [r, g, b, a] = color.to_array();
+        let t = self.solved_tint_intensity;
+        let tint = |c: f32| c + (a - c) * t;
+        egui::Rgba::from_rgba_premultiplied(tint(r), tint(g), tint(b), a)
     }
 
     /// Returns the MSAA sample count.
@@ -30,3 +87,42 @@ impl GfxPreferences {
         }
     }
 }
+
+/// Reflective floor under 3D puzzles, for presentation.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone)]
+#[serde(default)]
+pub struct FloorReflectionPreferences {
+    pub enabled: bool,
+    /// Opacity of the reflection where it touches the floor, from 0.0 to 1.0.
+    /// It fades out farther from the floor.
+    pub reflectivity: f32,
+    /// Radius of the blur, as a fraction of the size of the puzzle.
+    pub blur: f32,
+}
+impl Default for FloorReflectionPreferences {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            reflectivity: 0.4,
+            blur: 0.02,
+        }
+    }
+}
+
+/// How frames are presented to the screen.
+#[derive(
+    Serialize, Deserialize, Debug, Display, Default, EnumIter, Copy, Clone, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum PresentMode {
+    /// Waits for vertical blank, which is supported everywhere.
+    #[default]
+    #[strum(serialize = "VSync")]
+    Fifo,
+    /// Presents immediately, which may cause tearing.
+    #[strum(serialize = "Immediate")]
+    Immediate,
+    /// Waits for vertical blank, but replaces queued frames with newer ones.
+    #[strum(serialize = "Mailbox")]
+    Mailbox,
+}
@@ -1,5 +1,8 @@
+use instant::Duration;
 use serde::{Deserialize, Serialize};
 
+use super::PerPuzzle;
+use crate::diagram::NetLayout;
 use crate::puzzle::TwistMetric;
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
@@ -8,15 +11,51 @@ pub struct InfoPreferences {
     pub metric: TwistMetric,
     #[serde(skip)]
     pub qtm: bool,
+    /// Whether to remove whole-puzzle rotations from the solution when
+    /// counting twists and copying the solution.
+    pub normalize_rotations: bool,
+    /// Target twist count for each puzzle, in the selected metric. The twist
+    /// count is highlighted when it goes over the target.
+    pub twist_targets: PerPuzzle<Option<usize>>,
 
     pub keybinds_reference: KeybindsReferencePreferences,
+    pub touch_controls: TouchControlsPreferences,
+    pub last_layer_view: LastLayerViewPreferences,
+    pub mirror_view: MirrorViewPreferences,
 
     pub modifier_toggles: bool,
+
+    /// Whether to show the puzzle name in the window title.
+    pub title_puzzle: bool,
+    /// Whether to show the timer in the window title.
+    pub title_timer: bool,
+
+    /// Whether to show a bar in the timer window estimating how solved the
+    /// puzzle is.
+    pub solved_progress: bool,
+    /// Whether to show the time of the solve in progress. Solves are timed
+    /// either way.
+    pub show_time: bool,
+    /// Whether to show the twist count in the status bar. Twists are counted
+    /// either way.
+    pub show_twist_count: bool,
+
+    /// Width of each bar in the solve time histogram, in seconds.
+    pub histogram_bin_width: f32,
+
+    /// Arrangement of faces in the net diagram.
+    pub net_layout: NetLayout,
+
+    pub move_log: MoveLogPreferences,
+    pub penalty_flash: PenaltyFlashPreferences,
+    pub streak_goal: StreakGoalPreferences,
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Copy, Clone)]
 #[serde(default)]
 pub struct KeybindsReferencePreferences {
+    pub visible: bool,
+
     pub function: bool,
     pub navigation: bool,
     pub numpad: bool,
@@ -25,3 +64,143 @@ pub struct KeybindsReferencePreferences {
 
     pub max_font_size: f32,
 }
+
+#[derive(Serialize, Deserialize, Debug, Default, Copy, Clone)]
+#[serde(default)]
+pub struct TouchControlsPreferences {
+    pub visible: bool,
+    /// Whether to show the touch controls the first time the screen is
+    /// touched. This turns itself off once it happens, so that closing the
+    /// touch controls afterwards sticks.
+    pub show_on_touch: bool,
+    /// Width and height of each button.
+    pub button_size: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct LastLayerViewPreferences {
+    pub visible: bool,
+    /// Symbol of the face whose layer is shown.
+    pub up_face: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct MirrorViewPreferences {
+    pub visible: bool,
+    /// Whether to mirror the main puzzle view while the mirrored view window
+    /// is open, instead of showing a mirrored copy of the puzzle in it.
+    pub mirror_main_view: bool,
+    pub plane: MirrorPlane,
+}
+impl MirrorViewPreferences {
+    /// Returns the factors to multiply the X and Y screen coordinates of the
+    /// main puzzle view by.
+    pub fn main_view_scale(&self) -> [f32; 2] {
+        if self.visible && self.mirror_main_view {
+            self.plane.scale()
+        } else {
+            [1.0, 1.0]
+        }
+    }
+}
+
+/// Plane to reflect the puzzle across, relative to the screen.
+#[derive(
+    Serialize, Deserialize, Debug, Display, Default, EnumIter, Copy, Clone, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum MirrorPlane {
+    /// Vertical plane, which swaps left and right.
+    #[default]
+    #[strum(serialize = "Left-right")]
+    Vertical,
+    /// Horizontal plane, which swaps top and bottom.
+    #[strum(serialize = "Top-bottom")]
+    Horizontal,
+}
+impl MirrorPlane {
+    /// Returns the factors to multiply X and Y screen coordinates by to
+    /// reflect them across the plane.
+    pub fn scale(self) -> [f32; 2] {
+        match self {
+            Self::Vertical => [-1.0, 1.0],
+            Self::Horizontal => [1.0, -1.0],
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Copy, Clone)]
+#[serde(default)]
+pub struct MoveLogPreferences {
+    pub visible: bool,
+    /// Number of recent moves to show.
+    pub count: usize,
+    /// Corner of the puzzle view where the moves are shown.
+    pub corner: ScreenCorner,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Copy, Clone)]
+#[serde(default)]
+pub struct PenaltyFlashPreferences {
+    /// Whether to flash the edges of the puzzle view when a penalty is
+    /// assigned to a solve.
+    pub enabled: bool,
+    /// Opacity of the flash, from 0.0 to 1.0.
+    pub intensity: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Copy, Clone)]
+#[serde(default)]
+pub struct StreakGoalPreferences {
+    /// Whether to track a streak of solves under a target time.
+    pub enabled: bool,
+    /// Number of solves in a row under the target time that reach the goal.
+    pub solve_count: usize,
+    /// Time in seconds that each solve must be under, including penalties.
+    pub target_time: f32,
+    /// Whether to reset the streak after a long enough time between solves.
+    pub reset_on_break: bool,
+    /// Time in minutes between solves that counts as a break.
+    pub break_minutes: f32,
+}
+impl StreakGoalPreferences {
+    /// Returns the time that each solve must be under.
+    pub fn target_duration(&self) -> Duration {
+        Duration::from_secs_f32(self.target_time.max(0.0))
+    }
+    /// Returns the time between solves that resets the streak, or `None` if
+    /// breaks don't reset it.
+    pub fn max_break(&self) -> Option<Duration> {
+        self.reset_on_break
+            .then(|| Duration::from_secs_f32(self.break_minutes.max(0.0) * 60.0))
+    }
+}
+
+/// Corner of the puzzle view.
+#[derive(
+    Serialize, Deserialize, Debug, Display, Default, EnumIter, Copy, Clone, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ScreenCorner {
+    #[strum(serialize = "Top left")]
+    TopLeft,
+    #[strum(serialize = "Top right")]
+    TopRight,
+    #[default]
+    #[strum(serialize = "Bottom left")]
+    BottomLeft,
+    #[strum(serialize = "Bottom right")]
+    BottomRight,
+}
+impl ScreenCorner {
+    pub fn align(self) -> egui::Align2 {
+        match self {
+            ScreenCorner::TopLeft => egui::Align2::LEFT_TOP,
+            ScreenCorner::TopRight => egui::Align2::RIGHT_TOP,
+            ScreenCorner::BottomLeft => egui::Align2::LEFT_BOTTOM,
+            ScreenCorner::BottomRight => egui::Align2::RIGHT_BOTTOM,
+        }
+    }
+}
@@ -1,16 +1,231 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use super::{PerPuzzle, PerPuzzleFamily, ViewAngle};
+use crate::puzzle::{traits::*, NotationStyle, PuzzleTypeEnum, SolvedDefinition, Twist, TwistAxis};
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 #[serde(default)]
 pub struct InteractionPreferences {
     pub confirm_discard_only_when_scrambled: bool,
+    /// Whether to ask for confirmation before discarding a solve in progress.
+    pub confirm_discard_solve: bool,
+    /// Whether to pause the timer while the window is unfocused during a
+    /// solve, without counting the paused time.
+    pub pause_timer_on_focus_loss: bool,
+    /// Whether retrying a scramble starts the timer right away (after the
+    /// countdown, if it's enabled), unless there is inspection.
+    pub retry_starts_timer: bool,
+    pub scramble_on_startup: bool,
+    /// What the "New" command does. The "New (other)" command does the other
+    /// thing.
+    pub new_action: NewAction,
+    pub inspection: InspectionPreferences,
+    pub countdown: CountdownPreferences,
 
     pub drag_sensitivity: f32,
     pub realign_on_release: bool,
+    /// Whether to snap the view so that the nearest face, edge, or corner
+    /// points at the camera when the mouse is released after dragging, if the
+    /// puzzle isn't realigned instead.
+    pub snap_view_on_release: bool,
+    /// View angle to rotate to while peeking at the last layer.
+    pub peek_angle: ViewAngle,
     pub realign_on_keypress: bool,
     pub smart_realign: bool,
 
+    /// Minimum time between two presses of the same key, in milliseconds.
+    /// Presses that come sooner are ignored as key bounce. 0 disables this.
+    pub key_debounce_ms: u32,
+    /// Maximum time between pressing the two keys of a chord keybind, in
+    /// milliseconds. Keys that are part of a chord wait this long before
+    /// triggering their own keybinds.
+    pub chord_window_ms: u32,
+    /// Whether holding several layer keys grips every layer between them,
+    /// instead of only the layers for each key.
+    pub select_layer_ranges: bool,
+    /// Whether new puzzle keybinds use virtual keycodes, which depend on the
+    /// keyboard layout, instead of scancodes, which depend on the physical
+    /// key position.
+    pub puzzle_keybinds_use_keycodes: bool,
+    /// Which twist directions are considered clockwise for each puzzle.
+    pub twist_conventions: PerPuzzleFamily<TwistConvention>,
+    /// Notation style for each puzzle. Puzzles without one use their most
+    /// common style.
+    pub notation_styles: PerPuzzle<NotationStyle>,
+    /// Rules for when each puzzle counts as solved.
+    pub solved_definitions: PerPuzzle<SolvedDefinition>,
+    /// Maximum time between two clicks on the background of the puzzle view
+    /// for them to count as a double-click, which resets the view, in
+    /// milliseconds. 0 disables this.
+    pub double_click_ms: u32,
+    /// Whether to hide the mouse cursor while it is over the puzzle and there
+    /// hasn't been any mouse input for a while.
+    pub hide_cursor: bool,
+    /// Time without mouse input before the cursor is hidden, in seconds.
+    pub hide_cursor_delay: f32,
+
+    pub idle_rotation: IdleRotationPreferences,
+    pub idle_dim: IdleDimPreferences,
+
     pub dynamic_twist_speed: bool,
+    pub proportional_twist_duration: bool,
     pub twist_duration: f32,
     pub other_anim_duration: f32,
+    /// Whether to animate scrambles twist by twist instead of applying them
+    /// instantly.
+    pub animate_scramble: bool,
+    /// Duration of each twist in an animated scramble, in seconds.
+    pub scramble_twist_duration: f32,
+}
+impl InteractionPreferences {
+    /// Returns the notation style for a puzzle.
+    pub fn notation_style(&self, ty: PuzzleTypeEnum) -> NotationStyle {
+        match self.notation_styles.get(ty) {
+            Some(&style) => style,
+            None => ty.default_notation_style(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Copy, Clone)]
+#[serde(default)]
+pub struct IdleRotationPreferences {
+    /// Whether to slowly rotate the puzzle when there hasn't been any input
+    /// for a while.
+    pub enabled: bool,
+    /// Time without any input before the puzzle starts rotating, in seconds.
+    pub delay: f32,
+    /// Rotation speed around the vertical axis, in degrees per second.
+    pub yaw_speed: f32,
+    /// Rotation speed around the horizontal axis, in degrees per second.
+    pub pitch_speed: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Copy, Clone)]
+#[serde(default)]
+pub struct IdleDimPreferences {
+    /// Whether to dim the display and save everything when there hasn't been
+    /// any input for a while. Any input wakes the display up again.
+    pub enabled: bool,
+    /// Time without any input before the display is dimmed, in seconds.
+    pub delay: f32,
+    /// Brightness of the dimmed display, from 0.0 (black) to 1.0 (not
+    /// dimmed).
+    pub brightness: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Copy, Clone)]
+#[serde(default)]
+pub struct InspectionPreferences {
+    /// Whether to count down an inspection time after a full scramble, like
+    /// in competitions. The timer starts at the end of inspection.
+    pub enabled: bool,
+    /// Inspection time, in seconds. Going up to 2 seconds over this is a +2
+    /// penalty, and going any further over is a DNF.
+    pub duration: f32,
+    /// What happens when the puzzle is twisted during inspection. Rotating
+    /// the whole puzzle is always allowed.
+    pub twist_action: InspectionTwistAction,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Copy, Clone)]
+#[serde(default)]
+pub struct CountdownPreferences {
+    /// Whether to count down before the timer starts when it's started from
+    /// the timer window. Unlike inspection, the countdown always ends on time
+    /// and the puzzle can't be twisted until it does.
+    pub enabled: bool,
+    /// Countdown time, in seconds.
+    pub seconds: f32,
+}
+
+/// What happens when the puzzle is twisted during inspection.
+#[derive(
+    Serialize, Deserialize, Debug, Display, Default, EnumIter, Copy, Clone, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum InspectionTwistAction {
+    /// Start the solve with that twist.
+    #[default]
+    #[strum(serialize = "Start solve")]
+    StartSolve,
+    /// Start the solve with that twist, with a +2 penalty for applying moves
+    /// during inspection.
+    #[strum(serialize = "+2 penalty")]
+    Penalize,
+}
+
+/// What the "New" command does.
+#[derive(
+    Serialize, Deserialize, Debug, Display, Default, EnumIter, Copy, Clone, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum NewAction {
+    /// Scramble the current puzzle fully.
+    #[default]
+    #[strum(serialize = "New scramble")]
+    NewScramble,
+    /// Open the dialog for choosing a new puzzle.
+    #[strum(serialize = "Choose puzzle")]
+    ChoosePuzzle,
+}
+impl NewAction {
+    /// Returns the other action.
+    pub fn other(self) -> Self {
+        match self {
+            Self::NewScramble => Self::ChoosePuzzle,
+            Self::ChoosePuzzle => Self::NewScramble,
+        }
+    }
+}
+
+/// Twist axes whose directions are reversed compared to the standard
+/// convention, to match muscle memory or notation from other programs. This
+/// affects puzzle keybinds and notation shown in the UI, but log files always
+/// use the standard convention.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct TwistConvention {
+    /// Whether to reverse every twist axis.
+    pub reverse_all: bool,
+    /// Names of twist axes to reverse. If `reverse_all` is set, these are
+    /// reversed back to the standard convention instead.
+    pub reversed_axes: BTreeSet<String>,
+}
+impl TwistConvention {
+    /// Returns whether twists around `axis` are reversed.
+    pub fn is_reversed(&self, ty: PuzzleTypeEnum, axis: TwistAxis) -> bool {
+        self.reverse_all ^ self.reversed_axes.contains(ty.info(axis).name)
+    }
+    /// Converts a twist between this convention and the standard one. This
+    /// is its own inverse.
+    pub fn apply(&self, ty: PuzzleTypeEnum, twist: Twist) -> Twist {
+        if self.is_reversed(ty, twist.axis) {
+            ty.reverse_twist(twist)
+        } else {
+            twist
+        }
+    }
+
+    /// Returns the notation for a twist in this convention.
+    pub fn twist_to_string(
+        &self,
+        ty: PuzzleTypeEnum,
+        style: NotationStyle,
+        twist: Twist,
+    ) -> String {
+        ty.notation_scheme_for_style(style)
+            .twist_to_string(ty.canonicalize_twist(self.apply(ty, twist)))
+    }
+    /// Parses notation in this convention.
+    pub fn parse_alg(
+        &self,
+        ty: PuzzleTypeEnum,
+        style: NotationStyle,
+        s: &str,
+    ) -> Result<Vec<Twist>, String> {
+        let twists = ty.notation_scheme_for_style(style).parse_alg(s)?;
+        Ok(twists.into_iter().map(|t| self.apply(ty, t)).collect())
+    }
 }
@@ -40,6 +40,11 @@ pub struct KeyCombo {
     alt: bool,
     #[serde(skip_serializing_if = "is_false")]
     logo: bool,
+
+    /// Second key that must be pressed at nearly the same time as `key`, for
+    /// binding a chord of two keys.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chord: Option<Key>,
 }
 impl fmt::Display for KeyCombo {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -47,10 +52,22 @@ impl fmt::Display for KeyCombo {
         write!(f, "{}", mods)?;
 
         match self.key {
-            Some(Key::Sc(sc)) => write!(f, "{}", key_names::key_name(sc)),
+            Some(key) => write!(f, "{key}")?,
+            None => write!(f, "(no key set)")?,
+        }
+        if let Some(chord) = self.chord {
+            write!(f, "&{chord}")?;
+        }
+        Ok(())
+    }
+}
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Key::Sc(sc) => write!(f, "{}", key_names::key_name(sc)),
             // TODO: virtual key code names aren't platform-aware and might not
             // match scancode names
-            Some(Key::Vk(vk)) => match vk {
+            Key::Vk(vk) => match vk {
                 VirtualKeyCode::Key1 => write!(f, "1"),
                 VirtualKeyCode::Key2 => write!(f, "2"),
                 VirtualKeyCode::Key3 => write!(f, "3"),
@@ -67,7 +84,6 @@ impl fmt::Display for KeyCombo {
                 VirtualKeyCode::Capital => write!(f, "CapsLock"),
                 other => write!(f, "{:?}", other),
             },
-            None => write!(f, "(no key set)"),
         }
     }
 }
@@ -79,9 +95,16 @@ impl KeyCombo {
             shift: mods.shift(),
             alt: mods.alt(),
             logo: mods.logo(),
+            chord: None,
         }
         .validate()
     }
+    /// Returns the key combo with a second key that must be pressed at nearly
+    /// the same time as the first.
+    #[must_use]
+    pub fn with_chord(self, chord: Option<Key>) -> Self {
+        Self { chord, ..self }.validate()
+    }
     #[must_use]
     pub fn validate(self) -> Self {
         Self {
@@ -93,6 +116,11 @@ impl KeyCombo {
             shift: self.shift() && self.key().map_or(true, |k| !k.is_shift()),
             alt: self.alt() && self.key().map_or(true, |k| !k.is_alt()),
             logo: self.logo() && self.key().map_or(true, |k| !k.is_logo()),
+
+            // Chords need two different keys, neither of which is a modifier.
+            chord: self.chord.filter(|&chord| {
+                self.key().map_or(false, |k| k != chord && !k.is_modifier()) && !chord.is_modifier()
+            }),
         }
     }
     pub fn key(self) -> Option<Key> {
@@ -110,6 +138,14 @@ impl KeyCombo {
     pub fn logo(self) -> bool {
         self.logo
     }
+    pub fn chord(self) -> Option<Key> {
+        self.chord
+    }
+    /// Returns whether `key` is one of the keys in the combo, not counting
+    /// modifiers.
+    pub fn contains_key(self, key: Key) -> bool {
+        self.key() == Some(key) || self.chord() == Some(key)
+    }
 
     pub fn mods(self) -> ModifiersState {
         let mut ret = ModifiersState::empty();
@@ -175,6 +211,10 @@ impl Key {
         }
     }
 
+    pub fn is_modifier(self) -> bool {
+        !self.modifier_bit().is_empty()
+    }
+
     pub fn modifier_bit(self) -> ModifiersState {
         match self {
             _ if self.is_shift() => ModifiersState::SHIFT,
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// Position and open state of a window in the GUI.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct WindowLayout {
+    /// Whether the window is open.
+    pub is_open: bool,
+    /// Position of the top left corner of the window, in points, if it is a
+    /// floating window that has been shown.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pos: Option<[f32; 2]>,
+}
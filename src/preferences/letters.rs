@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{btree_map, BTreeMap};
+
+use super::PerPuzzle;
+use crate::puzzle::{traits::*, Face, PuzzleTypeEnum, Sticker};
+use crate::serde_impl::hex_color;
+
+/// Face symbols in Speffz order.
+const SPEFFZ_FACE_ORDER: [&str; 6] = ["U", "L", "F", "R", "B", "D"];
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct LetterPreferences {
+    /// Whether to draw letters on stickers.
+    pub visible: bool,
+    #[serde(with = "hex_color")]
+    pub color: egui::Color32,
+
+    /// Custom letter schemes. Puzzles without a custom scheme use Speffz.
+    pub schemes: PerPuzzle<LetterScheme>,
+
+    /// Buffer for tracing corners, named by its faces with the face of the
+    /// buffer sticker first.
+    pub corner_buffer: String,
+    /// Buffer for tracing edges, named the same way as `corner_buffer`.
+    pub edge_buffer: String,
+}
+impl LetterPreferences {
+    /// Returns the letter scheme for a puzzle.
+    pub fn scheme(&self, ty: PuzzleTypeEnum) -> Cow<'_, LetterScheme> {
+        match self.schemes.get(ty) {
+            Some(scheme) => Cow::Borrowed(scheme),
+            None => Cow::Owned(LetterScheme::speffz(ty)),
+        }
+    }
+    /// Returns the letter scheme for a puzzle, creating a custom one if there
+    /// isn't one already.
+    pub fn scheme_mut(&mut self, ty: PuzzleTypeEnum) -> &mut LetterScheme {
+        self.schemes
+            .entry(ty)
+            .or_insert_with(|| LetterScheme::speffz(ty))
+    }
+    /// Removes the custom letter scheme for a puzzle. Returns `true` if there
+    /// was one.
+    pub fn reset_scheme(&mut self, ty: PuzzleTypeEnum) -> bool {
+        match self.schemes.entry(ty) {
+            btree_map::Entry::Occupied(e) => {
+                e.remove();
+                true
+            }
+            btree_map::Entry::Vacant(_) => false,
+        }
+    }
+}
+
+/// Letter on each facelet of a puzzle, for blindfolded memorization. Each face
+/// symbol maps to the letters on that face in row-major order, using the
+/// layout from `PuzzleType::sticker_grid_position()`.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct LetterScheme(BTreeMap<String, Vec<String>>);
+impl LetterScheme {
+    /// Returns the Speffz lettering scheme for corners and middle edges, or an
+    /// empty scheme if the puzzle isn't a cube.
+    pub fn speffz(ty: PuzzleTypeEnum) -> Self {
+        let mut ret = Self::default();
+        let n = ty.layer_count();
+        if n < 2 || ty.faces().len() != 6 || ty.sticker_grid_position(Sticker(0)).is_none() {
+            return ret;
+        }
+        let (max, mid) = (n - 1, n / 2);
+        let corners = [[0, 0], [0, max], [max, max], [max, 0]];
+        let edges = [[0, mid], [mid, max], [max, mid], [mid, 0]];
+
+        let mut letters = ('A'..='X').map(String::from);
+        for symbol in SPEFFZ_FACE_ORDER {
+            let Some(face) = ty.face_from_symbol(symbol) else {
+                return Self::default();
+            };
+            for (corner, edge) in corners.into_iter().zip(edges) {
+                let letter = letters.next().unwrap_or_default();
+                if n % 2 == 1 {
+                    *ret.letter_mut(ty, face, edge) = letter.clone();
+                }
+                *ret.letter_mut(ty, face, corner) = letter;
+            }
+        }
+        ret
+    }
+
+    /// Returns the letter on a sticker, if it has one.
+    pub fn letter(&self, ty: PuzzleTypeEnum, sticker: Sticker) -> Option<&str> {
+        let [row, col] = ty.sticker_grid_position(sticker)?;
+        let face = ty.info(sticker).color;
+        let index = row as usize * ty.layer_count() as usize + col as usize;
+        let letter = self.0.get(ty.info(face).symbol)?.get(index)?;
+        (!letter.is_empty()).then_some(letter.as_str())
+    }
+    /// Returns the letter at a position on a face, which may be empty.
+    pub fn letter_mut(
+        &mut self,
+        ty: PuzzleTypeEnum,
+        face: Face,
+        [row, col]: [u8; 2],
+    ) -> &mut String {
+        let n = ty.layer_count() as usize;
+        let letters = self.0.entry(ty.info(face).symbol.to_owned()).or_default();
+        letters.resize(n * n, String::new());
+        &mut letters[row as usize * n + col as usize]
+    }
+}
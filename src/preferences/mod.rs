@@ -11,10 +11,14 @@ use std::ops::{Index, IndexMut};
 use std::path::PathBuf;
 
 mod colors;
+#[cfg(not(target_arch = "wasm32"))]
+mod gamepad;
 mod gfx;
 mod info;
 mod interaction;
 mod keybinds;
+mod layout;
+mod letters;
 mod migration;
 mod mousebinds;
 mod opacity;
@@ -23,15 +27,24 @@ mod outlines;
 mod persist_local;
 #[cfg(target_arch = "wasm32")]
 mod persist_web;
+#[cfg(not(target_arch = "wasm32"))]
+mod solve_log;
 mod view;
+#[cfg(not(target_arch = "wasm32"))]
+mod window;
 
-use crate::commands::{Command, PuzzleCommand, PuzzleMouseCommand};
-use crate::puzzle::{traits::*, ProjectionType, PuzzleTypeEnum};
+use crate::commands::{Command, LayerMaskDesc, PuzzleCommand, PuzzleMouseCommand};
+use crate::puzzle::{traits::*, LayerMask, ProjectionType, PuzzleTypeEnum};
+use crate::stats::PersonalBests;
 pub use colors::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub use gamepad::*;
 pub use gfx::*;
 pub use info::*;
 pub use interaction::*;
 pub use keybinds::*;
+pub use layout::*;
+pub use letters::*;
 pub use mousebinds::*;
 pub use opacity::*;
 pub use outlines::*;
@@ -39,7 +52,11 @@ pub use outlines::*;
 use persist_local as persist;
 #[cfg(target_arch = "wasm32")]
 use persist_web as persist;
+#[cfg(not(target_arch = "wasm32"))]
+pub use solve_log::*;
 pub use view::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub use window::*;
 
 const PREFS_FILE_FORMAT: config::FileFormat = config::FileFormat::Yaml;
 const DEFAULT_PREFS_STR: &str = include_str!("default.yaml");
@@ -63,6 +80,17 @@ pub struct Preferences {
     pub log_file: Option<PathBuf>,
 
     pub show_welcome_at_startup: bool,
+    /// Version of the program that was last run, which is used to show
+    /// what's new after an update.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub last_seen_version: String,
+    /// Position and open state of each window, by window name.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub layout: BTreeMap<String, WindowLayout>,
+    #[cfg(not(target_arch = "wasm32"))]
+    pub window: WindowPreferences,
+    /// Whether to show tools for debugging puzzle states in the "Tools" menu.
+    pub show_advanced_tools: bool,
 
     #[cfg(target_arch = "wasm32")]
     pub use_clipboard_fallback: bool,
@@ -73,54 +101,74 @@ pub struct Preferences {
     pub interaction: InteractionPreferences,
     pub opacity: OpacityPreferences,
     pub outlines: OutlinePreferences,
+    #[cfg(not(target_arch = "wasm32"))]
+    pub gamepad: GamepadPreferences,
 
     pub view_3d: WithPresets<ViewPreferences>,
     pub view_4d: WithPresets<ViewPreferences>,
+    /// View angle for each puzzle, overriding the one in the view settings.
+    pub view_angles: PerPuzzle<Option<ViewAngle>>,
 
     pub colors: ColorPreferences,
+    pub letters: LetterPreferences,
 
     pub piece_filters: PerPuzzle<Vec<Preset<PieceFilter>>>,
+    pub layer_groups: PerPuzzle<Vec<Preset<LayerGroup>>>,
+    pub patterns: PerPuzzle<Vec<Preset<Pattern>>>,
+
+    pub personal_bests: PerPuzzle<PersonalBests>,
+    #[cfg(not(target_arch = "wasm32"))]
+    pub solve_log: SolveLogPreferences,
 
     pub global_keybinds: Vec<Keybind<Command>>,
     pub puzzle_keybinds: PerPuzzleFamily<PuzzleKeybindSets>,
     pub mousebinds: Vec<Mousebind<PuzzleMouseCommand>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    pub gamepad_binds: Vec<GamepadBind<Command>>,
 }
 impl Preferences {
     pub fn load(backup: Option<&Self>) -> Self {
+        // Load user preferences.
+        let user_config_source = persist::user_config_source()
+            .map_err(|e| log::warn!("Error loading user preferences: {}", e))
+            .ok();
+
+        Self::load_from_source(user_config_source).unwrap_or_else(|e| {
+            log::warn!("Error loading preferences: {}", e);
+
+            // Only warn the user if there was actually a file to back up.
+            if let Some(backup_path) = persist::backup_prefs_file() {
+                show_load_error_dialog(&e, &backup_path);
+            }
+
+            // Try backup
+            backup
+                .cloned()
+                // Try just default config
+                .or_else(|| {
+                    Self::load_from_source(
+                        None::<config::File<config::FileSourceString, config::FileFormat>>,
+                    )
+                    .ok()
+                })
+                .unwrap_or_default()
+        })
+    }
+    /// Loads the default preferences, overridden by the user preferences if
+    /// given.
+    fn load_from_source(
+        user_config_source: Option<impl 'static + config::Source + Send + Sync>,
+    ) -> Result<Self, config::ConfigError> {
         let mut config = config::Config::builder();
 
         // Load default preferences.
-        let default_config_source = config::File::from_str(DEFAULT_PREFS_STR, PREFS_FILE_FORMAT);
-        config = config.add_source(default_config_source.clone());
+        config = config.add_source(config::File::from_str(DEFAULT_PREFS_STR, PREFS_FILE_FORMAT));
 
-        // Load user preferences.
-        match persist::user_config_source() {
-            Ok(config_source) => config = config.add_source(config_source),
-            Err(e) => log::warn!("Error loading user preferences: {}", e),
+        if let Some(config_source) = user_config_source {
+            config = config.add_source(config_source);
         }
 
-        config
-            .build()
-            .and_then(migration::try_deserialize)
-            .unwrap_or_else(|e| {
-                log::warn!("Error loading preferences: {}", e);
-
-                persist::backup_prefs_file();
-
-                // Try backup
-                backup
-                    .cloned()
-                    // Try just default config
-                    .or_else(|| {
-                        config::Config::builder()
-                            .add_source(default_config_source)
-                            .build()
-                            .ok()?
-                            .try_deserialize()
-                            .ok()
-                    })
-                    .unwrap_or_default()
-            })
+        config.build().and_then(migration::try_deserialize)
     }
 
     pub fn save(&mut self) {
@@ -129,6 +177,7 @@ impl Preferences {
 
             // Clear empty entries.
             self.piece_filters.map.retain(|_k, v| !v.is_empty());
+            self.layer_groups.map.retain(|_k, v| !v.is_empty());
 
             // Set version number.
             self.version = migration::LATEST_VERSION;
@@ -142,6 +191,20 @@ impl Preferences {
         }
     }
 
+    /// Records that `version` of the program has been run. If a different
+    /// version was run before, returns that version, which is empty if it is
+    /// unknown.
+    pub fn update_last_seen_version(&mut self, version: &str) -> Option<String> {
+        if self.last_seen_version == version {
+            return None;
+        }
+        self.needs_save = true;
+        Some(std::mem::replace(
+            &mut self.last_seen_version,
+            version.to_owned(),
+        ))
+    }
+
     pub fn view(&self, ty: impl PuzzleType) -> &ViewPreferences {
         match ty.projection_type() {
             ProjectionType::_3D => &self.view_3d.current,
@@ -210,6 +273,55 @@ impl PuzzleKeybindSets {
             .into_iter()
             .flat_map(|set| &set.value.keybinds)
     }
+
+    /// Returns each keybind in `sets` whose key is bound to a different
+    /// command in an existing set with the same name, along with the name of
+    /// the set and the existing command.
+    pub fn conflicts<'a>(
+        &'a self,
+        sets: &'a [Preset<KeybindSet<PuzzleCommand>>],
+    ) -> Vec<(&'a str, &'a Keybind<PuzzleCommand>, &'a PuzzleCommand)> {
+        let mut ret = vec![];
+        for set in sets {
+            let Some(existing) = self.get(&set.preset_name) else {
+                continue;
+            };
+            for bind in &set.value.keybinds {
+                ret.extend(
+                    existing
+                        .value
+                        .keybinds
+                        .iter()
+                        .filter(|old| old.key == bind.key && old.command != bind.command)
+                        .map(|old| (set.preset_name.as_str(), bind, &old.command)),
+                );
+            }
+        }
+        ret
+    }
+    /// Adds keybind sets, combining them with existing sets of the same name.
+    /// Keybinds from `sets` replace existing keybinds for the same key.
+    pub fn merge(&mut self, sets: Vec<Preset<KeybindSet<PuzzleCommand>>>) {
+        for set in sets {
+            let existing = &mut self.get_mut(&set.preset_name).value;
+            let new_binds = set.value.keybinds;
+            existing
+                .keybinds
+                .retain(|old| new_binds.iter().all(|bind| bind.key != old.key));
+            existing.keybinds.extend(new_binds);
+            existing.includes.extend(set.value.includes);
+        }
+    }
+    /// Replaces all keybind sets.
+    pub fn replace(&mut self, sets: Vec<Preset<KeybindSet<PuzzleCommand>>>) {
+        self.sets = sets;
+        if self.get(&self.active).is_none() {
+            self.active = match self.sets.first() {
+                Some(set) => set.preset_name.clone(),
+                None => String::new(),
+            };
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
@@ -257,6 +369,9 @@ impl<T: Default> IndexMut<PuzzleTypeEnum> for PerPuzzle<T> {
     }
 }
 impl<T> PerPuzzle<T> {
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.map.values_mut()
+    }
     fn entry(&mut self, puzzle_type: PuzzleTypeEnum) -> btree_map::Entry<'_, String, T> {
         self.map.entry(puzzle_type.name().to_owned())
     }
@@ -308,3 +423,130 @@ pub struct PieceFilter {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub hidden_opacity: Option<f32>,
 }
+
+/// Named set of layers that can be twisted together by a keybind.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct LayerGroup {
+    pub layers: LayerMaskDesc,
+}
+impl LayerGroup {
+    /// Returns the layers in the group, or an error if the group doesn't fit
+    /// a puzzle with the given number of layers.
+    pub fn layer_mask(&self, layer_count: u8) -> Result<LayerMask, &'static str> {
+        if !self.layers.fits_layer_count(layer_count) {
+            return Err("layer group refers to layers outside the puzzle");
+        }
+        match self.layers.to_layer_mask(layer_count) {
+            LayerMask(0) => Err("layer group is empty"),
+            mask => Ok(mask),
+        }
+    }
+}
+
+/// Named algorithm that produces a pattern when applied to a solved puzzle.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct Pattern {
+    /// Twists in the puzzle's notation, separated by whitespace.
+    pub alg: String,
+    /// Finger-trick hint for each twist (such as "right index"), shown when
+    /// demonstrating the pattern.
+    pub hints: Vec<String>,
+}
+
+fn show_load_error_dialog(e: &config::ConfigError, backup_path: &std::path::Path) {
+    rfd::MessageDialog::new()
+        .set_level(rfd::MessageLevel::Warning)
+        .set_title("Unable to load preferences")
+        .set_description(&format!(
+            "{e}\n\n\
+             Default preferences will be used instead. \
+             The old preferences file has been moved to {}",
+            backup_path.display(),
+        ))
+        .show();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user_source(s: &str) -> Option<impl 'static + config::Source + Send + Sync> {
+        Some(config::File::from_str(s, PREFS_FILE_FORMAT))
+    }
+
+    #[test]
+    fn test_load_malformed_preferences() {
+        // Syntax error
+        assert!(Preferences::load_from_source(user_source("version: 1\ninfo: [")).is_err());
+        // Type error
+        assert!(Preferences::load_from_source(user_source("version: 1\ninfo: 5")).is_err());
+
+        // Valid preferences are merged with the defaults.
+        let prefs = Preferences::load_from_source(user_source(
+            "version: 1\nshow_welcome_at_startup: false",
+        ))
+        .unwrap();
+        assert!(!prefs.show_welcome_at_startup);
+        assert_eq!(prefs.gfx.fps_limit, DEFAULT_PREFS.gfx.fps_limit);
+    }
+
+    #[test]
+    fn test_chord_key_combo() {
+        use key_names::KeyMappingCode;
+        use winit::event::ModifiersState;
+
+        let combo: KeyCombo =
+            serde_yaml::from_str("sc: KeyJ\nchord:\n  sc: KeyK\nctrl: true").unwrap();
+        assert_eq!(combo.key(), Some(Key::Sc(KeyMappingCode::KeyJ)));
+        assert_eq!(combo.chord(), Some(Key::Sc(KeyMappingCode::KeyK)));
+        assert!(combo.ctrl());
+        assert!(combo.contains_key(Key::Sc(KeyMappingCode::KeyK)));
+        let s = serde_yaml::to_string(&combo).unwrap();
+        assert_eq!(serde_yaml::from_str::<KeyCombo>(&s).unwrap(), combo);
+
+        // Chords need two different keys that aren't modifiers.
+        let j = Some(Key::Sc(KeyMappingCode::KeyJ));
+        let mods = ModifiersState::empty();
+        let combo = KeyCombo::new(j, mods);
+        assert_eq!(combo.with_chord(j).chord(), None);
+        let shift = Some(Key::Sc(KeyMappingCode::ShiftLeft));
+        assert_eq!(combo.with_chord(shift).chord(), None);
+        assert_eq!(KeyCombo::new(None, mods).with_chord(j).chord(), None);
+    }
+
+    #[test]
+    fn test_update_last_seen_version() {
+        let mut prefs = Preferences::default();
+        assert_eq!(prefs.update_last_seen_version("1.0.9"), Some(String::new()));
+        assert!(prefs.needs_save);
+
+        prefs.needs_save = false;
+        assert_eq!(prefs.update_last_seen_version("1.0.9"), None);
+        assert!(!prefs.needs_save);
+
+        assert_eq!(
+            prefs.update_last_seen_version("1.1.0"),
+            Some("1.0.9".to_string()),
+        );
+        assert_eq!(prefs.last_seen_version, "1.1.0");
+    }
+
+    #[test]
+    fn test_default_patterns() {
+        use crate::puzzle::Puzzle;
+
+        let ty = PuzzleTypeEnum::Rubiks3D { layer_count: 3 };
+        let patterns = &DEFAULT_PREFS.patterns[ty];
+        assert!(patterns.iter().any(|p| p.preset_name == "Superflip"));
+        for pattern in patterns {
+            let twists = ty.notation_scheme().parse_alg(&pattern.value.alg).unwrap();
+            let mut puzzle = Puzzle::new(ty);
+            for twist in twists {
+                puzzle.twist(twist).unwrap();
+            }
+            assert!(!puzzle.is_solved(), "{} is solved", pattern.preset_name);
+        }
+    }
+}
@@ -10,5 +10,11 @@ pub struct OpacityPreferences {
 
     pub unhide_grip: bool,
 
+    /// Whether to draw every sticker semi-transparent, including the far
+    /// side of each sticker, to show the whole structure of the puzzle.
+    pub see_through: bool,
+    /// Opacity multiplier for every sticker in see-through mode.
+    pub see_through_opacity: f32,
+
     pub save_opacity_in_piece_filter_preset: bool,
 }
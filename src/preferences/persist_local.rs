@@ -75,30 +75,30 @@ pub fn save(prefs_data: &impl Serialize) -> anyhow::Result<()> {
     Ok(())
 }
 
-pub fn backup_prefs_file() {
-    if let Ok(prefs_path) = &*PREFS_FILE_PATH {
-        let mut backup_path = prefs_path.clone();
-        backup_path.pop();
+/// Moves the preferences file to a timestamped backup file. Returns the path
+/// of the backup, or `None` if there was no file to back up.
+pub fn backup_prefs_file() -> Option<PathBuf> {
+    let prefs_path = PREFS_FILE_PATH.as_ref().ok()?;
+    let mut backup_path = prefs_path.clone();
+    backup_path.pop();
 
-        let now =
-            time::OffsetDateTime::now_local().unwrap_or_else(|_| time::OffsetDateTime::now_utc());
-        backup_path.push(format!(
-            "{}_{:04}-{:02}-{:02}_{:02}-{:02}-{:02}_bak.{}",
-            PREFS_FILE_NAME,
-            now.year(),
-            now.month() as u8,
-            now.day(),
-            now.hour(),
-            now.minute(),
-            now.second(),
-            PREFS_FILE_EXTENSION,
-        ));
+    let now = time::OffsetDateTime::now_local().unwrap_or_else(|_| time::OffsetDateTime::now_utc());
+    backup_path.push(format!(
+        "{}_{:04}-{:02}-{:02}_{:02}-{:02}-{:02}_bak.{}",
+        PREFS_FILE_NAME,
+        now.year(),
+        now.month() as u8,
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second(),
+        PREFS_FILE_EXTENSION,
+    ));
 
-        if std::fs::rename(prefs_path, &backup_path).is_ok() {
-            log::info!(
-                "Backup of old preferences stored at {}",
-                backup_path.display(),
-            );
-        }
-    }
+    std::fs::rename(prefs_path, &backup_path).ok()?;
+    log::info!(
+        "Backup of old preferences stored at {}",
+        backup_path.display(),
+    );
+    Some(backup_path)
 }
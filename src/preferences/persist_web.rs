@@ -31,8 +31,9 @@ pub fn save(prefs_data: &impl Serialize) -> anyhow::Result<()> {
         .map_err(|e| anyhow!(format!("{e:?}")))
 }
 
-pub fn backup_prefs_file() {
-    log::warn!("Cannot backup preferences on web")
+pub fn backup_prefs_file() -> Option<std::path::PathBuf> {
+    log::warn!("Cannot backup preferences on web");
+    None
 }
 
 fn local_storage() -> Result<web_sys::Storage, PrefsError> {
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::logfile::solve_log::Rotation;
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct SolveLogPreferences {
+    /// Whether to append every completed solve to a solve log in
+    /// `directory`, separately from saving the session.
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub directory: Option<PathBuf>,
+    /// Size at which a new solve log is started, in kilobytes. 0 means there
+    /// is no maximum.
+    pub max_size_kb: u32,
+    /// Number of old solve logs to keep.
+    pub old_file_count: usize,
+}
+impl SolveLogPreferences {
+    /// Returns when to start a new solve log.
+    pub fn rotation(&self) -> Rotation {
+        Rotation {
+            max_bytes: self.max_size_kb as u64 * 1024,
+            old_file_count: self.old_file_count,
+        }
+    }
+}
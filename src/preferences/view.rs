@@ -1,4 +1,4 @@
-use cgmath::{Deg, Quaternion, Rotation3};
+use cgmath::{Deg, Matrix3, Quaternion, Rad, Rotation3};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -29,6 +29,9 @@ pub struct ViewPreferences {
 
     pub face_spacing: f32,
     pub sticker_spacing: f32,
+    /// How far pieces move outward when the puzzle is exploded, relative to
+    /// their distance from the center.
+    pub explode: f32,
 
     pub outline_thickness: f32,
 
@@ -37,6 +40,38 @@ pub struct ViewPreferences {
     pub light_pitch: f32,
     pub light_yaw: f32,
 }
+/// Puzzle orientation, in the same form as in `ViewPreferences`.
+#[derive(Serialize, Deserialize, Debug, Default, Copy, Clone, PartialEq)]
+#[serde(default)]
+pub struct ViewAngle {
+    /// Puzzle angle around X axis, in degrees.
+    pub pitch: f32,
+    /// Puzzle angle around Y axis, in degrees.
+    pub yaw: f32,
+    /// Puzzle angle around Z axis, in degrees.
+    pub roll: f32,
+}
+impl ViewAngle {
+    /// Decomposes a rotation into the angles used by
+    /// `ViewPreferences::view_angle()`.
+    pub fn from_quaternion(q: Quaternion<f32>) -> Self {
+        // The rotation is `Rz(roll) * Rx(pitch) * Ry(yaw)`. Note that cgmath
+        // matrices are column-major.
+        let m = Matrix3::from(q);
+        Self {
+            pitch: Deg::from(Rad(m.y.z.clamp(-1.0, 1.0).asin())).0,
+            yaw: Deg::from(Rad((-m.x.z).atan2(m.z.z))).0,
+            roll: Deg::from(Rad((-m.y.x).atan2(m.y.y))).0,
+        }
+    }
+    /// Returns the rotation for these angles.
+    pub fn to_quaternion(self) -> Quaternion<f32> {
+        Quaternion::from_angle_z(Deg(self.roll))
+            * Quaternion::from_angle_x(Deg(self.pitch))
+            * Quaternion::from_angle_y(Deg(self.yaw))
+    }
+}
+
 impl Default for ViewPreferences {
     fn default() -> Self {
         Self {
@@ -53,6 +88,7 @@ impl Default for ViewPreferences {
 
             face_spacing: 0.0,
             sticker_spacing: 0.0,
+            explode: 1.0,
 
             show_frontfaces: true,
             show_backfaces: true,
@@ -70,9 +106,17 @@ impl Default for ViewPreferences {
 
 impl ViewPreferences {
     pub fn view_angle(&self) -> Quaternion<f32> {
-        Quaternion::from_angle_z(Deg(self.roll))
-            * Quaternion::from_angle_x(Deg(self.pitch))
-            * Quaternion::from_angle_y(Deg(self.yaw))
+        ViewAngle {
+            pitch: self.pitch,
+            yaw: self.yaw,
+            roll: self.roll,
+        }
+        .to_quaternion()
+    }
+    pub fn set_view_angle(&mut self, angle: ViewAngle) {
+        self.pitch = angle.pitch;
+        self.yaw = angle.yaw;
+        self.roll = angle.roll;
     }
 
     // TODO: make a proc macro crate to generate a trait impl like this
@@ -103,6 +147,7 @@ impl ViewPreferences {
             clip_4d: if t < 0.5 { self.clip_4d } else { rhs.clip_4d },
             face_spacing: crate::util::mix(self.face_spacing, rhs.face_spacing, t),
             sticker_spacing: crate::util::mix(self.sticker_spacing, rhs.sticker_spacing, t),
+            explode: crate::util::mix(self.explode, rhs.explode, t),
             outline_thickness: crate::util::mix(self.outline_thickness, rhs.outline_thickness, t),
             light_ambient: crate::util::mix(self.light_ambient, rhs.light_ambient, t),
             light_directional: crate::util::mix(self.light_directional, rhs.light_directional, t),
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct WindowPreferences {
+    /// Whether to restore the position and size of the window on startup.
+    pub remember_geometry: bool,
+    /// Position and size of the window when the program was last closed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub geometry: Option<WindowGeometry>,
+}
+
+/// Position and size of the window, in physical pixels.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct WindowGeometry {
+    /// Name of the monitor that the window was on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monitor: Option<String>,
+    /// Position of the top left corner of the window, including decorations.
+    pub x: i32,
+    pub y: i32,
+    /// Size of the window, not including decorations.
+    pub width: u32,
+    pub height: u32,
+    pub maximized: bool,
+}
@@ -0,0 +1,249 @@
+//! Tracing the piece cycles of a scrambled puzzle as letters, for checking
+//! memorization for blindfolded solving.
+//!
+//! Each kind of piece is traced from a buffer sticker: the letter of the
+//! sticker on the buffer is the first target, the letter of the sticker on
+//! that target is the next one, and so on until the buffer piece comes back
+//! around. Then a cycle break starts a new cycle at the unsolved piece with
+//! the lowest letter, and ends it on the same piece. Pieces that are in place
+//! but twisted or flipped are listed separately. This is only supported for
+//! the 2x2x2 (corners) and the 3x3x3 (corners and edges).
+
+use itertools::Itertools;
+use std::collections::HashMap;
+
+use super::commutator::piece_name;
+use super::*;
+use crate::preferences::LetterScheme;
+
+/// Targets for one kind of piece, traced from a buffer.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PieceTrace {
+    /// Letter of each target, in order. A cycle break adds the letter of the
+    /// piece it breaks into at the start and end of the cycle.
+    pub letters: Vec<String>,
+    /// Names of the pieces other than the buffer that are in place but
+    /// twisted or flipped.
+    pub twisted: Vec<String>,
+}
+impl PieceTrace {
+    /// Returns the letters grouped into pairs, such as `AB CD E`.
+    pub fn letter_pairs(&self) -> String {
+        self.letters.chunks(2).map(|pair| pair.concat()).join(" ")
+    }
+}
+
+/// Targets for every kind of piece of a scrambled puzzle.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BlindTrace {
+    pub corners: PieceTrace,
+    /// Edge targets, or `None` if the puzzle has no edges.
+    pub edges: Option<PieceTrace>,
+}
+impl BlindTrace {
+    /// Returns whether there is an odd number of corner targets, which leaves
+    /// two corners and two edges swapped.
+    pub fn has_parity(&self) -> bool {
+        self.corners.letters.len() % 2 == 1
+    }
+}
+
+/// Returns whether there are blind traces for a puzzle type.
+pub fn is_supported(ty: PuzzleTypeEnum) -> bool {
+    matches!(ty, PuzzleTypeEnum::Rubiks3D { layer_count: 2 | 3 })
+}
+
+/// Traces the corners and edges of a puzzle with the letters from `scheme`.
+/// Each buffer is named by the faces of the piece, such as `UFR`, and the
+/// first face is the one with the buffer sticker.
+pub fn blind_trace(
+    puzzle: &Puzzle,
+    scheme: &LetterScheme,
+    corner_buffer: &str,
+    edge_buffer: &str,
+) -> Result<BlindTrace, String> {
+    let ty = puzzle.ty();
+    if !is_supported(ty) {
+        return Err(format!("{} does not support blind traces", ty.name()));
+    }
+    let unsupported = || format!("{} does not support sticker locations", ty.name());
+
+    // Find which sticker is on the solved location of each sticker.
+    let stickers = (0..ty.stickers().len() as _).map(Sticker);
+    let solved = Puzzle::new(ty);
+    let homes = stickers
+        .clone()
+        .map(|sticker| Some((solved.sticker_grid_location(sticker)?, sticker)))
+        .collect::<Option<HashMap<_, _>>>()
+        .ok_or_else(unsupported)?;
+    let mut occupants = vec![Sticker(0); ty.stickers().len()];
+    for sticker in stickers {
+        let location = puzzle
+            .sticker_grid_location(sticker)
+            .ok_or_else(unsupported)?;
+        let home = homes.get(&location).ok_or_else(unsupported)?;
+        occupants[home.0 as usize] = sticker;
+    }
+
+    // Letters are relative to the centers, so the centers must be solved.
+    let centers_solved = (0..ty.pieces().len() as _)
+        .map(Piece)
+        .filter(|&piece| ty.info(piece).stickers.len() == 1)
+        .flat_map(|piece| &ty.info(piece).stickers)
+        .all(|&sticker| occupants[sticker.0 as usize] == sticker);
+    if !centers_solved {
+        return Err("Centers must be solved, without rotating the puzzle".to_string());
+    }
+
+    Ok(BlindTrace {
+        corners: trace_pieces(ty, scheme, &occupants, 3, corner_buffer)?,
+        edges: match ty.layer_count() {
+            3 => Some(trace_pieces(ty, scheme, &occupants, 2, edge_buffer)?),
+            _ => None,
+        },
+    })
+}
+
+/// Traces the pieces with `sticker_count` stickers from the buffer named
+/// `buffer`, given the sticker on the solved location of each sticker.
+fn trace_pieces(
+    ty: PuzzleTypeEnum,
+    scheme: &LetterScheme,
+    occupants: &[Sticker],
+    sticker_count: usize,
+    buffer: &str,
+) -> Result<PieceTrace, String> {
+    let letter = |sticker: Sticker| scheme.letter(ty, sticker).unwrap_or("?").to_owned();
+    let occupant = |sticker: Sticker| occupants[sticker.0 as usize];
+    let piece_of = |sticker: Sticker| ty.info(sticker).piece;
+    // Cycle breaks target the lowest letter of a piece.
+    let target = |piece: Piece| {
+        let stickers = ty.info(piece).stickers.iter().copied();
+        stickers.min_by_key(|&s| letter(s)).unwrap_or_default()
+    };
+
+    let buffer_sticker = find_buffer(ty, sticker_count, buffer)?;
+    let buffer_piece = piece_of(buffer_sticker);
+
+    let mut ret = PieceTrace::default();
+    let mut unsolved = vec![];
+    let pieces = (0..ty.pieces().len() as _)
+        .map(Piece)
+        .filter(|&piece| ty.info(piece).stickers.len() == sticker_count)
+        .filter(|&piece| piece != buffer_piece)
+        .sorted_by_key(|&piece| letter(target(piece)));
+    for piece in pieces {
+        let stickers = &ty.info(piece).stickers;
+        if stickers.iter().all(|&s| occupant(s) == s) {
+            continue;
+        } else if stickers.iter().all(|&s| piece_of(occupant(s)) == piece) {
+            ret.twisted.push(piece_name(ty, piece));
+        } else {
+            unsolved.push(piece);
+        }
+    }
+    let mut unsolved = unsolved.into_iter();
+
+    let mut location = buffer_sticker;
+    let mut cycle_piece = buffer_piece;
+    let mut visited = vec![false; ty.pieces().len()];
+    visited[buffer_piece.0 as usize] = true;
+    // Every step visits a new location, so this is more than enough.
+    for _ in 0..=2 * ty.stickers().len() {
+        let sticker = occupant(location);
+        if piece_of(sticker) != cycle_piece {
+            ret.letters.push(letter(sticker));
+            visited[piece_of(sticker).0 as usize] = true;
+            location = sticker;
+            continue;
+        }
+
+        // The cycle is done.
+        if cycle_piece != buffer_piece {
+            ret.letters.push(letter(sticker));
+        }
+        let Some(piece) = unsolved.find(|piece| !visited[piece.0 as usize]) else {
+            return Ok(ret);
+        };
+        visited[piece.0 as usize] = true;
+        location = target(piece);
+        ret.letters.push(letter(location));
+        cycle_piece = piece;
+    }
+    Err("Failed to trace the puzzle".to_string())
+}
+
+/// Returns the buffer sticker for pieces with `sticker_count` stickers, given
+/// the faces of the buffer piece with the face of the buffer sticker first.
+fn find_buffer(ty: PuzzleTypeEnum, sticker_count: usize, buffer: &str) -> Result<Sticker, String> {
+    let symbols = buffer.trim().to_uppercase();
+    let Some(first_symbol) = symbols.get(..1) else {
+        return Err("Missing buffer".to_string());
+    };
+    (0..ty.pieces().len() as _)
+        .map(Piece)
+        .filter(|&piece| ty.info(piece).stickers.len() == sticker_count)
+        .filter(|&piece| {
+            piece_name(ty, piece)
+                .chars()
+                .sorted()
+                .eq(symbols.chars().sorted())
+        })
+        .flat_map(|piece| ty.info(piece).stickers.iter().copied())
+        .find(|&sticker| ty.info(ty.info(sticker).color).symbol == first_symbol)
+        .ok_or_else(|| format!("There is no buffer {symbols}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trace_alg(ty: PuzzleTypeEnum, alg: &str) -> Result<BlindTrace, String> {
+        let mut puzzle = Puzzle::new(ty);
+        for twist in ty.notation_scheme().parse_alg(alg).unwrap() {
+            puzzle.twist(twist).unwrap();
+        }
+        blind_trace(&puzzle, &LetterScheme::speffz(ty), "UFR", "UF")
+    }
+
+    #[test]
+    fn test_blind_trace() {
+        let ty = PuzzleTypeEnum::Rubiks3D { layer_count: 3 };
+
+        let trace = trace_alg(ty, "").unwrap();
+        assert!(trace.corners.letters.is_empty());
+        assert_eq!(trace.edges, Some(PieceTrace::default()));
+        assert!(!trace.has_parity());
+
+        // A corner 3-cycle through the buffer has two targets.
+        let trace = trace_alg(ty, "R U R' D R U' R' D'").unwrap();
+        assert_eq!(trace.corners.letters.len(), 2);
+        assert!(trace.corners.twisted.is_empty());
+        assert_eq!(trace.edges, Some(PieceTrace::default()));
+        assert!(!trace.has_parity());
+
+        // The T permutation swaps the buffer with a corner, and swaps two
+        // edges that don't include the buffer, which takes a cycle break.
+        let trace = trace_alg(ty, "R U R' U' R' F R2 U' R' U' R U R' F'").unwrap();
+        assert_eq!(trace.corners.letters.len(), 1);
+        assert!(trace.has_parity());
+        let edges = trace.edges.unwrap();
+        assert_eq!(edges.letters.len(), 3);
+        assert_eq!(edges.letters[0], edges.letters[2]);
+        assert_eq!(edges.letter_pairs().split(' ').count(), 2);
+
+        // A superflip flips every edge in place.
+        let superflip = "U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2";
+        let edges = trace_alg(ty, superflip).unwrap().edges.unwrap();
+        assert!(edges.letters.is_empty());
+        assert_eq!(edges.twisted.len(), 11);
+
+        assert!(trace_alg(ty, "x").is_err());
+        let speffz = LetterScheme::speffz(ty);
+        assert!(blind_trace(&Puzzle::new(ty), &speffz, "UFB", "UF").is_err());
+
+        let ty = PuzzleTypeEnum::Rubiks3D { layer_count: 2 };
+        assert_eq!(trace_alg(ty, "").unwrap().edges, None);
+        assert!(!is_supported(PuzzleTypeEnum::Rubiks3D { layer_count: 4 }));
+    }
+}
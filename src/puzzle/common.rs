@@ -1,13 +1,15 @@
-use cgmath::{One, Quaternion, Rotation};
+use cgmath::{Angle, One, Quaternion, Rad, Rotation};
 use enum_iterator::Sequence;
 use itertools::Itertools;
 use rand::Rng;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
+use std::collections::HashMap;
 use std::fmt;
 use std::ops::*;
 use std::str::FromStr;
+use std::sync::{Arc, Mutex};
 use strum::{Display, EnumIter, EnumMessage};
 
 use super::*;
@@ -35,6 +37,13 @@ pub trait PuzzleType {
     fn twist_directions(&self) -> &[TwistDirectionInfo];
     fn piece_types(&self) -> &[PieceTypeInfo];
 
+    /// Returns the row and column of a sticker's solved position within the
+    /// grid of stickers on its face, as seen from outside the puzzle in a
+    /// standard net, or `None` if the puzzle's faces aren't laid out as grids.
+    fn sticker_grid_position(&self, _sticker: Sticker) -> Option<[u8; 2]> {
+        None
+    }
+
     fn twist_axis_from_name(&self, name: &str) -> Option<TwistAxis> {
         (0..self.twist_axes().len() as u8)
             .map(TwistAxis)
@@ -45,9 +54,55 @@ pub trait PuzzleType {
             .map(TwistDirection)
             .find(|&twist_direction| self.info(twist_direction).name == name)
     }
+    /// Returns the twist direction that rotates by `increments` of the
+    /// smallest increment of an axis, positive for clockwise.
+    fn twist_direction_from_increments(&self, increments: i8) -> Option<TwistDirection> {
+        (0..self.twist_directions().len() as u8)
+            .map(TwistDirection)
+            .find(|&twist_direction| self.info(twist_direction).increments == increments)
+    }
     fn opposite_twist_axis(&self, twist_axis: TwistAxis) -> Option<TwistAxis>;
-    fn count_quarter_turns(&self, twist: Twist) -> usize;
 
+    /// Returns the number of increments that a twist rotates by, positive for
+    /// clockwise, and the number of increments in a full turn around its
+    /// axis. Returns `None` if twists around the axis are not multiples of a
+    /// single increment.
+    fn twist_increments(&self, twist: Twist) -> Option<(i8, u8)> {
+        let per_turn = self.info(twist.axis).increments_per_turn?;
+        Some((self.info(twist.direction).increments, per_turn))
+    }
+    /// Returns the number of increments that a twist rotates by, so a quarter
+    /// turn on a cube counts as one and a half turn counts as two.
+    fn count_quarter_turns(&self, twist: Twist) -> usize {
+        self.twist_increments(twist)
+            .map_or(1, |(increments, _)| increments.unsigned_abs() as usize)
+    }
+    /// Returns the angle that a twist rotates pieces by.
+    ///
+    /// Puzzles with twist axes that have no increment must override this.
+    fn twist_angle(&self, twist: Twist) -> Rad<f32> {
+        let (increments, per_turn) = self
+            .twist_increments(twist)
+            .expect("twist axis has no increment");
+        Rad::full_turn() * increments.unsigned_abs() as f32 / per_turn as f32
+    }
+
+    /// Returns an error if `twist` does not exist on the puzzle, such as a
+    /// twist by a number of increments that its axis doesn't support.
+    fn check_twist(&self, twist: Twist) -> Result<(), &'static str> {
+        if twist.axis.0 as usize >= self.twist_axes().len() {
+            return Err("invalid twist axis");
+        }
+        if twist.direction.0 as usize >= self.twist_directions().len() {
+            return Err("invalid twist direction");
+        }
+        if let Some((increments, per_turn)) = self.twist_increments(twist) {
+            if increments as i32 % per_turn as i32 == 0 {
+                return Err("twist direction is not supported by twist axis");
+            }
+        }
+        self.check_layers(twist.layers)
+    }
     fn check_layers(&self, layers: LayerMask) -> Result<(), &'static str> {
         let layer_count = self.layer_count() as u32;
         if layers.0 > 0 || layers.0 < 1 << layer_count {
@@ -76,11 +131,71 @@ pub trait PuzzleType {
         }
     }
     fn canonicalize_twist(&self, twist: Twist) -> Twist;
+    /// Returns the twist that has the same effect as `twist` applied after
+    /// the whole-puzzle rotations `rotations`, but without the rotations. For
+    /// example, `x U` on a cube has the same effect as `F x`. Returns `None` if
+    /// this isn't supported for the puzzle.
+    fn unrotate_twist(&self, _rotations: &[Twist], _twist: Twist) -> Option<Twist> {
+        None
+    }
 
-    fn reverse_twist_direction(&self, direction: TwistDirection) -> TwistDirection;
-    fn chain_twist_directions(&self, dirs: &[TwistDirection]) -> Option<TwistDirection>;
+    /// Returns the twist direction that undoes `direction`.
+    ///
+    /// Puzzles with twist axes that have no increment must override this.
+    fn reverse_twist_direction(&self, direction: TwistDirection) -> TwistDirection {
+        self.twist_direction_from_increments(-self.info(direction).increments)
+            .expect("twist direction has no reverse")
+    }
+    /// Returns the single twist direction that has the same effect as all of
+    /// `dirs` around `axis`, or `None` if they cancel out.
+    ///
+    /// Puzzles with twist axes that have no increment must override this.
+    fn chain_twist_directions(
+        &self,
+        axis: TwistAxis,
+        dirs: &[TwistDirection],
+    ) -> Option<TwistDirection> {
+        let per_turn = self
+            .info(axis)
+            .increments_per_turn
+            .expect("twist axis has no increment") as i32;
+        let total: i32 = dirs
+            .iter()
+            .map(|&dir| self.info(dir).increments as i32)
+            .sum();
+
+        let cw = total.rem_euclid(per_turn);
+        if cw == 0 {
+            return None;
+        }
+        let ccw = cw - per_turn;
+        // Prefer the smaller rotation, or the same direction as the twists
+        // if both are the same size.
+        let (preferred, other) = match cw.cmp(&-ccw) {
+            std::cmp::Ordering::Less => (cw, ccw),
+            std::cmp::Ordering::Greater => (ccw, cw),
+            std::cmp::Ordering::Equal if total < 0 => (ccw, cw),
+            std::cmp::Ordering::Equal => (cw, ccw),
+        };
+        [preferred, other]
+            .into_iter()
+            .find_map(|increments| self.twist_direction_from_increments(increments as i8))
+    }
 
     fn notation_scheme(&self) -> &NotationScheme;
+    /// Returns the notation scheme for a notation style, or the standard one
+    /// if the puzzle doesn't have that style.
+    fn notation_scheme_for_style(&self, _style: NotationStyle) -> &NotationScheme {
+        self.notation_scheme()
+    }
+    /// Returns the notation styles that the puzzle has.
+    fn notation_styles(&self) -> &'static [NotationStyle] {
+        &[NotationStyle::Standard]
+    }
+    /// Returns the most common notation style for the puzzle.
+    fn default_notation_style(&self) -> NotationStyle {
+        NotationStyle::Standard
+    }
     fn split_twists_string<'s>(&self, string: &'s str) -> regex::Matches<'static, 's> {
         const TWIST_PATTERN: &str = r"(\{[\d\s,]*\}|[^\s()])+";
         // one or more of either      (                    )+
@@ -177,7 +292,164 @@ pub trait PuzzleState: PuzzleType {
         p: StickerGeometryParams,
     ) -> Option<StickerGeometry>;
 
+    /// Returns the face that a sticker is currently on.
+    fn sticker_facet(&self, sticker: Sticker) -> Face;
+
     fn is_solved(&self) -> bool;
+    /// Returns whether each sticker is in place, indexed by sticker.
+    ///
+    /// A sticker counts as in place if it is on the face where most stickers
+    /// of its color are, so that rotating the whole puzzle doesn't change the
+    /// result.
+    fn solved_stickers(&self) -> Vec<bool> {
+        let face_count = self.faces().len();
+        let mut facets_per_color = vec![vec![0_usize; face_count]; face_count];
+        for (i, sticker) in self.stickers().iter().enumerate() {
+            let facet = self.sticker_facet(Sticker(i as _));
+            facets_per_color[sticker.color.0 as usize][facet.0 as usize] += 1;
+        }
+        let home_facet_per_color = facets_per_color
+            .iter()
+            .map(|facet_counts| facet_counts.iter().position_max())
+            .collect_vec();
+
+        (0..self.stickers().len() as _)
+            .map(|i| {
+                let color = self.stickers()[i as usize].color;
+                let facet = self.sticker_facet(Sticker(i));
+                home_facet_per_color[color.0 as usize] == Some(facet.0 as usize)
+            })
+            .collect()
+    }
+    /// Returns the fraction of pieces that are in place, from 0.0 to 1.0.
+    ///
+    /// This is a rough estimate meant only for motivation, not a real measure
+    /// of how close the puzzle is to solved. A piece counts as in place if
+    /// all its stickers are in place according to `solved_stickers()`.
+    /// Pieces without stickers are ignored.
+    fn solved_fraction(&self) -> f32 {
+        self.solved_fraction_by(SolvedDefinition::Standard)
+    }
+    /// Returns the fraction of pieces that are in place according to
+    /// `definition`, from 0.0 to 1.0. See `solved_fraction()`.
+    ///
+    /// For supercubes, a piece also has to be in the same orientation as most
+    /// other pieces to count as in place.
+    fn solved_fraction_by(&self, definition: SolvedDefinition) -> f32 {
+        let solved_stickers = self.solved_stickers();
+        let orientation = match definition {
+            SolvedDefinition::Supercube => self.solved_orientation(),
+            _ => None,
+        };
+
+        let pieces = (0..self.pieces().len() as _)
+            .map(Piece)
+            .filter(|&piece| definition.counts_piece(self.info(piece)));
+        let total = pieces.clone().count();
+        let in_place = pieces
+            .filter(|&piece| {
+                let stickers = &self.info(piece).stickers;
+                stickers.iter().all(|s| solved_stickers[s.0 as usize])
+                    && orientation.as_ref().map_or(true, |orientation| {
+                        self.piece_orientation(piece).as_ref() == Some(orientation)
+                    })
+            })
+            .count();
+        if total == 0 {
+            1.0
+        } else {
+            in_place as f32 / total as f32
+        }
+    }
+    /// Returns whether the puzzle is solved according to `definition`.
+    fn is_solved_by(&self, definition: SolvedDefinition) -> bool {
+        match definition {
+            SolvedDefinition::Standard => self.is_solved(),
+            SolvedDefinition::Supercube => {
+                self.is_solved()
+                    && self.solved_orientation().map_or(true, |orientation| {
+                        (0..self.pieces().len() as _)
+                            .map(Piece)
+                            .filter(|&piece| !self.info(piece).stickers.is_empty())
+                            .all(|piece| {
+                                self.piece_orientation(piece).as_ref() == Some(&orientation)
+                            })
+                    })
+            }
+            SolvedDefinition::IgnoreCenters => {
+                let mut color_per_facet = vec![None; self.faces().len()];
+                for (i, sticker) in self.stickers().iter().enumerate() {
+                    if !definition.counts_piece(self.info(sticker.piece)) {
+                        continue;
+                    }
+                    let facet = self.sticker_facet(Sticker(i as _)).0 as usize;
+                    if color_per_facet[facet].is_none() {
+                        color_per_facet[facet] = Some(sticker.color);
+                    } else if color_per_facet[facet] != Some(sticker.color) {
+                        return false;
+                    }
+                }
+                true
+            }
+        }
+    }
+    /// Returns the most common orientation among pieces with stickers, which
+    /// is the orientation of every piece once the puzzle is solved as a
+    /// supercube. Returns `None` if the puzzle does not support piece
+    /// orientations.
+    fn solved_orientation(&self) -> Option<Vec<Face>> {
+        let orientations = (0..self.pieces().len() as _)
+            .map(Piece)
+            .filter(|&piece| !self.info(piece).stickers.is_empty())
+            .map(|piece| self.piece_orientation(piece))
+            .collect::<Option<Vec<_>>>()?;
+        orientations
+            .into_iter()
+            .counts()
+            .into_iter()
+            .max_by_key(|(_, count)| *count)
+            .map(|(orientation, _)| orientation)
+    }
+
+    /// Returns the face that a sticker is currently on and its row and column
+    /// within the grid of stickers on that face, using the same layout as
+    /// `PuzzleType::sticker_grid_position()`.
+    fn sticker_grid_location(&self, _sticker: Sticker) -> Option<(Face, [u8; 2])> {
+        None
+    }
+
+    /// Checks invariants that hold for every state reachable by twisting the
+    /// puzzle, such as permutation parity and orientation sums. Returns a
+    /// description of each violated invariant, or `None` if the puzzle does
+    /// not support checking its state.
+    fn check_state(&self) -> Option<Vec<String>> {
+        None
+    }
+
+    /// Returns the face that each axis of a piece points toward, which
+    /// describes the orientation of the piece. Returns `None` if the puzzle
+    /// does not support this.
+    fn piece_orientation(&self, _piece: Piece) -> Option<Vec<Face>> {
+        None
+    }
+    /// Sets the orientation of a piece, using the same representation as
+    /// `piece_orientation()`. Returns an error if the orientation is not valid
+    /// for the piece.
+    fn set_piece_orientation(
+        &mut self,
+        _piece: Piece,
+        _orientation: &[Face],
+    ) -> Result<(), String> {
+        Err(format!(
+            "{} does not support setting piece orientations",
+            self.name()
+        ))
+    }
+    /// Returns the piece whose original location a piece currently occupies.
+    /// Returns `None` if the puzzle does not support this.
+    fn piece_position(&self, _piece: Piece) -> Option<Piece> {
+        None
+    }
 
     #[cfg(debug_assertions)]
     fn sticker_debug_info(&self, _s: &mut String, _sticker: Sticker) {}
@@ -196,6 +468,8 @@ pub enum PuzzleTypeEnum {
         #[serde(deserialize_with = "rubiks_4d::deserialize_layer_count")]
         layer_count: u8,
     },
+    /// Ivy cube.
+    Ivy,
 }
 #[delegate_to_methods]
 #[delegate(PuzzleType, target_ref = "as_dyn_type")]
@@ -204,6 +478,7 @@ impl PuzzleTypeEnum {
         match *self {
             PuzzleTypeEnum::Rubiks3D { layer_count } => rubiks_3d::puzzle_type(layer_count),
             PuzzleTypeEnum::Rubiks4D { layer_count } => rubiks_4d::puzzle_type(layer_count),
+            PuzzleTypeEnum::Ivy => ivy::puzzle_type(),
         }
     }
     pub fn validate(self) -> Result<(), String> {
@@ -222,6 +497,7 @@ impl PuzzleTypeEnum {
                     Err(format!("invalid layer count {layer_count} for this puzzle"))
                 }
             }
+            PuzzleTypeEnum::Ivy => Ok(()),
         }
     }
 
@@ -229,9 +505,211 @@ impl PuzzleTypeEnum {
         match *self {
             PuzzleTypeEnum::Rubiks3D { .. } => false,
             PuzzleTypeEnum::Rubiks4D { .. } => true,
+            PuzzleTypeEnum::Ivy => false,
+        }
+    }
+
+    /// Returns the face with the given symbol.
+    pub fn face_from_symbol(self, symbol: &str) -> Option<Face> {
+        let i = self.faces().iter().position(|f| f.symbol == symbol)?;
+        Some(Face(i as _))
+    }
+    /// Returns the face that is considered "down" for color-neutral practice.
+    pub fn down_face(self) -> Option<Face> {
+        self.face_from_symbol("D")
+    }
+    /// Returns the face that is considered "up" for last-layer recognition.
+    pub fn up_face(self) -> Option<Face> {
+        self.face_from_symbol("U")
+    }
+    /// Returns the face that is considered "front" for orienting the color
+    /// scheme.
+    pub fn front_face(self) -> Option<Face> {
+        self.face_from_symbol("F")
+    }
+    /// Returns the face at the center of the 4D projection, or `None` if the
+    /// puzzle isn't 4D.
+    pub fn center_face(self) -> Option<Face> {
+        match self.projection_type() {
+            ProjectionType::_4D => self.face_from_symbol("I"),
+            _ => None,
         }
     }
+
+    /// Returns the face that each color is moved onto by each whole-puzzle
+    /// rotation (including the identity), along with the view rotation
+    /// equivalent to it.
+    fn rotated_color_locations(self) -> impl Iterator<Item = (Vec<Face>, Quaternion<f32>)> {
+        let solved = Puzzle::new(self);
+        std::iter::once((vec![], Quaternion::one()))
+            .chain(solved.rotation_candidates())
+            .filter_map(move |(twists, rot)| Some((color_locations(&solved, &twists)?, rot)))
+    }
+    /// Same as `rotated_color_locations()`, but also including rotations that
+    /// bring another face to the center of a 4D projection.
+    ///
+    /// This simulates every pair of recentering and rotation, so the result
+    /// is cached for each puzzle type.
+    fn recentered_color_locations(self) -> Arc<[Vec<Face>]> {
+        lazy_static! {
+            static ref CACHE: Mutex<HashMap<PuzzleTypeEnum, Arc<[Vec<Face>]>>> =
+                Mutex::new(HashMap::new());
+        }
+
+        if let Some(ret) = CACHE.lock().unwrap().get(&self) {
+            return Arc::clone(ret);
+        }
+        let solved = Puzzle::new(self);
+        let recenter_twists = (0..self.twist_axes().len() as u8)
+            .filter_map(|i| self.make_recenter_twist(TwistAxis(i)).ok())
+            // Twice for the face opposite the center.
+            .flat_map(|twist| [vec![twist], vec![twist, twist]]);
+        let recenters = std::iter::once(vec![]).chain(recenter_twists).collect_vec();
+        let rotations = std::iter::once(vec![])
+            .chain(
+                solved
+                    .rotation_candidates()
+                    .into_iter()
+                    .map(|(twists, _)| twists),
+            )
+            .collect_vec();
+        let ret = recenters
+            .into_iter()
+            .cartesian_product(rotations)
+            .filter_map(|(recenter, rotation)| {
+                color_locations(&solved, &[recenter, rotation].concat())
+            })
+            .collect::<Arc<[_]>>();
+        Arc::clone(CACHE.lock().unwrap().entry(self).or_insert(ret))
+    }
+
+    /// Returns a recoloring of the puzzle that corresponds to a whole-puzzle
+    /// rotation bringing `down_color` onto the down face, or `None` if there
+    /// is no such rotation. Element `i` of the result is the face whose color
+    /// should be displayed on stickers of color `i`.
+    ///
+    /// This only affects the color assignment, not the puzzle state.
+    pub fn color_scheme_rotation(self, down_color: Face) -> Option<Vec<Face>> {
+        self.color_scheme_rotation_with_center(Some(down_color), None)
+    }
+    /// Same as `color_scheme_rotation()`, but also bringing `center_color`
+    /// onto the center face of a 4D projection. Each constraint is optional,
+    /// and `None` is returned if they cannot both be satisfied.
+    pub fn color_scheme_rotation_with_center(
+        self,
+        down_color: Option<Face>,
+        center_color: Option<Face>,
+    ) -> Option<Vec<Face>> {
+        self.color_scheme_orientation(down_color, None, center_color)
+    }
+    /// Same as `color_scheme_rotation_with_center()`, but also bringing
+    /// `front_color` onto the front face. Together, these can pick out any
+    /// whole-puzzle rotation.
+    ///
+    /// The result is cached, because finding it simulates twists and the
+    /// color scheme is needed on every frame.
+    pub fn color_scheme_orientation(
+        self,
+        down_color: Option<Face>,
+        front_color: Option<Face>,
+        center_color: Option<Face>,
+    ) -> Option<Vec<Face>> {
+        type Key = (PuzzleTypeEnum, [Option<Face>; 3]);
+        lazy_static! {
+            static ref CACHE: Mutex<HashMap<Key, Option<Vec<Face>>>> = Mutex::new(HashMap::new());
+        }
+
+        // Find the rotation without holding the lock, like puzzle
+        // descriptions.
+        let key = (self, [down_color, front_color, center_color]);
+        if let Some(ret) = CACHE.lock().unwrap().get(&key) {
+            return ret.clone();
+        }
+        let ret = self.find_color_scheme_orientation(down_color, front_color, center_color);
+        CACHE.lock().unwrap().insert(key, ret.clone());
+        ret
+    }
+    fn find_color_scheme_orientation(
+        self,
+        down_color: Option<Face>,
+        front_color: Option<Face>,
+        center_color: Option<Face>,
+    ) -> Option<Vec<Face>> {
+        let mut constraints = vec![];
+        if let Some(color) = down_color {
+            constraints.push((color, self.down_face()?));
+        }
+        if let Some(color) = front_color {
+            constraints.push((color, self.front_face()?));
+        }
+        if let Some(color) = center_color {
+            constraints.push((color, self.center_face()?));
+        }
+        let is_valid = |moved_to: &Vec<Face>| {
+            constraints
+                .iter()
+                .all(|&(color, face)| moved_to[color.0 as usize] == face)
+        };
+        let moved_to = if center_color.is_some() {
+            self.recentered_color_locations()
+                .iter()
+                .find(|&moved_to| is_valid(moved_to))?
+                .clone()
+        } else {
+            self.rotated_color_locations()
+                .map(|(moved_to, _)| moved_to)
+                .find(is_valid)?
+        };
+        let mut ret = vec![Face::default(); moved_to.len()];
+        for (color, face) in moved_to.into_iter().enumerate() {
+            ret[face.0 as usize] = Face(color as _);
+        }
+        Some(ret)
+    }
+    /// Returns the recoloring `scheme_rotation` (in the same format as
+    /// `color_scheme_rotation()`) followed by the whole-puzzle rotation
+    /// `twists`, so that each color ends up where `twists` would move it.
+    /// Returns `None` if any of the twists is invalid.
+    ///
+    /// This only affects the color assignment, not the puzzle state.
+    pub fn rotate_color_scheme(
+        self,
+        scheme_rotation: &[Face],
+        twists: &[Twist],
+    ) -> Option<Vec<Face>> {
+        let moved_to = color_locations(&Puzzle::new(self), twists)?;
+        let mut ret = scheme_rotation.to_vec();
+        for (color, face) in moved_to.into_iter().enumerate() {
+            ret[face.0 as usize] = scheme_rotation[color];
+        }
+        Some(ret)
+    }
+
+    /// Returns a view angle offset that shows `face` where the up face would
+    /// normally be, or `None` if there is no such rotation.
+    pub fn rotation_to_up(self, face: Face) -> Option<Quaternion<f32>> {
+        let up_face = self.up_face()?;
+        let (_, rot) = self
+            .rotated_color_locations()
+            .find(|(moved_to, _)| moved_to[face.0 as usize] == up_face)?;
+        Some(rot)
+    }
+}
+/// Returns the face that each color is moved onto by applying twists to a
+/// solved puzzle, or `None` if any of the twists is invalid.
+fn color_locations(solved: &Puzzle, twists: &[Twist]) -> Option<Vec<Face>> {
+    let mut p = solved.clone();
+    for &twist in twists {
+        p.twist(twist).ok()?;
+    }
+    // `moved_to[c]` is the face that color `c` was rotated onto.
+    let mut moved_to = vec![Face::default(); p.faces().len()];
+    for (i, sticker) in p.stickers().iter().enumerate() {
+        moved_to[sticker.color.0 as usize] = p.sticker_facet(Sticker(i as _));
+    }
+    Some(moved_to)
 }
+
 impl Default for PuzzleTypeEnum {
     fn default() -> Self {
         Self::Rubiks4D { layer_count: 3 }
@@ -304,6 +782,8 @@ pub enum Puzzle {
     Rubiks3D(Rubiks3D),
     /// 4D Rubik's cube.
     Rubiks4D(Rubiks4D),
+    /// Ivy cube.
+    Ivy(Ivy),
 }
 impl Default for Puzzle {
     fn default() -> Self {
@@ -316,6 +796,39 @@ impl Puzzle {
         match ty {
             PuzzleTypeEnum::Rubiks3D { layer_count } => Self::Rubiks3D(Rubiks3D::new(layer_count)),
             PuzzleTypeEnum::Rubiks4D { layer_count } => Self::Rubiks4D(Rubiks4D::new(layer_count)),
+            PuzzleTypeEnum::Ivy => Self::Ivy(Ivy::new()),
+        }
+    }
+}
+
+/// Rules for when a puzzle counts as solved, which decide when the timer stops.
+#[derive(
+    Serialize, Deserialize, Debug, Display, Default, EnumIter, Copy, Clone, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SolvedDefinition {
+    /// Every face is a single color, which ignores the orientation of pieces
+    /// that look the same in every orientation, such as the centers of a
+    /// 3x3x3.
+    #[default]
+    #[strum(serialize = "Standard")]
+    Standard,
+    /// Every face is a single color and every piece is oriented the same way,
+    /// for puzzles with markings that show the orientation of centers.
+    #[strum(serialize = "Supercube")]
+    Supercube,
+    /// Every face is a single color, not counting pieces with a single
+    /// sticker such as centers.
+    #[strum(serialize = "Ignore centers")]
+    IgnoreCenters,
+}
+impl SolvedDefinition {
+    /// Returns whether a piece counts toward whether the puzzle is solved.
+    /// Pieces without stickers never count.
+    fn counts_piece(self, piece: &PieceInfo) -> bool {
+        match self {
+            SolvedDefinition::IgnoreCenters => piece.stickers.len() > 1,
+            _ => !piece.stickers.is_empty(),
         }
     }
 }
@@ -380,6 +893,10 @@ impl FaceInfo {
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct TwistAxisInfo {
     pub name: &'static str, // e.g., "R"
+    /// Number of twists by the smallest increment that make a full turn
+    /// around the axis (e.g., 4 for a face of a cube), or `None` if twists
+    /// around the axis are not multiples of a single increment.
+    pub increments_per_turn: Option<u8>,
 }
 impl AsRef<str> for TwistAxisInfo {
     fn as_ref(&self) -> &str {
@@ -391,6 +908,10 @@ impl AsRef<str> for TwistAxisInfo {
 pub struct TwistDirectionInfo {
     pub symbol: &'static str, // "'"
     pub name: &'static str,   // "CCW"
+    /// Number of increments of the twist axis that the twist rotates by,
+    /// positive for clockwise. This is 0 for puzzles whose twist axes have no
+    /// increment.
+    pub increments: i8,
 }
 impl AsRef<str> for TwistDirectionInfo {
     fn as_ref(&self) -> &str {
@@ -398,8 +919,12 @@ impl AsRef<str> for TwistDirectionInfo {
     }
 }
 impl TwistDirectionInfo {
-    pub const fn new(symbol: &'static str, name: &'static str) -> Self {
-        Self { symbol, name }
+    pub const fn new(symbol: &'static str, name: &'static str, increments: i8) -> Self {
+        Self {
+            symbol,
+            name,
+            increments,
+        }
     }
 }
 
@@ -608,6 +1133,60 @@ impl TwistMetric {
     }
 }
 
+/// Returns the sequence of twists that undoes `twists`.
+pub fn invert_alg(puzzle: impl PuzzleType, twists: &[Twist]) -> Vec<Twist> {
+    twists
+        .iter()
+        .rev()
+        .map(|&t| puzzle.reverse_twist(t))
+        .collect()
+}
+
+/// Removes whole-puzzle rotations from a sequence of twists, remapping each
+/// twist after a rotation so that the sequence has the same effect up to the
+/// orientation of the whole puzzle. Rotations are kept if the puzzle doesn't
+/// support remapping twists.
+pub fn strip_rotations(
+    puzzle: impl PuzzleType,
+    twists: impl IntoIterator<Item = Twist>,
+) -> Vec<Twist> {
+    let mut rotations = vec![];
+    let mut ret = vec![];
+    for twist in twists {
+        let is_rotation = twist.layers == puzzle.all_layers();
+        match puzzle.unrotate_twist(&rotations, twist) {
+            Some(_) if is_rotation => rotations.push(twist),
+            Some(t) => ret.push(puzzle.canonicalize_twist(t)),
+            None => ret.push(twist),
+        }
+    }
+    ret
+}
+
+/// Merges consecutive twists of the same layers around the same axis, and
+/// removes any that cancel out, so that equivalent move sequences can be
+/// compared. Twists on other axes in between are not moved out of the way.
+pub fn simplify_moves(
+    puzzle: impl PuzzleType,
+    twists: impl IntoIterator<Item = Twist>,
+) -> Vec<Twist> {
+    let mut ret: Vec<Twist> = vec![];
+    for twist in twists {
+        let twist = puzzle.canonicalize_twist(twist);
+        match ret.last() {
+            Some(prev) if prev.axis == twist.axis && prev.layers == twist.layers => {
+                let directions = [prev.direction, twist.direction];
+                ret.pop();
+                if let Some(direction) = puzzle.chain_twist_directions(twist.axis, &directions) {
+                    ret.push(puzzle.canonicalize_twist(Twist { direction, ..twist }));
+                }
+            }
+            _ => ret.push(twist),
+        }
+    }
+    ret
+}
+
 /// Positive or negative.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Sign {
@@ -0,0 +1,108 @@
+//! Commutators and the pieces they cycle, for designing algorithms for
+//! 3-cycle-based methods.
+
+use itertools::Itertools;
+
+use super::*;
+
+/// Pieces moved or twisted by an algorithm applied to a solved puzzle.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PieceCycles {
+    /// Cycles of pieces that moved. Each piece moves to the original location
+    /// of the next one, and the last one moves to the original location of
+    /// the first one.
+    pub cycles: Vec<Vec<Piece>>,
+    /// Pieces that stayed in place but were twisted. Pieces with only one
+    /// sticker look the same in every orientation, so they are not included.
+    pub twisted: Vec<Piece>,
+}
+
+/// Returns the commutator `[A, B] = A B A' B'`, with twists that cancel out
+/// between the parts simplified away.
+pub fn commutator(ty: PuzzleTypeEnum, a: &[Twist], b: &[Twist]) -> Vec<Twist> {
+    let a_inv = invert_alg(ty, a);
+    let b_inv = invert_alg(ty, b);
+    let twists = a.iter().chain(b).chain(&a_inv).chain(&b_inv).copied();
+    simplify_moves(ty, twists)
+}
+
+/// Applies `twists` to a solved puzzle and returns which pieces moved or were
+/// twisted. Returns an error if the puzzle does not support tracking the
+/// position of pieces.
+pub fn piece_cycles(ty: PuzzleTypeEnum, twists: &[Twist]) -> Result<PieceCycles, String> {
+    let solved = Puzzle::new(ty);
+    let mut puzzle = solved.clone();
+    for &twist in twists {
+        puzzle.twist(twist)?;
+    }
+
+    let unsupported = || format!("{} does not support piece cycles", ty.name());
+    let positions = (0..ty.pieces().len() as _)
+        .map(|i| puzzle.piece_position(Piece(i)))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(unsupported)?;
+
+    let mut ret = PieceCycles::default();
+    let mut visited = vec![false; positions.len()];
+    for (i, &position) in positions.iter().enumerate() {
+        let piece = Piece(i as _);
+        if visited[i] || ty.info(piece).stickers.is_empty() {
+            continue;
+        }
+        if position == piece {
+            let is_twisted = puzzle.piece_orientation(piece) != solved.piece_orientation(piece);
+            if is_twisted && ty.info(piece).stickers.len() > 1 {
+                ret.twisted.push(piece);
+            }
+            continue;
+        }
+
+        let mut cycle = vec![];
+        let mut p = piece;
+        while !visited[p.0 as usize] {
+            visited[p.0 as usize] = true;
+            cycle.push(p);
+            p = positions[p.0 as usize];
+        }
+        ret.cycles.push(cycle);
+    }
+    Ok(ret)
+}
+
+/// Returns a name for a piece made of the symbols of the faces its stickers
+/// are on in the solved state, such as "UFR".
+pub fn piece_name(ty: PuzzleTypeEnum, piece: Piece) -> String {
+    ty.info(piece)
+        .stickers
+        .iter()
+        .map(|&sticker| ty.info(ty.info(sticker).color).symbol)
+        .join("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commutator_piece_cycles() {
+        let ty = PuzzleTypeEnum::Rubiks3D { layer_count: 3 };
+        let notation = ty.notation_scheme();
+        let alg = |s: &str| notation.parse_alg(s).unwrap();
+
+        // A corner 3-cycle.
+        let comm = commutator(ty, &alg("R U R'"), &alg("D"));
+        assert_eq!(comm.len(), 8);
+        let cycles = piece_cycles(ty, &comm).unwrap();
+        assert_eq!(cycles.cycles.len(), 1);
+        assert_eq!(cycles.cycles[0].len(), 3);
+        assert!(cycles.twisted.is_empty());
+
+        // Twists between the parts cancel out.
+        let comm = commutator(ty, &alg("R U"), &alg("U'"));
+        assert_eq!(comm, simplify_moves(ty, alg("R U' R' U")));
+
+        // Commuting parts leave the puzzle solved.
+        let cycles = piece_cycles(ty, &commutator(ty, &alg("R"), &alg("L"))).unwrap();
+        assert_eq!(cycles, PieceCycles::default());
+    }
+}
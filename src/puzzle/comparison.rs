@@ -0,0 +1,133 @@
+//! Move-by-move comparison of a solution against a reference solution to the
+//! same scramble, for fewest-moves practice.
+
+use super::*;
+
+/// Twist in the comparison of a solution against a reference solution.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TwistDiff {
+    /// Twist in both solutions.
+    Same(Twist),
+    /// Twist in the solution but not the reference solution.
+    Inserted(Twist),
+    /// Twist in the reference solution but not the solution.
+    Deleted(Twist),
+}
+
+/// Compares `solution` against `reference` after removing whole-puzzle
+/// rotations and simplifying both. Returns an error if either one doesn't
+/// solve `scramble`.
+pub fn compare_solutions(
+    ty: PuzzleTypeEnum,
+    scramble: &[Twist],
+    solution: &[Twist],
+    reference: &[Twist],
+) -> Result<Vec<TwistDiff>, String> {
+    if !solves(ty, scramble, solution)? {
+        return Err("Your solution doesn't solve the scramble".to_string());
+    }
+    if !solves(ty, scramble, reference)? {
+        return Err("Reference solution doesn't solve the scramble".to_string());
+    }
+
+    let normalize = |twists: &[Twist]| simplify_moves(ty, strip_rotations(ty, twists.to_vec()));
+    Ok(diff(&normalize(solution), &normalize(reference)))
+}
+
+fn solves(ty: PuzzleTypeEnum, scramble: &[Twist], solution: &[Twist]) -> Result<bool, String> {
+    let mut puzzle = Puzzle::new(ty);
+    for &twist in scramble.iter().chain(solution) {
+        puzzle.twist(twist)?;
+    }
+    Ok(puzzle.is_solved())
+}
+
+/// Returns a shortest edit from `reference` to `solution`, using their longest
+/// common subsequence.
+fn diff(solution: &[Twist], reference: &[Twist]) -> Vec<TwistDiff> {
+    let (n, m) = (solution.len(), reference.len());
+
+    // `lcs[i][j]` is the length of the longest common subsequence of
+    // `solution[i..]` and `reference[j..]`.
+    let mut lcs = vec![vec![0_usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if solution[i] == reference[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                std::cmp::max(lcs[i + 1][j], lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ret = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && solution[i] == reference[j] {
+            ret.push(TwistDiff::Same(solution[i]));
+            i += 1;
+            j += 1;
+        } else if j == m || i < n && lcs[i + 1][j] >= lcs[i][j + 1] {
+            ret.push(TwistDiff::Inserted(solution[i]));
+            i += 1;
+        } else {
+            ret.push(TwistDiff::Deleted(reference[j]));
+            j += 1;
+        }
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_solutions() {
+        let ty = PuzzleTypeEnum::Rubiks3D { layer_count: 3 };
+        let notation = ty.notation_scheme();
+        let alg = |s: &str| notation.parse_alg(s).unwrap();
+        let twist = |s: &str| ty.canonicalize_twist(notation.parse_twist(s).unwrap());
+
+        let scramble = alg("R U");
+        let reference = alg("U' R'");
+
+        // Cancelling and merging twists are simplified away.
+        let diff = compare_solutions(ty, &scramble, &alg("U2 U R2 R"), &reference).unwrap();
+        assert_eq!(
+            diff,
+            [TwistDiff::Same(twist("U'")), TwistDiff::Same(twist("R'"))],
+        );
+
+        let diff = compare_solutions(ty, &scramble, &alg("U' L R' L'"), &reference).unwrap();
+        assert_eq!(
+            diff,
+            [
+                TwistDiff::Same(twist("U'")),
+                TwistDiff::Inserted(twist("L")),
+                TwistDiff::Same(twist("R'")),
+                TwistDiff::Inserted(twist("L'")),
+            ],
+        );
+
+        let diff = compare_solutions(ty, &scramble, &alg("U' y F' y'"), &reference).unwrap();
+        assert_eq!(
+            diff,
+            [TwistDiff::Same(twist("U'")), TwistDiff::Same(twist("R'"))],
+        );
+
+        let diff = compare_solutions(ty, &scramble, &reference, &alg("U' L R' L'")).unwrap();
+        assert_eq!(
+            diff,
+            [
+                TwistDiff::Same(twist("U'")),
+                TwistDiff::Deleted(twist("L")),
+                TwistDiff::Same(twist("R'")),
+                TwistDiff::Deleted(twist("L'")),
+            ],
+        );
+
+        assert!(compare_solutions(ty, &scramble, &alg("U'"), &reference).is_err());
+        assert!(compare_solutions(ty, &scramble, &reference, &alg("R' U'")).is_err());
+    }
+}
@@ -4,8 +4,12 @@ use anyhow::Result;
 use bitvec::bitvec;
 use bitvec::slice::BitSlice;
 use bitvec::vec::BitVec;
-use cgmath::{Deg, InnerSpace, One, Quaternion, Rotation, Rotation3};
+use cgmath::{
+    Angle, Deg, InnerSpace, One, Point3, Quaternion, Rad, Rotation, Rotation3, Transform, Vector3,
+    Vector4, Zero,
+};
 use instant::Duration;
+use itertools::Itertools;
 use num_enum::FromPrimitive;
 use std::borrow::Cow;
 use std::collections::{HashSet, VecDeque};
@@ -22,6 +26,9 @@ const EXP_TWIST_FACTOR: f32 = 0.5;
 /// Higher number means slower exponential decay of view angle offset.
 const VIEW_ANGLE_OFFSET_DECAY_RATE: f32 = 0.02_f32;
 
+/// 3D FOV for the last layer view, in degrees.
+const LAST_LAYER_VIEW_FOV: f32 = 60.0;
+
 /// Interpolation functions.
 pub mod interpolate {
     use std::f32::consts::PI;
@@ -40,7 +47,9 @@ pub mod interpolate {
 
 use super::*;
 use crate::commands::PARTIAL_SCRAMBLE_MOVE_COUNT_MAX;
-use crate::preferences::{InteractionPreferences, Preferences, ViewPreferences};
+use crate::preferences::{
+    InteractionPreferences, Preferences, TwistConvention, ViewAngle, ViewPreferences,
+};
 use crate::util;
 use interpolate::InterpolateFn;
 
@@ -71,8 +80,15 @@ pub struct PuzzleController {
 
     /// Whether the puzzle has been scrambled.
     scramble_state: ScrambleState,
+    /// Rules for when the puzzle counts as solved.
+    solved_definition: SolvedDefinition,
     /// Scramble twists.
     scramble: Vec<Twist>,
+    /// Side of the scramble that is currently being solved.
+    side: ScrambleSide,
+    /// Twists applied on the side of the scramble that is not currently being
+    /// solved.
+    other_side_twists: Vec<Twist>,
     /// Undo history.
     undo_buffer: Vec<HistoryEntry>,
     /// Redo history.
@@ -102,6 +118,12 @@ pub struct PuzzleController {
     /// represented as `f32` for animation.
     visual_piece_states: Vec<VisualPieceState>,
 
+    /// Whether pieces are moved outward from the center of the puzzle.
+    is_exploded: bool,
+    /// Animation progress for exploding the puzzle, from 0.0 (assembled) to
+    /// 1.0 (exploded).
+    explode_progress: f32,
+
     /// Cached sticker geometry.
     cached_geometry: Option<Arc<Vec<ProjectedStickerGeometry>>>,
     cached_geometry_params: Option<StickerGeometryParams>,
@@ -136,7 +158,10 @@ impl PuzzleController {
             is_unsaved_in_local_storage: true,
 
             scramble_state: ScrambleState::None,
+            solved_definition: SolvedDefinition::default(),
             scramble: vec![],
+            side: ScrambleSide::Normal,
+            other_side_twists: vec![],
             undo_buffer: vec![],
             redo_buffer: vec![],
 
@@ -152,13 +177,18 @@ impl PuzzleController {
 
             visual_piece_states: vec![VisualPieceState::default(); ty.pieces().len()],
 
+            is_exploded: false,
+            explode_progress: 0.0,
+
             cached_geometry: None,
             cached_geometry_params: None,
         }
     }
     /// Resets the puzzle.
     pub fn reset(&mut self) {
+        let solved_definition = self.solved_definition;
         *self = Self::new(self.ty());
+        self.solved_definition = solved_definition;
     }
 
     /// Returns whether the puzzle has been scrambled, solved, etc..
@@ -189,11 +219,112 @@ impl PuzzleController {
         self.scramble_state = ScrambleState::Full;
         Ok(())
     }
+    /// Reset and then apply a full scramble that was generated in advance, such
+    /// as one shown in the scramble preview.
+    pub fn scramble_full_with(&mut self, scramble: Vec<Twist>) -> Result<(), &'static str> {
+        self.apply_scramble(scramble, ScrambleState::Full)
+    }
+    /// Reset and then apply a scramble that leaves every piece in its original
+    /// location but may twist or flip it, for practicing orientation. The
+    /// scramble comes from an `OrientationScrambleJob`.
+    pub fn scramble_orientation(&mut self, scramble: Vec<Twist>) -> Result<(), String> {
+        Ok(self.apply_scramble(scramble, ScrambleState::Partial)?)
+    }
+    fn apply_scramble(
+        &mut self,
+        scramble: Vec<Twist>,
+        scramble_state: ScrambleState,
+    ) -> Result<(), &'static str> {
+        self.reset();
+        for twist in scramble {
+            self.twist_no_collapse(twist)?;
+        }
+        self.add_scramble_marker(scramble_state);
+        Ok(())
+    }
+    /// Replays the scramble as twist animations, ending in the same state as
+    /// before. This must be called right after scrambling, before any other
+    /// twists. The animations use `scramble_twist_duration` instead of the
+    /// usual twist speed, and any other twist skips them.
+    pub fn animate_scramble(&mut self) -> Result<(), &'static str> {
+        if !self.undo_buffer.is_empty() || self.side != ScrambleSide::Normal {
+            return Err("Puzzle has been twisted since scrambling");
+        }
+        self.skip_twist_animations();
+        let scramble = self.scramble.clone();
+        for twist in invert_alg(self.ty(), &scramble) {
+            self.puzzle.twist(twist)?;
+        }
+        for twist in scramble {
+            self.animate_twist(twist)?;
+        }
+        self.twist_anim.is_scramble = true;
+        Ok(())
+    }
+    /// Returns whether a scramble is being animated.
+    pub fn is_animating_scramble(&self) -> bool {
+        self.twist_anim.is_scramble
+    }
+    /// Returns the notation for a random full scramble of a puzzle, without
+    /// applying it to any existing puzzle.
+    pub fn random_scramble_string(
+        ty: PuzzleTypeEnum,
+        style: NotationStyle,
+    ) -> Result<String, &'static str> {
+        let mut puzzle = Self::new(ty);
+        puzzle.scramble_full()?;
+        Ok(puzzle.scramble_string_in_style(style))
+    }
+    /// Resets the puzzle and then reapplies the same scramble, such as to
+    /// retry a solve from the same starting position.
+    pub fn retry_scramble(&mut self) -> Result<(), &'static str> {
+        if self.scramble.is_empty() {
+            return Err("No scramble to retry");
+        }
+        let scramble_state = if self.has_been_fully_scrambled() {
+            ScrambleState::Full
+        } else {
+            ScrambleState::Partial
+        };
+        let scramble = std::mem::take(&mut self.scramble);
+        self.apply_scramble(scramble, scramble_state)
+    }
+    /// Resets the puzzle and then applies a pattern as a single history entry,
+    /// so that it can be undone all at once.
+    pub fn apply_pattern(&mut self, twists: Vec<Twist>) -> Result<(), &'static str> {
+        self.reset();
+        let mut canonical_twists = Vec::with_capacity(twists.len());
+        for mut twist in twists {
+            twist.layers &= self.all_layers(); // Restrict layer mask.
+            if twist.layers == LayerMask(0) {
+                return Err("invalid layer mask");
+            }
+            canonical_twists.push(self.canonicalize_twist(twist));
+        }
+        self.apply_twists_instantly(&canonical_twists)?;
+        self.mark_unsaved();
+        self.undo_buffer
+            .push(HistoryEntry::Pattern(canonical_twists));
+        Ok(())
+    }
+    /// Applies a sequence of twists to the puzzle without animation. Does
+    /// _not_ handle undo/redo stack or `is_unsaved`.
+    fn apply_twists_instantly(&mut self, twists: &[Twist]) -> Result<(), &'static str> {
+        self.skip_twist_animations();
+        for &twist in twists {
+            self.puzzle.twist(twist)?;
+        }
+        self.cached_geometry = None;
+        Ok(())
+    }
     /// Marks the puzzle as scrambled.
     pub fn add_scramble_marker(&mut self, new_scramble_state: ScrambleState) {
         self.skip_twist_animations();
-        self.scramble
-            .extend(self.undo_buffer.drain(..).filter_map(HistoryEntry::twist));
+        self.scramble.extend(
+            self.undo_buffer
+                .drain(..)
+                .flat_map(|entry| entry.twists().to_vec()),
+        );
         if new_scramble_state == ScrambleState::None {
             // This is technically invalid? But I've seen some older MC4D log files that do this, so just assume it's a full scramble.
             self.scramble_state = ScrambleState::Full;
@@ -216,6 +347,10 @@ impl PuzzleController {
         if twist.layers == LayerMask(0) {
             return Err("invalid layer mask");
         }
+        self.check_twist(twist)?;
+        if self.twist_anim.is_scramble {
+            self.skip_twist_animations();
+        }
 
         self.mark_unsaved();
         self.redo_buffer.clear();
@@ -251,6 +386,9 @@ impl PuzzleController {
             }
             // Remove this rotation from `current`.
             self.view_angle.current = self.view_angle.current * rot.invert();
+            if let Some(target) = &mut self.view_angle.snap_target {
+                *target = *target * rot.invert();
+            }
             if let Some(t) = self.twist_anim.queue.back_mut() {
                 // Actually, instead of just removing the rotation from
                 // `current`, transfer it from `current` to `queued_delta`.
@@ -305,6 +443,11 @@ impl PuzzleController {
             None => &self.puzzle,
         }
     }
+    /// Returns the number of twists waiting to be animated after the one
+    /// currently being animated.
+    pub fn queued_twist_count(&self) -> usize {
+        self.twist_anim.queue.len().saturating_sub(1)
+    }
     /// Returns the state of the cube after all queued twists have been applied.
     pub fn latest(&self) -> &Puzzle {
         &self.puzzle
@@ -337,6 +480,26 @@ impl PuzzleController {
             Quaternion::from_angle_x(Deg(offset[1])) * Quaternion::from_angle_y(Deg(offset[0]));
         self.view_angle.current =
             prefs_view_angle.invert() * offset * prefs_view_angle * self.view_angle.current;
+        self.view_angle.snap_target = None;
+    }
+    /// Begins animating the view angle offset so that the nearest face,
+    /// edge, or corner of the puzzle points directly at the camera. The view
+    /// angle offset stays frozen there.
+    pub fn snap_view_angle_offset(&mut self, view_prefs: &ViewPreferences) {
+        let rot = view_prefs.view_angle() * self.view_angle.current;
+        let camera = Vector3::unit_z();
+        // Direction in puzzle space that currently points at the camera.
+        let facing = rot.invert().rotate_vector(camera);
+        // Every nonzero vector with coordinates in {-1, 0, 1} points from the
+        // center of a cube toward a face, edge, or corner.
+        let target = itertools::iproduct!(-1..=1, -1..=1, -1..=1)
+            .map(|(x, y, z)| Vector3::new(x as f32, y as f32, z as f32))
+            .filter(|v| *v != Vector3::zero())
+            .map(|v| v.normalize())
+            .max_by(|a, b| f32::total_cmp(&a.dot(facing), &b.dot(facing)))
+            .unwrap();
+        self.view_angle.snap_target =
+            Some(self.view_angle.current * Quaternion::from_arc(target, facing, None));
     }
     /// Freezes the view angle offset, so that it will not animate back to zero
     /// automatically. It can still be changed with `set_view_angle_offset()`.
@@ -348,9 +511,77 @@ impl PuzzleController {
     pub fn unfreeze_view_angle_offset(&mut self) {
         self.apply_transient_rotation();
         self.view_angle.is_frozen = false;
+        self.view_angle.snap_target = None;
+    }
+    /// Rotates the view as part of the idle animation, without affecting the
+    /// view angle offset used for realigning the puzzle.
+    pub fn add_idle_rotation(&mut self, offset: [f32; 2], view_prefs: &ViewPreferences) {
+        let prefs_view_angle = view_prefs.view_angle();
+        let offset =
+            Quaternion::from_angle_x(Deg(offset[1])) * Quaternion::from_angle_y(Deg(offset[0]));
+        self.view_angle.idle =
+            prefs_view_angle.invert() * offset * prefs_view_angle * self.view_angle.idle;
+        self.view_angle.is_idling = true;
+    }
+    /// Stops the idle animation and begins animating the view back to where
+    /// it was.
+    pub fn stop_idle_rotation(&mut self) {
+        self.view_angle.is_idling = false;
+    }
+    /// Returns whether pieces are moved outward from the center of the
+    /// puzzle.
+    pub fn is_exploded(&self) -> bool {
+        self.is_exploded
+    }
+    /// Begins animating pieces outward from the center of the puzzle, or back
+    /// to their positions.
+    pub fn set_exploded(&mut self, is_exploded: bool) {
+        self.is_exploded = is_exploded;
+    }
+    /// Discards any whole-puzzle rotation from dragging and begins animating
+    /// the view angle offset back to zero.
+    pub fn reset_view_angle_offset(&mut self) {
+        self.view_angle.transient_rotation = None;
+        self.view_angle.is_frozen = false;
+        self.view_angle.snap_target = None;
+        self.view_angle.peek_return = None;
+    }
+    /// Begins animating the view so that the puzzle is seen from `angle`,
+    /// such as to peek at the last layer. The view angle offset from before
+    /// is restored by `end_peek()`.
+    pub(crate) fn start_peek(&mut self, angle: ViewAngle, prefs: &Preferences) {
+        let view_angle = self.view_prefs(prefs).view_angle();
+        if self.view_angle.peek_return.is_none() {
+            self.view_angle.peek_return =
+                Some((self.view_angle.current, self.view_angle.is_frozen));
+        }
+        self.view_angle.transient_rotation = None;
+        self.view_angle.is_frozen = true;
+        self.view_angle.snap_target = Some(view_angle.invert() * angle.to_quaternion());
+    }
+    /// Begins animating the view back to where it was before peeking.
+    pub fn end_peek(&mut self) {
+        let Some((offset, was_frozen)) = self.view_angle.peek_return.take() else {
+            return;
+        };
+        self.view_angle.is_frozen = was_frozen;
+        self.view_angle.snap_target = was_frozen.then_some(offset);
+    }
+    /// Returns whether the view is peeking, such as at the last layer.
+    pub fn is_peeking(&self) -> bool {
+        self.view_angle.peek_return.is_some()
+    }
+    /// Removes the whole-puzzle rotation nearest to the view angle offset
+    /// from dragging without twisting the puzzle, and returns it.
+    pub fn take_view_rotation(&mut self) -> Vec<Twist> {
+        let (twists, rot) = self.puzzle.nearest_rotation(self.view_angle.current);
+        self.view_angle.current = self.view_angle.current * rot.invert();
+        self.reset_view_angle_offset();
+        twists
     }
     fn update_transient_rotation(&mut self, interaction_prefs: &InteractionPreferences) {
-        if interaction_prefs.smart_realign {
+        // Peeking is only temporary, so it shouldn't rotate the puzzle.
+        if interaction_prefs.smart_realign && !self.is_peeking() {
             let nearest_twists = self.puzzle.nearest_rotation(self.view_angle.current);
             self.view_angle.transient_rotation =
                 (!nearest_twists.0.is_empty()).then_some(nearest_twists);
@@ -407,7 +638,7 @@ impl PuzzleController {
             // currently displaying;
             self.view_settings_anim.queue.pop_back();
         }
-        if let Some(old) = self.view_settings_anim.queue.get(0) {
+        let mut ret = if let Some(old) = self.view_settings_anim.queue.get(0) {
             let new = self
                 .view_settings_anim
                 .queue
@@ -417,19 +648,42 @@ impl PuzzleController {
             Cow::Owned(ViewPreferences::interpolate(old, new, t))
         } else {
             Cow::Borrowed(old_view_prefs)
+        };
+        if let Some(angle) = prefs.view_angles[self.ty()] {
+            ret.to_mut().set_view_angle(angle);
         }
+        ret
+    }
+    /// Returns the view angle currently displayed, including any offset from
+    /// dragging or the idle animation, and then removes that offset so that
+    /// the view doesn't change when the returned angle is saved as the view
+    /// angle for this puzzle.
+    pub(crate) fn take_view_angle(&mut self, prefs: &Preferences) -> ViewAngle {
+        let view_angle =
+            self.view_prefs(prefs).view_angle() * self.view_angle.idle * self.view_angle.current;
+        self.view_angle.current = Quaternion::one();
+        self.view_angle.idle = Quaternion::one();
+        self.view_angle.is_idling = false;
+        self.reset_view_angle_offset();
+        ViewAngle::from_quaternion(view_angle)
     }
     pub(crate) fn geometry(&mut self, prefs: &Preferences) -> Arc<Vec<ProjectedStickerGeometry>> {
         let view_prefs = self.view_prefs(prefs);
 
         self.update_transient_rotation(&prefs.interaction);
 
-        let params = StickerGeometryParams::new(
+        let mut params = StickerGeometryParams::new(
             &view_prefs,
             self.ty(),
             self.current_twist(),
-            self.view_angle.current * self.view_angle.queued_delta,
+            self.view_angle_offset(),
         );
+        params.explode = view_prefs.explode * self.explode_progress;
+        if prefs.opacity.see_through {
+            // Show stickers on the far side of 3D puzzles.
+            params.show_frontfaces = true;
+            params.show_backfaces = true;
+        }
 
         if self.cached_geometry_params != Some(params) {
             // Invalidate the cache.
@@ -441,90 +695,168 @@ impl PuzzleController {
         let ret = self.cached_geometry.take().unwrap_or_else(|| {
             log::trace!("Regenerating puzzle geometry");
 
-            // Project stickers.
-            let mut sticker_geometries: Vec<ProjectedStickerGeometry> = vec![];
-            for sticker in (0..self.stickers().len() as _).map(Sticker) {
+            Arc::new(self.project_stickers(params, |sticker| {
                 let piece = self.info(sticker).piece;
                 let vis_piece = self.visual_piece_state(piece);
-                if !self.is_sticker_hoverable(sticker) && vis_piece.opacity(prefs) == 0.0 {
-                    continue;
-                }
+                self.is_sticker_hoverable(sticker) || vis_piece.opacity(prefs) != 0.0
+            }))
+        });
 
-                // Compute geometry, including vertex positions before 3D
-                // perspective projection.
-                let sticker_geom = match self.displayed().sticker_geometry(sticker, params) {
-                    Some(s) => s,
-                    None => continue, // invisible; skip this sticker
-                };
+        self.cached_geometry = Some(Arc::clone(&ret));
+        ret
+    }
+    /// Returns the edges of a tesseract around a 4D puzzle, projected the same
+    /// way as the stickers, for drawing a reference frame that stays still as
+    /// pieces move. Returns nothing for other puzzles.
+    pub(crate) fn reference_frame_edges(&mut self, prefs: &Preferences) -> Vec<[Point3<f32>; 2]> {
+        if self.projection_type() != ProjectionType::_4D {
+            return vec![];
+        }
 
-                // Compute vertex positions after 3D perspective projection.
-                let projected_verts = match sticker_geom
-                    .verts
-                    .iter()
-                    .map(|&v| params.project_3d(v))
-                    .collect::<Option<Vec<_>>>()
-                {
-                    Some(s) => s,
-                    None => continue, // behind camera; skip this sticker
-                };
-
-                let mut projected_front_polygons = vec![];
-                let mut projected_back_polygons = vec![];
-
-                for (indices, twists) in sticker_geom
-                    .polygon_indices
-                    .iter()
-                    .zip(sticker_geom.polygon_twists)
-                {
-                    let projected_normal =
-                        geometry::polygon_normal_from_indices(&projected_verts, indices);
-                    if projected_normal.z > 0.0 {
-                        // This polygon is front-facing.
-                        let lighting_normal =
-                            geometry::polygon_normal_from_indices(&sticker_geom.verts, indices)
-                                .normalize();
-                        let illumination =
-                            params.ambient_light + lighting_normal.dot(params.light_vector);
-                        projected_front_polygons.push(geometry::polygon_from_indices(
-                            &projected_verts,
-                            indices,
-                            illumination,
-                            twists,
-                        ));
-                    } else {
-                        // This polygon is back-facing.
-                        let illumination = 0.0; // don't care
-                        projected_back_polygons.push(geometry::polygon_from_indices(
-                            &projected_verts,
-                            indices,
-                            illumination,
-                            ClickTwists::default(), // don't care
-                        ));
-                    }
-                }
+        let view_prefs = self.view_prefs(prefs);
+        let params =
+            StickerGeometryParams::new(&view_prefs, self.ty(), None, self.view_angle_offset());
+        let project = |point: Vector4<f32>| {
+            params.project_3d(
+                params
+                    .view_transform
+                    .transform_point(params.project_4d(point)?),
+            )
+        };
 
-                let (min_bound, max_bound) = util::min_and_max_bound(&projected_verts);
+        // The outer cube matches the outer boundary of the projection and the
+        // inner cube matches the cell at the center.
+        let r = 1.0 - params.face_spacing;
+        let corners = itertools::iproduct!([-1.0, 1.0], [-1.0, 1.0], [-1.0, 1.0], [-1.0, r])
+            .map(|(x, y, z, w)| Vector4::new(x, y, z, w))
+            .collect_vec();
+        corners
+            .iter()
+            .tuple_combinations()
+            // Corners differing in exactly one coordinate share an edge.
+            .filter(|(a, b)| (0..4).filter(|&i| a[i] != b[i]).count() == 1)
+            .filter_map(|(&a, &b)| Some([project(a)?, project(b)?]))
+            .collect()
+    }
+    fn view_angle_offset(&self) -> Quaternion<f32> {
+        self.view_angle.idle * self.view_angle.current * self.view_angle.queued_delta
+    }
+    /// Returns top-down sticker geometry for the pieces in the layer adjacent
+    /// to `up_face`, for last-layer recognition. Returns `None` if the puzzle
+    /// does not support it.
+    pub(crate) fn last_layer_geometry(
+        &mut self,
+        prefs: &Preferences,
+        up_face: Face,
+    ) -> Option<Vec<ProjectedStickerGeometry>> {
+        if self.projection_type() != ProjectionType::_3D {
+            return None;
+        }
+        let rot = self.ty().rotation_to_up(up_face)?;
+        let axis = self.twist_axis_from_name(self.info(up_face).symbol)?;
+
+        // Look straight down at the up face, with inverted perspective so
+        // that the sides of the last layer are visible too.
+        let view_prefs = ViewPreferences {
+            pitch: 90.0,
+            yaw: 0.0,
+            roll: 0.0,
+            fov_3d: -LAST_LAYER_VIEW_FOV,
+            ..self.view_prefs(prefs).into_owned()
+        };
+        let params = StickerGeometryParams::new(&view_prefs, self.ty(), self.current_twist(), rot);
+
+        Some(self.project_stickers(params, |sticker| {
+            let piece = self.info(sticker).piece;
+            self.displayed().layer_from_twist_axis(axis, piece) == 0
+        }))
+    }
+    fn project_stickers(
+        &self,
+        params: StickerGeometryParams,
+        include_sticker: impl Fn(Sticker) -> bool,
+    ) -> Vec<ProjectedStickerGeometry> {
+        // Project stickers.
+        let mut sticker_geometries: Vec<ProjectedStickerGeometry> = vec![];
+        for sticker in (0..self.stickers().len() as _).map(Sticker) {
+            if !include_sticker(sticker) {
+                continue;
+            }
 
-                sticker_geometries.push(ProjectedStickerGeometry {
-                    sticker,
+            // Compute geometry, including vertex positions before 3D
+            // perspective projection.
+            let sticker_geom = match self.displayed().sticker_geometry(sticker, params) {
+                Some(s) => s,
+                None => continue, // invisible; skip this sticker
+            };
 
-                    verts: projected_verts.into_boxed_slice(),
-                    min_bound,
-                    max_bound,
+            // Compute vertex positions after 3D perspective projection.
+            let projected_verts = match sticker_geom
+                .verts
+                .iter()
+                .map(|&v| params.project_3d(v))
+                .collect::<Option<Vec<_>>>()
+            {
+                Some(s) => s,
+                None => continue, // behind camera; skip this sticker
+            };
 
-                    front_polygons: projected_front_polygons.into_boxed_slice(),
-                    back_polygons: projected_back_polygons.into_boxed_slice(),
-                });
+            let mut projected_front_polygons = vec![];
+            let mut projected_back_polygons = vec![];
+
+            for (indices, twists) in sticker_geom
+                .polygon_indices
+                .iter()
+                .zip(sticker_geom.polygon_twists)
+            {
+                let projected_normal =
+                    geometry::polygon_normal_from_indices(&projected_verts, indices);
+                let lighting_normal =
+                    geometry::polygon_normal_from_indices(&sticker_geom.verts, indices).normalize();
+                let illumination = params.ambient_light + lighting_normal.dot(params.light_vector);
+                if projected_normal.z > 0.0 {
+                    // This polygon is front-facing.
+                    projected_front_polygons.push(geometry::polygon_from_indices(
+                        &projected_verts,
+                        indices,
+                        illumination,
+                        twists,
+                    ));
+                } else {
+                    // This polygon is back-facing, and only visible in
+                    // see-through mode.
+                    projected_back_polygons.push(geometry::polygon_from_indices(
+                        &projected_verts,
+                        indices,
+                        illumination,
+                        ClickTwists::default(), // don't care
+                    ));
+                }
             }
 
-            // Sort stickers by depth.
-            geometry::sort_by_depth(&mut sticker_geometries);
+            let (min_bound, max_bound) = util::min_and_max_bound(&projected_verts);
+            let orientation_marker = sticker_geom
+                .orientation_marker
+                .and_then(|p| params.project_3d(p));
 
-            Arc::new(sticker_geometries)
-        });
+            sticker_geometries.push(ProjectedStickerGeometry {
+                sticker,
 
-        self.cached_geometry = Some(Arc::clone(&ret));
-        ret
+                verts: projected_verts.into_boxed_slice(),
+                min_bound,
+                max_bound,
+
+                front_polygons: projected_front_polygons.into_boxed_slice(),
+                back_polygons: projected_back_polygons.into_boxed_slice(),
+
+                orientation_marker,
+            });
+        }
+
+        // Sort stickers by depth.
+        geometry::sort_by_depth(&mut sticker_geometries);
+
+        sticker_geometries
     }
 
     /// Advances the puzzle geometry and internal state to the next frame, using
@@ -538,10 +870,8 @@ impl PuzzleController {
         self.view_settings_anim.proceed(base_speed);
 
         // Animate view angle offset.
-        if !self.view_angle.is_frozen {
-            let offset = &mut self.view_angle.current;
-
-            let decay_multiplier = VIEW_ANGLE_OFFSET_DECAY_RATE.powf(delta.as_secs_f32());
+        let decay_multiplier = VIEW_ANGLE_OFFSET_DECAY_RATE.powf(delta.as_secs_f32());
+        let decay = |offset: &mut Quaternion<f32>| {
             let new_offset = Quaternion::one().slerp(*offset, decay_multiplier);
             if offset.s == new_offset.s {
                 // Stop the animation once we're not making any more progress.
@@ -549,13 +879,47 @@ impl PuzzleController {
             } else {
                 *offset = new_offset;
             }
+        };
+        if !self.view_angle.is_frozen {
+            decay(&mut self.view_angle.current);
+        } else if let Some(target) = self.view_angle.snap_target {
+            let mut remaining = target.invert() * self.view_angle.current;
+            decay(&mut remaining);
+            self.view_angle.current = target * remaining;
+            if remaining == Quaternion::one() {
+                self.view_angle.snap_target = None;
+            }
+        }
+        if !self.view_angle.is_idling {
+            decay(&mut self.view_angle.idle);
+        }
+
+        // Animate explosion.
+        let explode_delta = delta.as_secs_f32() / prefs.other_anim_duration;
+        self.explode_progress = match self.is_exploded {
+            true => self.explode_progress + explode_delta,
+            false => self.explode_progress - explode_delta,
+        }
+        .clamp(0.0, 1.0);
+        if self.explode_progress.is_nan() {
+            self.explode_progress = if self.is_exploded { 1.0 } else { 0.0 };
         }
 
         // Animate twist.
         let anim = &mut self.twist_anim;
         if anim.queue.is_empty() {
             anim.queue_max = 0;
+            anim.is_scramble = false;
             self.view_angle.queued_delta = Quaternion::one();
+        } else if anim.is_scramble {
+            // Animate scrambles at their own constant speed.
+            let mut twist_delta = delta.as_secs_f32() / prefs.scramble_twist_duration;
+            if !(0.0..MIN_TWIST_DELTA).contains(&twist_delta) {
+                twist_delta = 1.0; // Instantly complete the twist.
+            }
+            if let Some(q) = self.twist_anim.proceed(twist_delta) {
+                self.view_angle.queued_delta = self.view_angle.queued_delta * q;
+            }
         } else {
             // Update queue_max.
             anim.queue_max = std::cmp::max(anim.queue_max, anim.queue.len());
@@ -565,7 +929,13 @@ impl PuzzleController {
                 true => ((anim.queue.len() - 1) as f32 * EXP_TWIST_FACTOR).exp(),
                 false => 1.0,
             };
-            let mut twist_delta = base_speed * speed_mod;
+            // Take longer for twists with larger angles, so that a half turn
+            // takes twice as long as a quarter turn.
+            let angle_mod = match (prefs.proportional_twist_duration, anim.queue.front()) {
+                (true, Some(front)) => self.puzzle.twist_angle(front.twist) / Rad::turn_div_4(),
+                _ => 1.0,
+            };
+            let mut twist_delta = base_speed * speed_mod / angle_mod;
             // Cap the twist delta at 1.0, and also handle the case where
             // something went wrong with the calculation (e.g., division by
             // zero).
@@ -732,6 +1102,7 @@ impl PuzzleController {
     /// Skips the animations for all twists in the queue.
     pub fn skip_twist_animations(&mut self) {
         self.twist_anim.queue.clear();
+        self.twist_anim.is_scramble = false;
     }
 
     /// Returns whether there is a twist to undo.
@@ -748,11 +1119,15 @@ impl PuzzleController {
     pub fn undo(&mut self) -> Result<(), &'static str> {
         if let Some(entry) = self.undo_buffer.pop() {
             self.mark_unsaved();
-            match entry {
+            match &entry {
                 HistoryEntry::Twist(twist) => {
-                    let rev = self.reverse_twist(twist);
+                    let rev = self.reverse_twist(*twist);
                     self.animate_twist(rev)?;
                 }
+                HistoryEntry::Pattern(twists) => {
+                    let rev = invert_alg(self.ty(), twists);
+                    self.apply_twists_instantly(&rev)?;
+                }
             }
             self.redo_buffer.push(entry);
             Ok(())
@@ -760,13 +1135,22 @@ impl PuzzleController {
             Err("Nothing to undo")
         }
     }
+    /// Undoes one twist without adding it to the redo buffer, removing it from
+    /// the history as if it never happened. Returns an error if there was
+    /// nothing to undo or the twist could not be applied to the puzzle.
+    pub fn discard_last_twist(&mut self) -> Result<(), &'static str> {
+        self.undo()?;
+        self.redo_buffer.pop();
+        Ok(())
+    }
     /// Redoes one twist. Returns an error if there was nothing to redo or the
     /// twist could not be applied to the puzzle.
     pub fn redo(&mut self) -> Result<(), &'static str> {
         if let Some(entry) = self.redo_buffer.pop() {
             self.mark_unsaved();
-            match entry {
-                HistoryEntry::Twist(twist) => self.animate_twist(twist)?,
+            match &entry {
+                HistoryEntry::Twist(twist) => self.animate_twist(*twist)?,
+                HistoryEntry::Pattern(twists) => self.apply_twists_instantly(twists)?,
             }
             self.undo_buffer.push(entry);
             Ok(())
@@ -820,9 +1204,19 @@ impl PuzzleController {
     pub fn has_been_solved(&self) -> bool {
         self.scramble_state == ScrambleState::Solved
     }
-    /// Returns whether the puzzle is currently in a solved configuration.
+    /// Sets the rules for when the puzzle counts as solved.
+    pub fn set_solved_definition(&mut self, definition: SolvedDefinition) {
+        self.solved_definition = definition;
+    }
+    /// Returns whether the puzzle is currently in a solved configuration,
+    /// according to the rules set by `set_solved_definition()`.
     pub fn is_solved(&self) -> bool {
-        self.puzzle.is_solved()
+        self.puzzle.is_solved_by(self.solved_definition)
+    }
+    /// Returns a rough estimate of how solved the puzzle is, from 0.0 to 1.0.
+    /// See [`PuzzleState::solved_fraction_by()`].
+    pub fn solved_fraction(&self) -> f32 {
+        self.puzzle.solved_fraction_by(self.solved_definition)
     }
     /// Checks whether the puzzle was scrambled and is now solved. If so,
     /// updates the scramble state, and returns `true`.
@@ -839,20 +1233,119 @@ impl PuzzleController {
         }
     }
 
-    /// Returns the number of twists applied to the puzzle, not including the scramble.
-    pub fn twist_count(&self, metric: TwistMetric) -> usize {
-        metric.count_twists(
-            self,
+    /// Returns the number of twists in the combined solution on both sides of
+    /// the scramble, not including the scramble.
+    pub fn twist_count(&self, metric: TwistMetric, normalize_rotations: bool) -> usize {
+        metric.count_twists(self, self.solution(normalize_rotations))
+    }
+
+    /// Returns which side of the scramble is currently being solved.
+    pub fn side(&self) -> ScrambleSide {
+        self.side
+    }
+    /// Returns the twists applied on one side of the scramble.
+    pub fn twists_on_side(&self, side: ScrambleSide) -> Vec<Twist> {
+        if side == self.side {
             self.undo_buffer
                 .iter()
+                .flat_map(|entry| entry.twists())
                 .copied()
-                .filter_map(HistoryEntry::twist),
-        )
+                .collect()
+        } else {
+            self.other_side_twists.clone()
+        }
+    }
+    /// Returns the solution to the normal scramble formed by the twists on the
+    /// normal side followed by the reverse of the twists on the inverse side.
+    pub fn combined_solution(&self) -> Vec<Twist> {
+        let mut ret = self.twists_on_side(ScrambleSide::Normal);
+        ret.extend(invert_alg(
+            self.ty(),
+            &self.twists_on_side(ScrambleSide::Inverse),
+        ));
+        ret
+    }
+    /// Returns the combined solution, optionally with whole-puzzle rotations
+    /// removed by remapping the twists after them.
+    pub fn solution(&self, normalize_rotations: bool) -> Vec<Twist> {
+        let solution = self.combined_solution();
+        if normalize_rotations {
+            strip_rotations(self, solution)
+        } else {
+            solution
+        }
+    }
+    /// Returns the notation for the combined solution.
+    pub fn solution_string(
+        &self,
+        normalize_rotations: bool,
+        convention: &TwistConvention,
+        style: NotationStyle,
+    ) -> String {
+        self.solution(normalize_rotations)
+            .into_iter()
+            .map(|twist| convention.twist_to_string(self.ty(), style, twist))
+            .join(" ")
+    }
+    /// Switches between solving the normal scramble and the inverse scramble.
+    /// Twists applied on one side become premoves on the other side.
+    pub fn switch_side(&mut self) -> Result<(), &'static str> {
+        let current_twists = self.twists_on_side(self.side);
+        let new_twists = std::mem::replace(&mut self.other_side_twists, current_twists);
+        self.side = self.side.opposite();
+        self.rebuild_from_side_twists(new_twists)
+    }
+    /// Sets the twists applied on the inverse side of the scramble, such as
+    /// when loading a log file. The normal side must be the current side.
+    pub fn set_inverse_twists(&mut self, twists: Vec<Twist>) -> Result<(), &'static str> {
+        if self.side != ScrambleSide::Normal {
+            return Err("Cannot set inverse twists while solving the inverse");
+        }
+        self.other_side_twists = twists;
+        let normal_twists = self.twists_on_side(ScrambleSide::Normal);
+        self.rebuild_from_side_twists(normal_twists)
+    }
+    /// Rebuilds the puzzle state from the premoves (reverse of the twists on
+    /// the other side), the scramble (or its reverse), and the twists on the
+    /// current side.
+    fn rebuild_from_side_twists(&mut self, twists: Vec<Twist>) -> Result<(), &'static str> {
+        let premoves = invert_alg(self.ty(), &self.other_side_twists);
+        let scramble = match self.side {
+            ScrambleSide::Normal => self.scramble.clone(),
+            ScrambleSide::Inverse => invert_alg(self.ty(), &self.scramble),
+        };
+
+        let mut puzzle = Puzzle::new(self.ty());
+        for &twist in premoves.iter().chain(&scramble).chain(&twists) {
+            puzzle.twist(twist)?;
+        }
+
+        self.skip_twist_animations();
+        self.puzzle = puzzle;
+        self.undo_buffer = twists.into_iter().map(HistoryEntry::from).collect();
+        self.redo_buffer.clear();
+        self.cached_geometry = None;
+        self.mark_unsaved();
+        Ok(())
     }
+
     /// Returns the moves used to scramble the puzzle.
     pub fn scramble(&self) -> &[Twist] {
         &self.scramble
     }
+    /// Returns the notation for the moves used to scramble the puzzle.
+    pub fn scramble_string(&self) -> String {
+        self.scramble_string_in_style(NotationStyle::Standard)
+    }
+    /// Returns the notation for the moves used to scramble the puzzle, in a
+    /// notation style.
+    pub fn scramble_string_in_style(&self, style: NotationStyle) -> String {
+        let notation = self.notation_scheme_for_style(style);
+        self.scramble
+            .iter()
+            .map(|&twist| notation.twist_to_string(twist))
+            .join(" ")
+    }
     /// Returns the twists and other actions applied to the puzzle, not
     /// including the scramble.
     pub fn undo_buffer(&self) -> &[HistoryEntry] {
@@ -872,6 +1365,9 @@ struct TwistAnimationState {
     queue_max: usize,
     /// Progress of the animation in the current twist, from 0.0 to 1.0.
     progress: f32,
+    /// Whether the queue is a scramble being animated (reset when queue is
+    /// empty).
+    is_scramble: bool,
 }
 impl TwistAnimationState {
     #[must_use]
@@ -944,7 +1440,8 @@ impl ViewSettingsAnimState {
 ///
 /// 1. `queued_delta`
 /// 2. `current`
-/// 3. `view_prefs.view_angle` (from `ViewPreferences`)
+/// 3. `idle`
+/// 4. `view_prefs.view_angle` (from `ViewPreferences`)
 #[derive(Debug, Clone)]
 pub struct ViewAngleAnimState {
     /// Cumulative view angle offset delta from all the twists in the twist
@@ -959,6 +1456,19 @@ pub struct ViewAngleAnimState {
     /// Whether to freeze the view angle offset, versus animating it back to
     /// zero.
     is_frozen: bool,
+    /// View angle offset that a frozen view angle offset is animating toward
+    /// after snapping.
+    snap_target: Option<Quaternion<f32>>,
+
+    /// Rotation from the idle animation. This is never turned into
+    /// whole-puzzle rotations, so it doesn't affect the puzzle state.
+    idle: Quaternion<f32>,
+    /// Whether the idle animation is running, versus animating back to zero.
+    is_idling: bool,
+
+    /// View angle offset from before peeking, and whether it was frozen,
+    /// which are restored after peeking.
+    peek_return: Option<(Quaternion<f32>, bool)>,
 }
 impl Default for ViewAngleAnimState {
     fn default() -> Self {
@@ -968,13 +1478,21 @@ impl Default for ViewAngleAnimState {
 
             transient_rotation: None,
             is_frozen: false,
+            snap_target: None,
+
+            idle: Quaternion::one(),
+            is_idling: false,
+
+            peek_return: None,
         }
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum HistoryEntry {
     Twist(Twist),
+    /// Pattern applied from the solved state, which is undone all at once.
+    Pattern(Vec<Twist>),
 }
 impl From<Twist> for HistoryEntry {
     fn from(twist: Twist) -> Self {
@@ -982,15 +1500,22 @@ impl From<Twist> for HistoryEntry {
     }
 }
 impl HistoryEntry {
-    pub fn twist(self) -> Option<Twist> {
+    pub fn twists(&self) -> &[Twist] {
         match self {
-            Self::Twist(twist) => Some(twist),
+            Self::Twist(twist) => std::slice::from_ref(twist),
+            Self::Pattern(twists) => twists,
         }
     }
-    pub fn to_string(self, notation: &NotationScheme) -> String {
-        match self {
-            Self::Twist(twist) => notation.twist_to_string(twist),
-        }
+    pub fn to_string(
+        &self,
+        ty: PuzzleTypeEnum,
+        convention: &TwistConvention,
+        style: NotationStyle,
+    ) -> String {
+        self.twists()
+            .iter()
+            .map(|&twist| convention.twist_to_string(ty, style, twist))
+            .join(" ")
     }
 }
 
@@ -1009,6 +1534,25 @@ pub enum ScrambleState {
     Solved = 3,
 }
 
+/// Side of the scramble being solved, for solving on the inverse scramble.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum ScrambleSide {
+    /// Solving the scramble.
+    #[default]
+    Normal,
+    /// Solving the inverse of the scramble.
+    Inverse,
+}
+impl ScrambleSide {
+    /// Returns the other side.
+    pub fn opposite(self) -> Self {
+        match self {
+            Self::Normal => Self::Inverse,
+            Self::Inverse => Self::Normal,
+        }
+    }
+}
+
 /// Which parts of the puzzle to twist.
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct Grip {
@@ -1106,12 +1650,20 @@ pub struct VisualPieceState {
     hidden_opacity_override: Option<f32>,
 }
 impl VisualPieceState {
-    pub fn outline_color(self, prefs: &Preferences, is_sticker_selected: bool) -> egui::Rgba {
+    pub fn outline_color(
+        self,
+        prefs: &Preferences,
+        sticker_color: egui::Rgba,
+        is_sticker_selected: bool,
+    ) -> egui::Rgba {
         let pr = &prefs.outlines;
 
         let hidden_or_ungripped = f32::max(self.hidden, self.ungripped);
 
-        let mut ret = egui::Rgba::from(pr.default_color);
+        let mut ret = match prefs.gfx.stickerless {
+            true => sticker_color,
+            false => egui::Rgba::from(pr.default_color),
+        };
         // In order from lowest to highest priority:
         ret = util::mix(ret, egui::Rgba::from(pr.hidden_color), hidden_or_ungripped);
         ret = util::mix(ret, egui::Rgba::from(pr.hovered_color), self.hovered);
@@ -1161,6 +1713,9 @@ impl VisualPieceState {
         if pr.base * pr.ungripped < ret {
             ret = util::mix(ret, pr.base * pr.ungripped, self.ungripped);
         }
+        if pr.see_through {
+            ret *= pr.see_through_opacity;
+        }
         ret
     }
 }
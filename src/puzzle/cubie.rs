@@ -0,0 +1,143 @@
+//! Reading the pieces of a 2x2x2 or 3x3x3 Rubik's cube by slot, for the
+//! solvers.
+
+use itertools::Itertools;
+
+use super::*;
+
+/// Face symbols, ordered so that opposite faces are 3 apart.
+pub(super) const FACE_SYMBOLS: [&str; 6] = ["U", "R", "F", "D", "L", "B"];
+pub(super) const U: u8 = 0;
+pub(super) const R: u8 = 1;
+pub(super) const F: u8 = 2;
+pub(super) const D: u8 = 3;
+pub(super) const L: u8 = 4;
+pub(super) const B: u8 = 5;
+
+/// Faces around each corner slot, in clockwise order starting from the U or D
+/// face. DBL is last, so that the 2x2x2 solver can hold it fixed.
+pub(super) const CORNER_SLOTS: [[u8; 3]; 8] = [
+    [U, R, F],
+    [U, F, L],
+    [U, L, B],
+    [U, B, R],
+    [D, F, R],
+    [D, L, F],
+    [D, R, B],
+    [D, B, L],
+];
+/// Faces around each edge slot, starting from the U, D, F, or B face. The last
+/// four are the E slice.
+pub(super) const EDGE_SLOTS: [[u8; 2]; 12] = [
+    [U, R],
+    [U, F],
+    [U, L],
+    [U, B],
+    [D, R],
+    [D, F],
+    [D, L],
+    [D, B],
+    [F, R],
+    [F, L],
+    [B, L],
+    [B, R],
+];
+
+/// Returns the current face and solved face of each sticker on each piece, as
+/// indices into `FACE_SYMBOLS`.
+pub(super) fn piece_stickers(puzzle: &Puzzle) -> Vec<Vec<(u8, u8)>> {
+    let ty = puzzle.ty();
+    let face_index = |face: Face| {
+        let symbol = ty.info(face).symbol;
+        FACE_SYMBOLS.iter().position(|&s| s == symbol).unwrap() as u8
+    };
+    ty.pieces()
+        .iter()
+        .map(|piece| {
+            piece
+                .stickers
+                .iter()
+                .map(|&s| {
+                    (
+                        face_index(puzzle.sticker_facet(s)),
+                        face_index(ty.info(s).color),
+                    )
+                })
+                .collect_vec()
+        })
+        .collect()
+}
+
+/// Returns which piece is in each slot and how it is oriented, for the pieces
+/// with `N` stickers. `color_map` relabels solved faces relative to the
+/// current orientation of the puzzle.
+///
+/// Each piece is numbered by its solved slot. Its orientation is the position
+/// in its current slot of the sticker that belongs on the first face of its
+/// solved slot.
+pub(super) fn read_slots<const N: usize, const M: usize>(
+    pieces: &[Vec<(u8, u8)>],
+    color_map: &[Option<u8>; 6],
+    slots: &[[u8; N]; M],
+) -> Result<([u8; M], [u8; M]), String> {
+    let mut perm: [u8; M] = std::array::from_fn(|i| i as u8);
+    let mut ori = [0; M];
+
+    let mut seen = [false; M];
+    for stickers in pieces.iter().filter(|stickers| stickers.len() == N) {
+        let faces = stickers.iter().map(|&(face, _)| face).collect_vec();
+        let colors: Vec<u8> = stickers
+            .iter()
+            .map(|&(_, color)| color_map[color as usize])
+            .collect::<Option<_>>()
+            .ok_or("invalid piece colors")?;
+        let slot = find_slot(slots, &faces).ok_or("invalid piece position")?;
+        let piece = find_slot(slots, &colors).ok_or("invalid piece colors")?;
+        let primary_face = faces
+            .iter()
+            .zip(&colors)
+            .find(|(_, &color)| color == slots[piece][0])
+            .map(|(&face, _)| face)
+            .unwrap();
+        if std::mem::replace(&mut seen[slot], true) {
+            return Err("multiple pieces in the same position".to_string());
+        }
+        perm[slot] = piece as u8;
+        ori[slot] = slots[slot].iter().position(|&f| f == primary_face).unwrap() as u8;
+    }
+    Ok((perm, ori))
+}
+
+/// Returns the slot whose faces are exactly `faces`, in any order.
+pub(super) fn find_slot<const N: usize>(slots: &[[u8; N]], faces: &[u8]) -> Option<usize> {
+    slots
+        .iter()
+        .position(|slot| faces.len() == N && slot.iter().all(|f| faces.contains(f)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_slots() {
+        let ty = PuzzleTypeEnum::Rubiks3D { layer_count: 3 };
+        let identity = std::array::from_fn(|i| i as u8);
+        let mut puzzle = Puzzle::new(ty);
+        let color_map = [0, 1, 2, 3, 4, 5].map(Some);
+        let pieces = piece_stickers(&puzzle);
+        assert_eq!(
+            read_slots(&pieces, &color_map, &CORNER_SLOTS),
+            Ok((identity, [0; 8])),
+        );
+
+        // F moves four edges and flips them relative to the U and D faces.
+        puzzle
+            .twist(ty.notation_scheme().parse_twist("F").unwrap())
+            .unwrap();
+        let pieces = piece_stickers(&puzzle);
+        let (edge_perm, edge_ori) = read_slots(&pieces, &color_map, &EDGE_SLOTS).unwrap();
+        assert_ne!(edge_perm, std::array::from_fn(|i| i as u8));
+        assert_eq!(edge_ori.iter().filter(|&&o| o == 1).count(), 4);
+    }
+}
@@ -31,6 +31,9 @@ pub struct StickerGeometryParams {
     pub face_scale: f32,
     /// `(sticker width) / (puzzle diameter)`. Ranges from 0.0 to 1.0.
     pub sticker_scale: f32,
+    /// `(distance each piece is moved outward) / (distance from the center of
+    /// the puzzle to the center of the piece)`. Ranges from 0.0 to infinity.
+    pub explode: f32,
 
     /// 4D FOV, in degrees.
     pub fov_4d: f32,
@@ -103,6 +106,7 @@ impl StickerGeometryParams {
             sticker_grid_scale,
             face_scale,
             sticker_scale,
+            explode: 0.0,
 
             fov_4d: view_prefs.fov_4d,
             fov_3d: view_prefs.fov_3d,
@@ -178,6 +182,9 @@ pub struct StickerGeometry {
     pub polygon_indices: Vec<Box<[u16]>>,
     /// Twists on left/right/middle mouse click per polygon.
     pub polygon_twists: Vec<ClickTwists>,
+    /// Point on the sticker in the direction of its orientation marker, for
+    /// stickers whose orientation is otherwise invisible (such as centers).
+    pub orientation_marker: Option<Point3<f32>>,
 }
 impl StickerGeometry {
     pub(super) fn new_double_quad(
@@ -190,6 +197,7 @@ impl StickerGeometry {
             verts: verts.to_vec(),
             polygon_indices: vec![Box::new([0, 2, 3, 1]), Box::new([2, 0, 1, 3])],
             polygon_twists: vec![twists, twists.rev()],
+            orientation_marker: None,
         };
         if !back_face {
             ret.polygon_indices.pop();
@@ -222,6 +230,7 @@ impl StickerGeometry {
                 Box::new([1, 3, 7, 5]),
             ],
             polygon_twists: twists.to_vec(),
+            orientation_marker: None,
         })
     }
 }
@@ -236,6 +245,9 @@ pub(crate) struct ProjectedStickerGeometry {
 
     pub front_polygons: Box<[Polygon]>,
     pub back_polygons: Box<[Polygon]>,
+
+    /// Orientation marker position, after 3D perspective projection.
+    pub orientation_marker: Option<Point3<f32>>,
 }
 impl ProjectedStickerGeometry {
     pub(crate) fn twists_for_point(&self, point: Point2<f32>) -> Option<ClickTwists> {
@@ -258,8 +270,9 @@ pub(crate) struct Polygon {
     pub twists: ClickTwists,
 }
 impl Polygon {
-    /// Constructs a convex polygon from a list of coplanar vertices in
-    /// counterclockwise order. The polygon must not be degenerate, and no three
+    /// Constructs a polygon from a list of coplanar vertices in counterclockwise
+    /// order. The polygon must be star-shaped around its first vertex (which
+    /// includes any convex polygon), it must not be degenerate, and no three
     /// vertices may be colinear.
     pub fn new(verts: SmallVec<[Point3<f32>; 4]>, illumination: f32, twists: ClickTwists) -> Self {
         let mut min_bound = verts[0];
@@ -288,12 +301,18 @@ impl Polygon {
             && self.min_bound.y <= point.y
             && point.x <= self.max_bound.x
             && point.y <= self.max_bound.y
-            && self
-                .verts
-                .iter()
-                .map(|v| cgmath::point2(v.x, v.y))
-                .cyclic_pairs()
-                .all(|(a, b)| (b - a).perp_dot(point - a) <= 0.0)
+            && self.triangles().any(|tri| {
+                tri.into_iter()
+                    .cyclic_pairs()
+                    .all(|(a, b)| (b - a).perp_dot(point - a) <= 0.0)
+            })
+    }
+
+    /// Returns the triangles in a fan around the first vertex, projected onto
+    /// the XY plane. This matches how polygons are triangulated for rendering.
+    fn triangles(&self) -> impl '_ + Iterator<Item = [Point2<f32>; 3]> {
+        let v = |i: usize| cgmath::point2(self.verts[i].x, self.verts[i].y);
+        (2..self.verts.len()).map(move |i| [v(0), v(i - 1), v(i)])
     }
 }
 
@@ -0,0 +1,660 @@
+//! Ivy cube.
+//!
+//! The Ivy cube is a corner-turning cube where only four of the eight corners
+//! turn. Each twist rotates one corner by 120 degrees along with the three
+//! face centers adjacent to it. The other three corners never move, so the
+//! puzzle has no whole-puzzle rotations.
+
+use cgmath::*;
+use itertools::Itertools;
+use num_enum::FromPrimitive;
+use smallvec::smallvec;
+use std::f32::consts::FRAC_PI_2;
+use std::ops::{Index, IndexMut};
+use strum::IntoEnumIterator;
+
+use super::*;
+
+/// Number of line segments used to approximate each circular cut on a face.
+const ARC_SEGMENTS: usize = 8;
+
+/// Outward normal vectors of the faces, in the same order as the faces of
+/// `Rubiks3D`.
+const FACE_NORMALS: [[i8; 3]; 6] = [
+    [1, 0, 0],
+    [-1, 0, 0],
+    [0, 1, 0],
+    [0, -1, 0],
+    [0, 0, 1],
+    [0, 0, -1],
+];
+const FACES: [FaceInfo; 6] = [
+    FaceInfo::new("R", "Right"),
+    FaceInfo::new("L", "Left"),
+    FaceInfo::new("U", "Up"),
+    FaceInfo::new("D", "Down"),
+    FaceInfo::new("F", "Front"),
+    FaceInfo::new("B", "Back"),
+];
+
+/// Directions of the turning corners. These form a tetrahedron, so every face
+/// has exactly two of them at opposite corners.
+const CORNERS: [[i8; 3]; 4] = [[1, 1, 1], [1, -1, -1], [-1, 1, -1], [-1, -1, 1]];
+/// Twist axis names, in the same order as `CORNERS`. `R`, `L`, and `U` match
+/// the corners used in Skewb notation (DBR, DFL, and UBL respectively), and
+/// `F` is the remaining corner (UFR).
+const TWIST_AXES: [TwistAxisInfo; 4] = [
+    TwistAxisInfo {
+        name: "F",
+        increments_per_turn: Some(3),
+    },
+    TwistAxisInfo {
+        name: "R",
+        increments_per_turn: Some(3),
+    },
+    TwistAxisInfo {
+        name: "U",
+        increments_per_turn: Some(3),
+    },
+    TwistAxisInfo {
+        name: "L",
+        increments_per_turn: Some(3),
+    },
+];
+
+/// Index of the first center piece. Corners come before centers.
+const FIRST_CENTER: usize = CORNERS.len();
+
+pub(super) fn puzzle_type() -> &'static dyn PuzzleType {
+    puzzle_description()
+}
+
+fn puzzle_description() -> &'static IvyDescription {
+    lazy_static! {
+        static ref DESCRIPTION: IvyDescription = IvyDescription::new();
+    }
+    &DESCRIPTION
+}
+
+#[derive(Debug, Clone)]
+struct IvyDescription {
+    pieces: Vec<PieceInfo>,
+    stickers: Vec<StickerInfo>,
+    twist_directions: Vec<TwistDirectionInfo>,
+    piece_types: Vec<PieceTypeInfo>,
+    notation: NotationScheme,
+
+    /// Polygon for each sticker in the coordinates of its face, in clockwise
+    /// order as seen from outside the puzzle. Each polygon is star-shaped
+    /// around its first vertex.
+    sticker_shapes: Vec<Vec<Point2<f32>>>,
+    /// Corner that each sticker twists when clicked, if any.
+    sticker_corners: Vec<Option<usize>>,
+}
+impl IvyDescription {
+    fn new() -> Self {
+        let mut pieces = vec![];
+        let mut stickers = vec![];
+        let mut sticker_shapes = vec![];
+        let mut sticker_corners = vec![];
+
+        for (i, &corner) in CORNERS.iter().enumerate() {
+            let piece = Piece(pieces.len() as _);
+            let mut piece_stickers = smallvec![];
+            for (face, &normal) in FACE_NORMALS.iter().enumerate() {
+                if dot(normal, corner) != 1 {
+                    continue;
+                }
+                let [u, v] = face_basis(normal);
+                let corner_2d = point2(dot(corner, u) as f32, dot(corner, v) as f32);
+                let shape = std::iter::once(corner_2d).chain(arc(corner_2d)).collect();
+
+                piece_stickers.push(Sticker(stickers.len() as _));
+                stickers.push(StickerInfo {
+                    piece,
+                    color: Face(face as _),
+                });
+                sticker_shapes.push(clockwise(shape));
+                sticker_corners.push(Some(i));
+            }
+            pieces.push(PieceInfo {
+                stickers: piece_stickers,
+                piece_type: PieceType(0),
+            });
+        }
+
+        for (face, &normal) in FACE_NORMALS.iter().enumerate() {
+            let piece = Piece(pieces.len() as _);
+            let [u, v] = face_basis(normal);
+            // The center is bounded by the arcs of the two corners on this
+            // face.
+            let corners = CORNERS
+                .iter()
+                .filter(|&&corner| dot(normal, corner) == 1)
+                .map(|&corner| point2(dot(corner, u) as f32, dot(corner, v) as f32))
+                .collect_vec();
+            let mut shape = vec![];
+            for &corner in &corners {
+                shape.extend(arc(corner));
+                shape.pop(); // This is the first point of the next arc.
+            }
+
+            let sticker = Sticker(stickers.len() as _);
+            stickers.push(StickerInfo {
+                piece,
+                color: Face(face as _),
+            });
+            sticker_shapes.push(clockwise(shape));
+            // A center is adjacent to two corners, so clicking it would be
+            // ambiguous.
+            sticker_corners.push(None);
+            pieces.push(PieceInfo {
+                stickers: smallvec![sticker],
+                piece_type: PieceType(1),
+            });
+        }
+
+        let notation = NotationScheme {
+            axis_names: TWIST_AXES.iter().map(|a| a.name.to_string()).collect(),
+            direction_names: TwistDirectionEnum::iter()
+                .map(|dir| TwistDirectionName::Same(dir.symbol().to_string()))
+                .collect(),
+            block_suffix: None,
+            block_axis_names: None,
+            aliases: vec![],
+        };
+
+        Self {
+            pieces,
+            stickers,
+            twist_directions: TwistDirectionEnum::iter().map(|dir| dir.info()).collect(),
+            piece_types: vec![
+                PieceTypeInfo::new("corner".to_string()),
+                PieceTypeInfo::new("center".to_string()),
+            ],
+            notation,
+
+            sticker_shapes,
+            sticker_corners,
+        }
+    }
+}
+impl PuzzleType for IvyDescription {
+    fn ty(&self) -> PuzzleTypeEnum {
+        PuzzleTypeEnum::Ivy
+    }
+    fn name(&self) -> &str {
+        "Ivy cube"
+    }
+    fn family_display_name(&self) -> &'static str {
+        "Ivy cube"
+    }
+    fn family_internal_name(&self) -> &'static str {
+        "Ivy"
+    }
+    fn projection_type(&self) -> ProjectionType {
+        ProjectionType::_3D
+    }
+
+    fn layer_count(&self) -> u8 {
+        1
+    }
+    fn family_max_layer_count(&self) -> u8 {
+        1
+    }
+    fn projection_radius_3d(&self, _p: StickerGeometryParams) -> f32 {
+        3.0_f32.sqrt()
+    }
+    fn scramble_moves_count(&self) -> usize {
+        12
+    }
+
+    fn faces(&self) -> &[FaceInfo] {
+        &FACES
+    }
+    fn pieces(&self) -> &[PieceInfo] {
+        &self.pieces
+    }
+    fn stickers(&self) -> &[StickerInfo] {
+        &self.stickers
+    }
+    fn twist_axes(&self) -> &[TwistAxisInfo] {
+        &TWIST_AXES
+    }
+    fn twist_directions(&self) -> &[TwistDirectionInfo] {
+        &self.twist_directions
+    }
+    fn piece_types(&self) -> &[PieceTypeInfo] {
+        &self.piece_types
+    }
+
+    fn opposite_twist_axis(&self, _twist_axis: TwistAxis) -> Option<TwistAxis> {
+        None
+    }
+
+    fn make_recenter_twist(&self, _axis: TwistAxis) -> Result<Twist, String> {
+        Err("cannot recenter Ivy cube".to_string())
+    }
+
+    fn canonicalize_twist(&self, twist: Twist) -> Twist {
+        twist
+    }
+
+    fn notation_scheme(&self) -> &NotationScheme {
+        &self.notation
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Ivy {
+    desc: &'static IvyDescription,
+    piece_states: Box<[Orientation]>,
+}
+impl Eq for Ivy {}
+impl PartialEq for Ivy {
+    fn eq(&self, other: &Self) -> bool {
+        self.piece_states == other.piece_states
+    }
+}
+impl Index<Piece> for Ivy {
+    type Output = Orientation;
+
+    fn index(&self, piece: Piece) -> &Self::Output {
+        &self.piece_states[piece.0 as usize]
+    }
+}
+impl IndexMut<Piece> for Ivy {
+    fn index_mut(&mut self, piece: Piece) -> &mut Self::Output {
+        &mut self.piece_states[piece.0 as usize]
+    }
+}
+impl PuzzleState for Ivy {
+    fn twist(&mut self, twist: Twist) -> Result<(), &'static str> {
+        if twist.layers != self.all_layers() {
+            return Err("invalid layer mask");
+        }
+        let corner = CORNERS[twist.axis.0 as usize];
+        let rotation = Orientation::corner_twist(corner, twist.direction.into());
+        for piece in self.pieces_affected_by_twist(twist) {
+            self[piece] = rotation * self[piece];
+        }
+        Ok(())
+    }
+    fn layer_from_twist_axis(&self, twist_axis: TwistAxis, piece: Piece) -> u8 {
+        let corner = CORNERS[twist_axis.0 as usize];
+        // Pieces that aren't next to the corner are in a "layer" that can't be
+        // twisted.
+        let is_adjacent = match piece.0 as usize {
+            i if i < FIRST_CENTER => i == twist_axis.0 as usize,
+            i => dot(self[piece] * FACE_NORMALS[i - FIRST_CENTER], corner) > 0,
+        };
+        if is_adjacent {
+            0
+        } else {
+            1
+        }
+    }
+
+    fn rotation_candidates(&self) -> Vec<(Vec<Twist>, Quaternion<f32>)> {
+        // The three fixed corners make whole-puzzle rotations impossible.
+        vec![]
+    }
+
+    fn sticker_geometry(
+        &self,
+        sticker: Sticker,
+        p: StickerGeometryParams,
+    ) -> Option<StickerGeometry> {
+        let sticker_index = sticker.0 as usize;
+        let piece = self.info(sticker).piece;
+        let normal = FACE_NORMALS[self.info(sticker).color.0 as usize];
+        let [u, v] = face_basis(normal).map(to_vec3);
+
+        let mut transform = p.view_transform;
+        if let Some((twist, progress)) = p.twist_animation {
+            if self.is_piece_affected_by_twist(twist, piece) {
+                let corner = CORNERS[twist.axis.0 as usize];
+                let direction: TwistDirectionEnum = twist.direction.into();
+                let angle = self.twist_angle(twist) * direction.sign().float() * progress;
+                transform =
+                    transform * Matrix3::from_axis_angle(to_vec3(corner).normalize(), angle);
+            }
+        }
+        transform = transform * self[piece].to_matrix();
+
+        // Move the piece outward in the direction of its stickers.
+        let explode_offset = self
+            .info(piece)
+            .stickers
+            .iter()
+            .map(|&s| to_vec3(FACE_NORMALS[self.info(s).color.0 as usize]))
+            .fold(Vector3::zero(), |a, b| a + b)
+            * p.explode;
+
+        let verts = self.desc.sticker_shapes[sticker_index]
+            .iter()
+            .map(|pt| {
+                let point = to_vec3(normal) + (u * pt.x + v * pt.y) * p.face_scale + explode_offset;
+                transform.transform_point(Point3::from_vec(point))
+            })
+            .collect_vec();
+
+        let twists = match self.desc.sticker_corners[sticker_index] {
+            Some(corner) => {
+                let cw_twist = Twist {
+                    axis: TwistAxis(corner as _),
+                    direction: TwistDirectionEnum::CW.into(),
+                    layers: self.all_layers(),
+                };
+                ClickTwists {
+                    cw: Some(cw_twist),
+                    ccw: Some(self.reverse_twist(cw_twist)),
+                    recenter: None,
+                }
+            }
+            None => ClickTwists::default(),
+        };
+
+        // Keep the first vertex first so that both polygons are star-shaped
+        // around it.
+        let n = verts.len() as u16;
+        let front = (0..n).collect();
+        let back = std::iter::once(0).chain((1..n).rev()).collect();
+        let mut polygon_indices = vec![];
+        let mut polygon_twists = vec![];
+        if p.show_frontfaces {
+            polygon_indices.push(front);
+            polygon_twists.push(twists);
+        }
+        if p.show_backfaces {
+            polygon_indices.push(back);
+            polygon_twists.push(twists.rev());
+        }
+
+        Some(StickerGeometry {
+            verts,
+            polygon_indices,
+            polygon_twists,
+            orientation_marker: None,
+        })
+    }
+
+    fn sticker_facet(&self, sticker: Sticker) -> Face {
+        let info = self.info(sticker);
+        let normal = self[info.piece] * FACE_NORMALS[info.color.0 as usize];
+        let face = FACE_NORMALS.iter().position(|&n| n == normal);
+        Face(face.expect("invalid sticker orientation") as _)
+    }
+
+    fn is_solved(&self) -> bool {
+        let mut color_per_facet = vec![None; self.faces().len()];
+        for (i, sticker) in self.stickers().iter().enumerate() {
+            let facet = self.sticker_facet(Sticker(i as _)).0 as usize;
+            if color_per_facet[facet].is_none() {
+                color_per_facet[facet] = Some(sticker.color);
+            } else if color_per_facet[facet] != Some(sticker.color) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn piece_orientation(&self, piece: Piece) -> Option<Vec<Face>> {
+        let m = self[piece].0;
+        (0..3)
+            .map(|j| {
+                let column = [m[0][j], m[1][j], m[2][j]];
+                let face = FACE_NORMALS.iter().position(|&n| n == column)?;
+                Some(Face(face as _))
+            })
+            .collect()
+    }
+    fn set_piece_orientation(&mut self, piece: Piece, orientation: &[Face]) -> Result<(), String> {
+        let faces: [Face; 3] = orientation.try_into().map_err(|_| {
+            format!(
+                "expected 3 faces in piece orientation, got {}",
+                orientation.len()
+            )
+        })?;
+        let mut columns = [[0; 3]; 3];
+        for (column, face) in columns.iter_mut().zip(faces) {
+            *column = *FACE_NORMALS
+                .get(face.0 as usize)
+                .ok_or("invalid face in piece orientation")?;
+        }
+        let rotation = Orientation(std::array::from_fn(|i| columns.map(|c| c[i])));
+        if rotation.determinant() != 1 {
+            return Err("piece orientation is not a rotation".to_string());
+        }
+        if let Some(&corner) = CORNERS.get(piece.0 as usize) {
+            if rotation * corner != corner {
+                return Err("corner piece can only rotate around its corner".to_string());
+            }
+        }
+        self[piece] = rotation;
+        Ok(())
+    }
+    fn piece_position(&self, piece: Piece) -> Option<Piece> {
+        match piece.0 as usize {
+            // Corners never move.
+            i if i < FIRST_CENTER => Some(piece),
+            i => {
+                let normal = self[piece] * FACE_NORMALS[i - FIRST_CENTER];
+                let face = FACE_NORMALS.iter().position(|&n| n == normal)?;
+                Some(Piece((FIRST_CENTER + face) as _))
+            }
+        }
+    }
+}
+#[delegate_to_methods]
+#[delegate(PuzzleType, target_ref = "desc")]
+impl Ivy {
+    pub fn new() -> Self {
+        let desc = puzzle_description();
+        let piece_states = vec![Orientation::IDENTITY; desc.pieces().len()].into_boxed_slice();
+        Self { desc, piece_states }
+    }
+
+    fn desc(&self) -> &IvyDescription {
+        self.desc
+    }
+}
+impl Default for Ivy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Rotation matrix with integer entries. Every orientation reachable on the
+/// Ivy cube can be represented exactly this way.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Orientation([[i8; 3]; 3]);
+impl std::ops::Mul for Orientation {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let mut ret = [[0; 3]; 3];
+        for (i, row) in ret.iter_mut().enumerate() {
+            for (j, x) in row.iter_mut().enumerate() {
+                *x = (0..3).map(|k| self.0[i][k] * rhs.0[k][j]).sum();
+            }
+        }
+        Self(ret)
+    }
+}
+impl std::ops::Mul<[i8; 3]> for Orientation {
+    type Output = [i8; 3];
+
+    fn mul(self, rhs: [i8; 3]) -> [i8; 3] {
+        self.0.map(|row| dot(row, rhs))
+    }
+}
+impl Orientation {
+    const IDENTITY: Self = Self([[1, 0, 0], [0, 1, 0], [0, 0, 1]]);
+
+    fn determinant(self) -> i8 {
+        let [a, b, c] = self.0;
+        a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0])
+            + a[2] * (b[0] * c[1] - b[1] * c[0])
+    }
+
+    /// Returns the rotation of a twist around a corner.
+    fn corner_twist(corner: [i8; 3], direction: TwistDirectionEnum) -> Self {
+        // A counterclockwise rotation around [1, 1, 1] cycles the axes X -> Y
+        // -> Z. Flipping the signs of two axes gives the rotation around any
+        // other corner.
+        let mut ret = [[0; 3]; 3];
+        for j in 0..3 {
+            let i = (j + 1) % 3;
+            ret[i][j] = corner[i] * corner[j];
+        }
+        let ccw = Self(ret);
+        match direction {
+            TwistDirectionEnum::CCW => ccw,
+            TwistDirectionEnum::CW => ccw * ccw,
+        }
+    }
+
+    fn to_matrix(self) -> Matrix3<f32> {
+        // cgmath matrices are column-major.
+        let [c0, c1, c2] = [0, 1, 2].map(|j| to_vec3([self.0[0][j], self.0[1][j], self.0[2][j]]));
+        Matrix3::from_cols(c0, c1, c2)
+    }
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(EnumIter, FromPrimitive, Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[repr(u8)]
+enum TwistDirectionEnum {
+    #[default]
+    CW = 0,
+    CCW = 1,
+}
+impl From<TwistDirectionEnum> for TwistDirection {
+    fn from(direction: TwistDirectionEnum) -> Self {
+        Self(direction as _)
+    }
+}
+impl From<TwistDirection> for TwistDirectionEnum {
+    fn from(TwistDirection(i): TwistDirection) -> Self {
+        Self::from(i)
+    }
+}
+impl TwistDirectionEnum {
+    fn info(self) -> TwistDirectionInfo {
+        TwistDirectionInfo {
+            symbol: self.symbol(),
+            name: self.name(),
+            increments: match self {
+                Self::CW => 1,
+                Self::CCW => -1,
+            },
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::CW => "",
+            Self::CCW => "'",
+        }
+    }
+    fn name(self) -> &'static str {
+        match self {
+            Self::CW => "CW",
+            Self::CCW => "CCW",
+        }
+    }
+
+    fn sign(self) -> Sign {
+        match self {
+            Self::CW => Sign::Neg,
+            Self::CCW => Sign::Pos,
+        }
+    }
+}
+
+fn dot(a: [i8; 3], b: [i8; 3]) -> i8 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+fn to_vec3(v: [i8; 3]) -> Vector3<f32> {
+    vec3(v[0] as f32, v[1] as f32, v[2] as f32)
+}
+
+/// Returns two axes spanning a face, such that their cross product is the
+/// face's outward normal.
+fn face_basis(normal: [i8; 3]) -> [[i8; 3]; 2] {
+    let axis = normal.iter().position(|&x| x != 0).unwrap();
+    let sign = normal[axis];
+    let mut u = [0; 3];
+    let mut v = [0; 3];
+    u[(axis + 1) % 3] = 1;
+    v[(axis + 2) % 3] = 1;
+    if sign > 0 {
+        [u, v]
+    } else {
+        [v, u]
+    }
+}
+
+/// Returns points along the cut that separates a corner from the rest of the
+/// face, in face coordinates. The cut is a quarter circle centered on the
+/// opposite corner of the face, with radius equal to the edge length of the
+/// cube.
+fn arc(corner: Point2<f32>) -> impl Iterator<Item = Point2<f32>> {
+    let center = point2(-corner.x, -corner.y);
+    (0..=ARC_SEGMENTS).map(move |i| {
+        let t = i as f32 / ARC_SEGMENTS as f32 * FRAC_PI_2;
+        center + vec2(corner.x * t.cos(), corner.y * t.sin()) * 2.0
+    })
+}
+
+/// Reverses a polygon if necessary so that it's clockwise, keeping the first
+/// vertex first.
+fn clockwise(mut polygon: Vec<Point2<f32>>) -> Vec<Point2<f32>> {
+    let signed_area: f32 = polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(a, b)| a.x * b.y - b.x * a.y)
+        .sum();
+    if signed_area > 0.0 {
+        polygon[1..].reverse();
+    }
+    polygon
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ivy_twist_serialization() {
+        crate::puzzle::tests::test_twist_serialization(&Ivy::new());
+    }
+
+    #[test]
+    fn test_ivy_twists() {
+        let solved = Ivy::new();
+        assert!(solved.is_solved());
+
+        for axis in (0..TWIST_AXES.len() as _).map(TwistAxis) {
+            let twist = Twist {
+                axis,
+                direction: TwistDirectionEnum::CW.into(),
+                layers: solved.all_layers(),
+            };
+            // Each twist moves one corner and three centers.
+            assert_eq!(4, solved.pieces_affected_by_twist(twist).len());
+
+            let mut p = solved.clone();
+            p.twist(twist).unwrap();
+            assert!(!p.is_solved());
+            p.twist(solved.reverse_twist(twist)).unwrap();
+            assert_eq!(solved, p);
+
+            for _ in 0..3 {
+                p.twist(twist).unwrap();
+            }
+            assert_eq!(solved, p);
+        }
+    }
+}
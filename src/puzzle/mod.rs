@@ -3,18 +3,30 @@
 #[macro_use]
 mod common;
 
+pub mod blind_trace;
+pub mod commutator;
+pub mod comparison;
 pub mod controller;
+mod cubie;
 pub mod geometry;
+pub mod ivy;
 pub mod notation;
+pub mod orientation_scramble;
 pub mod rubiks_3d;
 pub mod rubiks_4d;
+pub mod snapshot;
+pub mod solve_job;
+pub mod solver;
+pub mod two_phase;
 
 pub use common::*;
 pub use controller::*;
 pub use geometry::*;
+pub use ivy::Ivy;
 pub use notation::*;
 pub use rubiks_3d::Rubiks3D;
 pub use rubiks_4d::Rubiks4D;
+pub use snapshot::*;
 
 pub mod traits {
     pub use super::{PuzzleInfo, PuzzleState, PuzzleType};
@@ -77,18 +89,23 @@ mod tests {
         p: &impl PuzzleType,
         twists: impl IntoIterator<Item = Twist>,
     ) {
-        let notation = p.notation_scheme();
-
-        for twist in twists {
-            let serialized_twist = notation.twist_to_string(twist);
-            let deserialized_twist = notation.parse_twist(&serialized_twist);
-            assert_eq!(
-                Ok(twist),
-                deserialized_twist,
-                "Error deserializing {:?} for {}",
-                serialized_twist,
-                p.name(),
-            );
+        let twists = twists.into_iter().collect::<Vec<_>>();
+
+        for &style in p.notation_styles() {
+            let notation = p.notation_scheme_for_style(style);
+
+            for &twist in &twists {
+                let serialized_twist = notation.twist_to_string(twist);
+                let deserialized_twist = notation.parse_twist(&serialized_twist);
+                assert_eq!(
+                    Ok(twist),
+                    deserialized_twist,
+                    "Error deserializing {:?} for {} in {} notation",
+                    serialized_twist,
+                    p.name(),
+                    style,
+                );
+            }
         }
     }
 
@@ -1,14 +1,40 @@
 use itertools::Itertools;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::fmt;
+use strum::{Display, EnumIter};
 
 use super::*;
 
+/// Style of notation shown in the UI. Puzzles that don't have a style use
+/// the standard one instead. Log files always use the standard style.
+#[derive(
+    Serialize, Deserialize, Debug, Display, Default, EnumIter, Copy, Clone, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum NotationStyle {
+    /// WCA notation for 3D puzzles, with wide twists such as `Rw` and `3Rw`,
+    /// and twist directions named by faces for 4D puzzles, such as `IUF`.
+    #[default]
+    Standard,
+    /// SiGN notation for 3D puzzles, with wide twists such as `r` and `3r`.
+    #[strum(serialize = "SiGN")]
+    Sign,
+    /// Twist directions named by the axes of the cell that is twisted for 4D
+    /// puzzles, such as `Ixy`, closer to how MC4D describes twists.
+    #[strum(serialize = "MC4D")]
+    Mc4d,
+}
+
 #[derive(Debug, Clone)]
 pub struct NotationScheme {
     pub(super) axis_names: Vec<String>,
     pub(super) direction_names: Vec<TwistDirectionName>,
     pub(super) block_suffix: Option<String>,
+    /// Axis names for wide twists, such as `r` in SiGN notation. Like
+    /// `block_suffix`, these are used for twists of several layers starting
+    /// from the outermost one.
+    pub(super) block_axis_names: Option<Vec<String>>,
     pub(super) aliases: Vec<(String, Alias)>,
     // TODO: flag to allow chaining directions (e.g., "Rxyx'y")
 }
@@ -74,10 +100,16 @@ impl NotationScheme {
         }
 
         // If that doesn't work, format the twist normally.
+        let is_block = twist.layers.is_contiguous_from_outermost() && twist.layers.count() > 1;
         self.format_layers(f, twist.layers)?;
-        self.format_axis(f, twist.axis)?;
+        match &self.block_axis_names {
+            Some(block_axis_names) if is_block => {
+                write!(f, "{}", block_axis_names[twist.axis.0 as usize])?;
+            }
+            _ => self.format_axis(f, twist.axis)?,
+        }
         if let Some(block_suffix) = &self.block_suffix {
-            if twist.layers.is_contiguous_from_outermost() && twist.layers.count() > 1 {
+            if is_block {
                 write!(f, "{block_suffix}")?;
             }
         }
@@ -88,7 +120,7 @@ impl NotationScheme {
     fn format_layers(&self, f: &mut fmt::Formatter<'_>, layers: LayerMask) -> fmt::Result {
         if layers.is_default() {
             Ok(()) // Layer mask is not necessary.
-        } else if self.block_suffix.is_some() && layers.is_contiguous_from_outermost() {
+        } else if self.has_block_notation() && layers.is_contiguous_from_outermost() {
             if layers.count() <= 2 {
                 Ok(()) // Layer mask is not necessary.
             } else {
@@ -98,6 +130,9 @@ impl NotationScheme {
             write!(f, "{}", layers)
         }
     }
+    fn has_block_notation(&self) -> bool {
+        self.block_suffix.is_some() || self.block_axis_names.is_some()
+    }
     fn format_axis(&self, f: &mut fmt::Formatter<'_>, axis: TwistAxis) -> fmt::Result {
         write!(f, "{}", self.axis_names[axis.0 as usize])
     }
@@ -142,16 +177,28 @@ impl NotationScheme {
             // Parse layer mask if present.
             let (prefix_layers, remaining) = self.strip_layer_mask_prefix(s)?;
             let mut layers = prefix_layers.unwrap_or_default();
+            let block_layers = || {
+                let leading_zeros = prefix_layers.unwrap_or(LayerMask(3)).0.leading_zeros();
+                LayerMask(u32::MAX >> leading_zeros)
+            };
             // Parse twist axis.
-            let (axis, mut remaining) =
-                strip_any_prefix(remaining, self.axis_names.iter().enumerate())
-                    .ok_or_else(|| GENERIC_ERR_MSG.to_string())?;
+            let block_axis = self
+                .block_axis_names
+                .as_ref()
+                .and_then(|names| strip_any_prefix(remaining, names.iter().enumerate()));
+            let (axis, mut remaining) = match block_axis {
+                Some((axis, after_block_axis)) => {
+                    layers = block_layers();
+                    (axis, after_block_axis)
+                }
+                None => strip_any_prefix(remaining, self.axis_names.iter().enumerate())
+                    .ok_or_else(|| GENERIC_ERR_MSG.to_string())?,
+            };
             let axis = TwistAxis(axis as u8);
             if let Some(block_suffix) = &self.block_suffix {
                 if let Some(after_block_suffix) = remaining.strip_prefix(block_suffix) {
                     remaining = after_block_suffix;
-                    let leading_zeros = prefix_layers.unwrap_or(LayerMask(3)).0.leading_zeros();
-                    layers = LayerMask(u32::MAX >> leading_zeros);
+                    layers = block_layers();
                 }
             }
             // Parse twist direction.
@@ -165,6 +212,16 @@ impl NotationScheme {
         }
     }
 
+    /// Parses a sequence of twists separated by whitespace.
+    pub fn parse_alg(&self, s: &str) -> Result<Vec<Twist>, String> {
+        s.split_whitespace()
+            .map(|twist_str| {
+                self.parse_twist(twist_str)
+                    .map_err(|e| format!("{e} in {twist_str:?}"))
+            })
+            .collect()
+    }
+
     fn parse_twist_direction(
         &self,
         axis: TwistAxis,
@@ -0,0 +1,203 @@
+//! Scrambles that only twist and flip pieces in place, for practicing
+//! orientation (such as OLL) without permutation.
+//!
+//! A random state where every piece is in its original location is chosen
+//! among the states reachable by twisting the puzzle, and then solved, so that
+//! the scramble is the inverse of the solution. This is only supported for
+//! puzzles that have a solver: the 2x2x2 (where corners are twisted) and the
+//! 3x3x3 (where corners are twisted and edges are flipped).
+
+use itertools::Itertools;
+use rand::Rng;
+
+use super::solve_job::SolveJob;
+use super::*;
+
+/// Number of random states to try before giving up.
+const MAX_ATTEMPTS: usize = 100;
+
+/// Returns whether there are orientation-only scrambles for a puzzle type.
+pub fn is_supported(ty: PuzzleTypeEnum) -> bool {
+    SolveJob::is_supported(ty)
+}
+
+/// Orientation scramble being found in the background, by solving a random
+/// orientation state with a [`SolveJob`].
+#[derive(Clone)]
+pub struct OrientationScrambleJob {
+    job: SolveJob,
+}
+impl OrientationScrambleJob {
+    /// Starts finding a random scramble that leaves every piece in its
+    /// original location but may twist or flip it. On web, this blocks until
+    /// the scramble is found.
+    pub fn start(ty: PuzzleTypeEnum) -> Result<Self, String> {
+        if !is_supported(ty) {
+            return Err(format!(
+                "{} does not support orientation scrambles",
+                ty.name(),
+            ));
+        }
+        let state = random_orientation_state(ty)?;
+        Ok(Self {
+            job: SolveJob::start(state),
+        })
+    }
+
+    /// Returns the puzzle type being scrambled.
+    pub fn ty(&self) -> PuzzleTypeEnum {
+        self.job.puzzle().ty()
+    }
+    /// Stops searching for a scramble.
+    pub fn cancel(&self) {
+        self.job.cancel();
+    }
+    /// Returns the scramble, or `None` if it is still being found. The
+    /// scrambled state is always reachable by twisting the puzzle and is never
+    /// solved.
+    pub fn result(&self) -> Option<Result<Vec<Twist>, String>> {
+        let solution = self.job.result()?;
+        Some(solution.and_then(|solution| scramble_from_solution(self.job.puzzle(), &solution)))
+    }
+}
+
+/// Returns the scramble that reaches `state` from the solved state, given a
+/// solution for `state`.
+fn scramble_from_solution(state: &Puzzle, solution: &[Twist]) -> Result<Vec<Twist>, String> {
+    let ty = state.ty();
+    let scramble = invert_alg(ty, solution);
+
+    // The 2x2x2 solver holds the DBL corner fixed, so if that corner is
+    // twisted then the solution leaves the whole puzzle rotated. Start the
+    // scramble with the rotation that undoes that. Stickers are compared
+    // rather than pieces because the 3x3x3 solver may leave centers turned in
+    // place.
+    let is_state = |puzzle: &Puzzle| {
+        (0..ty.stickers().len() as _)
+            .map(Sticker)
+            .all(|sticker| puzzle.sticker_facet(sticker) == state.sticker_facet(sticker))
+    };
+    let rotations = std::iter::once(vec![]).chain(
+        state
+            .rotation_candidates()
+            .into_iter()
+            .map(|(twists, _)| twists),
+    );
+    for rotation in rotations {
+        let twists = [rotation, scramble.clone()].concat();
+        let mut puzzle = Puzzle::new(ty);
+        if twists.iter().all(|&twist| puzzle.twist(twist).is_ok()) && is_state(&puzzle) {
+            return Ok(twists);
+        }
+    }
+    Err("Failed to generate an orientation scramble".to_string())
+}
+
+/// Returns a random state where every piece is in its original location, but
+/// pieces with more than one sticker may be twisted or flipped. The state is
+/// always reachable by twisting the puzzle and is never solved.
+pub fn random_orientation_state(ty: PuzzleTypeEnum) -> Result<Puzzle, String> {
+    let choices = (0..ty.pieces().len() as _)
+        .map(Piece)
+        .filter(|&piece| ty.info(piece).stickers.len() > 1)
+        .map(|piece| (piece, orientations_in_place(ty, piece)))
+        .filter(|(_, orientations)| !orientations.is_empty())
+        .collect_vec();
+    if choices.is_empty() {
+        return Err(format!("{} has no pieces to orient", ty.name()));
+    }
+
+    let mut rng = rand::thread_rng();
+    for _ in 0..MAX_ATTEMPTS {
+        let mut puzzle = Puzzle::new(ty);
+        let mut is_solved = true;
+        for (piece, orientations) in &choices {
+            // Each piece may also stay solved.
+            if let Some(orientation) = orientations.get(rng.gen_range(0..=orientations.len())) {
+                puzzle.set_piece_orientation(*piece, orientation)?;
+                is_solved = false;
+            }
+        }
+        // Most combinations of orientations can't be reached by twisting the
+        // puzzle, such as a single twisted corner.
+        if !is_solved && puzzle.check_state() == Some(vec![]) {
+            return Ok(puzzle);
+        }
+    }
+    Err("Failed to generate an orientation state".to_string())
+}
+
+/// Returns every orientation of a piece that keeps it in its original
+/// location, other than the solved orientation.
+fn orientations_in_place(ty: PuzzleTypeEnum, piece: Piece) -> Vec<Vec<Face>> {
+    let mut puzzle = Puzzle::new(ty);
+    let Some(solved_orientation) = puzzle.piece_orientation(piece) else {
+        return vec![];
+    };
+    let faces = (0..ty.faces().len() as _).map(Face).collect_vec();
+
+    std::iter::repeat(faces)
+        .take(solved_orientation.len())
+        .multi_cartesian_product()
+        .filter(|orientation| *orientation != solved_orientation)
+        .filter(|orientation| {
+            let is_in_place = puzzle.set_piece_orientation(piece, orientation).is_ok()
+                && puzzle.piece_position(piece) == Some(piece);
+            puzzle
+                .set_piece_orientation(piece, &solved_orientation)
+                .unwrap();
+            is_in_place
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orientation_scramble() {
+        for layer_count in 2..=3 {
+            let ty = PuzzleTypeEnum::Rubiks3D { layer_count };
+            for _ in 0..5 {
+                let job = OrientationScrambleJob::start(ty).unwrap();
+                let scramble = loop {
+                    if let Some(result) = job.result() {
+                        break result.unwrap();
+                    }
+                    std::thread::sleep(std::time::Duration::from_millis(10));
+                };
+                let mut puzzle = Puzzle::new(ty);
+                for twist in scramble {
+                    puzzle.twist(twist).unwrap();
+                }
+                assert!(!puzzle.is_solved());
+                assert_eq!(Some(vec![]), puzzle.check_state());
+                for i in 0..ty.pieces().len() as _ {
+                    assert_eq!(Some(Piece(i)), puzzle.piece_position(Piece(i)));
+                }
+            }
+        }
+
+        assert!(!is_supported(PuzzleTypeEnum::Ivy));
+        assert!(OrientationScrambleJob::start(PuzzleTypeEnum::Ivy).is_err());
+    }
+
+    #[test]
+    fn test_orientations_in_place() {
+        let ty = PuzzleTypeEnum::Rubiks3D { layer_count: 3 };
+        let counts = (0..ty.pieces().len() as _)
+            .map(Piece)
+            .filter(|&piece| ty.info(piece).stickers.len() > 1)
+            .map(|piece| {
+                (
+                    ty.info(piece).stickers.len(),
+                    orientations_in_place(ty, piece).len(),
+                )
+            })
+            .counts();
+        // Corners can be twisted two ways and edges can be flipped one way.
+        assert_eq!(counts.get(&(3, 2)), Some(&8));
+        assert_eq!(counts.get(&(2, 1)), Some(&12));
+    }
+}
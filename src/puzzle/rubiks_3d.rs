@@ -5,7 +5,7 @@ use itertools::Itertools;
 use num_enum::FromPrimitive;
 use serde::{de::Error, Deserialize, Deserializer};
 use smallvec::smallvec;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ops::{Index, IndexMut, RangeInclusive};
 use std::sync::Mutex;
 use strum::IntoEnumIterator;
@@ -17,6 +17,10 @@ pub const MIN_LAYER_COUNT: u8 = 1;
 pub const MAX_LAYER_COUNT: u8 = 9;
 pub const LAYER_COUNT_RANGE: RangeInclusive<u8> = MIN_LAYER_COUNT..=MAX_LAYER_COUNT;
 
+/// Distance from the center of a sticker to the tip of its orientation marker,
+/// relative to half the width of the sticker.
+const ORIENTATION_MARKER_LENGTH: f32 = 0.7;
+
 pub(super) fn deserialize_layer_count<'de, D>(deserializer: D) -> Result<u8, D::Error>
 where
     D: Deserializer<'de>,
@@ -42,7 +46,12 @@ fn puzzle_description(layer_count: u8) -> &'static Rubiks3DDescription {
 
     assert!(LAYER_COUNT_RANGE.contains(&layer_count));
 
-    CACHE.lock().unwrap().entry(layer_count).or_insert_with(|| {
+    // Build the description without holding the lock, because this can take a
+    // while for big puzzles and other sizes may be needed in the meantime.
+    if let Some(&desc) = CACHE.lock().unwrap().get(&layer_count) {
+        return desc;
+    }
+    let desc = {
         let mut pieces = vec![];
         let mut stickers = vec![];
 
@@ -130,18 +139,21 @@ fn puzzle_description(layer_count: u8) -> &'static Rubiks3DDescription {
                 aliases.push(("E".to_string(), Alias::AxisLayers(D.into(), slice_layers)));
                 aliases.push(("S".to_string(), Alias::AxisLayers(F.into(), slice_layers)));
             }
-
-            if layer_count >= 4 {
-                for f in FaceEnum::iter() {
-                    aliases.push((
-                        f.symbol_lower().to_string(),
-                        Alias::AxisLayers(f.into(), LayerMask(2)),
-                    ))
-                }
+        }
+        // SiGN notation uses lowercase letters for wide twists instead of
+        // inner slices.
+        let mut sign_aliases = aliases.clone();
+        if layer_count >= 4 {
+            for f in FaceEnum::iter() {
+                aliases.push((
+                    f.symbol_lower().to_string(),
+                    Alias::AxisLayers(f.into(), LayerMask(2)),
+                ))
             }
         }
         // Try to match longer aliases first.
         aliases.sort_by_key(|(s, _)| -(s.len() as isize));
+        sign_aliases.sort_by_key(|(s, _)| -(s.len() as isize));
 
         let notation = NotationScheme {
             axis_names: FaceEnum::iter()
@@ -151,8 +163,19 @@ fn puzzle_description(layer_count: u8) -> &'static Rubiks3DDescription {
                 .map(|dir| TwistDirectionName::Same(dir.symbol().to_string()))
                 .collect(),
             block_suffix: Some("w".to_string()),
+            block_axis_names: None,
             aliases,
         };
+        let sign_notation = NotationScheme {
+            block_suffix: None,
+            block_axis_names: Some(
+                FaceEnum::iter()
+                    .map(|f| f.symbol_lower().to_string())
+                    .collect(),
+            ),
+            aliases: sign_aliases,
+            ..notation.clone()
+        };
 
         // It's not like we'll ever clear the cache anyway, so just leak it
         // and let us have the 'static lifetimes.
@@ -171,10 +194,12 @@ fn puzzle_description(layer_count: u8) -> &'static Rubiks3DDescription {
                 .map(|piece_type| PieceTypeInfo::new(piece_type.to_string()))
                 .collect(),
             notation,
+            sign_notation,
 
             piece_locations,
         }))
-    })
+    };
+    CACHE.lock().unwrap().entry(layer_count).or_insert(desc)
 }
 
 #[derive(Debug, Clone)]
@@ -190,6 +215,7 @@ struct Rubiks3DDescription {
     twist_directions: Vec<TwistDirectionInfo>,
     piece_types: Vec<PieceTypeInfo>,
     notation: NotationScheme,
+    sign_notation: NotationScheme,
 
     piece_locations: Vec<[u8; 3]>,
 }
@@ -244,16 +270,14 @@ impl PuzzleType for Rubiks3DDescription {
         &self.piece_types
     }
 
-    fn opposite_twist_axis(&self, twist_axis: TwistAxis) -> Option<TwistAxis> {
-        Some(FaceEnum::from(twist_axis).opposite().into())
+    fn sticker_grid_position(&self, sticker: Sticker) -> Option<[u8; 2]> {
+        let info = self.info(sticker);
+        let location = self.piece_locations[info.piece.0 as usize];
+        Some(grid_position(self.layer_count, info.color.into(), location))
     }
-    fn count_quarter_turns(&self, twist: Twist) -> usize {
-        use TwistDirectionEnum::*;
 
-        match twist.direction.into() {
-            CW90 | CCW90 => 1,
-            CW180 | CCW180 => 2,
-        }
+    fn opposite_twist_axis(&self, twist_axis: TwistAxis) -> Option<TwistAxis> {
+        Some(FaceEnum::from(twist_axis).opposite().into())
     }
 
     fn make_recenter_twist(&self, axis: TwistAxis) -> Result<Twist, String> {
@@ -296,41 +320,40 @@ impl PuzzleType for Rubiks3DDescription {
         }
     }
 
-    fn reverse_twist_direction(&self, direction: TwistDirection) -> TwistDirection {
-        use TwistDirectionEnum::*;
-
-        match direction.into() {
-            CW90 => CCW90.into(),
-            CCW90 => CW90.into(),
-            CW180 => CCW180.into(),
-            CCW180 => CW180.into(),
-        }
-    }
-    fn chain_twist_directions(&self, dirs: &[TwistDirection]) -> Option<TwistDirection> {
-        use TwistDirectionEnum::*;
-
-        let total: i32 = dirs
-            .iter()
-            .map(|&dir| match dir.into() {
-                CW90 => 1,
-                CCW90 => -1,
-                CW180 => 2,
-                CCW180 => -2,
-            })
-            .sum();
-
-        match total.rem_euclid(4) {
-            0 => None,
-            1 => Some(CW90.into()),
-            2 => Some(if total < 0 { CCW180 } else { CW180 }.into()),
-            3 => Some(CCW90.into()),
-            _ => unreachable!(),
-        }
+    fn unrotate_twist(&self, rotations: &[Twist], twist: Twist) -> Option<Twist> {
+        // Compose the rotations, each of which is around a fixed axis.
+        let total_rotation = rotations.iter().fold(Quaternion::one(), |acc, rot| {
+            FaceEnum::from(rot.axis).twist_rotation(rot.direction.into()) * acc
+        });
+        // The face that the rotations move to the twisted face is the one
+        // that is twisted instead.
+        let v = total_rotation.invert() * FaceEnum::from(twist.axis).vector();
+        let face = FaceEnum::iter().find(|f| (f.vector() - v).magnitude2() < 0.01)?;
+        Some(Twist {
+            axis: face.into(),
+            ..twist
+        })
     }
 
     fn notation_scheme(&self) -> &NotationScheme {
         &self.notation
     }
+    fn notation_scheme_for_style(&self, style: NotationStyle) -> &NotationScheme {
+        match style {
+            NotationStyle::Sign => &self.sign_notation,
+            _ => &self.notation,
+        }
+    }
+    fn notation_styles(&self) -> &'static [NotationStyle] {
+        &[NotationStyle::Standard, NotationStyle::Sign]
+    }
+    fn default_notation_style(&self) -> NotationStyle {
+        // SiGN notation is more common for big cubes.
+        match self.layer_count {
+            ..=3 => NotationStyle::Standard,
+            _ => NotationStyle::Sign,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -411,7 +434,7 @@ impl PuzzleState for Rubiks3D {
             vec![y, x2],
             // 120-degree vertex rotations
             vec![x, y],
-            vec![xi, yi],
+            vec![yi, xi],
             vec![z, y],
             vec![x, yi],
             vec![x, z],
@@ -455,7 +478,8 @@ impl PuzzleState for Rubiks3D {
         }
 
         // Compute the center of the sticker.
-        let center = transform.transform_point(self.sticker_center_3d(sticker, p));
+        let explode_offset = self.piece_center_3d(piece, p).to_vec() * p.explode;
+        let center = transform.transform_point(self.sticker_center_3d(sticker, p) + explode_offset);
 
         // Compute the vectors that span the plane of the sticker.
         let [u_span_axis, v_span_axis] = face.parallel_axes();
@@ -477,7 +501,7 @@ impl PuzzleState for Rubiks3D {
         let ccw_twist = self.reverse_twist(cw_twist);
         let recenter = self.make_recenter_twist(face.into()).ok();
 
-        Some(StickerGeometry::new_double_quad(
+        let mut ret = StickerGeometry::new_double_quad(
             [
                 center - u - v,
                 center - u + v,
@@ -491,7 +515,26 @@ impl PuzzleState for Rubiks3D {
             },
             p.show_frontfaces,
             p.show_backfaces,
-        ))
+        );
+
+        // Centers look the same in every orientation, so mark which way is
+        // "up" for the sticker in its original position. The marker moves
+        // with the piece, including during twist animations.
+        if self.info(piece).stickers.len() == 1 {
+            let original_face: FaceEnum = self.info(sticker).color.into();
+            let [_, up_axis] = original_face.parallel_axes();
+            let up: Vector3<f32> = <Matrix3<f32> as Transform<Point3<f32>>>::transform_vector(
+                &transform,
+                self[piece][up_axis].vector() * p.sticker_scale,
+            );
+            ret.orientation_marker = Some(center + up * ORIENTATION_MARKER_LENGTH);
+        }
+
+        Some(ret)
+    }
+
+    fn sticker_facet(&self, sticker: Sticker) -> Face {
+        self.sticker_face(sticker).into()
     }
 
     fn is_solved(&self) -> bool {
@@ -507,6 +550,96 @@ impl PuzzleState for Rubiks3D {
         }
         true
     }
+
+    fn sticker_grid_location(&self, sticker: Sticker) -> Option<(Face, [u8; 2])> {
+        let face = self.sticker_face(sticker);
+        let location = self.piece_location(self.info(sticker).piece);
+        Some((
+            face.into(),
+            grid_position(self.layer_count(), face, location),
+        ))
+    }
+
+    fn check_state(&self) -> Option<Vec<String>> {
+        let layer_count = self.layer_count();
+        let mut errors = vec![];
+
+        let reflected_count = self
+            .piece_states
+            .iter()
+            .filter(|s| !s.is_rotation())
+            .count();
+        if reflected_count > 0 {
+            errors.push(format!("{reflected_count} pieces are reflected"));
+        }
+        let locations: HashSet<[u8; 3]> = (0..self.pieces().len() as _)
+            .map(|i| self.piece_location(Piece(i)))
+            .collect();
+        if locations.len() < self.pieces().len() {
+            errors.push("Multiple pieces are in the same position".to_owned());
+        }
+        if !errors.is_empty() {
+            // The remaining checks assume a valid permutation of pieces.
+            return Some(errors);
+        }
+
+        let corners = self.pieces_with_middle_coordinates(3, 0);
+        let corner_twist_sum: u32 = corners.iter().map(|&p| self.corner_twist(p) as u32).sum();
+        if corner_twist_sum % 3 != 0 {
+            errors.push(format!(
+                "Corner twist sum is {} mod 3 (should be 0)",
+                corner_twist_sum % 3,
+            ));
+        }
+
+        // Middle edges and centers only exist on odd puzzles.
+        if layer_count % 2 == 1 && layer_count >= 3 {
+            let edges = self.pieces_with_middle_coordinates(2, 1);
+            let centers = self.pieces_with_middle_coordinates(1, 2);
+
+            let flipped_edge_count = edges.iter().filter(|&&p| self.is_edge_flipped(p)).count();
+            if flipped_edge_count % 2 != 0 {
+                errors.push(format!(
+                    "{flipped_edge_count} middle edges are flipped (should be even)",
+                ));
+            }
+
+            // Face turns swap both corners and edges; middle slice turns swap
+            // both edges and centers.
+            let parities = [&corners, &edges, &centers].map(|p| self.permutation_parity(p));
+            if parities[0] ^ parities[1] ^ parities[2] {
+                errors.push(format!(
+                    "Permutation parity mismatch (corners {}, middle edges {}, centers {})",
+                    parity_str(parities[0]),
+                    parity_str(parities[1]),
+                    parity_str(parities[2]),
+                ));
+            }
+        }
+
+        Some(errors)
+    }
+
+    fn piece_orientation(&self, piece: Piece) -> Option<Vec<Face>> {
+        Some(self[piece].0.iter().map(|&face| face.into()).collect())
+    }
+    fn set_piece_orientation(&mut self, piece: Piece, orientation: &[Face]) -> Result<(), String> {
+        let state = PieceState::from_faces(orientation)?;
+        if !state.is_rotation() {
+            return Err("piece orientation is not a rotation".to_string());
+        }
+        self[piece] = state;
+        Ok(())
+    }
+    fn piece_position(&self, piece: Piece) -> Option<Piece> {
+        let location = self.piece_location(piece);
+        let i = self
+            .desc()
+            .piece_locations
+            .iter()
+            .position(|&l| l == location)?;
+        Some(Piece(i as _))
+    }
 }
 #[delegate_to_methods]
 #[delegate(PuzzleType, target_ref = "desc")]
@@ -565,6 +698,119 @@ impl Rubiks3D {
     fn piece_center_coordinate(&self, x: u8, p: StickerGeometryParams) -> f32 {
         (2.0 * x as f32 - (self.layer_count() - 1) as f32) * p.sticker_grid_scale
     }
+
+    /// Returns the pieces with `sticker_count` stickers that have
+    /// `middle_count` coordinates in the middle layer when solved. On odd
+    /// puzzles, `(3, 0)` is corners, `(2, 1)` is middle edges, and `(1, 2)` is
+    /// middle centers.
+    fn pieces_with_middle_coordinates(
+        &self,
+        sticker_count: usize,
+        middle_count: usize,
+    ) -> Vec<Piece> {
+        let middle = self.layer_count() / 2;
+        (0..self.pieces().len() as _)
+            .map(Piece)
+            .filter(|&piece| self.info(piece).stickers.len() == sticker_count)
+            .filter(|&piece| {
+                let location = self.desc.piece_locations[piece.0 as usize];
+                let is_middle = |&&x: &&u8| self.layer_count() % 2 == 1 && x == middle;
+                location.iter().filter(is_middle).count() == middle_count
+            })
+            .collect()
+    }
+    /// Returns the number of clockwise twists applied to a corner, relative to
+    /// the U/D sticker being on the U or D face.
+    fn corner_twist(&self, piece: Piece) -> u8 {
+        let Some(&sticker) = self
+            .info(piece)
+            .stickers
+            .iter()
+            .find(|&&s| FaceEnum::from(self.info(s).color).axis() == Axis::Y)
+        else {
+            return 0;
+        };
+        // Corners with an even number of negative coordinates have their faces
+        // in the order X, Y, Z counterclockwise; the rest are mirrored.
+        let location = self.piece_location(piece);
+        let is_right_handed = location.iter().filter(|&&x| x == 0).count() % 2 == 0;
+        match (self.sticker_face(sticker).axis(), is_right_handed) {
+            (Axis::Y, _) => 0,
+            (Axis::X, true) | (Axis::Z, false) => 1,
+            (Axis::X, false) | (Axis::Z, true) => 2,
+        }
+    }
+    /// Returns whether an edge is flipped, using the U/D sticker (or the F/B
+    /// sticker if there is none) as the reference.
+    fn is_edge_flipped(&self, piece: Piece) -> bool {
+        let stickers = &self.info(piece).stickers;
+        let axis_of = |&&s: &&Sticker| FaceEnum::from(self.info(s).color).axis();
+        let Some(&sticker) = stickers
+            .iter()
+            .find(|s| axis_of(s) == Axis::Y)
+            .or_else(|| stickers.iter().find(|s| axis_of(s) == Axis::Z))
+        else {
+            return false;
+        };
+        let max = self.layer_count() - 1;
+        let y = self.piece_location(piece)[Axis::Y as usize];
+        let reference_axis = if y == 0 || y == max { Axis::Y } else { Axis::Z };
+        self.sticker_face(sticker).axis() != reference_axis
+    }
+    /// Returns whether the permutation of a set of pieces among their solved
+    /// locations is odd.
+    fn permutation_parity(&self, pieces: &[Piece]) -> bool {
+        let location_indices: HashMap<[u8; 3], usize> = pieces
+            .iter()
+            .enumerate()
+            .map(|(i, piece)| (self.desc.piece_locations[piece.0 as usize], i))
+            .collect();
+        let permutation = pieces
+            .iter()
+            .map(|&piece| location_indices.get(&self.piece_location(piece)).copied())
+            .collect::<Option<Vec<usize>>>();
+        let Some(permutation) = permutation else {
+            return false; // Pieces have left their orbit; already invalid.
+        };
+
+        // A cycle of length `k` is the product of `k - 1` swaps.
+        let mut visited = vec![false; permutation.len()];
+        let mut swap_count = 0;
+        for start in 0..permutation.len() {
+            let mut i = start;
+            let mut cycle_len: usize = 0;
+            while !visited[i] {
+                visited[i] = true;
+                i = permutation[i];
+                cycle_len += 1;
+            }
+            swap_count += cycle_len.saturating_sub(1);
+        }
+        swap_count % 2 == 1
+    }
+}
+
+/// Returns the row and column of a sticker on `face` of a piece at `location`.
+fn grid_position(layer_count: u8, face: FaceEnum, [x, y, z]: [u8; 3]) -> [u8; 2] {
+    use FaceEnum::*;
+
+    let max = layer_count - 1;
+    // Side faces have U at the top; U has B at the top; D has F at the top.
+    match face {
+        R => [max - y, max - z],
+        L => [max - y, z],
+        U => [z, x],
+        D => [max - z, x],
+        F => [max - y, x],
+        B => [max - y, max - x],
+    }
+}
+
+fn parity_str(is_odd: bool) -> &'static str {
+    match is_odd {
+        true => "odd",
+        false => "even",
+    }
 }
 
 /// The facing directions of the X+, Y+, and Z+ stickers on this piece (assuming
@@ -591,6 +837,29 @@ impl IndexMut<Axis> for PieceState {
     }
 }
 impl PieceState {
+    /// Constructs a piece state from the face that each axis points toward.
+    fn from_faces(faces: &[Face]) -> Result<Self, String> {
+        let faces: [Face; 3] = faces
+            .try_into()
+            .map_err(|_| format!("expected 3 faces in piece orientation, got {}", faces.len()))?;
+        if faces.iter().any(|f| f.0 as usize >= FaceEnum::iter().len()) {
+            return Err("invalid face in piece orientation".to_string());
+        }
+        Ok(Self(faces.map(FaceEnum::from)))
+    }
+
+    /// Returns whether the piece is rotated (as opposed to reflected) from its
+    /// original orientation.
+    fn is_rotation(self) -> bool {
+        let [x, y, z] = self.0.map(|face| face.axis() as u8);
+        if x == y || y == z || z == x {
+            return false;
+        }
+        let is_even_permutation = matches!([x, y, z], [0, 1, 2] | [1, 2, 0] | [2, 0, 1]);
+        let negative_count = self.0.iter().filter(|f| f.sign() == Sign::Neg).count();
+        is_even_permutation == (negative_count % 2 == 0)
+    }
+
     #[must_use]
     fn rotate(mut self, from: Axis, to: Axis) -> Self {
         let diff = (from as u8 ^ to as u8) << 1;
@@ -669,6 +938,7 @@ impl FaceEnum {
     fn twist_axis_info(self) -> TwistAxisInfo {
         TwistAxisInfo {
             name: self.symbol_upper_str(),
+            increments_per_turn: Some(4),
         }
     }
 
@@ -753,13 +1023,12 @@ impl FaceEnum {
     }
 
     fn twist_rotation(self, direction: TwistDirectionEnum) -> Quaternion<f32> {
-        let angle = Rad::full_turn() * direction.sign().float() / direction.period() as f32;
-        Quaternion::from_axis_angle(self.vector(), angle)
+        Quaternion::from_axis_angle(self.vector(), direction.angle())
     }
     fn twist_matrix(self, direction: TwistDirectionEnum, progress: f32) -> Matrix3<f32> {
-        Quaternion::one()
-            .slerp(self.twist_rotation(direction), progress)
-            .into()
+        // Interpolate the angle rather than the rotation, which would take the
+        // shortest path for twists larger than a half turn.
+        Matrix3::from_axis_angle(self.vector(), direction.angle() * progress)
     }
 }
 
@@ -787,6 +1056,7 @@ impl TwistDirectionEnum {
         TwistDirectionInfo {
             symbol: self.symbol(),
             name: self.name(),
+            increments: self.increments(),
         }
     }
 
@@ -811,6 +1081,16 @@ impl TwistDirectionEnum {
         }
     }
 
+    fn increments(self) -> i8 {
+        use TwistDirectionEnum::*;
+
+        match self {
+            CW90 => 1,
+            CCW90 => -1,
+            CW180 => 2,
+            CCW180 => -2,
+        }
+    }
     fn period(self) -> usize {
         use TwistDirectionEnum::*;
 
@@ -819,6 +1099,9 @@ impl TwistDirectionEnum {
             CW180 | CCW180 => 2,
         }
     }
+    fn angle(self) -> Rad<f32> {
+        Rad::full_turn() * self.sign().float() / self.period() as f32
+    }
     fn sign(self) -> Sign {
         use TwistDirectionEnum::*;
 
@@ -954,6 +1237,320 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_rubiks_3d_sign_notation() {
+        let ty = PuzzleTypeEnum::Rubiks3D { layer_count: 5 };
+        let wca = ty.notation_scheme_for_style(NotationStyle::Standard);
+        let sign = ty.notation_scheme_for_style(NotationStyle::Sign);
+        for (wca_str, sign_str) in [
+            ("R", "R"),
+            ("Rw'", "r'"),
+            ("3Rw2", "3r2"),
+            ("r", "2R"),
+            ("{2-3}R", "{2-3}R"),
+            ("M", "M"),
+            ("x", "x"),
+        ] {
+            let twist = wca.parse_twist(wca_str).unwrap();
+            assert_eq!(sign_str, sign.twist_to_string(twist));
+            assert_eq!(Ok(twist), sign.parse_twist(sign_str));
+        }
+        assert_eq!(
+            NotationStyle::Standard,
+            PuzzleTypeEnum::Rubiks3D { layer_count: 3 }.default_notation_style(),
+        );
+        assert_eq!(NotationStyle::Sign, ty.default_notation_style());
+    }
+
+    #[test]
+    fn test_rubiks_3d_twist_increments() {
+        use TwistDirectionEnum::*;
+
+        let p = Rubiks3D::new(3);
+        let twist = |direction: TwistDirectionEnum| Twist {
+            axis: FaceEnum::R.into(),
+            direction: direction.into(),
+            layers: LayerMask(1),
+        };
+        let chain = |dirs: &[TwistDirectionEnum]| {
+            let dirs = dirs.iter().map(|&d| d.into()).collect_vec();
+            p.chain_twist_directions(FaceEnum::R.into(), &dirs)
+                .map(TwistDirectionEnum::from)
+        };
+
+        assert_eq!(p.twist_angle(twist(CCW90)), Rad::turn_div_4());
+        assert_eq!(p.twist_angle(twist(CW180)), Rad::turn_div_2());
+        assert_eq!(p.count_quarter_turns(twist(CCW180)), 2);
+        for direction in TwistDirectionEnum::iter() {
+            let rev = p.reverse_twist_direction(direction.into());
+            assert_eq!(TwistDirectionEnum::from(rev), direction.rev());
+            assert!(p.check_twist(twist(direction)).is_ok());
+        }
+
+        assert_eq!(chain(&[CW90, CW90]), Some(CW180));
+        assert_eq!(chain(&[CCW90, CCW90]), Some(CCW180));
+        assert_eq!(chain(&[CW180, CW90]), Some(CCW90));
+        assert_eq!(chain(&[CW180, CCW180]), None);
+    }
+
+    #[test]
+    fn test_rubiks_3d_strip_rotations() {
+        for layer_count in 2..=4 {
+            let ty = PuzzleTypeEnum::Rubiks3D { layer_count };
+            let mut twists = vec![];
+            for i in 0..100 {
+                let mut twist = Twist::from_rng(ty);
+                if i % 3 == 0 {
+                    twist.layers = ty.all_layers();
+                }
+                twists.push(twist);
+            }
+
+            let stripped = strip_rotations(ty, twists.iter().copied());
+            assert!(stripped.iter().all(|t| t.layers != ty.all_layers()));
+
+            // The stripped twists followed by the rotations must have the same
+            // effect as the original twists.
+            let rotations = twists.iter().filter(|t| t.layers == ty.all_layers());
+            let mut p = Rubiks3D::new(layer_count);
+            for &twist in stripped.iter().chain(rotations) {
+                p.twist(twist).unwrap();
+            }
+            for &twist in twists.iter().rev() {
+                p.twist(ty.reverse_twist(twist)).unwrap();
+            }
+            assert!(p.is_solved());
+        }
+    }
+
+    #[test]
+    fn test_rubiks_3d_rotation_candidates() {
+        // Every rotation other than the identity must appear exactly once.
+        let p = Rubiks3D::new(2);
+        let orientations = p
+            .rotation_candidates()
+            .into_iter()
+            .map(|(twists, _)| {
+                let mut p = Rubiks3D::new(2);
+                for twist in twists {
+                    p.twist(twist).unwrap();
+                }
+                p.piece_orientation(Piece(0))
+            })
+            .collect_vec();
+        assert_eq!(23, orientations.len());
+        assert!(orientations.iter().all_unique());
+        assert!(!orientations.contains(&p.piece_orientation(Piece(0))));
+    }
+
+    #[test]
+    fn test_rubiks_3d_solved_fraction() {
+        for layer_count in 1..=4 {
+            let ty = PuzzleTypeEnum::Rubiks3D { layer_count };
+            let mut p = Rubiks3D::new(layer_count);
+            assert_eq!(1.0, p.solved_fraction());
+
+            // Rotating the whole puzzle doesn't unsolve any pieces.
+            let mut rotation = Twist::from_rng(ty);
+            rotation.layers = ty.all_layers();
+            p.twist(rotation).unwrap();
+            assert_eq!(1.0, p.solved_fraction());
+
+            // On smaller puzzles, one twist can split a color evenly between
+            // two faces.
+            if layer_count >= 3 {
+                let mut twist = Twist::from_rng(ty);
+                twist.layers = LayerMask(1);
+                p.twist(twist).unwrap();
+                let fraction = p.solved_fraction();
+                assert!(0.0 < fraction && fraction < 1.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rubiks_3d_solved_definitions() {
+        let ty = PuzzleTypeEnum::Rubiks3D { layer_count: 3 };
+        let u = Twist {
+            axis: FaceEnum::U.into(),
+            direction: TwistDirectionEnum::CW90.into(),
+            layers: LayerMask(1),
+        };
+        let mut twisted = Rubiks3D::new(3);
+        twisted.twist(u).unwrap();
+
+        // Rotating the whole puzzle keeps every piece oriented the same way.
+        let mut p = Rubiks3D::new(3);
+        p.twist(Twist {
+            layers: ty.all_layers(),
+            ..u
+        })
+        .unwrap();
+        for definition in SolvedDefinition::iter() {
+            assert!(p.is_solved_by(definition));
+        }
+
+        // Rotating only the U center is solved unless it is a supercube.
+        let mut p = Rubiks3D::new(3);
+        let center = (0..ty.pieces().len() as _)
+            .map(Piece)
+            .filter(|&piece| ty.info(piece).stickers.len() == 1)
+            .find(|&piece| twisted.piece_orientation(piece) != p.piece_orientation(piece))
+            .unwrap();
+        p.set_piece_orientation(center, &twisted.piece_orientation(center).unwrap())
+            .unwrap();
+        assert!(p.is_solved_by(SolvedDefinition::Standard));
+        assert!(!p.is_solved_by(SolvedDefinition::Supercube));
+        assert!(p.is_solved_by(SolvedDefinition::IgnoreCenters));
+        assert!(p.solved_fraction_by(SolvedDefinition::Supercube) < 1.0);
+
+        for definition in SolvedDefinition::iter() {
+            assert!(!twisted.is_solved_by(definition));
+        }
+    }
+
+    #[test]
+    fn test_rubiks_3d_solved_stickers() {
+        let ty = PuzzleTypeEnum::Rubiks3D { layer_count: 3 };
+        let mut p = Rubiks3D::new(3);
+        assert!(p.solved_stickers().iter().all(|&solved| solved));
+
+        let mut rotation = Twist::from_rng(ty);
+        rotation.layers = ty.all_layers();
+        p.twist(rotation).unwrap();
+        assert!(p.solved_stickers().iter().all(|&solved| solved));
+
+        // One outer layer twist moves 12 stickers off their faces: 3 on each
+        // of the 4 adjacent faces.
+        let mut twist = Twist::from_rng(ty);
+        twist.layers = LayerMask(1);
+        p.twist(twist).unwrap();
+        let unsolved_count = p.solved_stickers().iter().filter(|&&s| !s).count();
+        assert_eq!(12, unsolved_count);
+    }
+
+    #[test]
+    fn test_rubiks_3d_color_scheme_rotation() {
+        for layer_count in 1..=4 {
+            let ty = PuzzleTypeEnum::Rubiks3D { layer_count };
+            let down = ty.down_face().unwrap();
+            for face in FaceEnum::iter() {
+                let rotation = ty.color_scheme_rotation(face.into()).unwrap();
+                assert_eq!(Face::from(face), rotation[down.0 as usize]);
+                // Opposite colors must stay opposite.
+                let up: Face = FaceEnum::U.into();
+                let opposite: Face = face.opposite().into();
+                assert_eq!(opposite, rotation[up.0 as usize]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rubiks_3d_color_scheme_orientation() {
+        let ty = PuzzleTypeEnum::Rubiks3D { layer_count: 3 };
+        let on = |rotation: &[Face], face: FaceEnum| rotation[Face::from(face).0 as usize];
+        let identity = (0..ty.faces().len() as _).map(Face).collect::<Vec<_>>();
+
+        // `y` brings the right face to the front.
+        let y = ty.notation_scheme().parse_alg("y").unwrap();
+        let rotation = ty.rotate_color_scheme(&identity, &y).unwrap();
+        assert_eq!(Face::from(FaceEnum::R), on(&rotation, FaceEnum::F));
+        assert_eq!(Face::from(FaceEnum::D), on(&rotation, FaceEnum::D));
+
+        // Every rotation is determined by the down and front colors.
+        for (twists, _) in Puzzle::new(ty).rotation_candidates() {
+            let rotation = ty.rotate_color_scheme(&identity, &twists).unwrap();
+            let (down, front) = (on(&rotation, FaceEnum::D), on(&rotation, FaceEnum::F));
+            assert_eq!(
+                Some(rotation),
+                ty.color_scheme_orientation(Some(down), Some(front), None),
+            );
+        }
+    }
+
+    #[test]
+    fn test_rubiks_3d_sticker_grid_positions() {
+        for layer_count in 1..=5 {
+            let p = Rubiks3D::new(layer_count);
+            let mut seen = std::collections::HashSet::new();
+            for sticker in (0..p.stickers().len() as _).map(Sticker) {
+                let [row, col] = p.sticker_grid_position(sticker).unwrap();
+                assert!(row < layer_count && col < layer_count);
+                // Each position on each face must have exactly one sticker.
+                assert!(seen.insert((p.info(sticker).color, row, col)));
+            }
+        }
+
+        // After scrambling, each position must still have exactly one sticker.
+        for layer_count in 1..=5 {
+            let mut p = Rubiks3D::new(layer_count);
+            for _ in 0..100 {
+                p.twist(Twist::from_rng(p.ty())).unwrap();
+            }
+            let mut seen = std::collections::HashSet::new();
+            for sticker in (0..p.stickers().len() as _).map(Sticker) {
+                assert!(seen.insert(p.sticker_grid_location(sticker).unwrap()));
+            }
+        }
+
+        // The UFR corner is at the bottom right of U, the top left of R, and
+        // the top right of F.
+        let p = Rubiks3D::new(3);
+        let ufr = p.desc.piece_locations.iter().position(|&loc| loc == [2; 3]);
+        for &sticker in &p.info(Piece(ufr.unwrap() as _)).stickers {
+            let expected = match p.info(sticker).color.into() {
+                FaceEnum::U => [2, 2],
+                FaceEnum::R => [0, 0],
+                FaceEnum::F => [0, 2],
+                _ => panic!("UFR corner has wrong stickers"),
+            };
+            assert_eq!(Some(expected), p.sticker_grid_position(sticker));
+        }
+    }
+
+    #[test]
+    fn test_rubiks_3d_check_state() {
+        for layer_count in 1..=5 {
+            let mut p = Rubiks3D::new(layer_count);
+            assert_eq!(Some(vec![]), p.check_state());
+            for _ in 0..100 {
+                p.twist(Twist::from_rng(p.ty())).unwrap();
+            }
+            assert_eq!(
+                Some(vec![]),
+                p.check_state(),
+                "{layer_count}^3 scramble is invalid"
+            );
+        }
+
+        use FaceEnum::*;
+        let position_of = |p: &Rubiks3D, location| {
+            p.desc
+                .piece_locations
+                .iter()
+                .position(|&loc| loc == location)
+                .unwrap()
+        };
+
+        // Twist the UFR corner in place.
+        let mut p = Rubiks3D::new(3);
+        let ufr = position_of(&p, [2, 2, 2]);
+        p.piece_states[ufr] = PieceState([U, F, R]);
+        assert_eq!(1, p.check_state().unwrap().len());
+
+        // Flip the UF edge in place.
+        let mut p = Rubiks3D::new(3);
+        let uf = position_of(&p, [1, 2, 2]);
+        p.piece_states[uf] = PieceState([L, F, U]);
+        assert_eq!(1, p.check_state().unwrap().len());
+
+        // Mirror a corner, which also moves it onto another corner.
+        let mut p = Rubiks3D::new(2);
+        let ufr = position_of(&p, [1, 1, 1]);
+        p.piece_states[ufr] = PieceState([R, U, B]);
+        assert_eq!(2, p.check_state().unwrap().len());
+    }
+
     fn twist_comparison_key(p: &Rubiks3D, twist: Twist) -> impl PartialEq {
         const SOME_PROGRESS: f32 = 0.1;
 
@@ -42,7 +42,12 @@ fn puzzle_description(layer_count: u8) -> &'static Rubiks4DDescription {
 
     assert!(LAYER_COUNT_RANGE.contains(&layer_count));
 
-    CACHE.lock().unwrap().entry(layer_count).or_insert_with(|| {
+    // Build the description without holding the lock, because this can take a
+    // while for big puzzles and other sizes may be needed in the meantime.
+    if let Some(&desc) = CACHE.lock().unwrap().get(&layer_count) {
+        return desc;
+    }
+    let desc = {
         let mut pieces = vec![];
         let mut stickers = vec![];
 
@@ -174,8 +179,15 @@ fn puzzle_description(layer_count: u8) -> &'static Rubiks4DDescription {
                 })
                 .collect(),
             block_suffix: None,
+            block_axis_names: None,
             aliases,
         };
+        let mc4d_notation = NotationScheme {
+            direction_names: TwistDirectionEnum::iter()
+                .map(|dir| TwistDirectionName::Same(dir.symbol_xyz().to_string()))
+                .collect(),
+            ..notation.clone()
+        };
 
         // It's not like we'll ever clear the cache anyway, so just leak it
         // and let us have the 'static lifetimes.
@@ -194,10 +206,12 @@ fn puzzle_description(layer_count: u8) -> &'static Rubiks4DDescription {
                 .map(|piece_type| PieceTypeInfo::new(piece_type.to_string()))
                 .collect(),
             notation,
+            mc4d_notation,
 
             piece_locations,
         }))
-    })
+    };
+    CACHE.lock().unwrap().entry(layer_count).or_insert(desc)
 }
 
 #[derive(Debug, Clone)]
@@ -213,6 +227,7 @@ struct Rubiks4DDescription {
     twist_directions: Vec<TwistDirectionInfo>,
     piece_types: Vec<PieceTypeInfo>,
     notation: NotationScheme,
+    mc4d_notation: NotationScheme,
 
     piece_locations: Vec<[u8; 4]>,
 }
@@ -288,6 +303,9 @@ impl PuzzleType for Rubiks4DDescription {
             UFR | DBL | UFL | DBR | DFR | UBL | UBR | DFL => 2,
         }
     }
+    fn twist_angle(&self, twist: Twist) -> Rad<f32> {
+        Rad::full_turn() / TwistDirectionEnum::from(twist.direction).period() as f32
+    }
 
     fn make_recenter_twist(&self, axis: TwistAxis) -> Result<Twist, String> {
         use FaceEnum::*;
@@ -358,7 +376,11 @@ impl PuzzleType for Rubiks4DDescription {
         direction.0 ^= 1;
         direction
     }
-    fn chain_twist_directions(&self, dirs: &[TwistDirection]) -> Option<TwistDirection> {
+    fn chain_twist_directions(
+        &self,
+        _axis: TwistAxis,
+        dirs: &[TwistDirection],
+    ) -> Option<TwistDirection> {
         match dirs {
             [] => None,
             [dir] => Some(*dir),
@@ -386,6 +408,15 @@ impl PuzzleType for Rubiks4DDescription {
     fn notation_scheme(&self) -> &NotationScheme {
         &self.notation
     }
+    fn notation_scheme_for_style(&self, style: NotationStyle) -> &NotationScheme {
+        match style {
+            NotationStyle::Mc4d => &self.mc4d_notation,
+            _ => &self.notation,
+        }
+    }
+    fn notation_styles(&self) -> &'static [NotationStyle] {
+        &[NotationStyle::Standard, NotationStyle::Mc4d]
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -460,7 +491,8 @@ impl PuzzleState for Rubiks4D {
         }
 
         // Compute the center of the sticker.
-        let center = model_transform * self.sticker_center_4d(sticker, p);
+        let explode_offset = self.piece_center_4d(piece, p) * p.explode;
+        let center = model_transform * (self.sticker_center_4d(sticker, p) + explode_offset);
 
         // Compute the vectors that span the volume of the sticker.
         let Matrix4 { x, y, z, w: _ } = model_transform
@@ -528,6 +560,10 @@ impl PuzzleState for Rubiks4D {
         )
     }
 
+    fn sticker_facet(&self, sticker: Sticker) -> Face {
+        self.sticker_face(sticker).into()
+    }
+
     fn is_solved(&self) -> bool {
         let mut color_per_facet = vec![None; self.faces().len()];
         for (i, sticker) in self.stickers().iter().enumerate() {
@@ -541,6 +577,27 @@ impl PuzzleState for Rubiks4D {
         }
         true
     }
+
+    fn piece_orientation(&self, piece: Piece) -> Option<Vec<Face>> {
+        Some(self[piece].0.iter().map(|&face| face.into()).collect())
+    }
+    fn set_piece_orientation(&mut self, piece: Piece, orientation: &[Face]) -> Result<(), String> {
+        let state = PieceState::from_faces(orientation)?;
+        if !state.is_rotation() {
+            return Err("piece orientation is not a rotation".to_string());
+        }
+        self[piece] = state;
+        Ok(())
+    }
+    fn piece_position(&self, piece: Piece) -> Option<Piece> {
+        let location = self.piece_location(piece);
+        let i = self
+            .desc()
+            .piece_locations
+            .iter()
+            .position(|&l| l == location)?;
+        Some(Piece(i as _))
+    }
 }
 #[delegate_to_methods]
 #[delegate(PuzzleType, target_ref = "desc")]
@@ -749,6 +806,33 @@ impl IndexMut<Axis> for PieceState {
     }
 }
 impl PieceState {
+    /// Constructs a piece state from the face that each axis points toward.
+    fn from_faces(faces: &[Face]) -> Result<Self, String> {
+        let faces: [Face; 4] = faces
+            .try_into()
+            .map_err(|_| format!("expected 4 faces in piece orientation, got {}", faces.len()))?;
+        if faces.iter().any(|f| f.0 as usize >= FaceEnum::iter().len()) {
+            return Err("invalid face in piece orientation".to_string());
+        }
+        Ok(Self(faces.map(FaceEnum::from)))
+    }
+
+    /// Returns whether the piece is rotated (as opposed to reflected) from its
+    /// original orientation.
+    fn is_rotation(self) -> bool {
+        let axes = self.0.map(|face| face.axis() as u8);
+        if !axes.iter().all_unique() {
+            return false;
+        }
+        let inversions = axes
+            .iter()
+            .tuple_combinations()
+            .filter(|(a, b)| a > b)
+            .count();
+        let negative_count = self.0.iter().filter(|f| f.sign() == Sign::Neg).count();
+        (inversions + negative_count) % 2 == 0
+    }
+
     #[must_use]
     fn rotate(mut self, from: Axis, to: Axis) -> Self {
         let diff = (from as u8 ^ to as u8) << 1;
@@ -847,6 +931,9 @@ impl FaceEnum {
     fn twist_axis_info(self) -> TwistAxisInfo {
         TwistAxisInfo {
             name: self.symbol_upper_str(),
+            // Twist directions are rotations in different planes, with
+            // different periods.
+            increments_per_turn: None,
         }
     }
 
@@ -1052,6 +1139,7 @@ impl TwistDirectionEnum {
         TwistDirectionInfo {
             symbol: self.symbol_xyz(),
             name: self.name(),
+            increments: 0,
         }
     }
 
@@ -1484,6 +1572,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_rubiks_4d_center_color_rotation() {
+        for layer_count in 1..=3 {
+            let ty = PuzzleTypeEnum::Rubiks4D { layer_count };
+            let center = ty.center_face().unwrap();
+            let down = ty.down_face().unwrap();
+            for face in FaceEnum::iter() {
+                let rotation = ty
+                    .color_scheme_rotation_with_center(None, Some(face.into()))
+                    .unwrap();
+                assert_eq!(Face::from(face), rotation[center.0 as usize]);
+                // Opposite colors must stay opposite.
+                let outer: Face = FaceEnum::O.into();
+                let opposite: Face = face.opposite().into();
+                assert_eq!(opposite, rotation[outer.0 as usize]);
+
+                // The down color can still be chosen freely among the
+                // remaining colors.
+                for down_color in FaceEnum::iter().filter(|&f| f != face && f != face.opposite()) {
+                    let rotation = ty
+                        .color_scheme_rotation_with_center(
+                            Some(down_color.into()),
+                            Some(face.into()),
+                        )
+                        .unwrap();
+                    assert_eq!(Face::from(face), rotation[center.0 as usize]);
+                    assert_eq!(Face::from(down_color), rotation[down.0 as usize]);
+                }
+            }
+        }
+    }
+
     fn twist_comparison_key(p: &Rubiks4D, twist: Twist) -> impl PartialEq {
         const SOME_PROGRESS: f32 = 0.1;
 
@@ -0,0 +1,152 @@
+//! Serializable snapshot of the position and orientation of every piece, for
+//! tools outside Hyperspeedcube such as trainers and solvers.
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// Position and orientation of every piece of a puzzle.
+///
+/// This representation is stable: pieces are always numbered in the same
+/// order for a given puzzle type, and orientations are given using face
+/// symbols.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PuzzleSnapshot {
+    /// Puzzle type.
+    pub puzzle: PuzzleTypeEnum,
+    /// Position and orientation of each piece, in piece order.
+    pub pieces: Vec<PieceSnapshot>,
+}
+
+/// Position and orientation of a single piece.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PieceSnapshot {
+    /// Index of the piece whose original location this piece occupies.
+    pub position: u16,
+    /// Symbol of the face that each axis of the piece points toward, in the
+    /// order X, Y, Z (and W for 4D puzzles). A solved piece points toward R,
+    /// U, F (and O).
+    pub orientation: Vec<String>,
+}
+
+impl Puzzle {
+    /// Returns the position and orientation of every piece, or `None` if the
+    /// puzzle does not support this.
+    pub fn state(&self) -> Option<PuzzleSnapshot> {
+        let ty = self.ty();
+        let pieces = (0..ty.pieces().len() as _)
+            .map(Piece)
+            .map(|piece| {
+                Some(PieceSnapshot {
+                    position: self.piece_position(piece)?.0,
+                    orientation: self
+                        .piece_orientation(piece)?
+                        .into_iter()
+                        .map(|face| ty.info(face).symbol.to_owned())
+                        .collect(),
+                })
+            })
+            .collect::<Option<_>>()?;
+        Some(PuzzleSnapshot { puzzle: ty, pieces })
+    }
+
+    /// Constructs a puzzle from the position and orientation of every piece.
+    /// Returns an error if the snapshot is not a valid arrangement of pieces,
+    /// but does not check whether it is reachable by twisting the puzzle (see
+    /// `PuzzleState::check_state()`).
+    pub fn from_state(snapshot: &PuzzleSnapshot) -> Result<Self, String> {
+        let ty = snapshot.puzzle;
+        ty.validate()?;
+        if snapshot.pieces.len() != ty.pieces().len() {
+            return Err(format!(
+                "expected {} pieces for {}, got {}",
+                ty.pieces().len(),
+                ty.name(),
+                snapshot.pieces.len(),
+            ));
+        }
+
+        let mut puzzle = Puzzle::new(ty);
+        for (i, piece_snapshot) in snapshot.pieces.iter().enumerate() {
+            let piece = Piece(i as _);
+            let orientation: Vec<Face> = piece_snapshot
+                .orientation
+                .iter()
+                .map(|symbol| {
+                    ty.face_from_symbol(symbol)
+                        .ok_or_else(|| format!("invalid face {symbol:?} for piece {i}"))
+                })
+                .collect::<Result<_, _>>()?;
+            puzzle
+                .set_piece_orientation(piece, &orientation)
+                .map_err(|e| format!("{e} for piece {i}"))?;
+            // The position is determined by the orientation, so it's only
+            // used as a check.
+            if puzzle.piece_position(piece) != Some(Piece(piece_snapshot.position)) {
+                return Err(format!(
+                    "position {} does not match the orientation of piece {i}",
+                    piece_snapshot.position,
+                ));
+            }
+        }
+
+        if !snapshot.pieces.iter().map(|p| p.position).all_unique() {
+            return Err("multiple pieces in the same position".to_string());
+        }
+
+        Ok(puzzle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn puzzle_types() -> Vec<PuzzleTypeEnum> {
+        let mut ret = vec![PuzzleTypeEnum::Ivy];
+        for layer_count in 1..=4 {
+            ret.push(PuzzleTypeEnum::Rubiks3D { layer_count });
+            ret.push(PuzzleTypeEnum::Rubiks4D { layer_count });
+        }
+        ret
+    }
+
+    #[test]
+    fn test_puzzle_state_round_trip() {
+        for ty in puzzle_types() {
+            let mut puzzle = Puzzle::new(ty);
+            for _ in 0..50 {
+                puzzle.twist(Twist::from_rng(ty)).unwrap();
+            }
+
+            let snapshot = puzzle.state().unwrap();
+            let serialized = serde_yaml::to_string(&snapshot).unwrap();
+            let deserialized: PuzzleSnapshot = serde_yaml::from_str(&serialized).unwrap();
+            assert_eq!(snapshot, deserialized);
+            assert_eq!(puzzle, Puzzle::from_state(&deserialized).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_puzzle_state_validation() {
+        let ty = PuzzleTypeEnum::Rubiks3D { layer_count: 3 };
+        let solved = Puzzle::new(ty).state().unwrap();
+
+        let mut wrong_count = solved.clone();
+        wrong_count.pieces.pop();
+        assert!(Puzzle::from_state(&wrong_count).is_err());
+
+        let mut reflected = solved.clone();
+        reflected.pieces[0].orientation = vec!["L".into(), "U".into(), "F".into()];
+        assert!(Puzzle::from_state(&reflected).is_err());
+
+        let mut invalid_face = solved.clone();
+        invalid_face.pieces[0].orientation[0] = "O".into();
+        assert!(Puzzle::from_state(&invalid_face).is_err());
+
+        let mut wrong_position = solved;
+        wrong_position.pieces[0].position = 1;
+        assert!(Puzzle::from_state(&wrong_position).is_err());
+    }
+}
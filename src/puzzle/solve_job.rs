@@ -0,0 +1,126 @@
+//! Solves that run in the background using the 2x2x2 or 3x3x3 solver.
+
+use itertools::Itertools;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use super::*;
+
+/// Length of 3x3x3 solution (in the half-turn metric) that is good enough to
+/// stop searching.
+pub const TWO_PHASE_TARGET_LENGTH: usize = 20;
+
+/// Solve running in the background.
+#[derive(Clone)]
+pub struct SolveJob {
+    /// Puzzle state being solved.
+    puzzle: Puzzle,
+    cancel: Arc<AtomicBool>,
+    progress: Arc<Mutex<SolveProgress>>,
+}
+#[derive(Default)]
+struct SolveProgress {
+    /// Shortest solution found so far.
+    best: Option<Vec<Twist>>,
+    /// Final result, once the solve is done.
+    result: Option<Result<Vec<Twist>, String>>,
+}
+impl SolveJob {
+    /// Returns whether there is a solver for a puzzle type.
+    pub fn is_supported(ty: PuzzleTypeEnum) -> bool {
+        matches!(ty, PuzzleTypeEnum::Rubiks3D { layer_count: 2..=3 })
+    }
+
+    /// Checks the invariants of a puzzle state that every solvable state
+    /// satisfies, such as after importing a log file. Returns an error naming
+    /// each invariant that fails.
+    pub fn check_solvable(puzzle: &Puzzle) -> Result<(), String> {
+        match puzzle.check_state() {
+            Some(errors) if !errors.is_empty() => Err(format!(
+                "Puzzle state cannot be solved:\n{}",
+                errors.iter().map(|e| format!("• {e}")).join("\n"),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Starts solving a puzzle state. On web, this blocks until the solve is
+    /// done. If the state cannot be solved, the job finishes right away with
+    /// an error instead of searching.
+    pub fn start(puzzle: Puzzle) -> Self {
+        let ret = Self {
+            puzzle,
+            cancel: Arc::new(AtomicBool::new(false)),
+            progress: Arc::new(Mutex::new(SolveProgress::default())),
+        };
+        if let Err(e) = Self::check_solvable(&ret.puzzle) {
+            ret.progress.lock().unwrap().result = Some(Err(e));
+            return ret;
+        }
+
+        let job = ret.clone();
+        let run = move || {
+            let result = match job.puzzle.ty() {
+                PuzzleTypeEnum::Rubiks3D { layer_count: 2 } => solver::solve_2x2x2(&job.puzzle),
+                _ => two_phase::solve_3x3x3(
+                    &job.puzzle,
+                    TWO_PHASE_TARGET_LENGTH,
+                    &job.cancel,
+                    |best| job.progress.lock().unwrap().best = Some(best.to_vec()),
+                ),
+            };
+            job.progress.lock().unwrap().result = Some(result);
+        };
+        // There are no threads on web, so the solve blocks the UI there.
+        #[cfg(not(target_arch = "wasm32"))]
+        std::thread::spawn(run);
+        #[cfg(target_arch = "wasm32")]
+        run();
+
+        ret
+    }
+
+    /// Returns the puzzle state being solved.
+    pub fn puzzle(&self) -> &Puzzle {
+        &self.puzzle
+    }
+    /// Stops the search, which then finishes with the shortest solution found
+    /// so far.
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+    /// Returns the shortest solution found so far.
+    pub fn best(&self) -> Option<Vec<Twist>> {
+        self.progress.lock().unwrap().best.clone()
+    }
+    /// Returns the final result, or `None` if the solve is still running.
+    pub fn result(&self) -> Option<Result<Vec<Twist>, String>> {
+        self.progress.lock().unwrap().result.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve_job_unsolvable_state() {
+        let ty = PuzzleTypeEnum::Rubiks3D { layer_count: 3 };
+        let mut puzzle = Puzzle::new(ty);
+        assert_eq!(Ok(()), SolveJob::check_solvable(&puzzle));
+
+        // Twist one corner in place.
+        let corner = (0..ty.pieces().len() as _)
+            .map(Piece)
+            .find(|&piece| ty.info(piece).stickers.len() == 3)
+            .unwrap();
+        let mut orientation = puzzle.piece_orientation(corner).unwrap();
+        orientation.rotate_left(1);
+        puzzle.set_piece_orientation(corner, &orientation).unwrap();
+
+        // The job fails without searching.
+        let job = SolveJob::start(puzzle);
+        let e = job.result().unwrap().unwrap_err();
+        assert!(e.contains("Corner twist sum"), "{e}");
+    }
+}
@@ -0,0 +1,264 @@
+//! Optimal solver for the 2x2x2 Rubik's cube.
+//!
+//! The 2x2x2 has only 3,674,160 states once one corner is held fixed, so the
+//! distance from solved of every state is computed once using a breadth-first
+//! search. Solving then just follows decreasing distances, which never takes
+//! more than God's number (11) steps.
+
+use itertools::Itertools;
+
+use super::cubie::{self, CORNER_SLOTS};
+use super::*;
+
+/// Corner slot that the solver never moves, which is DBL.
+const FIXED_SLOT: usize = 7;
+
+/// Twists used by the solver, none of which move the DBL corner.
+const MOVES: [&str; 9] = ["U", "U'", "U2", "R", "R'", "R2", "F", "F'", "F2"];
+
+/// Number of permutations of the 7 corners that can move.
+const PERM_COUNT: usize = 5040;
+/// Number of orientations of the 7 corners that can move. The orientation of
+/// the last one is determined by the others.
+const ORI_COUNT: usize = 729;
+
+const PUZZLE_TYPE: PuzzleTypeEnum = PuzzleTypeEnum::Rubiks3D { layer_count: 2 };
+
+lazy_static! {
+    static ref TABLES: Tables = Tables::new();
+}
+
+/// Returns an optimal solution (in the half-turn metric) for a 2x2x2 Rubik's
+/// cube. The solution may leave the puzzle in a different orientation from
+/// how it started.
+pub fn solve_2x2x2(puzzle: &Puzzle) -> Result<Vec<Twist>, String> {
+    let notation = PUZZLE_TYPE.notation_scheme();
+    let twists: Vec<Twist> = MOVES
+        .iter()
+        .map(|s| notation.parse_twist(s))
+        .collect::<Result<_, _>>()?;
+
+    let tables = &*TABLES;
+    let state = CornerState::from_puzzle(puzzle)?;
+    let (mut perm, mut ori) = (state.perm_index(), state.ori_index());
+    let mut ret = vec![];
+    loop {
+        let distance = tables.distance(perm, ori);
+        if distance == 0 {
+            return Ok(ret);
+        }
+        let m = (0..MOVES.len())
+            .find(|&m| {
+                let (new_perm, new_ori) = tables.apply(perm, ori, m);
+                tables.distance(new_perm, new_ori) < distance
+            })
+            .ok_or("puzzle state cannot be solved")?;
+        (perm, ori) = tables.apply(perm, ori, m);
+        ret.push(twists[m]);
+    }
+}
+
+/// Move tables and distance from solved of every state.
+struct Tables {
+    perm_moves: Vec<[u16; MOVES.len()]>,
+    ori_moves: Vec<[u16; MOVES.len()]>,
+    distances: Vec<u8>,
+}
+impl Tables {
+    fn new() -> Self {
+        let moves = MOVES.map(|s| {
+            let mut puzzle = Puzzle::new(PUZZLE_TYPE);
+            let twist = PUZZLE_TYPE.notation_scheme().parse_twist(s).unwrap();
+            puzzle.twist(twist).unwrap();
+            CornerState::from_puzzle(&puzzle).unwrap()
+        });
+
+        let perm_moves = (0..PERM_COUNT)
+            .map(|i| {
+                let state = CornerState::from_indices(i, 0);
+                moves.map(|m| state.apply(&m).perm_index() as u16)
+            })
+            .collect();
+        let ori_moves = (0..ORI_COUNT)
+            .map(|i| {
+                let state = CornerState::from_indices(0, i);
+                moves.map(|m| state.apply(&m).ori_index() as u16)
+            })
+            .collect();
+
+        let mut ret = Self {
+            perm_moves,
+            ori_moves,
+            distances: vec![u8::MAX; PERM_COUNT * ORI_COUNT],
+        };
+
+        // Breadth-first search from the solved state.
+        let solved = CornerState::SOLVED;
+        let mut frontier = vec![(solved.perm_index(), solved.ori_index())];
+        ret.distances[solved.perm_index() * ORI_COUNT + solved.ori_index()] = 0;
+        let mut depth = 0;
+        while !frontier.is_empty() {
+            depth += 1;
+            let mut next_frontier = vec![];
+            for (perm, ori) in frontier {
+                for m in 0..MOVES.len() {
+                    let (new_perm, new_ori) = ret.apply(perm, ori, m);
+                    let d = &mut ret.distances[new_perm * ORI_COUNT + new_ori];
+                    if *d == u8::MAX {
+                        *d = depth;
+                        next_frontier.push((new_perm, new_ori));
+                    }
+                }
+            }
+            frontier = next_frontier;
+        }
+
+        ret
+    }
+
+    fn apply(&self, perm: usize, ori: usize, m: usize) -> (usize, usize) {
+        (
+            self.perm_moves[perm][m] as usize,
+            self.ori_moves[ori][m] as usize,
+        )
+    }
+    fn distance(&self, perm: usize, ori: usize) -> u8 {
+        self.distances[perm * ORI_COUNT + ori]
+    }
+}
+
+/// Which corner is in each slot and how it is twisted.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct CornerState {
+    /// Corner in each slot, numbered by its solved slot.
+    perm: [u8; 8],
+    /// Number of clockwise twists of the corner in each slot, compared to its
+    /// U or D sticker facing U or D.
+    ori: [u8; 8],
+}
+impl CornerState {
+    const SOLVED: Self = Self {
+        perm: [0, 1, 2, 3, 4, 5, 6, 7],
+        ori: [0; 8],
+    };
+
+    /// Reads the corner state of a 2x2x2 puzzle. Colors are relabeled
+    /// relative to the corner in the DBL slot, so that the puzzle can be in
+    /// any orientation.
+    fn from_puzzle(puzzle: &Puzzle) -> Result<Self, String> {
+        let ty = puzzle.ty();
+        if ty != PUZZLE_TYPE {
+            return Err(format!(
+                "Optimal solver only supports {}",
+                PUZZLE_TYPE.name(),
+            ));
+        }
+        let pieces = cubie::piece_stickers(puzzle);
+
+        let fixed_piece = pieces
+            .iter()
+            .find(|stickers| {
+                let faces = stickers.iter().map(|&(face, _)| face).collect_vec();
+                cubie::find_slot(&CORNER_SLOTS, &faces) == Some(FIXED_SLOT)
+            })
+            .ok_or("no piece in the DBL slot")?;
+        let mut color_map = [None; 6];
+        for &(face, color) in fixed_piece {
+            color_map[color as usize] = Some(face);
+            color_map[(color as usize + 3) % 6] = Some((face + 3) % 6);
+        }
+
+        let (perm, ori) = cubie::read_slots(&pieces, &color_map, &CORNER_SLOTS)?;
+        let ret = Self { perm, ori };
+        if ret.ori.iter().sum::<u8>() % 3 != 0 {
+            return Err("puzzle state cannot be solved because a corner is twisted".to_string());
+        }
+
+        Ok(ret)
+    }
+
+    fn from_indices(mut perm_index: usize, mut ori_index: usize) -> Self {
+        let mut ret = Self::SOLVED;
+
+        let mut digits = [0; FIXED_SLOT];
+        for (i, digit) in digits.iter_mut().enumerate().rev() {
+            *digit = perm_index % (FIXED_SLOT - i);
+            perm_index /= FIXED_SLOT - i;
+        }
+        let mut remaining = (0..FIXED_SLOT as u8).collect_vec();
+        for (i, digit) in digits.into_iter().enumerate() {
+            ret.perm[i] = remaining.remove(digit);
+        }
+
+        for i in (0..FIXED_SLOT - 1).rev() {
+            ret.ori[i] = (ori_index % 3) as u8;
+            ori_index /= 3;
+        }
+        let sum: u8 = ret.ori[..FIXED_SLOT - 1].iter().sum();
+        ret.ori[FIXED_SLOT - 1] = (3 - sum % 3) % 3;
+
+        ret
+    }
+    fn perm_index(self) -> usize {
+        (0..FIXED_SLOT).fold(0, |acc, i| {
+            let smaller_after = (i + 1..FIXED_SLOT)
+                .filter(|&j| self.perm[j] < self.perm[i])
+                .count();
+            acc * (FIXED_SLOT - i) + smaller_after
+        })
+    }
+    fn ori_index(self) -> usize {
+        self.ori[..FIXED_SLOT - 1]
+            .iter()
+            .fold(0, |acc, &o| acc * 3 + o as usize)
+    }
+
+    /// Returns the state after applying a move, given as the state that the
+    /// move produces from solved.
+    #[must_use]
+    fn apply(self, m: &Self) -> Self {
+        let mut ret = self;
+        for i in 0..8 {
+            let src = m.perm[i] as usize;
+            ret.perm[i] = self.perm[src];
+            ret.ori[i] = (self.ori[src] + m.ori[i]) % 3;
+        }
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_solves(puzzle: &Puzzle, max_len: usize) {
+        let solution = solve_2x2x2(puzzle).unwrap();
+        assert!(solution.len() <= max_len, "solution too long: {solution:?}");
+        let mut puzzle = puzzle.clone();
+        for twist in solution {
+            puzzle.twist(twist).unwrap();
+        }
+        assert!(puzzle.is_solved());
+    }
+
+    #[test]
+    fn test_2x2x2_solver() {
+        let notation = PUZZLE_TYPE.notation_scheme();
+        let mut puzzle = Puzzle::new(PUZZLE_TYPE);
+        assert_solves(&puzzle, 0);
+
+        // Rotating the whole puzzle shouldn't matter.
+        for twist in notation.parse_alg("x y L").unwrap() {
+            puzzle.twist(twist).unwrap();
+        }
+        assert_solves(&puzzle, 1);
+
+        for _ in 0..10 {
+            let mut puzzle = Puzzle::new(PUZZLE_TYPE);
+            for _ in 0..30 {
+                puzzle.twist(Twist::from_rng(PUZZLE_TYPE)).unwrap();
+            }
+            assert_solves(&puzzle, 11);
+        }
+    }
+}
@@ -0,0 +1,607 @@
+//! Two-phase solver for the 3x3x3 Rubik's cube, based on Herbert Kociemba's
+//! algorithm.
+//!
+//! Phase 1 brings the cube into the subgroup generated by U, D, R2, L2, F2,
+//! and B2, where every piece is oriented and the E-slice edges are in the E
+//! slice. Phase 2 solves the cube using only those moves. Both phases use
+//! iterative-deepening A* with pruning tables, which are computed once on first
+//! use. Once a solution is found, the search continues looking for shorter
+//! ones until it finds one that is short enough or is cancelled.
+
+use itertools::Itertools;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::cubie::{self, CORNER_SLOTS, EDGE_SLOTS};
+use super::*;
+
+/// First edge slot in the E slice.
+const SLICE_START: usize = 8;
+
+/// Twists used by the solver. Move `m` turns face `m / 3`.
+const MOVES: [&str; 18] = [
+    "U", "U2", "U'", "R", "R2", "R'", "F", "F2", "F'", //
+    "D", "D2", "D'", "L", "L2", "L'", "B", "B2", "B'",
+];
+/// Moves that stay within the phase 2 subgroup: U, D, R2, F2, L2, and B2.
+const PHASE_2_MOVES: [usize; 10] = [0, 1, 2, 4, 7, 9, 10, 11, 13, 16];
+
+const TWIST_COUNT: usize = 2187; // 3^7
+const FLIP_COUNT: usize = 2048; // 2^11
+const SLICE_COUNT: usize = 495; // 12 choose 4
+const CORNER_PERM_COUNT: usize = 40320; // 8!
+const UD_EDGE_PERM_COUNT: usize = 40320; // 8!
+const SLICE_PERM_COUNT: usize = 24; // 4!
+
+/// Longest phase 1 solution needed for any state.
+const MAX_PHASE_1_LENGTH: usize = 12;
+/// Longest phase 2 solution needed for any state.
+const MAX_PHASE_2_LENGTH: usize = 18;
+
+const PUZZLE_TYPE: PuzzleTypeEnum = PuzzleTypeEnum::Rubiks3D { layer_count: 3 };
+
+lazy_static! {
+    static ref TABLES: Tables = Tables::new();
+}
+
+/// Returns a solution for a 3x3x3 Rubik's cube.
+///
+/// The search stops as soon as it finds a solution of at most `max_length`
+/// moves (in the half-turn metric) or `cancel` is set, and returns the
+/// shortest solution found so far. `on_solution` is called every time a
+/// shorter solution is found.
+pub fn solve_3x3x3(
+    puzzle: &Puzzle,
+    max_length: usize,
+    cancel: &AtomicBool,
+    mut on_solution: impl FnMut(&[Twist]),
+) -> Result<Vec<Twist>, String> {
+    let cube = CubieCube::from_puzzle(puzzle)?;
+    let tables = &*TABLES;
+
+    let mut search = Search {
+        tables,
+        cube,
+        max_length,
+        cancel,
+        on_solution: &mut |moves: &[usize]| {
+            on_solution(&moves.iter().map(|&m| tables.twists[m]).collect_vec())
+        },
+        moves: vec![],
+        best: None,
+    };
+    for depth in 0..=MAX_PHASE_1_LENGTH {
+        if search.is_done() || search.best.as_ref().map_or(false, |b| b.len() <= depth) {
+            break;
+        }
+        search.phase_1(cube.twist(), cube.flip(), cube.slice(), depth);
+    }
+
+    match search.best {
+        Some(moves) => Ok(moves.iter().map(|&m| tables.twists[m]).collect()),
+        None if cancel.load(Ordering::Relaxed) => Err("Cancelled".to_string()),
+        None => Err("no solution found".to_string()),
+    }
+}
+
+/// State of a search in progress.
+struct Search<'a> {
+    tables: &'a Tables,
+    /// Cube being solved.
+    cube: CubieCube,
+    max_length: usize,
+    cancel: &'a AtomicBool,
+    on_solution: &'a mut dyn FnMut(&[usize]),
+    /// Moves of the current branch of the search.
+    moves: Vec<usize>,
+    /// Shortest solution found so far.
+    best: Option<Vec<usize>>,
+}
+impl Search<'_> {
+    fn is_done(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+            || self
+                .best
+                .as_ref()
+                .map_or(false, |b| b.len() <= self.max_length)
+    }
+
+    /// Returns whether move `m` is redundant after the previous move, either
+    /// because they turn the same face or because they turn opposite faces in
+    /// the wrong order.
+    fn is_redundant(&self, m: usize) -> bool {
+        self.moves.last().map_or(false, |&last| {
+            let (face, last_face) = (m / 3, last / 3);
+            face == last_face || (face % 3 == last_face % 3 && face < last_face)
+        })
+    }
+
+    /// Searches for phase 1 solutions of exactly `remaining` more moves and
+    /// tries to continue each with phase 2.
+    fn phase_1(&mut self, twist: usize, flip: usize, slice: usize, remaining: usize) {
+        let t = self.tables;
+        if remaining == 0 {
+            // If the last move is a phase 2 move, then a shorter phase 1
+            // solution has already been tried.
+            let last_is_phase_2 = self
+                .moves
+                .last()
+                .map_or(false, |m| PHASE_2_MOVES.contains(m));
+            if twist == 0 && flip == 0 && slice == SOLVED_SLICE && !last_is_phase_2 {
+                self.start_phase_2();
+            }
+            return;
+        }
+        if self.is_done() {
+            return;
+        }
+        let lower_bound = std::cmp::max(
+            t.twist_slice_prune[twist * SLICE_COUNT + slice],
+            t.flip_slice_prune[flip * SLICE_COUNT + slice],
+        );
+        if lower_bound as usize > remaining {
+            return;
+        }
+
+        for m in 0..MOVES.len() {
+            if self.is_redundant(m) {
+                continue;
+            }
+            self.moves.push(m);
+            self.phase_1(
+                t.twist_moves[twist][m] as usize,
+                t.flip_moves[flip][m] as usize,
+                t.slice_moves[slice][m] as usize,
+                remaining - 1,
+            );
+            self.moves.pop();
+        }
+    }
+
+    fn start_phase_2(&mut self) {
+        let t = self.tables;
+        let phase_1_len = self.moves.len();
+        let max_depth = match &self.best {
+            Some(best) if best.len() <= phase_1_len => return,
+            Some(best) => std::cmp::min(MAX_PHASE_2_LENGTH, best.len() - 1 - phase_1_len),
+            None => MAX_PHASE_2_LENGTH,
+        };
+
+        let cube = self
+            .moves
+            .iter()
+            .fold(self.cube, |cube, &m| cube.apply(&t.moves[m]));
+        let (corner_perm, edge_perm, slice_perm) =
+            (cube.corner_perm(), cube.ud_edge_perm(), cube.slice_perm());
+        for depth in 0..=max_depth {
+            if self.phase_2(corner_perm, edge_perm, slice_perm, depth) {
+                (self.on_solution)(&self.moves);
+                self.best = Some(self.moves.clone());
+                self.moves.truncate(phase_1_len);
+                return;
+            }
+        }
+    }
+
+    /// Searches for a phase 2 solution of exactly `remaining` more moves.
+    /// Returns `true` and leaves the solution on the move stack if one is
+    /// found.
+    fn phase_2(
+        &mut self,
+        corner_perm: usize,
+        edge_perm: usize,
+        slice_perm: usize,
+        remaining: usize,
+    ) -> bool {
+        let t = self.tables;
+        if remaining == 0 {
+            return corner_perm == 0 && edge_perm == 0 && slice_perm == 0;
+        }
+        let lower_bound = std::cmp::max(
+            t.corner_slice_prune[corner_perm * SLICE_PERM_COUNT + slice_perm],
+            t.edge_slice_prune[edge_perm * SLICE_PERM_COUNT + slice_perm],
+        );
+        if lower_bound as usize > remaining {
+            return false;
+        }
+
+        for (i, &m) in PHASE_2_MOVES.iter().enumerate() {
+            if self.is_redundant(m) {
+                continue;
+            }
+            self.moves.push(m);
+            if self.phase_2(
+                t.corner_perm_moves[corner_perm][i] as usize,
+                t.ud_edge_perm_moves[edge_perm][i] as usize,
+                t.slice_perm_moves[slice_perm][i] as usize,
+                remaining - 1,
+            ) {
+                return true;
+            }
+            self.moves.pop();
+        }
+        false
+    }
+}
+
+/// Move tables and pruning tables.
+struct Tables {
+    /// Twist for each move.
+    twists: [Twist; MOVES.len()],
+    /// Effect of each move, as the state that it produces from solved.
+    moves: [CubieCube; MOVES.len()],
+
+    twist_moves: Vec<[u16; MOVES.len()]>,
+    flip_moves: Vec<[u16; MOVES.len()]>,
+    slice_moves: Vec<[u16; MOVES.len()]>,
+    corner_perm_moves: Vec<[u16; PHASE_2_MOVES.len()]>,
+    ud_edge_perm_moves: Vec<[u16; PHASE_2_MOVES.len()]>,
+    slice_perm_moves: Vec<[u16; PHASE_2_MOVES.len()]>,
+
+    twist_slice_prune: Vec<u8>,
+    flip_slice_prune: Vec<u8>,
+    corner_slice_prune: Vec<u8>,
+    edge_slice_prune: Vec<u8>,
+}
+impl Tables {
+    fn new() -> Self {
+        let notation = PUZZLE_TYPE.notation_scheme();
+        let twists = MOVES.map(|s| notation.parse_twist(s).unwrap());
+        let moves = twists.map(|twist| {
+            let mut puzzle = Puzzle::new(PUZZLE_TYPE);
+            puzzle.twist(twist).unwrap();
+            CubieCube::from_puzzle(&puzzle).unwrap()
+        });
+        let phase_2_moves = PHASE_2_MOVES.map(|m| moves[m]);
+
+        let twist_moves = move_table(TWIST_COUNT, CubieCube::with_twist, CubieCube::twist, &moves);
+        let flip_moves = move_table(FLIP_COUNT, CubieCube::with_flip, CubieCube::flip, &moves);
+        let slice_moves = move_table(SLICE_COUNT, CubieCube::with_slice, CubieCube::slice, &moves);
+        let corner_perm_moves = move_table(
+            CORNER_PERM_COUNT,
+            CubieCube::with_corner_perm,
+            CubieCube::corner_perm,
+            &phase_2_moves,
+        );
+        let ud_edge_perm_moves = move_table(
+            UD_EDGE_PERM_COUNT,
+            CubieCube::with_ud_edge_perm,
+            CubieCube::ud_edge_perm,
+            &phase_2_moves,
+        );
+        let slice_perm_moves = move_table(
+            SLICE_PERM_COUNT,
+            CubieCube::with_slice_perm,
+            CubieCube::slice_perm,
+            &phase_2_moves,
+        );
+
+        Self {
+            twist_slice_prune: pruning_table(&twist_moves, &slice_moves, SOLVED_SLICE),
+            flip_slice_prune: pruning_table(&flip_moves, &slice_moves, SOLVED_SLICE),
+            corner_slice_prune: pruning_table(&corner_perm_moves, &slice_perm_moves, 0),
+            edge_slice_prune: pruning_table(&ud_edge_perm_moves, &slice_perm_moves, 0),
+
+            twists,
+            moves,
+
+            twist_moves,
+            flip_moves,
+            slice_moves,
+            corner_perm_moves,
+            ud_edge_perm_moves,
+            slice_perm_moves,
+        }
+    }
+}
+
+/// Returns the new value of a coordinate after each move.
+fn move_table<const N: usize>(
+    count: usize,
+    from_coord: fn(usize) -> CubieCube,
+    to_coord: fn(&CubieCube) -> usize,
+    moves: &[CubieCube; N],
+) -> Vec<[u16; N]> {
+    (0..count)
+        .map(|i| {
+            let cube = from_coord(i);
+            moves.map(|m| to_coord(&cube.apply(&m)) as u16)
+        })
+        .collect()
+}
+
+/// Returns the number of moves needed to solve each pair of coordinates `a`
+/// and `b`, using breadth-first search from `a = 0` and `b = solved_b`.
+fn pruning_table<const N: usize>(
+    a_moves: &[[u16; N]],
+    b_moves: &[[u16; N]],
+    solved_b: usize,
+) -> Vec<u8> {
+    let b_count = b_moves.len();
+    let mut ret = vec![u8::MAX; a_moves.len() * b_count];
+    ret[solved_b] = 0;
+    let mut frontier = vec![(0, solved_b)];
+    let mut depth = 0;
+    while !frontier.is_empty() {
+        depth += 1;
+        let mut next_frontier = vec![];
+        for (a, b) in frontier {
+            for m in 0..N {
+                let (new_a, new_b) = (a_moves[a][m] as usize, b_moves[b][m] as usize);
+                let d = &mut ret[new_a * b_count + new_b];
+                if *d == u8::MAX {
+                    *d = depth;
+                    next_frontier.push((new_a, new_b));
+                }
+            }
+        }
+        frontier = next_frontier;
+    }
+    ret
+}
+
+/// Slice coordinate of the solved state.
+const SOLVED_SLICE: usize = SLICE_COUNT - 1;
+
+/// Which piece is in each slot and how it is oriented.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct CubieCube {
+    /// Corner in each slot, numbered by its solved slot.
+    corner_perm: [u8; 8],
+    /// Number of clockwise twists of the corner in each slot, compared to its
+    /// U or D sticker facing U or D.
+    corner_ori: [u8; 8],
+    /// Edge in each slot, numbered by its solved slot.
+    edge_perm: [u8; 12],
+    /// Whether the edge in each slot is flipped.
+    edge_ori: [u8; 12],
+}
+impl CubieCube {
+    const SOLVED: Self = Self {
+        corner_perm: [0, 1, 2, 3, 4, 5, 6, 7],
+        corner_ori: [0; 8],
+        edge_perm: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+        edge_ori: [0; 12],
+    };
+
+    /// Reads the state of a 3x3x3 puzzle. Colors are relabeled relative to
+    /// the centers, so that the puzzle can be in any orientation.
+    fn from_puzzle(puzzle: &Puzzle) -> Result<Self, String> {
+        let ty = puzzle.ty();
+        if ty != PUZZLE_TYPE {
+            return Err(format!(
+                "Two-phase solver only supports {}",
+                PUZZLE_TYPE.name(),
+            ));
+        }
+        let pieces = cubie::piece_stickers(puzzle);
+
+        let mut color_map = [None; 6];
+        for stickers in pieces.iter().filter(|stickers| stickers.len() == 1) {
+            let (face, color) = stickers[0];
+            color_map[color as usize] = Some(face);
+        }
+
+        let (corner_perm, corner_ori) = cubie::read_slots(&pieces, &color_map, &CORNER_SLOTS)?;
+        let (edge_perm, edge_ori) = cubie::read_slots(&pieces, &color_map, &EDGE_SLOTS)?;
+        let ret = Self {
+            corner_perm,
+            corner_ori,
+            edge_perm,
+            edge_ori,
+        };
+
+        if ret.corner_ori.iter().sum::<u8>() % 3 != 0 {
+            return Err("puzzle state cannot be solved because a corner is twisted".to_string());
+        }
+        if ret.edge_ori.iter().sum::<u8>() % 2 != 0 {
+            return Err("puzzle state cannot be solved because an edge is flipped".to_string());
+        }
+        if is_odd(&ret.corner_perm) != is_odd(&ret.edge_perm) {
+            return Err("puzzle state cannot be solved because two pieces are swapped".to_string());
+        }
+
+        Ok(ret)
+    }
+
+    /// Returns the state after applying a move, given as the state that the
+    /// move produces from solved.
+    #[must_use]
+    fn apply(&self, m: &Self) -> Self {
+        let mut ret = *self;
+        for i in 0..8 {
+            let src = m.corner_perm[i] as usize;
+            ret.corner_perm[i] = self.corner_perm[src];
+            ret.corner_ori[i] = (self.corner_ori[src] + m.corner_ori[i]) % 3;
+        }
+        for i in 0..12 {
+            let src = m.edge_perm[i] as usize;
+            ret.edge_perm[i] = self.edge_perm[src];
+            ret.edge_ori[i] = (self.edge_ori[src] + m.edge_ori[i]) % 2;
+        }
+        ret
+    }
+
+    /// Returns the orientation of the first 7 corners. The orientation of the
+    /// last one is determined by the others.
+    fn twist(&self) -> usize {
+        self.corner_ori[..7]
+            .iter()
+            .fold(0, |acc, &o| acc * 3 + o as usize)
+    }
+    fn with_twist(mut twist: usize) -> Self {
+        let mut ret = Self::SOLVED;
+        for i in (0..7).rev() {
+            ret.corner_ori[i] = (twist % 3) as u8;
+            twist /= 3;
+        }
+        ret.corner_ori[7] = (3 - ret.corner_ori[..7].iter().sum::<u8>() % 3) % 3;
+        ret
+    }
+
+    /// Returns the orientation of the first 11 edges. The orientation of the
+    /// last one is determined by the others.
+    fn flip(&self) -> usize {
+        self.edge_ori[..11]
+            .iter()
+            .fold(0, |acc, &o| acc * 2 + o as usize)
+    }
+    fn with_flip(mut flip: usize) -> Self {
+        let mut ret = Self::SOLVED;
+        for i in (0..11).rev() {
+            ret.edge_ori[i] = (flip % 2) as u8;
+            flip /= 2;
+        }
+        ret.edge_ori[11] = ret.edge_ori[..11].iter().sum::<u8>() % 2;
+        ret
+    }
+
+    /// Returns which slots contain the E-slice edges, ignoring their order.
+    fn slice(&self) -> usize {
+        (0..12)
+            .filter(|&i| self.edge_perm[i] as usize >= SLICE_START)
+            .enumerate()
+            .map(|(k, i)| binomial(i, k + 1))
+            .sum()
+    }
+    fn with_slice(mut slice: usize) -> Self {
+        let mut is_slice_slot = [false; 12];
+        for k in (1..=4).rev() {
+            let i = (0..12).rev().find(|&i| binomial(i, k) <= slice).unwrap();
+            slice -= binomial(i, k);
+            is_slice_slot[i] = true;
+        }
+        let mut ret = Self::SOLVED;
+        let mut slice_edges = SLICE_START as u8..12;
+        let mut other_edges = 0..SLICE_START as u8;
+        for (slot, is_slice) in is_slice_slot.into_iter().enumerate() {
+            ret.edge_perm[slot] = match is_slice {
+                true => slice_edges.next(),
+                false => other_edges.next(),
+            }
+            .unwrap();
+        }
+        ret
+    }
+
+    /// Returns the permutation of the corners.
+    fn corner_perm(&self) -> usize {
+        perm_rank(&self.corner_perm)
+    }
+    fn with_corner_perm(corner_perm: usize) -> Self {
+        let mut ret = Self::SOLVED;
+        ret.corner_perm
+            .copy_from_slice(&perm_unrank(8, corner_perm, 0));
+        ret
+    }
+
+    /// Returns the permutation of the U and D edges, assuming that they are
+    /// all in the U and D layers.
+    fn ud_edge_perm(&self) -> usize {
+        perm_rank(&self.edge_perm[..SLICE_START])
+    }
+    fn with_ud_edge_perm(edge_perm: usize) -> Self {
+        let mut ret = Self::SOLVED;
+        ret.edge_perm[..SLICE_START].copy_from_slice(&perm_unrank(SLICE_START, edge_perm, 0));
+        ret
+    }
+
+    /// Returns the permutation of the E-slice edges, assuming that they are
+    /// all in the E slice.
+    fn slice_perm(&self) -> usize {
+        perm_rank(&self.edge_perm[SLICE_START..])
+    }
+    fn with_slice_perm(slice_perm: usize) -> Self {
+        let mut ret = Self::SOLVED;
+        ret.edge_perm[SLICE_START..].copy_from_slice(&perm_unrank(
+            12 - SLICE_START,
+            slice_perm,
+            SLICE_START as u8,
+        ));
+        ret
+    }
+}
+
+/// Returns n choose k (0 if k > n).
+fn binomial(n: usize, k: usize) -> usize {
+    if k > n {
+        return 0;
+    }
+    (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+}
+
+/// Returns the index of a permutation of distinct values in lexicographic
+/// order.
+fn perm_rank(perm: &[u8]) -> usize {
+    let n = perm.len();
+    (0..n).fold(0, |acc, i| {
+        let smaller_after = perm[i + 1..].iter().filter(|&&x| x < perm[i]).count();
+        acc * (n - i) + smaller_after
+    })
+}
+/// Returns the permutation of `offset..offset+n` with the given index in
+/// lexicographic order.
+fn perm_unrank(n: usize, mut index: usize, offset: u8) -> Vec<u8> {
+    let mut digits = vec![0; n];
+    for (i, digit) in digits.iter_mut().enumerate().rev() {
+        *digit = index % (n - i);
+        index /= n - i;
+    }
+    let mut remaining = (offset..offset + n as u8).collect_vec();
+    digits.into_iter().map(|d| remaining.remove(d)).collect()
+}
+
+fn is_odd(perm: &[u8]) -> bool {
+    perm.iter()
+        .tuple_combinations()
+        .filter(|(a, b)| a > b)
+        .count()
+        % 2
+        == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_3x3x3_coordinates() {
+        for i in 0..SLICE_COUNT {
+            assert_eq!(i, CubieCube::with_slice(i).slice());
+        }
+        for i in 0..CORNER_PERM_COUNT {
+            assert_eq!(i, CubieCube::with_corner_perm(i).corner_perm());
+        }
+        assert_eq!(SOLVED_SLICE, CubieCube::SOLVED.slice());
+    }
+
+    #[test]
+    fn test_3x3x3_solver() {
+        let cancel = AtomicBool::new(false);
+        let notation = PUZZLE_TYPE.notation_scheme();
+
+        let mut puzzles = vec![Puzzle::new(PUZZLE_TYPE)];
+        // Rotations and slice moves shouldn't matter.
+        let mut puzzle = Puzzle::new(PUZZLE_TYPE);
+        for twist in notation.parse_alg("x y M E S R U").unwrap() {
+            puzzle.twist(twist).unwrap();
+        }
+        puzzles.push(puzzle);
+        for _ in 0..5 {
+            let mut puzzle = Puzzle::new(PUZZLE_TYPE);
+            for _ in 0..50 {
+                puzzle.twist(Twist::from_rng(PUZZLE_TYPE)).unwrap();
+            }
+            puzzles.push(puzzle);
+        }
+
+        for puzzle in puzzles {
+            let solution = solve_3x3x3(&puzzle, 30, &cancel, |_| ()).unwrap();
+            assert!(solution.len() <= 30, "solution too long: {solution:?}");
+            let mut puzzle = puzzle.clone();
+            for twist in solution {
+                puzzle.twist(twist).unwrap();
+            }
+            assert!(puzzle.is_solved());
+        }
+    }
+}
@@ -0,0 +1,256 @@
+//! Rendering of the puzzle into separate color, depth, and normal layers, for
+//! compositing the puzzle into other images with correct depth.
+//!
+//! The GPU never sees real depth values (see `mesh.rs`), so these layers are
+//! rasterized on the CPU from the same geometry as the puzzle view.
+
+use cgmath::{point2, InnerSpace, Point2, Point3, Vector3, Zero};
+use std::path::Path;
+
+use super::mesh;
+use crate::app::App;
+use crate::puzzle::traits::*;
+
+/// Color, depth, and surface normal of each pixel of the puzzle view.
+pub(crate) struct RenderLayers {
+    width: u32,
+    height: u32,
+    /// Linear premultiplied color of each pixel, with a transparent
+    /// background.
+    color: Vec<[f32; 4]>,
+    /// Projected Z coordinate of the nearest sticker at each pixel, which
+    /// increases nearer to the camera, or `None` if there is no sticker.
+    depth: Vec<Option<f32>>,
+    /// Unit normal vector of the nearest sticker at each pixel, pointing
+    /// toward the camera.
+    normal: Vec<Vector3<f32>>,
+}
+impl RenderLayers {
+    fn new(width: u32, height: u32) -> Self {
+        let len = width as usize * height as usize;
+        Self {
+            width,
+            height,
+            color: vec![[0.0; 4]; len],
+            depth: vec![None; len],
+            normal: vec![Vector3::zero(); len],
+        }
+    }
+
+    /// Saves the color layer to `path`, and the depth and normal layers next
+    /// to it with `.depth.png` and `.normal.png` extensions.
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        std::fs::write(path, self.color_png()?)?;
+        std::fs::write(path.with_extension("depth.png"), self.depth_png()?)?;
+        std::fs::write(path.with_extension("normal.png"), self.normal_png()?)?;
+        Ok(())
+    }
+
+    /// Returns the color layer as an 8-bit PNG image with a transparent
+    /// background.
+    pub fn color_png(&self) -> Result<Vec<u8>, png::EncodingError> {
+        let data = self
+            .color
+            .iter()
+            .flat_map(|&[r, g, b, a]| {
+                egui::Rgba::from_rgba_premultiplied(r, g, b, a).to_srgba_unmultiplied()
+            })
+            .collect::<Vec<u8>>();
+        self.encode_png(png::ColorType::Rgba, png::BitDepth::Eight, &data)
+    }
+
+    /// Returns the depth layer as a 16-bit grayscale PNG image. The nearest
+    /// point of the puzzle is white, the farthest point of the puzzle is
+    /// almost black, and pixels with no sticker are black.
+    pub fn depth_png(&self) -> Result<Vec<u8>, png::EncodingError> {
+        let depths = self.depth.iter().flatten();
+        let min = depths.clone().copied().fold(f32::INFINITY, f32::min);
+        let max = depths.copied().fold(f32::NEG_INFINITY, f32::max);
+
+        let data = self
+            .depth
+            .iter()
+            .flat_map(|&z| {
+                let value = match z {
+                    None => 0,
+                    Some(_) if max <= min => u16::MAX,
+                    Some(z) => 1 + ((z - min) / (max - min) * (u16::MAX - 1) as f32) as u16,
+                };
+                value.to_be_bytes()
+            })
+            .collect::<Vec<u8>>();
+        self.encode_png(png::ColorType::Grayscale, png::BitDepth::Sixteen, &data)
+    }
+
+    /// Returns the normal layer as a 16-bit PNG image with a transparent
+    /// background. Each component of the normal vector is mapped from -1..=1
+    /// to the full range of its channel.
+    pub fn normal_png(&self) -> Result<Vec<u8>, png::EncodingError> {
+        let to_channel = |x: f32| ((x * 0.5 + 0.5).clamp(0.0, 1.0) * u16::MAX as f32) as u16;
+        let data = std::iter::zip(&self.normal, &self.depth)
+            .flat_map(|(n, z)| match z {
+                None => [0; 4],
+                Some(_) => [to_channel(n.x), to_channel(n.y), to_channel(n.z), u16::MAX],
+            })
+            .flat_map(u16::to_be_bytes)
+            .collect::<Vec<u8>>();
+        self.encode_png(png::ColorType::Rgba, png::BitDepth::Sixteen, &data)
+    }
+
+    fn encode_png(
+        &self,
+        color_type: png::ColorType,
+        bit_depth: png::BitDepth,
+        data: &[u8],
+    ) -> Result<Vec<u8>, png::EncodingError> {
+        let mut ret = vec![];
+        let mut encoder = png::Encoder::new(&mut ret, self.width, self.height);
+        encoder.set_color(color_type);
+        encoder.set_depth(bit_depth);
+        encoder.write_header()?.write_image_data(data)?;
+        Ok(ret)
+    }
+}
+
+/// Renders the puzzle as it is shown in the puzzle view into separate layers,
+/// at the given size in pixels.
+pub(crate) fn render_layers(app: &mut App, width: u32, height: u32) -> RenderLayers {
+    let puzzle = &mut app.puzzle;
+    let prefs = &app.prefs;
+    let view_prefs = puzzle.view_prefs(prefs);
+    let scale = super::main_view_scale(width, height, &view_prefs, prefs);
+
+    // Convert from puzzle view coordinates to pixel coordinates, the same way
+    // as the vertex shader and then the viewport transform.
+    let to_pixel = |[x, y]: [f32; 2]| {
+        point2(
+            (x * scale.x + view_prefs.align_h + 1.0) / 2.0 * width as f32,
+            (1.0 - (y * scale.y + view_prefs.align_v)) / 2.0 * height as f32,
+        )
+    };
+
+    let geometry = puzzle.geometry(prefs);
+    let (verts, indices) = mesh::make_puzzle_mesh(puzzle, prefs, &geometry);
+
+    let mut layers = RenderLayers::new(width, height);
+
+    // Blend the color of each triangle in order, the same way as the GPU.
+    for triangle_indices in indices.chunks_exact(3) {
+        let triangle = [0, 1, 2].map(|i| verts[triangle_indices[i] as usize]);
+        let points = triangle.map(|v| to_pixel([v.pos[0], v.pos[1]]));
+        rasterize_triangle([width, height], points, |i, weights| {
+            let [r, g, b, a] = [0, 1, 2, 3].map(|c| {
+                std::iter::zip(&triangle, weights)
+                    .map(|(v, w)| v.color[c] * w)
+                    .sum::<f32>()
+            });
+            let [dst_r, dst_g, dst_b, dst_a] = layers.color[i];
+            layers.color[i] = [
+                r * a + dst_r * (1.0 - a),
+                g * a + dst_g * (1.0 - a),
+                b * a + dst_b * (1.0 - a),
+                a + dst_a * (1.0 - a),
+            ];
+        });
+    }
+
+    // Draw the real depth and normal of each visible sticker.
+    for geom in &*geometry {
+        let piece = puzzle.info(geom.sticker).piece;
+        if puzzle.visual_piece_state(piece).opacity(prefs) <= 0.0 {
+            continue;
+        }
+        for polygon in &*geom.front_polygons {
+            let Some((&first, rest)) = polygon.verts.split_first() else {
+                continue;
+            };
+            for (&b, &c) in rest.iter().zip(rest.iter().skip(1)) {
+                let triangle: [Point3<f32>; 3] = [first, b, c];
+                let points = triangle.map(|v| to_pixel([v.x, v.y]));
+                rasterize_triangle([width, height], points, |i, weights| {
+                    let z = std::iter::zip(&triangle, weights)
+                        .map(|(v, w)| v.z * w)
+                        .sum::<f32>();
+                    if layers.depth[i].map_or(true, |old| z > old) {
+                        layers.depth[i] = Some(z);
+                        layers.normal[i] = polygon.normal.normalize();
+                    }
+                });
+            }
+        }
+    }
+
+    layers
+}
+
+/// Calls `f` with the index and barycentric coordinates of each pixel whose
+/// center is inside a triangle given in pixel coordinates, in an image of the
+/// given width and height.
+fn rasterize_triangle(
+    [width, height]: [u32; 2],
+    [a, b, c]: [Point2<f32>; 3],
+    mut f: impl FnMut(usize, [f32; 3]),
+) {
+    let area = edge(a, b, c);
+    if area == 0.0 || !area.is_finite() {
+        return;
+    }
+
+    // Top-left fill rule: a pixel exactly on an edge belongs only to the
+    // triangle for which that edge is a top or left edge, so that the pixels
+    // between two triangles of a polygon are drawn exactly once.
+    let owns_edge = |u: Point2<f32>, v: Point2<f32>| {
+        let d = (v - u) * area.signum();
+        d.y < 0.0 || d.y == 0.0 && d.x > 0.0
+    };
+    let owned = [owns_edge(b, c), owns_edge(c, a), owns_edge(a, b)];
+
+    let x_range = pixel_range(a.x.min(b.x).min(c.x), a.x.max(b.x).max(c.x), width);
+    let y_range = pixel_range(a.y.min(b.y).min(c.y), a.y.max(b.y).max(c.y), height);
+    for y in y_range {
+        for x in x_range.clone() {
+            let p = point2(x as f32 + 0.5, y as f32 + 0.5);
+            let weights = [edge(b, c, p), edge(c, a, p), edge(a, b, p)].map(|w| w / area);
+            let inside = weights
+                .iter()
+                .zip(owned)
+                .all(|(&w, owns)| w > 0.0 || w == 0.0 && owns);
+            if inside {
+                f(y as usize * width as usize + x as usize, weights);
+            }
+        }
+    }
+}
+
+/// Twice the signed area of the triangle `abp`.
+fn edge(a: Point2<f32>, b: Point2<f32>, p: Point2<f32>) -> f32 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+/// Returns the range of pixels whose centers may be between `min` and `max`.
+fn pixel_range(min: f32, max: f32, size: u32) -> std::ops::Range<u32> {
+    let start = min.floor().clamp(0.0, size as f32) as u32;
+    let end = max.ceil().clamp(0.0, size as f32) as u32;
+    start..end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rasterize_shared_edges() {
+        // Two triangles covering a square, in opposite windings, with their
+        // shared diagonal passing through pixel centers.
+        let [a, b, c, d] = [[0.5, 0.5], [4.5, 0.5], [4.5, 4.5], [0.5, 4.5]].map(Point2::from);
+        let mut coverage = [0; 36];
+        for triangle in [[a, b, c], [a, d, c]] {
+            rasterize_triangle([6, 6], triangle, |i, _| coverage[i] += 1);
+        }
+        assert!(coverage.iter().all(|&n| n <= 1));
+        // Pixels on the diagonal are drawn once.
+        for i in 1..4 {
+            assert_eq!(coverage[i * 6 + i], 1);
+        }
+    }
+}
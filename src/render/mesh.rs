@@ -4,7 +4,7 @@ use cgmath::*;
 use itertools::Itertools;
 
 use super::RgbaVertex;
-use crate::preferences::Preferences;
+use crate::preferences::{FloorReflectionPreferences, Preferences};
 use crate::puzzle::*;
 use crate::util::IterCyclicPairsExt;
 
@@ -28,6 +28,18 @@ pub(super) fn make_puzzle_mesh(
 
     let face_colors = &prefs.colors.face_colors_list(puzzle.ty());
 
+    // In see-through mode, the far side of each sticker is visible too. The
+    // stickers of 3D puzzles are flat, so this only matters for 4D puzzles.
+    let draw_back_polygons =
+        prefs.opacity.see_through && puzzle.projection_type() == ProjectionType::_4D;
+
+    // Tinting stickers that are in place would give away information while
+    // blindfolded, and there is no progress to show once every sticker is in
+    // place.
+    let solved_stickers = (prefs.gfx.tint_solved_stickers && !prefs.colors.blindfold)
+        .then(|| puzzle.displayed().solved_stickers())
+        .filter(|solved| !solved.iter().all(|&s| s));
+
     for geom in sticker_geometries {
         let sticker_info = puzzle.info(geom.sticker);
 
@@ -37,19 +49,36 @@ pub(super) fn make_puzzle_mesh(
         let alpha = visual_state.opacity(prefs);
 
         // Determine sticker fill color.
-        let sticker_color = egui::Rgba::from(if prefs.colors.blindfold {
+        let face_color = egui::Rgba::from(if prefs.colors.blindfold {
             prefs.colors.blind_face
         } else {
             face_colors[puzzle.info(geom.sticker).color.0 as usize]
-        })
-        .multiply(alpha);
+        });
+        let mut face_color = prefs.gfx.adjust_sticker_color(face_color);
+        if solved_stickers
+            .as_ref()
+            .map_or(false, |solved| solved[geom.sticker.0 as usize])
+        {
+            face_color = prefs.gfx.tint_solved_sticker(face_color);
+        }
+        let sticker_color = face_color.multiply(alpha);
 
         // Determine outline appearance.
+        let is_sticker_selected = puzzle.selection().contains(&geom.sticker);
         let outline_color = visual_state
-            .outline_color(prefs, puzzle.selection().contains(&geom.sticker))
+            .outline_color(prefs, face_color, is_sticker_selected)
             .multiply(alpha);
         let outline_size = visual_state.outline_size(prefs);
 
+        // Generate back face vertices. Stickers are convex, so drawing them
+        // before the front faces of the same sticker keeps them in depth order.
+        if draw_back_polygons && !geom.back_polygons.is_empty() {
+            for polygon in &*geom.back_polygons {
+                generate_polygon_geometry(&mut verts, &mut indices, polygon, sticker_color, z);
+            }
+            z = f32::from_bits(z.to_bits() + 1);
+        }
+
         // Generate outline vertices.
         if outline_size > 0.0 {
             let mut outlines = vec![];
@@ -81,18 +110,7 @@ pub(super) fn make_puzzle_mesh(
 
         // Generate face vertices.
         for polygon in &*geom.front_polygons {
-            let base = verts.len() as u32;
-            verts.extend(polygon.verts.iter().map(|v| RgbaVertex {
-                pos: [v.x, v.y, z],
-                color: [
-                    sticker_color.r() * polygon.illumination,
-                    sticker_color.g() * polygon.illumination,
-                    sticker_color.b() * polygon.illumination,
-                    sticker_color.a(),
-                ],
-            }));
-            let n = polygon.verts.len() as u32;
-            indices.extend((2..n).flat_map(|i| [base, base + i - 1, base + i]));
+            generate_polygon_geometry(&mut verts, &mut indices, polygon, sticker_color, z);
         }
 
         // Increase the Z value very slightly. If this scares you, click this
@@ -103,6 +121,101 @@ pub(super) fn make_puzzle_mesh(
     (verts, indices)
 }
 
+/// Adds a reflection of the puzzle in a floor below it, drawn behind the
+/// puzzle. The floor is one puzzle radius below the center, so it stays in
+/// place as the puzzle rotates.
+///
+/// This is a screen-space effect: the projected mesh is mirrored across a
+/// horizontal line on screen, rather than reflecting the 3D puzzle across a
+/// floor plane before projecting it. So the reflection shows the same side of
+/// the puzzle as the puzzle itself, not its underside, and its perspective
+/// isn't quite right. That's close enough for presentation.
+pub(super) fn add_screen_space_floor_reflection(
+    verts: &mut Vec<RgbaVertex>,
+    indices: &mut Vec<u32>,
+    prefs: &FloorReflectionPreferences,
+) {
+    // Number of copies of the reflection along each axis, which are offset
+    // from each other to blur it.
+    const BLUR_STEPS: i32 = 3;
+
+    let radius = verts
+        .iter()
+        .map(|v| v.pos[0].hypot(v.pos[1]))
+        .fold(0.0, f32::max);
+    if radius <= 0.0 {
+        return;
+    }
+    let floor = -radius;
+
+    let blur_steps = if prefs.blur > 0.0 { BLUR_STEPS } else { 1 };
+    let copy_count = (blur_steps * blur_steps) as f32;
+    let blur_offset = |i: i32| (i - blur_steps / 2) as f32 * prefs.blur * radius;
+
+    // Every Z value in the puzzle mesh is at least 0.5, so the reflection goes
+    // below that. Each copy after the first is drawn in front of the previous
+    // one, so that they blend together.
+    let base_z = 0.5_f32.to_bits();
+    let z_span = verts
+        .iter()
+        .map(|v| v.pos[2].to_bits() - base_z + 1)
+        .max()
+        .unwrap_or(1);
+
+    let mut reflection_verts = vec![];
+    let mut reflection_indices = vec![];
+    for (copy, (i, j)) in (0..blur_steps).cartesian_product(0..blur_steps).enumerate() {
+        let (dx, dy) = (blur_offset(i), blur_offset(j));
+        let base = reflection_verts.len() as u32;
+        reflection_verts.extend(verts.iter().map(|v| {
+            let [x, y, z] = v.pos;
+            let y = 2.0 * floor - y;
+            // Fade out farther from the floor.
+            let fade = (1.0 - (floor - y) / radius).clamp(0.0, 1.0);
+            let [r, g, b, a] = v.color;
+            RgbaVertex {
+                pos: [
+                    x + dx,
+                    y + dy,
+                    f32::from_bits(
+                        0.25_f32.to_bits() + (z.to_bits() - base_z) + copy as u32 * z_span,
+                    ),
+                ],
+                color: [r, g, b, a * prefs.reflectivity * fade / copy_count],
+            }
+        }));
+        reflection_indices.extend(indices.iter().map(|&index| base + index));
+    }
+
+    // Draw the reflection first, so that the puzzle blends over it.
+    let offset = reflection_verts.len() as u32;
+    reflection_verts.append(verts);
+    reflection_indices.extend(indices.iter().map(|&index| offset + index));
+    *verts = reflection_verts;
+    *indices = reflection_indices;
+}
+
+fn generate_polygon_geometry(
+    verts_out: &mut Vec<RgbaVertex>,
+    indices_out: &mut Vec<u32>,
+    polygon: &Polygon,
+    color: egui::Rgba,
+    z: f32,
+) {
+    let base = verts_out.len() as u32;
+    verts_out.extend(polygon.verts.iter().map(|v| RgbaVertex {
+        pos: [v.x, v.y, z],
+        color: [
+            color.r() * polygon.illumination,
+            color.g() * polygon.illumination,
+            color.b() * polygon.illumination,
+            color.a(),
+        ],
+    }));
+    let n = polygon.verts.len() as u32;
+    indices_out.extend((2..n).flat_map(|i| [base, base + i - 1, base + i]));
+}
+
 fn generate_outline_geometry(
     verts_out: &mut Vec<RgbaVertex>,
     indices_out: &mut Vec<u32>,
@@ -4,17 +4,29 @@ use instant::Instant;
 use std::sync::Arc;
 
 mod cache;
+#[cfg(not(target_arch = "wasm32"))]
+mod layers;
 mod mesh;
 mod shaders;
 mod state;
 mod structs;
 
 use crate::app::App;
-use crate::puzzle::ProjectedStickerGeometry;
+use crate::preferences::{Preferences, ViewPreferences};
+use crate::puzzle::{traits::*, Face, ProjectedStickerGeometry, ProjectionType};
 use cache::{CachedDynamicBuffer, CachedUniformBuffer};
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use layers::render_layers;
 pub(crate) use state::GraphicsState;
 use structs::*;
 
+/// Scale of the last layer view, which is smaller than the main view because
+/// the sides of the last layer extend outward.
+const LAST_LAYER_VIEW_SCALE: f32 = 0.5;
+
+/// Speed of twist animations when slow motion is on.
+const SLOW_MOTION_SPEED: f32 = 0.25;
+
 #[derive(Debug, Clone, PartialEq)]
 struct PuzzleRenderParams {
     target_w: u32,
@@ -30,6 +42,7 @@ pub(crate) struct PuzzleRenderCache {
     last_render_time: Instant,
     last_params: Option<PuzzleRenderParams>,
     last_puzzle_geometry: Option<Arc<Vec<ProjectedStickerGeometry>>>,
+    last_up_face: Option<Face>,
 
     vertex_buffer: CachedDynamicBuffer,
     index_buffer: CachedDynamicBuffer,
@@ -47,6 +60,7 @@ impl Default for PuzzleRenderCache {
             last_render_time: Instant::now(),
             last_params: None,
             last_puzzle_geometry: None,
+            last_up_face: None,
 
             vertex_buffer: CachedDynamicBuffer::new::<RgbaVertex>(
                 Some("puzzle_vertex_buffer"),
@@ -103,7 +117,6 @@ pub(crate) fn draw_puzzle(
     mut force_redraw: bool,
 ) -> Option<wgpu::TextureView> {
     let (width, height) = app.puzzle_texture_size;
-    let size = cgmath::vec2(width as f32, height as f32);
 
     // Avoid divide-by-zero errors.
     if width == 0 || height == 0 {
@@ -116,17 +129,31 @@ pub(crate) fn draw_puzzle(
         app.prefs.gfx.msaa = false;
     }
 
+    let now = Instant::now();
+    let delta = app.animation_delta(now - app.render_cache.last_render_time);
+    app.render_cache.last_render_time = now;
+
+    let is_idle = app.is_idle();
     let puzzle = &mut app.puzzle;
     let prefs = &app.prefs;
     let view_prefs = puzzle.view_prefs(prefs);
     let cache = &mut app.render_cache;
 
-    let now = Instant::now();
-    let delta = now - cache.last_render_time;
-    cache.last_render_time = now;
-
-    // Animate puzzle geometry.
-    puzzle.update_geometry(delta, &prefs.interaction);
+    // Animate puzzle geometry. Slow motion only affects animations, so the
+    // timer is unaffected.
+    let geometry_delta = match app.slow_motion {
+        true => delta.mul_f32(SLOW_MOTION_SPEED),
+        false => delta,
+    };
+    puzzle.update_geometry(geometry_delta, &prefs.interaction);
+    if is_idle {
+        let idle_prefs = &prefs.interaction.idle_rotation;
+        let dt = delta.as_secs_f32();
+        puzzle.add_idle_rotation(
+            [idle_prefs.yaw_speed * dt, idle_prefs.pitch_speed * dt],
+            &view_prefs,
+        );
+    }
 
     // Invalidate cache if parameters changed.
     force_redraw |= cache.set_params_and_invalidate(PuzzleRenderParams {
@@ -139,12 +166,7 @@ pub(crate) fn draw_puzzle(
         align_v: view_prefs.align_v,
     });
 
-    // Calculate scale.
-    let scale = {
-        let min_dimen = f32::min(size.x, size.y);
-        let pixel_scale = min_dimen * view_prefs.scale;
-        cgmath::vec2(pixel_scale / size.x, pixel_scale / size.y)
-    };
+    let scale = main_view_scale(width, height, &view_prefs, prefs);
 
     // If the puzzle geometry has changed, force a redraw.
     let puzzle_geometry = puzzle.geometry(prefs);
@@ -183,7 +205,237 @@ pub(crate) fn draw_puzzle(
 
     // Generate the mesh.
     let (mut verts, mut indices) = mesh::make_puzzle_mesh(puzzle, prefs, &puzzle_geometry);
+    let floor_reflection = &prefs.gfx.floor_reflection;
+    if floor_reflection.enabled && puzzle.projection_type() == ProjectionType::_3D {
+        mesh::add_screen_space_floor_reflection(&mut verts, &mut indices, floor_reflection);
+    }
+
+    let uniform = BasicUniform {
+        scale: scale.into(),
+        align: [view_prefs.align_h, view_prefs.align_v],
+    };
+    Some(render_mesh(
+        gfx,
+        cache,
+        prefs,
+        (width, height),
+        uniform,
+        verts,
+        indices,
+    ))
+}
+
+/// Returns the factors to multiply puzzle geometry coordinates by to fit the
+/// main puzzle view, given its size in pixels.
+fn main_view_scale(
+    width: u32,
+    height: u32,
+    view_prefs: &ViewPreferences,
+    prefs: &Preferences,
+) -> cgmath::Vector2<f32> {
+    let size = cgmath::vec2(width as f32, height as f32);
+    let min_dimen = f32::min(size.x, size.y);
+    let pixel_scale = min_dimen * view_prefs.scale;
+    let [mirror_x, mirror_y] = prefs.info.mirror_view.main_view_scale();
+    cgmath::vec2(
+        pixel_scale / size.x * mirror_x,
+        pixel_scale / size.y * mirror_y,
+    )
+}
+
+/// Draws a top-down view of the last layer of the puzzle, if it is enabled.
+/// This reuses the geometry and decorations computed by `draw_puzzle()`, so
+/// it must be called afterward.
+pub(crate) fn draw_last_layer(
+    app: &mut App,
+    gfx: &mut GraphicsState,
+    mut force_redraw: bool,
+) -> Option<wgpu::TextureView> {
+    let ll_prefs = &app.prefs.info.last_layer_view;
+    if !ll_prefs.visible {
+        return None;
+    }
+
+    let (width, height) = app.last_layer_texture_size;
+    // Avoid divide-by-zero errors.
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    let puzzle = &mut app.puzzle;
+    let prefs = &app.prefs;
+    let ty = puzzle.ty();
+    let up_face = ty
+        .face_from_symbol(&ll_prefs.up_face)
+        .or_else(|| ty.up_face())?;
+    let cache = &mut app.last_layer_render_cache;
+
+    // Invalidate cache if parameters changed.
+    force_redraw |= cache.set_params_and_invalidate(PuzzleRenderParams {
+        target_w: width,
+        target_h: height,
+        sample_count: prefs.gfx.sample_count(),
+
+        scale: LAST_LAYER_VIEW_SCALE,
+        align_h: 0.0,
+        align_v: 0.0,
+    });
+    if cache.last_up_face != Some(up_face) {
+        cache.last_up_face = Some(up_face);
+        force_redraw = true;
+    }
+
+    if !force_redraw && cache.out_texture.is_some() {
+        return None; // No repaint needed.
+    }
+
+    let geometry = puzzle.last_layer_geometry(prefs, up_face)?;
+    let (verts, indices) = mesh::make_puzzle_mesh(puzzle, prefs, &geometry);
+
+    let min_dimen = f32::min(width as f32, height as f32);
+    let pixel_scale = min_dimen * LAST_LAYER_VIEW_SCALE;
+    let uniform = BasicUniform {
+        scale: [pixel_scale / width as f32, pixel_scale / height as f32],
+        align: [0.0, 0.0],
+    };
+    Some(render_mesh(
+        gfx,
+        cache,
+        prefs,
+        (width, height),
+        uniform,
+        verts,
+        indices,
+    ))
+}
+
+/// Draws a mirrored copy of the puzzle in the mirrored view window, if it is
+/// open and the main view isn't mirrored instead. This reuses the geometry and
+/// decorations computed by `draw_puzzle()`, so it must be called afterward.
+pub(crate) fn draw_mirror_view(
+    app: &mut App,
+    gfx: &mut GraphicsState,
+    mut force_redraw: bool,
+) -> Option<wgpu::TextureView> {
+    let mirror_prefs = &app.prefs.info.mirror_view;
+    if !mirror_prefs.visible || mirror_prefs.mirror_main_view {
+        return None;
+    }
+
+    let (width, height) = app.mirror_view_texture_size;
+    // Avoid divide-by-zero errors.
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    let puzzle = &mut app.puzzle;
+    let prefs = &app.prefs;
+    let scale = puzzle.view_prefs(prefs).scale;
+    let cache = &mut app.mirror_view_render_cache;
+
+    // Invalidate cache if parameters changed.
+    force_redraw |= cache.set_params_and_invalidate(PuzzleRenderParams {
+        target_w: width,
+        target_h: height,
+        sample_count: prefs.gfx.sample_count(),
+
+        scale,
+        align_h: 0.0,
+        align_v: 0.0,
+    });
+
+    if !force_redraw && cache.out_texture.is_some() {
+        return None; // No repaint needed.
+    }
 
+    let geometry = puzzle.geometry(prefs);
+    let (verts, indices) = mesh::make_puzzle_mesh(puzzle, prefs, &geometry);
+
+    // Stickers are drawn without culling, so flipping one axis mirrors the
+    // puzzle without having to change the polygon winding.
+    let [mirror_x, mirror_y] = mirror_prefs.plane.scale();
+    let min_dimen = f32::min(width as f32, height as f32);
+    let pixel_scale = min_dimen * scale;
+    let uniform = BasicUniform {
+        scale: [
+            pixel_scale / width as f32 * mirror_x,
+            pixel_scale / height as f32 * mirror_y,
+        ],
+        align: [0.0, 0.0],
+    };
+    Some(render_mesh(
+        gfx,
+        cache,
+        prefs,
+        (width, height),
+        uniform,
+        verts,
+        indices,
+    ))
+}
+
+/// Draws the puzzle in the scramble preview window, if there is one.
+pub(crate) fn draw_scramble_preview(
+    app: &mut App,
+    gfx: &mut GraphicsState,
+    mut force_redraw: bool,
+) -> Option<wgpu::TextureView> {
+    let (width, height) = app.scramble_preview_texture_size;
+    // Avoid divide-by-zero errors.
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    let puzzle = app.scramble_preview.as_mut()?;
+    let prefs = &app.prefs;
+    let scale = puzzle.view_prefs(prefs).scale;
+    let cache = &mut app.scramble_preview_render_cache;
+
+    // Invalidate cache if parameters changed.
+    force_redraw |= cache.set_params_and_invalidate(PuzzleRenderParams {
+        target_w: width,
+        target_h: height,
+        sample_count: prefs.gfx.sample_count(),
+
+        scale,
+        align_h: 0.0,
+        align_v: 0.0,
+    });
+
+    if !force_redraw && cache.out_texture.is_some() {
+        return None; // No repaint needed.
+    }
+
+    let geometry = puzzle.geometry(prefs);
+    let (verts, indices) = mesh::make_puzzle_mesh(puzzle, prefs, &geometry);
+
+    let min_dimen = f32::min(width as f32, height as f32);
+    let pixel_scale = min_dimen * scale;
+    let uniform = BasicUniform {
+        scale: [pixel_scale / width as f32, pixel_scale / height as f32],
+        align: [0.0, 0.0],
+    };
+    Some(render_mesh(
+        gfx,
+        cache,
+        prefs,
+        (width, height),
+        uniform,
+        verts,
+        indices,
+    ))
+}
+
+/// Renders a mesh to the cache's output texture and returns a view of it.
+fn render_mesh(
+    gfx: &mut GraphicsState,
+    cache: &mut PuzzleRenderCache,
+    prefs: &Preferences,
+    (width, height): (u32, u32),
+    uniform: BasicUniform,
+    mut verts: Vec<RgbaVertex>,
+    mut indices: Vec<u32>,
+) -> wgpu::TextureView {
     // Create "out" texture that will ultimately be returned.
     let (out_texture, out_texture_view) = cache.out_texture.get_or_insert_with(|| {
         gfx.create_texture(wgpu::TextureDescriptor {
@@ -337,10 +589,6 @@ pub(crate) fn draw_puzzle(
         render_pass.set_index_buffer(index_buffer, wgpu::IndexFormat::Uint32);
 
         // Populate and bind uniform.
-        let uniform = BasicUniform {
-            scale: scale.into(),
-            align: [view_prefs.align_h, view_prefs.align_v],
-        };
         cache.uniform_buffer.write(gfx, &uniform);
         render_pass.set_bind_group(0, cache.uniform_buffer.bind_group(gfx), &[]);
 
@@ -352,7 +600,7 @@ pub(crate) fn draw_puzzle(
 
     gfx.queue.submit(std::iter::once(encoder.finish()));
 
-    Some(out_texture.create_view(&wgpu::TextureViewDescriptor::default()))
+    out_texture.create_view(&wgpu::TextureViewDescriptor::default())
 }
 
 fn extent3d(width: u32, height: u32) -> wgpu::Extent3d {
@@ -1,5 +1,10 @@
+use anyhow::{anyhow, bail, Result};
+
 use super::shaders::Shaders;
 
+/// Sets of graphics backends to try, in order.
+const BACKENDS: &[wgpu::Backends] = &[wgpu::Backends::PRIMARY, wgpu::Backends::SECONDARY];
+
 /// Graphics state for the whole window.
 pub(crate) struct GraphicsState {
     pub(crate) size: winit::dpi::PhysicalSize<u32>,
@@ -7,6 +12,7 @@ pub(crate) struct GraphicsState {
     pub(crate) device: wgpu::Device,
     pub(crate) queue: wgpu::Queue,
     pub(crate) config: wgpu::SurfaceConfiguration,
+    supported_present_modes: Vec<wgpu::PresentMode>,
 
     pub(super) shaders: Shaders,
 
@@ -16,32 +22,40 @@ pub(crate) struct GraphicsState {
     pub(crate) dummy_texture: wgpu::Texture,
 }
 impl GraphicsState {
-    pub(crate) async fn new(window: &winit::window::Window) -> Self {
+    pub(crate) async fn new(window: &winit::window::Window) -> Result<Self> {
+        let mut errors = vec![];
+        for &backends in BACKENDS {
+            match Self::new_with_backends(window, backends).await {
+                Ok(ret) => return Ok(ret),
+                Err(e) => {
+                    log::warn!("Unable to initialize graphics with {backends:?}: {e}");
+                    errors.push(format!("{backends:?}: {e}"));
+                }
+            }
+        }
+        bail!(
+            "no compatible graphics adapter found\n\n{}",
+            errors.join("\n")
+        )
+    }
+
+    async fn new_with_backends(
+        window: &winit::window::Window,
+        backends: wgpu::Backends,
+    ) -> Result<Self> {
         let size = window.inner_size();
 
         // Create surface.
-        let instance = wgpu::Instance::new(wgpu::Backends::all());
+        let instance = wgpu::Instance::new(backends);
         let surface = unsafe { instance.create_surface(&window) };
 
         // Request adapter.
-        let adapter = request_adapter(&instance, &surface).await;
+        let adapter = request_adapter(&instance, &surface)
+            .await
+            .ok_or_else(|| anyhow!("unable to request graphics adapter"))?;
 
         // Request device.
-        let (device, queue) = adapter
-            .request_device(
-                &wgpu::DeviceDescriptor {
-                    features: wgpu::Features::default(),
-                    limits: if cfg!(target_arch = "wasm32") {
-                        wgpu::Limits::downlevel_webgl2_defaults()
-                    } else {
-                        wgpu::Limits::default()
-                    },
-                    label: None,
-                },
-                None,
-            )
-            .await
-            .unwrap();
+        let (device, queue) = request_device(&adapter).await?;
 
         // Configure surface.
         let config = wgpu::SurfaceConfiguration {
@@ -49,12 +63,13 @@ impl GraphicsState {
             format: *surface
                 .get_supported_formats(&adapter)
                 .get(0)
-                .expect("unsupported graphics adapter"),
+                .ok_or_else(|| anyhow!("unsupported graphics adapter"))?,
             width: size.width,
             height: size.height,
-            present_mode: wgpu::PresentMode::AutoNoVsync, // VSync on
+            present_mode: wgpu::PresentMode::Fifo, // VSync on
             alpha_mode: wgpu::CompositeAlphaMode::Auto,
         };
+        let supported_present_modes = surface.get_supported_present_modes(&adapter);
         surface.configure(&device, &config);
 
         let shaders = Shaders::new();
@@ -71,19 +86,20 @@ impl GraphicsState {
             usage: wgpu::TextureUsages::TEXTURE_BINDING,
         });
 
-        Self {
+        Ok(Self {
             size,
             surface,
             device,
             queue,
             config,
+            supported_present_modes,
 
             shaders,
 
             scale_factor,
 
             dummy_texture,
-        }
+        })
     }
 
     pub(crate) fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
@@ -95,6 +111,21 @@ impl GraphicsState {
         }
     }
 
+    /// Reconfigures the surface to use a new present mode, falling back to
+    /// `Fifo` if the mode is unsupported.
+    pub(crate) fn set_present_mode(&mut self, present_mode: wgpu::PresentMode) {
+        let present_mode = if self.supported_present_modes.contains(&present_mode) {
+            present_mode
+        } else {
+            wgpu::PresentMode::Fifo // always supported
+        };
+        if self.config.present_mode != present_mode {
+            log::info!("Using present mode {present_mode:?}");
+            self.config.present_mode = present_mode;
+            self.surface.configure(&self.device, &self.config);
+        }
+    }
+
     pub(crate) fn set_scale_factor(&mut self, new_scale_factor: f32) {
         self.scale_factor = new_scale_factor;
     }
@@ -180,7 +211,10 @@ impl GraphicsState {
     }
 }
 
-async fn request_adapter(instance: &wgpu::Instance, surface: &wgpu::Surface) -> wgpu::Adapter {
+async fn request_adapter(
+    instance: &wgpu::Instance,
+    surface: &wgpu::Surface,
+) -> Option<wgpu::Adapter> {
     let mut opts = wgpu::RequestAdapterOptions {
         power_preference: wgpu::PowerPreference::HighPerformance,
         compatible_surface: Some(surface),
@@ -188,12 +222,38 @@ async fn request_adapter(instance: &wgpu::Instance, surface: &wgpu::Surface) ->
     };
 
     if let Some(adapter) = instance.request_adapter(&opts).await {
-        return adapter;
+        return Some(adapter);
     }
     opts.force_fallback_adapter = true;
-    if let Some(adapter) = instance.request_adapter(&opts).await {
-        return adapter;
-    }
+    instance.request_adapter(&opts).await
+}
 
-    panic!("unable to request graphics adapter")
+async fn request_device(adapter: &wgpu::Adapter) -> Result<(wgpu::Device, wgpu::Queue)> {
+    let preferred_limits = if cfg!(target_arch = "wasm32") {
+        wgpu::Limits::downlevel_webgl2_defaults()
+    } else {
+        wgpu::Limits::default()
+    };
+
+    let device_descriptor = |limits| wgpu::DeviceDescriptor {
+        features: wgpu::Features::default(),
+        limits,
+        label: None,
+    };
+
+    match adapter
+        .request_device(&device_descriptor(preferred_limits), None)
+        .await
+    {
+        Ok(ret) => Ok(ret),
+        Err(e) => {
+            // Older hardware may not support the default limits, so fall back
+            // to the lowest limits we can work with.
+            log::warn!("Unable to request device with default limits: {e}");
+            let limits = wgpu::Limits::downlevel_webgl2_defaults();
+            Ok(adapter
+                .request_device(&device_descriptor(limits), None)
+                .await?)
+        }
+    }
 }
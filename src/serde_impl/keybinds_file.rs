@@ -0,0 +1,119 @@
+//! Standalone files for sharing puzzle keybind sets, separate from the rest of
+//! the preferences.
+
+use serde::{Deserialize, Serialize};
+
+use crate::commands::PuzzleCommand;
+use crate::preferences::{Keybind, KeybindSet, Preset};
+use crate::puzzle::{traits::*, PuzzleTypeEnum};
+
+type PuzzleKeybindSets = Vec<Preset<KeybindSet<PuzzleCommand>>>;
+
+#[derive(Serialize, Debug)]
+struct KeybindsFileOut<'a> {
+    puzzle_family: &'a str,
+    sets: &'a [Preset<KeybindSet<PuzzleCommand>>],
+}
+
+/// Commands are deserialized separately so that one unknown command doesn't
+/// prevent the rest of the file from loading.
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct KeybindsFileIn {
+    puzzle_family: String,
+    sets: Vec<Preset<KeybindSet<serde_yaml::Value>>>,
+}
+
+/// Serializes the keybind sets for a puzzle family.
+pub(crate) fn serialize(
+    ty: PuzzleTypeEnum,
+    sets: &[Preset<KeybindSet<PuzzleCommand>>],
+) -> Result<String, serde_yaml::Error> {
+    serde_yaml::to_string(&KeybindsFileOut {
+        puzzle_family: ty.family_internal_name(),
+        sets,
+    })
+}
+
+/// Deserializes keybind sets for the family of `ty`, and returns them along
+/// with a warning for each keybind that was skipped because its command is
+/// unknown, such as from a newer version.
+pub(crate) fn deserialize(
+    s: &str,
+    ty: PuzzleTypeEnum,
+) -> Result<(PuzzleKeybindSets, Vec<String>), String> {
+    let file: KeybindsFileIn = serde_yaml::from_str(s).map_err(|e| e.to_string())?;
+    if file.puzzle_family != ty.family_internal_name() {
+        return Err(format!(
+            "These keybinds are for {:?} puzzles, not {}",
+            file.puzzle_family,
+            ty.family_display_name(),
+        ));
+    }
+
+    let mut warnings = vec![];
+    let sets = file
+        .sets
+        .into_iter()
+        .map(|set| Preset {
+            value: KeybindSet {
+                includes: set.value.includes,
+                keybinds: set
+                    .value
+                    .keybinds
+                    .into_iter()
+                    .filter_map(|bind| match serde_yaml::from_value(bind.command) {
+                        Ok(PuzzleCommand::None) | Err(_) => {
+                            warnings.push(format!(
+                                "Skipped keybind for {} in {:?} with unknown command",
+                                bind.key, set.preset_name,
+                            ));
+                            None
+                        }
+                        Ok(command) => Some(Keybind {
+                            key: bind.key,
+                            command,
+                        }),
+                    })
+                    .collect(),
+            },
+            preset_name: set.preset_name,
+        })
+        .collect();
+    Ok((sets, warnings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skip_unknown_commands() {
+        let ty = PuzzleTypeEnum::Rubiks3D { layer_count: 3 };
+        let s = r#"
+puzzle_family: Rubiks3D
+sets:
+  - preset_name: Test
+    keybinds:
+      - sc: KeyA
+        command:
+          recenter:
+            axis: R
+      - sc: KeyB
+        command: some_new_command
+      - sc: KeyC
+        command:
+          some_new_command_with_fields:
+            x: 1
+"#;
+        let (sets, warnings) = deserialize(s, ty).unwrap();
+        assert_eq!(sets.len(), 1);
+        assert_eq!(sets[0].value.keybinds.len(), 1);
+        assert_eq!(warnings.len(), 2);
+
+        let round_trip = deserialize(&serialize(ty, &sets).unwrap(), ty).unwrap();
+        assert_eq!(round_trip, (sets, vec![]));
+
+        assert!(deserialize(s, PuzzleTypeEnum::Ivy).is_err());
+    }
+}
@@ -5,6 +5,8 @@ use serde::{Deserialize, Serialize};
 
 pub(crate) mod hex_bitvec;
 pub(crate) mod hex_color;
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod keybinds_file;
 
 /// Taken from docs here:
 /// https://docs.rs/keycode/0.3.0/keycode/enum.KeyMappingCode.html
@@ -0,0 +1,783 @@
+//! Solve statistics and personal bests.
+
+use instant::{Duration, Instant};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ops::Range;
+use strum::IntoEnumIterator;
+
+use crate::puzzle::{PuzzleTypeEnum, TwistMetric};
+
+/// Maximum number of recent personal bests to remember for each puzzle.
+const PB_HISTORY_LEN: usize = 10;
+
+/// Penalty applied to a solve.
+#[derive(
+    Serialize, Deserialize, Debug, Display, Default, EnumIter, Copy, Clone, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Penalty {
+    #[default]
+    #[strum(serialize = "OK")]
+    None,
+    #[strum(serialize = "+2")]
+    PlusTwo,
+    #[strum(serialize = "DNF")]
+    Dnf,
+}
+impl Penalty {
+    fn is_none(&self) -> bool {
+        *self == Penalty::None
+    }
+
+    /// Returns the penalty for going over the inspection time, given the
+    /// inspection time left in seconds. Going up to 2 seconds over is a +2
+    /// penalty, and going any further over is a DNF.
+    pub fn from_inspection_time_left(remaining: f32) -> Self {
+        if remaining >= 0.0 {
+            Penalty::None
+        } else if remaining >= -2.0 {
+            Penalty::PlusTwo
+        } else {
+            Penalty::Dnf
+        }
+    }
+}
+
+/// Result of a solve or an average, after penalties are applied.
+///
+/// DNF compares greater than any time, so the best result is the minimum.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SolveResult {
+    Time(Duration),
+    Dnf,
+}
+impl SolveResult {
+    pub fn millis(self) -> Option<u64> {
+        match self {
+            SolveResult::Time(d) => Some(d.as_millis() as u64),
+            SolveResult::Dnf => None,
+        }
+    }
+}
+
+/// Single timed solve.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Solve {
+    pub puzzle: PuzzleTypeEnum,
+    /// Time in milliseconds, not including any penalty.
+    pub millis: u64,
+    #[serde(default, skip_serializing_if = "Penalty::is_none")]
+    pub penalty: Penalty,
+    /// Time in milliseconds from the start of the timer to the first twist.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reaction_millis: Option<u64>,
+    /// Symbol of the face whose color was on the bottom, if the color scheme
+    /// was rotated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub down_color: Option<String>,
+    /// Number of twists in the solution, in each metric.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub twist_count: BTreeMap<TwistMetric, usize>,
+    /// Scramble in the puzzle's standard notation.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub scramble: String,
+}
+impl Solve {
+    pub fn result(&self) -> SolveResult {
+        match self.penalty {
+            Penalty::None => SolveResult::Time(Duration::from_millis(self.millis)),
+            Penalty::PlusTwo => SolveResult::Time(Duration::from_millis(self.millis + 2000)),
+            Penalty::Dnf => SolveResult::Dnf,
+        }
+    }
+}
+
+/// Computes a WCA-style average: the best and worst results are discarded and
+/// the rest are averaged. If more than one result is a DNF, then the average is
+/// a DNF. Returns `None` if there are fewer than three results.
+pub fn average(results: &[SolveResult]) -> Option<SolveResult> {
+    if results.len() < 3 {
+        return None;
+    }
+    let sorted = results.iter().copied().sorted().collect_vec();
+    let counted = &sorted[1..sorted.len() - 1];
+    let mut total = Duration::ZERO;
+    for result in counted {
+        match result {
+            SolveResult::Time(d) => total += *d,
+            SolveResult::Dnf => return Some(SolveResult::Dnf),
+        }
+    }
+    Some(SolveResult::Time(total / counted.len() as u32))
+}
+
+/// Solves completed since the application was opened or the session was
+/// cleared.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct Session {
+    pub solves: Vec<Solve>,
+}
+impl Session {
+    /// Returns the solves of a puzzle type, along with their indices in the
+    /// session.
+    pub fn solves_of(
+        &self,
+        ty: PuzzleTypeEnum,
+    ) -> impl '_ + DoubleEndedIterator<Item = (usize, &Solve)> {
+        self.solves
+            .iter()
+            .enumerate()
+            .filter(move |(_, solve)| solve.puzzle == ty)
+    }
+
+    /// Returns the most recent result of the given kind, or `None` if there
+    /// are not enough solves.
+    pub fn latest(&self, ty: PuzzleTypeEnum, kind: PbKind) -> Option<SolveResult> {
+        let n = kind.solve_count();
+        let mut results = self
+            .solves_of(ty)
+            .rev()
+            .take(n)
+            .map(|(_, s)| s.result())
+            .collect_vec();
+        if results.len() < n {
+            return None;
+        }
+        results.reverse();
+        match kind {
+            PbKind::Single => results.first().copied(),
+            PbKind::Ao5 | PbKind::Ao12 => average(&results),
+        }
+    }
+
+    /// Returns the best result of the given kind in this session.
+    pub fn best(&self, ty: PuzzleTypeEnum, kind: PbKind) -> Option<SolveResult> {
+        let results = self.solves_of(ty).map(|(_, s)| s.result()).collect_vec();
+        match kind {
+            PbKind::Single => results.iter().copied().min(),
+            PbKind::Ao5 | PbKind::Ao12 => results
+                .windows(kind.solve_count())
+                .filter_map(average)
+                .min(),
+        }
+    }
+
+    /// Returns the distribution of solve times of a puzzle type in this
+    /// session.
+    pub fn histogram(&self, ty: PuzzleTypeEnum, bin_width: Duration) -> Histogram {
+        Histogram::new(self.solves_of(ty).map(|(_, s)| s.result()), bin_width)
+    }
+
+    /// Returns the results of the solves of a puzzle type that started from
+    /// `scramble`, in the order they were solved.
+    pub fn attempts_on(&self, ty: PuzzleTypeEnum, scramble: &str) -> Vec<SolveResult> {
+        self.solves_of(ty)
+            .filter(|(_, s)| !s.scramble.is_empty() && s.scramble == scramble)
+            .map(|(_, s)| s.result())
+            .collect()
+    }
+
+    /// Returns the number of consecutive non-DNF solves at the end of the
+    /// session.
+    pub fn current_streak(&self, ty: PuzzleTypeEnum) -> usize {
+        self.solves_of(ty)
+            .rev()
+            .take_while(|(_, s)| s.penalty != Penalty::Dnf)
+            .count()
+    }
+
+    /// Returns the mean reaction time of the solves of a puzzle type that
+    /// have one, or `None` if there are none.
+    pub fn mean_reaction_time(&self, ty: PuzzleTypeEnum) -> Option<Duration> {
+        let reaction_times = self
+            .solves_of(ty)
+            .filter_map(|(_, s)| s.reaction_millis)
+            .collect_vec();
+        let total: u64 = reaction_times.iter().sum();
+        (!reaction_times.is_empty())
+            .then(|| Duration::from_millis(total / reaction_times.len() as u64))
+    }
+}
+
+/// Distribution of solve times.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Histogram {
+    /// Width of each bin.
+    pub bin_width: Duration,
+    /// Start of the first bin.
+    pub start: Duration,
+    /// Number of results in each bin.
+    pub bins: Vec<usize>,
+    /// Number of DNF results, which are not in any bin.
+    pub dnf_count: usize,
+}
+impl Histogram {
+    /// Sorts results into bins of the given width, aligned to multiples of the
+    /// width. Empty bins at either end are omitted.
+    pub fn new(results: impl IntoIterator<Item = SolveResult>, bin_width: Duration) -> Self {
+        let width_millis = (bin_width.as_millis() as u64).max(1);
+        let mut dnf_count = 0;
+        let bin_indices = results
+            .into_iter()
+            .filter_map(|result| match result.millis() {
+                Some(millis) => Some(millis / width_millis),
+                None => {
+                    dnf_count += 1;
+                    None
+                }
+            })
+            .collect_vec();
+
+        let first = bin_indices.iter().copied().min().unwrap_or(0);
+        let mut bins = vec![];
+        for i in bin_indices {
+            let i = (i - first) as usize;
+            if bins.len() <= i {
+                bins.resize(i + 1, 0);
+            }
+            bins[i] += 1;
+        }
+
+        Self {
+            bin_width: Duration::from_millis(width_millis),
+            start: Duration::from_millis(first * width_millis),
+            bins,
+            dnf_count,
+        }
+    }
+
+    /// Returns the range of times in a bin.
+    pub fn bin_range(&self, i: usize) -> Range<Duration> {
+        let start = self.start + self.bin_width * i as u32;
+        start..start + self.bin_width
+    }
+}
+
+/// Kind of personal best.
+#[derive(Serialize, Deserialize, Debug, Display, EnumIter, Copy, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum PbKind {
+    #[strum(serialize = "single")]
+    Single,
+    #[strum(serialize = "ao5")]
+    Ao5,
+    #[strum(serialize = "ao12")]
+    Ao12,
+}
+impl PbKind {
+    /// Returns the number of solves that make up a result of this kind.
+    pub fn solve_count(self) -> usize {
+        match self {
+            PbKind::Single => 1,
+            PbKind::Ao5 => 5,
+            PbKind::Ao12 => 12,
+        }
+    }
+}
+
+/// Streak of consecutive solves of one puzzle under a target time, for
+/// working toward a streak goal.
+#[derive(Debug, Default, Clone)]
+pub struct GoalStreak {
+    /// Puzzle type of the solves in the streak.
+    pub puzzle: Option<PuzzleTypeEnum>,
+    /// Number of consecutive solves under the target time.
+    pub count: usize,
+    /// When the most recent solve was recorded.
+    last_solve_time: Option<Instant>,
+    /// Index in the current session of the most recent solve recorded, and
+    /// the streak before it.
+    last_solve: Option<(usize, usize)>,
+}
+impl GoalStreak {
+    /// Extends the streak with a solve if it is under `target_time`, or resets
+    /// the streak otherwise. A solve of a different puzzle starts a new
+    /// streak. Returns whether the streak just reached `goal` solves.
+    pub fn record(
+        &mut self,
+        session: &Session,
+        solve_index: usize,
+        target_time: Duration,
+        goal: usize,
+    ) -> bool {
+        let Some(solve) = session.solves.get(solve_index) else {
+            return false;
+        };
+        self.last_solve = Some((solve_index, self.count));
+        if self.puzzle != Some(solve.puzzle) {
+            self.puzzle = Some(solve.puzzle);
+            self.count = 0;
+        }
+        match solve.result() {
+            SolveResult::Time(time) if time < target_time => self.count += 1,
+            _ => self.count = 0,
+        }
+        self.last_solve_time = Some(Instant::now());
+        goal > 0 && self.count == goal
+    }
+
+    /// Undoes the effect of a solve on the streak, so that it can be recorded
+    /// again after changing its penalty. Returns `false` if the solve was not
+    /// the most recent one recorded.
+    pub fn revert_solve(&mut self, solve_index: usize) -> bool {
+        match self.last_solve.take() {
+            Some((i, count_before)) if i == solve_index => {
+                self.count = count_before;
+                true
+            }
+            other => {
+                self.last_solve = other;
+                false
+            }
+        }
+    }
+
+    /// Resets the streak if more than `max_break` passed between the most
+    /// recent solve and `now`. Returns whether the streak was reset.
+    pub fn check_break(&mut self, max_break: Duration, now: Instant) -> bool {
+        let is_break = self.count > 0
+            && self
+                .last_solve_time
+                .map_or(false, |t| now.saturating_duration_since(t) > max_break);
+        if is_break {
+            self.reset();
+        }
+        is_break
+    }
+
+    /// Resets the streak to zero.
+    pub fn reset(&mut self) {
+        self.count = 0;
+        self.last_solve = None;
+    }
+}
+
+/// Time attack, where as many scrambles as possible are solved before a
+/// countdown runs out.
+#[derive(Debug, Clone)]
+pub struct TimeAttack {
+    /// Length of the countdown, in minutes.
+    pub minutes: f32,
+    /// When the time attack in progress started.
+    start: Option<Instant>,
+    /// Solves completed during the time attack in progress or the most recent
+    /// one.
+    pub session: Session,
+}
+impl Default for TimeAttack {
+    fn default() -> Self {
+        Self {
+            minutes: 5.0,
+            start: None,
+            session: Session::default(),
+        }
+    }
+}
+impl TimeAttack {
+    /// Starts a new time attack, forgetting the solves of the previous one.
+    pub fn start(&mut self, now: Instant) {
+        self.start = Some(now);
+        self.session = Session::default();
+    }
+    /// Stops the time attack in progress, keeping its solves.
+    pub fn stop(&mut self) {
+        self.start = None;
+    }
+    pub fn is_running(&self) -> bool {
+        self.start.is_some()
+    }
+
+    /// Returns the time left in the time attack in progress, or `None` if
+    /// there is none.
+    pub fn remaining(&self, now: Instant) -> Option<Duration> {
+        let length = Duration::from_secs_f32(self.minutes.max(0.0) * 60.0);
+        Some(length.saturating_sub(now.saturating_duration_since(self.start?)))
+    }
+    /// Returns whether the time attack in progress has run out of time.
+    pub fn is_over(&self, now: Instant) -> bool {
+        self.remaining(now) == Some(Duration::ZERO)
+    }
+
+    /// Adds a solve to the time attack in progress. Returns `false` if there
+    /// is no time attack in progress.
+    pub fn record_solve(&mut self, solve: Solve) -> bool {
+        if self.is_running() {
+            self.session.solves.push(solve);
+        }
+        self.is_running()
+    }
+
+    /// Returns the number of solves completed, not counting DNFs.
+    pub fn solve_count(&self) -> usize {
+        self.session
+            .solves
+            .iter()
+            .filter(|solve| solve.result() != SolveResult::Dnf)
+            .count()
+    }
+    /// Returns the mean of the solves completed, not counting DNFs, or `None`
+    /// if there are none.
+    pub fn mean(&self) -> Option<Duration> {
+        let times = self
+            .session
+            .solves
+            .iter()
+            .filter_map(|solve| solve.result().millis())
+            .collect_vec();
+        let total: u64 = times.iter().sum();
+        (!times.is_empty()).then(|| Duration::from_millis(total / times.len() as u64))
+    }
+}
+
+/// All-time personal bests for a single puzzle. Times are in milliseconds and
+/// include penalties.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct PersonalBests {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub single: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ao5: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ao12: Option<u64>,
+    /// Longest run of consecutive solves without a DNF.
+    pub longest_streak: usize,
+
+    /// Recent personal bests, from oldest to newest.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<PbRecord>,
+    /// Longest streaks set by solves in the current session, from oldest to
+    /// newest, so that they can be reverted like other personal bests.
+    #[serde(skip)]
+    pub streak_history: Vec<StreakRecord>,
+}
+impl PersonalBests {
+    pub fn get(&self, kind: PbKind) -> Option<u64> {
+        match kind {
+            PbKind::Single => self.single,
+            PbKind::Ao5 => self.ao5,
+            PbKind::Ao12 => self.ao12,
+        }
+    }
+    fn get_mut(&mut self, kind: PbKind) -> &mut Option<u64> {
+        match kind {
+            PbKind::Single => &mut self.single,
+            PbKind::Ao5 => &mut self.ao5,
+            PbKind::Ao12 => &mut self.ao12,
+        }
+    }
+
+    /// Updates the personal bests after the most recent solve of `ty` in the
+    /// session and returns the kinds of personal bests that were beaten.
+    pub fn update(&mut self, session: &Session, ty: PuzzleTypeEnum) -> Vec<PbKind> {
+        let Some((solve_index, _)) = session.solves_of(ty).last() else {
+            return vec![];
+        };
+
+        let mut new_pbs = vec![];
+        for kind in PbKind::iter() {
+            // A DNF never counts as a personal best.
+            let Some(millis) = session.latest(ty, kind).and_then(SolveResult::millis) else {
+                continue;
+            };
+            let previous = self.get(kind);
+            if previous.map_or(true, |old| millis < old) {
+                *self.get_mut(kind) = Some(millis);
+                self.history.push(PbRecord {
+                    kind,
+                    millis,
+                    previous,
+                    session_solve: Some(solve_index),
+                });
+                new_pbs.push(kind);
+            }
+        }
+
+        let streak = session.current_streak(ty);
+        if streak > self.longest_streak {
+            self.streak_history.push(StreakRecord {
+                previous: self.longest_streak,
+                session_solve: solve_index,
+            });
+            self.longest_streak = streak;
+        }
+
+        let excess = self.history.len().saturating_sub(PB_HISTORY_LEN);
+        self.history.drain(..excess);
+
+        new_pbs
+    }
+
+    /// Reverts any personal bests set by a solve in the current session, so
+    /// that it can be re-evaluated after changing its penalty.
+    pub fn revert_solve(&mut self, solve_index: usize) {
+        while self
+            .history
+            .last()
+            .map_or(false, |record| record.session_solve == Some(solve_index))
+        {
+            let record = self.history.pop().unwrap();
+            *self.get_mut(record.kind) = record.previous;
+        }
+        if self
+            .streak_history
+            .last()
+            .map_or(false, |record| record.session_solve == solve_index)
+        {
+            let record = self.streak_history.pop().unwrap();
+            self.longest_streak = record.previous;
+        }
+    }
+
+    /// Detaches the history from the current session so that clearing the
+    /// session can't revert old personal bests.
+    pub fn forget_session(&mut self) {
+        for record in &mut self.history {
+            record.session_solve = None;
+        }
+        self.streak_history.clear();
+    }
+}
+
+/// Record of a personal best being set.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PbRecord {
+    pub kind: PbKind,
+    pub millis: u64,
+    /// Personal best that this one replaced.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<u64>,
+    /// Index of the solve in the current session that set this record.
+    #[serde(skip)]
+    pub session_solve: Option<usize>,
+}
+
+/// Record of a longest streak being set by a solve in the current session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreakRecord {
+    /// Longest streak that this one replaced.
+    pub previous: usize,
+    /// Index of the solve in the current session that set this record.
+    pub session_solve: usize,
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Returns a 3x3x3 solve with no other details.
+    pub(crate) fn solve(millis: u64, penalty: Penalty) -> Solve {
+        Solve {
+            puzzle: PuzzleTypeEnum::Rubiks3D { layer_count: 3 },
+            millis,
+            penalty,
+            reaction_millis: None,
+            down_color: None,
+            twist_count: BTreeMap::new(),
+            scramble: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_wca_average() {
+        let t = |millis| SolveResult::Time(Duration::from_millis(millis));
+        let dnf = SolveResult::Dnf;
+
+        assert_eq!(average(&[t(1000), t(2000)]), None);
+        assert_eq!(
+            average(&[t(1000), t(2000), t(3000), t(4000), t(9000)]),
+            Some(t(3000))
+        );
+        assert_eq!(
+            average(&[t(1000), t(2000), t(3000), t(4000), dnf]),
+            Some(t(3000))
+        );
+        assert_eq!(average(&[t(1000), dnf, t(3000), t(4000), dnf]), Some(dnf));
+    }
+
+    #[test]
+    fn test_inspection_penalty() {
+        assert_eq!(Penalty::from_inspection_time_left(15.0), Penalty::None);
+        assert_eq!(Penalty::from_inspection_time_left(0.0), Penalty::None);
+        assert_eq!(Penalty::from_inspection_time_left(-0.5), Penalty::PlusTwo);
+        assert_eq!(Penalty::from_inspection_time_left(-2.0), Penalty::PlusTwo);
+        assert_eq!(Penalty::from_inspection_time_left(-2.5), Penalty::Dnf);
+    }
+
+    #[test]
+    fn test_mean_reaction_time() {
+        let ty = PuzzleTypeEnum::Rubiks3D { layer_count: 3 };
+        let with_reaction = |reaction_millis| Solve {
+            reaction_millis,
+            ..solve(10_000, Penalty::None)
+        };
+
+        let mut session = Session::default();
+        assert_eq!(session.mean_reaction_time(ty), None);
+        session.solves = vec![
+            with_reaction(Some(300)),
+            with_reaction(None),
+            with_reaction(Some(500)),
+        ];
+        assert_eq!(
+            session.mean_reaction_time(ty),
+            Some(Duration::from_millis(400))
+        );
+        let other_ty = PuzzleTypeEnum::Rubiks3D { layer_count: 2 };
+        assert_eq!(session.mean_reaction_time(other_ty), None);
+    }
+
+    #[test]
+    fn test_goal_streak() {
+        let target = Duration::from_secs(20);
+
+        let mut session = Session::default();
+        let mut streak = GoalStreak::default();
+        let mut add = |streak: &mut GoalStreak, solve: Solve| {
+            session.solves.push(solve);
+            streak.record(&session, session.solves.len() - 1, target, 3)
+        };
+
+        assert!(!add(&mut streak, solve(15_000, Penalty::None)));
+        assert!(!add(&mut streak, solve(19_999, Penalty::None)));
+        assert!(add(&mut streak, solve(10_000, Penalty::None)));
+        assert_eq!(streak.count, 3);
+
+        // Penalties count toward the time.
+        assert!(!add(&mut streak, solve(19_000, Penalty::PlusTwo)));
+        assert_eq!(streak.count, 0);
+
+        // Changing the penalty of the last solve re-evaluates it.
+        assert!(streak.revert_solve(3));
+        assert_eq!(streak.count, 3);
+        assert!(!streak.revert_solve(3));
+
+        // Solves of another puzzle start a new streak.
+        let other_ty = PuzzleTypeEnum::Rubiks3D { layer_count: 2 };
+        let other_solve = Solve {
+            puzzle: other_ty,
+            ..solve(5_000, Penalty::None)
+        };
+        add(&mut streak, other_solve);
+        assert_eq!(streak.count, 1);
+        assert_eq!(streak.puzzle, Some(other_ty));
+
+        let now = Instant::now();
+        assert!(!streak.check_break(Duration::from_secs(60), now));
+        assert!(streak.check_break(Duration::from_secs(60), now + Duration::from_secs(61)));
+        assert_eq!(streak.count, 0);
+    }
+
+    #[test]
+    fn test_revert_longest_streak() {
+        let ty = PuzzleTypeEnum::Rubiks3D { layer_count: 3 };
+
+        let mut session = Session::default();
+        let mut pbs = PersonalBests::default();
+        for millis in [10_000, 12_000, 11_000] {
+            session.solves.push(solve(millis, Penalty::None));
+            pbs.update(&session, ty);
+        }
+        assert_eq!(pbs.longest_streak, 3);
+
+        // Changing the last solve to a DNF ends the streak before it.
+        session.solves[2].penalty = Penalty::Dnf;
+        pbs.revert_solve(2);
+        pbs.update(&session, ty);
+        assert_eq!(pbs.longest_streak, 2);
+
+        // Changing it back extends the streak again.
+        session.solves[2].penalty = Penalty::None;
+        pbs.revert_solve(2);
+        pbs.update(&session, ty);
+        assert_eq!(pbs.longest_streak, 3);
+    }
+
+    #[test]
+    fn test_attempts_on_scramble() {
+        let ty = PuzzleTypeEnum::Rubiks3D { layer_count: 3 };
+        let with_scramble = |millis, penalty, scramble: &str| Solve {
+            scramble: scramble.to_string(),
+            ..solve(millis, penalty)
+        };
+
+        let session = Session {
+            solves: vec![
+                with_scramble(10_000, Penalty::None, "R U"),
+                with_scramble(9_000, Penalty::None, "F"),
+                with_scramble(8_000, Penalty::PlusTwo, "R U"),
+                with_scramble(7_000, Penalty::Dnf, "R U"),
+                with_scramble(6_000, Penalty::None, ""),
+            ],
+        };
+        assert_eq!(
+            session.attempts_on(ty, "R U"),
+            vec![
+                SolveResult::Time(Duration::from_millis(10_000)),
+                SolveResult::Time(Duration::from_millis(10_000)),
+                SolveResult::Dnf,
+            ],
+        );
+        // Solves without a scramble aren't attempts on any scramble.
+        assert!(session.attempts_on(ty, "").is_empty());
+        let other_ty = PuzzleTypeEnum::Rubiks3D { layer_count: 2 };
+        assert!(session.attempts_on(other_ty, "R U").is_empty());
+    }
+
+    #[test]
+    fn test_time_attack() {
+        let start = Instant::now();
+        let after = |secs| start + Duration::from_secs(secs);
+
+        let mut time_attack = TimeAttack {
+            minutes: 1.0,
+            ..TimeAttack::default()
+        };
+        assert!(!time_attack.record_solve(solve(10_000, Penalty::None)));
+        assert_eq!(time_attack.remaining(start), None);
+
+        time_attack.start(start);
+        assert_eq!(
+            time_attack.remaining(after(20)),
+            Some(Duration::from_secs(40))
+        );
+        assert!(!time_attack.is_over(after(59)));
+        assert!(time_attack.is_over(after(60)));
+        assert!(time_attack.is_over(after(90)));
+
+        assert_eq!(time_attack.mean(), None);
+        assert!(time_attack.record_solve(solve(10_000, Penalty::None)));
+        assert!(time_attack.record_solve(solve(12_000, Penalty::PlusTwo)));
+        assert!(time_attack.record_solve(solve(5_000, Penalty::Dnf)));
+        assert_eq!(time_attack.solve_count(), 2);
+        assert_eq!(time_attack.mean(), Some(Duration::from_secs(12)));
+
+        // Stopping keeps the solves, and starting again forgets them.
+        time_attack.stop();
+        assert!(!time_attack.is_running());
+        assert_eq!(time_attack.session.solves.len(), 3);
+        time_attack.start(after(120));
+        assert!(time_attack.session.solves.is_empty());
+    }
+
+    #[test]
+    fn test_histogram() {
+        let t = |millis| SolveResult::Time(Duration::from_millis(millis));
+        let dnf = SolveResult::Dnf;
+        let second = Duration::from_secs(1);
+
+        assert_eq!(Histogram::new([dnf], second).bins, Vec::<usize>::new());
+
+        let h = Histogram::new([t(12_500), t(10_000), dnf, t(12_999), t(14_100)], second);
+        assert_eq!(h.start, Duration::from_secs(10));
+        assert_eq!(h.bins, vec![1, 0, 2, 0, 1]);
+        assert_eq!(h.dnf_count, 1);
+        assert_eq!(
+            h.bin_range(2),
+            Duration::from_secs(12)..Duration::from_secs(13)
+        );
+    }
+}
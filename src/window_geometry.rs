@@ -0,0 +1,144 @@
+//! Saving and restoring the position and size of the main window.
+
+use itertools::Itertools;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::event_loop::EventLoopWindowTarget;
+use winit::monitor::MonitorHandle;
+use winit::window::{Window, WindowBuilder};
+
+use crate::preferences::{Preferences, WindowGeometry, WindowPreferences};
+
+/// Moves and resizes the window to where it was when the program was last
+/// closed, keeping it on a monitor that still exists.
+pub(crate) fn restore<T>(
+    builder: WindowBuilder,
+    event_loop: &EventLoopWindowTarget<T>,
+    prefs: &WindowPreferences,
+) -> WindowBuilder {
+    let Some(geometry) = prefs.geometry.as_ref().filter(|_| prefs.remember_geometry) else {
+        return builder;
+    };
+
+    // Prefer the same monitor as before, then whichever monitor is under the
+    // window now, in case monitors have been renamed or rearranged.
+    let monitors = event_loop.available_monitors().collect_vec();
+    let monitor = monitors
+        .iter()
+        .find(|m| geometry.monitor.is_some() && m.name() == geometry.monitor)
+        .or_else(|| {
+            let [x, y] = [geometry.x, geometry.y];
+            monitors.iter().find(|m| {
+                let (pos, size) = monitor_area(m);
+                (pos[0]..pos[0] + size[0] as i32).contains(&x)
+                    && (pos[1]..pos[1] + size[1] as i32).contains(&y)
+            })
+        })
+        .cloned()
+        .or_else(|| event_loop.primary_monitor())
+        .or_else(|| monitors.first().cloned());
+    let Some(monitor) = monitor else {
+        return builder;
+    };
+
+    let (area_pos, area_size) = monitor_area(&monitor);
+    let (pos, size) = clamp_to_area(
+        [geometry.x, geometry.y],
+        [geometry.width, geometry.height],
+        area_pos,
+        area_size,
+    );
+    builder
+        .with_position(PhysicalPosition::new(pos[0], pos[1]))
+        .with_inner_size(PhysicalSize::new(size[0], size[1]))
+        .with_maximized(geometry.maximized)
+}
+
+/// Records the position and size of the window in the preferences.
+pub(crate) fn save(window: &Window, prefs: &mut Preferences) {
+    if !prefs.window.remember_geometry {
+        return;
+    }
+    let Ok(position) = window.outer_position() else {
+        return;
+    };
+    let size = window.inner_size();
+    // Don't remember a minimized window.
+    if size.width == 0 || size.height == 0 {
+        return;
+    }
+
+    let geometry = WindowGeometry {
+        monitor: window.current_monitor().and_then(|m| m.name()),
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        maximized: window.is_maximized(),
+    };
+    if prefs.window.geometry.as_ref() != Some(&geometry) {
+        prefs.window.geometry = Some(geometry);
+        prefs.needs_save = true;
+    }
+}
+
+/// Moves the window to the middle of the primary monitor, shrinking it if it
+/// doesn't fit.
+pub(crate) fn reset(window: &Window) {
+    window.set_maximized(false);
+    let Some(monitor) = window
+        .primary_monitor()
+        .or_else(|| window.current_monitor())
+    else {
+        return;
+    };
+
+    let (area_pos, area_size) = monitor_area(&monitor);
+    let size = window.inner_size();
+    let size = [size.width.min(area_size[0]), size.height.min(area_size[1])];
+    let pos = [0, 1].map(|i| area_pos[i] + (area_size[i] - size[i]) as i32 / 2);
+    window.set_inner_size(PhysicalSize::new(size[0], size[1]));
+    window.set_outer_position(PhysicalPosition::new(pos[0], pos[1]));
+}
+
+fn monitor_area(monitor: &MonitorHandle) -> ([i32; 2], [u32; 2]) {
+    let pos = monitor.position();
+    let size = monitor.size();
+    ([pos.x, pos.y], [size.width, size.height])
+}
+
+/// Shrinks and moves a window so that it fits entirely within an area.
+fn clamp_to_area(
+    pos: [i32; 2],
+    size: [u32; 2],
+    area_pos: [i32; 2],
+    area_size: [u32; 2],
+) -> ([i32; 2], [u32; 2]) {
+    let size = [0, 1].map(|i| size[i].min(area_size[i]));
+    let pos = [0, 1].map(|i| {
+        let max = area_pos[i] + (area_size[i] - size[i]) as i32;
+        pos[i].clamp(area_pos[i], max)
+    });
+    (pos, size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clamp_window_to_area() {
+        let area = ([0, 0], [1920, 1080]);
+        let clamp = |pos, size| clamp_to_area(pos, size, area.0, area.1);
+
+        // A window that fits is left alone.
+        assert_eq!(clamp([100, 50], [800, 600]), ([100, 50], [800, 600]));
+        // A window hanging off the edges is moved back on.
+        assert_eq!(clamp([1500, -20], [800, 600]), ([1120, 0], [800, 600]));
+        // A window that's too big is shrunk to the area.
+        assert_eq!(clamp([-50, 10], [2560, 1440]), ([0, 0], [1920, 1080]));
+
+        // The area may be a monitor to the left of the primary one.
+        let (pos, size) = clamp_to_area([100, 100], [800, 600], [-1280, 0], [1280, 1024]);
+        assert_eq!((pos, size), ([-800, 100], [800, 600]));
+    }
+}